@@ -1,7 +1,6 @@
 //! Module implementation.
 
 use crate::error::Result;
-use reqwest::Method;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -55,36 +54,31 @@ pub struct CreateAssetRequest {
     pub metadata: Option<serde_json::Value>,
 }
 
-impl crate::client::AccountsClient {
+impl<A: crate::client::AccountsAdapter> crate::client::AccountsClient<A> {
     pub async fn get_account_details(&self) -> Result<AccountDetails> {
-        self.execute_request(Method::GET, "accounts/current", None::<&()>).await
+        self.adapter.get_json("accounts/current").await
     }
 
     pub async fn update_account_details(
         &self,
         request: &UpdateAccountRequest,
     ) -> Result<AccountDetails> {
-        self.execute_request(Method::PUT, "accounts/current", Some(request)).await
+        self.adapter.put_json("accounts/current", request).await
     }
 
     pub async fn get_price_packages(&self) -> Result<Vec<PricePackage>> {
-        self.execute_request(Method::GET, "accounts/current/price-packages", None::<&()>).await
+        self.adapter.get_json("accounts/current/price-packages").await
     }
 
     pub async fn list_assets(&self) -> Result<Vec<Asset>> {
-        self.execute_request(Method::GET, "accounts/current/assets", None::<&()>).await
+        self.adapter.get_json("accounts/current/assets").await
     }
 
     pub async fn upload_asset(&self, request: &CreateAssetRequest) -> Result<Asset> {
-        self.execute_request(Method::POST, "accounts/current/assets", Some(request)).await
+        self.adapter.post_json("accounts/current/assets", request).await
     }
 
     pub async fn delete_asset(&self, asset_id: &str) -> Result<()> {
-        self.execute_request(
-            Method::DELETE,
-            &format!("accounts/current/assets/{}", asset_id),
-            None::<&()>,
-        )
-        .await
+        self.adapter.delete(&format!("accounts/current/assets/{}", asset_id)).await
     }
 }
@@ -1,7 +1,6 @@
 //! Module implementation.
 
 use crate::error::Result;
-use reqwest::Method;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -90,54 +89,54 @@ pub struct GenerateRecoveryCodesResponse {
     pub codes: Vec<String>,
 }
 
-impl crate::client::AccountsClient {
+impl<A: crate::client::AccountsAdapter> crate::client::AccountsClient<A> {
     pub async fn create_token(&self, request: &TokenRequest) -> Result<TokenResponse> {
-        self.execute_request(Method::POST, "oauth/token", Some(request)).await
+        self.adapter.post_json("oauth/token", request).await
     }
 
     pub async fn revoke_token(&self, request: &RevokeTokenRequest) -> Result<()> {
-        self.execute_request(Method::POST, "oauth/revoke", Some(request)).await
+        self.adapter.post_json("oauth/revoke", request).await
     }
 
     pub async fn initiate_passwordless_auth(
         &self,
         request: &PasswordlessAuthRequest,
     ) -> Result<()> {
-        self.execute_request(Method::POST, "auth/passwordless/initiate", Some(request)).await
+        self.adapter.post_json("auth/passwordless/initiate", request).await
     }
 
     pub async fn verify_passwordless_auth(
         &self,
         request: &PasswordlessVerifyRequest,
     ) -> Result<TokenResponse> {
-        self.execute_request(Method::POST, "auth/passwordless/verify", Some(request)).await
+        self.adapter.post_json("auth/passwordless/verify", request).await
     }
 
     pub async fn setup_mfa(&self, request: &MfaSetupRequest) -> Result<MfaSetupResponse> {
-        self.execute_request(Method::POST, "auth/mfa/setup", Some(request)).await
+        self.adapter.post_json("auth/mfa/setup", request).await
     }
 
     pub async fn verify_mfa(&self, request: &MfaVerifyRequest) -> Result<()> {
-        self.execute_request(Method::POST, "auth/mfa/verify", Some(request)).await
+        self.adapter.post_json("auth/mfa/verify", request).await
     }
 
     pub async fn disable_mfa(&self) -> Result<()> {
-        self.execute_request(Method::DELETE, "auth/mfa", None::<&()>).await
+        self.adapter.delete("auth/mfa").await
     }
 
     pub async fn create_mfa_challenge(&self, request: &MfaChallengeRequest) -> Result<()> {
-        self.execute_request(Method::POST, "auth/mfa/challenge", Some(request)).await
+        self.adapter.post_json("auth/mfa/challenge", request).await
     }
 
     pub async fn get_oidc_configuration(&self) -> Result<OidcConfiguration> {
-        self.execute_request(Method::GET, ".well-known/openid-configuration", None::<&()>).await
+        self.adapter.get_json(".well-known/openid-configuration").await
     }
 
     pub async fn generate_recovery_codes(&self) -> Result<GenerateRecoveryCodesResponse> {
-        self.execute_request(Method::POST, "auth/recovery-codes", None::<&()>).await
+        self.adapter.post_json("auth/recovery-codes", &serde_json::json!({})).await
     }
 
     pub async fn list_recovery_codes(&self) -> Result<Vec<RecoveryCode>> {
-        self.execute_request(Method::GET, "auth/recovery-codes", None::<&()>).await
+        self.adapter.get_json("auth/recovery-codes").await
     }
 }
@@ -2,50 +2,146 @@
 
 use crate::error::Result;
 use crate::types::*;
-use reqwest::{Client, Response};
-use serde::de::DeserializeOwned;
-
-pub struct AccountsClient {
-    client: Client,
-    base_url: String,
-    api_token: String,
+use async_trait::async_trait;
+use dintero_core::{paginate, Endpoint, Page, PageRequest, Paginator};
+
+/// Endpoints called by the Accounts API client, used to name the spans and metrics
+/// of its calls.
+#[rustfmt::skip]
+pub const ENDPOINTS: &[Endpoint] = &[
+    Endpoint::new("GET", "accounts/current", "accounts.get_account_details"),
+    Endpoint::new("PUT", "accounts/current", "accounts.update_account_details"),
+    Endpoint::new("GET", "accounts/current/price-packages", "accounts.get_price_packages"),
+    Endpoint::new("GET", "accounts/current/assets", "accounts.list_assets"),
+    Endpoint::new("POST", "accounts/current/assets", "accounts.upload_asset"),
+    Endpoint::new("DELETE", "accounts/current/assets/{asset_id}", "accounts.delete_asset"),
+    Endpoint::new("POST", "oauth/token", "accounts.create_token"),
+    Endpoint::new("POST", "oauth/revoke", "accounts.revoke_token"),
+    Endpoint::new("POST", "auth/passwordless/initiate", "accounts.initiate_passwordless_auth"),
+    Endpoint::new("POST", "auth/passwordless/verify", "accounts.verify_passwordless_auth"),
+    Endpoint::new("POST", "auth/mfa/setup", "accounts.setup_mfa"),
+    Endpoint::new("POST", "auth/mfa/verify", "accounts.verify_mfa"),
+    Endpoint::new("DELETE", "auth/mfa", "accounts.disable_mfa"),
+    Endpoint::new("POST", "auth/mfa/challenge", "accounts.create_mfa_challenge"),
+    Endpoint::new("GET", ".well-known/openid-configuration", "accounts.get_oidc_configuration"),
+    Endpoint::new("POST", "auth/recovery-codes", "accounts.generate_recovery_codes"),
+    Endpoint::new("GET", "auth/recovery-codes", "accounts.list_recovery_codes"),
+    Endpoint::new("GET", "accounts/{account_id}", "accounts.get_account"),
+    Endpoint::new("GET", "accounts", "accounts.list_accounts"),
+    Endpoint::new("PATCH", "accounts/{account_id}", "accounts.update_account"),
+    Endpoint::new("GET", "accounts/{account_id}/profiles/{profile_id}", "accounts.get_profile"),
+    Endpoint::new("GET", "accounts/{account_id}/profiles", "accounts.list_profiles"),
+    Endpoint::new("PATCH", "accounts/{account_id}/profiles/{profile_id}", "accounts.update_profile"),
+    Endpoint::new("GET", "accounts/session", "accounts.get_session"),
+    Endpoint::new("GET", "oauth/clients", "accounts.list_oauth_clients"),
+    Endpoint::new("GET", "oauth/clients/{client_id}", "accounts.get_oauth_client"),
+    Endpoint::new("POST", "oauth/clients", "accounts.create_oauth_client"),
+    Endpoint::new("PUT", "oauth/clients/{client_id}", "accounts.update_oauth_client"),
+    Endpoint::new("DELETE", "oauth/clients/{client_id}", "accounts.delete_oauth_client"),
+    Endpoint::new("POST", "oauth/clients/{client_id}/secret", "accounts.rotate_client_secret"),
+    Endpoint::new("GET", "oauth/client-grants", "accounts.list_client_grants"),
+    Endpoint::new("POST", "oauth/client-grants", "accounts.create_client_grant"),
+    Endpoint::new("DELETE", "oauth/client-grants/{grant_id}", "accounts.delete_client_grant"),
+    Endpoint::new("GET", "accounts/current/api-credentials", "accounts.list_api_credentials"),
+    Endpoint::new("POST", "accounts/current/api-credentials", "accounts.create_api_credential"),
+    Endpoint::new("DELETE", "accounts/current/api-credentials/{credential_id}", "accounts.delete_api_credential"),
+    Endpoint::new("GET", "accounts/current/gateway-connections", "accounts.list_gateway_connections"),
+    Endpoint::new("GET", "accounts/current/gateway-connections/{gateway_id}", "accounts.get_gateway_connection"),
+    Endpoint::new("POST", "accounts/current/gateway-connections", "accounts.create_gateway_connection"),
+    Endpoint::new("PUT", "accounts/current/gateway-connections/{gateway_id}", "accounts.update_gateway_connection"),
+    Endpoint::new("DELETE", "accounts/current/gateway-connections/{gateway_id}", "accounts.delete_gateway_connection"),
+    Endpoint::new("GET", "accounts/current/locations", "accounts.list_locations"),
+    Endpoint::new("GET", "accounts/current/locations/{location_id}", "accounts.get_location"),
+    Endpoint::new("POST", "accounts/current/locations", "accounts.create_location"),
+    Endpoint::new("PUT", "accounts/current/locations/{location_id}", "accounts.update_location"),
+    Endpoint::new("DELETE", "accounts/current/locations/{location_id}", "accounts.delete_location"),
+    Endpoint::new("GET", "accounts/current/locations/{location_id}/terminals", "accounts.list_terminals"),
+    Endpoint::new("GET", "accounts/current/locations/{location_id}/terminals/{terminal_id}", "accounts.get_terminal"),
+    Endpoint::new("POST", "accounts/current/locations/{location_id}/terminals", "accounts.create_terminal"),
+    Endpoint::new("PUT", "accounts/current/locations/{location_id}/terminals/{terminal_id}", "accounts.update_terminal"),
+    Endpoint::new("DELETE", "accounts/current/locations/{location_id}/terminals/{terminal_id}", "accounts.delete_terminal"),
+    Endpoint::new("POST", "partners/onboarding-links", "accounts.create_onboarding_link"),
+    Endpoint::new("GET", "partners/onboarding-links/{link_id}", "accounts.get_onboarding_link"),
+    Endpoint::new("POST", "accounts/signup", "accounts.create_account_signup"),
+    Endpoint::new("POST", "accounts/signup/verify", "accounts.verify_signup"),
+    Endpoint::new("POST", "partners/merchants/search", "accounts.search_merchants"),
+    Endpoint::new("POST", "partners/merchants/terminate", "accounts.terminate_merchant"),
+    Endpoint::new("GET", "accounts/current/users", "accounts.list_users"),
+    Endpoint::new("GET", "accounts/current/users/{user_id}", "accounts.get_user"),
+    Endpoint::new("POST", "accounts/current/users", "accounts.create_user"),
+    Endpoint::new("PUT", "accounts/current/users/{user_id}", "accounts.update_user"),
+    Endpoint::new("DELETE", "accounts/current/users/{user_id}", "accounts.delete_user"),
+    Endpoint::new("GET", "accounts/current/users/{user_id}/activities", "accounts.list_user_activities"),
+    Endpoint::new("GET", "accounts/current/permissions", "accounts.list_permissions"),
+    Endpoint::new("GET", "accounts/current/roles", "accounts.list_roles"),
+    Endpoint::new("POST", "accounts/current/password", "accounts.update_password"),
+    Endpoint::new("POST", "accounts/password/reset", "accounts.reset_password"),
+];
+
+#[async_trait]
+pub trait AccountsAdapter: Send + Sync {
+    async fn get_json<T: serde::de::DeserializeOwned>(&self, path: &str) -> Result<T>;
+    async fn post_json<T: serde::de::DeserializeOwned, B: serde::Serialize + Send + Sync>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<T>;
+    async fn put_json<T: serde::de::DeserializeOwned, B: serde::Serialize + Send + Sync>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<T>;
+    async fn patch_json<T: serde::de::DeserializeOwned, B: serde::Serialize + Send + Sync>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<T>;
+    async fn delete(&self, path: &str) -> Result<()>;
+    /// Sends a `GET` request with query parameters serialized from `query`, e.g. a
+    /// struct or a slice of pairs.
+    async fn get_json_with_query<
+        T: serde::de::DeserializeOwned,
+        Q: serde::Serialize + Send + Sync + ?Sized,
+    >(
+        &self,
+        path: &str,
+        query: &Q,
+    ) -> Result<T> {
+        self.get_json(&dintero_core::path::with_query(path, query)?).await
+    }
 }
 
-impl AccountsClient {
-    pub fn new(base_url: String, api_token: String) -> Self {
-        Self {
-            client: Client::new(),
-            base_url,
-            api_token,
-        }
-    }
+pub struct AccountsClient<A: AccountsAdapter> {
+    pub(crate) adapter: A,
+}
 
-    async fn handle_response<T: DeserializeOwned>(&self, response: Response) -> Result<T> {
-        let status = response.status();
-        if status.is_success() {
-            Ok(response.json().await?)
-        } else {
-            let message = response.text().await.unwrap_or_default();
-            Err(crate::error::AccountError::ApiError { status: status.as_u16(), message })
-        }
+impl<A: AccountsAdapter> AccountsClient<A> {
+    pub fn new(adapter: A) -> Self {
+        Self { adapter }
     }
 
     pub async fn get_account(&self, account_id: &str) -> Result<Account> {
-        let url = format!("{}/v1/accounts/{}", self.base_url, account_id);
-        let response = self.client.get(&url).bearer_auth(&self.api_token).send().await?;
-
-        self.handle_response(response).await
+        let path = format!("accounts/{}", account_id);
+        self.adapter.get_json(&path).await
     }
 
     pub async fn list_accounts(&self, page_token: Option<&str>) -> Result<AccountList> {
-        let mut url = format!("{}/v1/accounts", self.base_url);
-        if let Some(token) = page_token {
-            url.push_str(&format!("?page_token={}", token));
-        }
-
-        let response = self.client.get(&url).bearer_auth(&self.api_token).send().await?;
+        let query: Vec<_> = page_token.map(|token| ("page_token", token)).into_iter().collect();
+        self.adapter.get_json_with_query("accounts", &query).await
+    }
 
-        self.handle_response(response).await
+    /// Streams all accounts, fetching further pages as needed.
+    ///
+    /// The endpoint does not accept a page size, so
+    /// [`Paginator::page_size`] has no effect.
+    pub fn accounts_stream(&self) -> Paginator<'_, Account> {
+        paginate(move |page: PageRequest| async move {
+            let response = self.list_accounts(page.page_token().as_deref()).await?;
+            Ok(Page::from_token(
+                response.accounts,
+                response.next_page_token,
+            ))
+        })
     }
 
     pub async fn update_account(
@@ -53,26 +149,13 @@ impl AccountsClient {
         account_id: &str,
         request: UpdateAccountRequest,
     ) -> Result<Account> {
-        let url = format!("{}/v1/accounts/{}", self.base_url, account_id);
-        let response = self
-            .client
-            .patch(&url)
-            .bearer_auth(&self.api_token)
-            .json(&request.build())
-            .send()
-            .await?;
-
-        self.handle_response(response).await
+        let path = format!("accounts/{}", account_id);
+        self.adapter.patch_json(&path, &request.build()).await
     }
 
     pub async fn get_profile(&self, account_id: &str, profile_id: &str) -> Result<Profile> {
-        let url = format!(
-            "{}/v1/accounts/{}/profiles/{}",
-            self.base_url, account_id, profile_id
-        );
-        let response = self.client.get(&url).bearer_auth(&self.api_token).send().await?;
-
-        self.handle_response(response).await
+        let path = format!("accounts/{}/profiles/{}", account_id, profile_id);
+        self.adapter.get_json(&path).await
     }
 
     pub async fn list_profiles(
@@ -80,14 +163,23 @@ impl AccountsClient {
         account_id: &str,
         page_token: Option<&str>,
     ) -> Result<ProfileList> {
-        let mut url = format!("{}/v1/accounts/{}/profiles", self.base_url, account_id);
-        if let Some(token) = page_token {
-            url.push_str(&format!("?page_token={}", token));
-        }
-
-        let response = self.client.get(&url).bearer_auth(&self.api_token).send().await?;
+        let path = format!("accounts/{}/profiles", account_id);
+        let query: Vec<_> = page_token.map(|token| ("page_token", token)).into_iter().collect();
+        self.adapter.get_json_with_query(&path, &query).await
+    }
 
-        self.handle_response(response).await
+    /// Streams all profiles of an account, fetching further pages as needed.
+    ///
+    /// The endpoint does not accept a page size, so
+    /// [`Paginator::page_size`] has no effect.
+    pub fn profiles_stream<'a>(&'a self, account_id: &'a str) -> Paginator<'a, Profile> {
+        paginate(move |page: PageRequest| async move {
+            let response = self.list_profiles(account_id, page.page_token().as_deref()).await?;
+            Ok(Page::from_token(
+                response.profiles,
+                response.next_page_token,
+            ))
+        })
     }
 
     pub async fn update_profile(
@@ -96,42 +188,11 @@ impl AccountsClient {
         profile_id: &str,
         request: UpdateProfileRequest,
     ) -> Result<Profile> {
-        let url = format!(
-            "{}/v1/accounts/{}/profiles/{}",
-            self.base_url, account_id, profile_id
-        );
-        let response = self
-            .client
-            .patch(&url)
-            .bearer_auth(&self.api_token)
-            .json(&request.build())
-            .send()
-            .await?;
-
-        self.handle_response(response).await
+        let path = format!("accounts/{}/profiles/{}", account_id, profile_id);
+        self.adapter.patch_json(&path, &request.build()).await
     }
 
     pub async fn get_session(&self) -> Result<Session> {
-        let url = format!("{}/v1/accounts/session", self.base_url);
-        let response = self.client.get(&url).bearer_auth(&self.api_token).send().await?;
-
-        self.handle_response(response).await
-    }
-
-    pub(crate) async fn execute_request<T: DeserializeOwned, B: serde::Serialize>(
-        &self,
-        method: reqwest::Method,
-        path: &str,
-        body: Option<&B>,
-    ) -> Result<T> {
-        let url = format!("{}/v1/{}", self.base_url, path);
-        let mut request = self.client.request(method, &url).bearer_auth(&self.api_token);
-
-        if let Some(b) = body {
-            request = request.json(b);
-        }
-
-        let response = request.send().await?;
-        self.handle_response(response).await
+        self.adapter.get_json("accounts/session").await
     }
 }
@@ -1,7 +1,6 @@
 //! Module implementation.
 
 use crate::error::Result;
-use reqwest::Method;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -74,25 +73,20 @@ pub struct RotateClientSecretResponse {
     pub client_secret: String,
 }
 
-impl crate::client::AccountsClient {
+impl<A: crate::client::AccountsAdapter> crate::client::AccountsClient<A> {
     pub async fn list_oauth_clients(&self) -> Result<Vec<OAuthClient>> {
-        self.execute_request(Method::GET, "oauth/clients", None::<&()>).await
+        self.adapter.get_json("oauth/clients").await
     }
 
     pub async fn get_oauth_client(&self, client_id: &str) -> Result<OAuthClient> {
-        self.execute_request(
-            Method::GET,
-            &format!("oauth/clients/{}", client_id),
-            None::<&()>,
-        )
-        .await
+        self.adapter.get_json(&format!("oauth/clients/{}", client_id)).await
     }
 
     pub async fn create_oauth_client(
         &self,
         request: &CreateOAuthClientRequest,
     ) -> Result<OAuthClient> {
-        self.execute_request(Method::POST, "oauth/clients", Some(request)).await
+        self.adapter.post_json("oauth/clients", request).await
     }
 
     pub async fn update_oauth_client(
@@ -100,77 +94,57 @@ impl crate::client::AccountsClient {
         client_id: &str,
         request: &UpdateOAuthClientRequest,
     ) -> Result<OAuthClient> {
-        self.execute_request(
-            Method::PUT,
-            &format!("oauth/clients/{}", client_id),
-            Some(request),
-        )
-        .await
+        self.adapter.put_json(&format!("oauth/clients/{}", client_id), request).await
     }
 
     pub async fn delete_oauth_client(&self, client_id: &str) -> Result<()> {
-        self.execute_request(
-            Method::DELETE,
-            &format!("oauth/clients/{}", client_id),
-            None::<&()>,
-        )
-        .await
+        self.adapter.delete(&format!("oauth/clients/{}", client_id)).await
     }
 
     pub async fn rotate_client_secret(
         &self,
         client_id: &str,
     ) -> Result<RotateClientSecretResponse> {
-        self.execute_request(
-            Method::POST,
-            &format!("oauth/clients/{}/secret", client_id),
-            None::<&()>,
-        )
-        .await
+        self.adapter
+            .post_json(
+                &format!("oauth/clients/{}/secret", client_id),
+                &serde_json::json!({}),
+            )
+            .await
     }
 
     pub async fn list_client_grants(&self) -> Result<Vec<ClientGrant>> {
-        self.execute_request(Method::GET, "oauth/client-grants", None::<&()>).await
+        self.adapter.get_json("oauth/client-grants").await
     }
 
     pub async fn create_client_grant(
         &self,
         request: &CreateClientGrantRequest,
     ) -> Result<ClientGrant> {
-        self.execute_request(Method::POST, "oauth/client-grants", Some(request)).await
+        self.adapter.post_json("oauth/client-grants", request).await
     }
 
     pub async fn delete_client_grant(&self, grant_id: &str) -> Result<()> {
-        self.execute_request(
-            Method::DELETE,
-            &format!("oauth/client-grants/{}", grant_id),
-            None::<&()>,
-        )
-        .await
+        self.adapter.delete(&format!("oauth/client-grants/{}", grant_id)).await
     }
 
     pub async fn list_api_credentials(&self) -> Result<Vec<ApiCredential>> {
-        self.execute_request(Method::GET, "accounts/current/api-credentials", None::<&()>).await
+        self.adapter.get_json("accounts/current/api-credentials").await
     }
 
     pub async fn create_api_credential(
         &self,
         request: &CreateApiCredentialRequest,
     ) -> Result<ApiCredential> {
-        self.execute_request(
-            Method::POST,
-            "accounts/current/api-credentials",
-            Some(request),
-        )
-        .await
+        self.adapter.post_json("accounts/current/api-credentials", request).await
     }
 
     pub async fn delete_api_credential(&self, credential_id: &str) -> Result<()> {
-        self.execute_request(
-            Method::DELETE,
-            &format!("accounts/current/api-credentials/{}", credential_id),
-            None::<&()>,
-        )
-        .await
+        self.adapter
+            .delete(&format!(
+                "accounts/current/api-credentials/{}",
+                credential_id
+            ))
+            .await
     }
 }
@@ -1,20 +1,7 @@
 //! Error types and handling.
 
-use thiserror::Error;
+pub use dintero_core::{ApiError, DinteroError};
 
-#[derive(Error, Debug)]
-pub enum AccountError {
-    #[error("HTTP request failed: {0}")]
-    HttpError(#[from] reqwest::Error),
-
-    #[error("JSON serialization/deserialization failed: {0}")]
-    JsonError(#[from] serde_json::Error),
-
-    #[error("API error: {status} - {message}")]
-    ApiError { status: u16, message: String },
-
-    #[error("Invalid input: {0}")]
-    InvalidInput(String),
-}
+pub type AccountError = DinteroError;
 
 pub type Result<T> = std::result::Result<T, AccountError>;
@@ -1,7 +1,6 @@
 //! Module implementation.
 
 use crate::error::Result;
-use reqwest::Method;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -135,35 +134,25 @@ pub struct UpdateGatewayRequest {
     pub configuration: Option<serde_json::Value>,
 }
 
-impl crate::client::AccountsClient {
+impl<A: crate::client::AccountsAdapter> crate::client::AccountsClient<A> {
     pub async fn list_gateway_connections(&self) -> Result<Vec<GatewayConnection>> {
-        self.execute_request(
-            Method::GET,
-            "accounts/current/gateway-connections",
-            None::<&()>,
-        )
-        .await
+        self.adapter.get_json("accounts/current/gateway-connections").await
     }
 
     pub async fn get_gateway_connection(&self, gateway_id: &str) -> Result<GatewayConnection> {
-        self.execute_request(
-            Method::GET,
-            &format!("accounts/current/gateway-connections/{}", gateway_id),
-            None::<&()>,
-        )
-        .await
+        self.adapter
+            .get_json(&format!(
+                "accounts/current/gateway-connections/{}",
+                gateway_id
+            ))
+            .await
     }
 
     pub async fn create_gateway_connection(
         &self,
         request: &CreateGatewayRequest,
     ) -> Result<GatewayConnection> {
-        self.execute_request(
-            Method::POST,
-            "accounts/current/gateway-connections",
-            Some(request),
-        )
-        .await
+        self.adapter.post_json("accounts/current/gateway-connections", request).await
     }
 
     pub async fn update_gateway_connection(
@@ -171,21 +160,21 @@ impl crate::client::AccountsClient {
         gateway_id: &str,
         request: &UpdateGatewayRequest,
     ) -> Result<GatewayConnection> {
-        self.execute_request(
-            Method::PUT,
-            &format!("accounts/current/gateway-connections/{}", gateway_id),
-            Some(request),
-        )
-        .await
+        self.adapter
+            .put_json(
+                &format!("accounts/current/gateway-connections/{}", gateway_id),
+                request,
+            )
+            .await
     }
 
     pub async fn delete_gateway_connection(&self, gateway_id: &str) -> Result<()> {
-        self.execute_request(
-            Method::DELETE,
-            &format!("accounts/current/gateway-connections/{}", gateway_id),
-            None::<&()>,
-        )
-        .await
+        self.adapter
+            .delete(&format!(
+                "accounts/current/gateway-connections/{}",
+                gateway_id
+            ))
+            .await
     }
 
     pub async fn create_bambora_connection(
@@ -16,6 +16,8 @@ pub mod partners;
 pub mod types;
 pub mod users;
 
-pub use client::AccountsClient;
+pub use client::{AccountsAdapter, AccountsClient, ENDPOINTS};
 pub use error::{AccountError, Result};
 pub use types::*;
+
+pub use dintero_core::{ApiError, DinteroError, ErrorCode, FieldError};
@@ -1,7 +1,6 @@
 //! Module implementation.
 
 use crate::error::Result;
-use reqwest::Method;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -84,22 +83,21 @@ pub struct UpdateTerminalRequest {
     pub settings: Option<TerminalSettings>,
 }
 
-impl crate::client::AccountsClient {
+impl<A: crate::client::AccountsAdapter> crate::client::AccountsClient<A> {
+    /// Lists the locations of the current account.
+    ///
+    /// The endpoint is not paginated and returns every location in one response, so there is
+    /// no stream counterpart.
     pub async fn list_locations(&self) -> Result<Vec<Location>> {
-        self.execute_request(Method::GET, "accounts/current/locations", None::<&()>).await
+        self.adapter.get_json("accounts/current/locations").await
     }
 
     pub async fn get_location(&self, location_id: &str) -> Result<Location> {
-        self.execute_request(
-            Method::GET,
-            &format!("accounts/current/locations/{}", location_id),
-            None::<&()>,
-        )
-        .await
+        self.adapter.get_json(&format!("accounts/current/locations/{}", location_id)).await
     }
 
     pub async fn create_location(&self, request: &CreateLocationRequest) -> Result<Location> {
-        self.execute_request(Method::POST, "accounts/current/locations", Some(request)).await
+        self.adapter.post_json("accounts/current/locations", request).await
     }
 
     pub async fn update_location(
@@ -107,42 +105,34 @@ impl crate::client::AccountsClient {
         location_id: &str,
         request: &UpdateLocationRequest,
     ) -> Result<Location> {
-        self.execute_request(
-            Method::PUT,
-            &format!("accounts/current/locations/{}", location_id),
-            Some(request),
-        )
-        .await
+        self.adapter
+            .put_json(
+                &format!("accounts/current/locations/{}", location_id),
+                request,
+            )
+            .await
     }
 
     pub async fn delete_location(&self, location_id: &str) -> Result<()> {
-        self.execute_request(
-            Method::DELETE,
-            &format!("accounts/current/locations/{}", location_id),
-            None::<&()>,
-        )
-        .await
+        self.adapter.delete(&format!("accounts/current/locations/{}", location_id)).await
     }
 
     pub async fn list_terminals(&self, location_id: &str) -> Result<Vec<Terminal>> {
-        self.execute_request(
-            Method::GET,
-            &format!("accounts/current/locations/{}/terminals", location_id),
-            None::<&()>,
-        )
-        .await
+        self.adapter
+            .get_json(&format!(
+                "accounts/current/locations/{}/terminals",
+                location_id
+            ))
+            .await
     }
 
     pub async fn get_terminal(&self, location_id: &str, terminal_id: &str) -> Result<Terminal> {
-        self.execute_request(
-            Method::GET,
-            &format!(
+        self.adapter
+            .get_json(&format!(
                 "accounts/current/locations/{}/terminals/{}",
                 location_id, terminal_id
-            ),
-            None::<&()>,
-        )
-        .await
+            ))
+            .await
     }
 
     pub async fn create_terminal(
@@ -150,12 +140,12 @@ impl crate::client::AccountsClient {
         location_id: &str,
         request: &CreateTerminalRequest,
     ) -> Result<Terminal> {
-        self.execute_request(
-            Method::POST,
-            &format!("accounts/current/locations/{}/terminals", location_id),
-            Some(request),
-        )
-        .await
+        self.adapter
+            .post_json(
+                &format!("accounts/current/locations/{}/terminals", location_id),
+                request,
+            )
+            .await
     }
 
     pub async fn update_terminal(
@@ -164,26 +154,23 @@ impl crate::client::AccountsClient {
         terminal_id: &str,
         request: &UpdateTerminalRequest,
     ) -> Result<Terminal> {
-        self.execute_request(
-            Method::PUT,
-            &format!(
-                "accounts/current/locations/{}/terminals/{}",
-                location_id, terminal_id
-            ),
-            Some(request),
-        )
-        .await
+        self.adapter
+            .put_json(
+                &format!(
+                    "accounts/current/locations/{}/terminals/{}",
+                    location_id, terminal_id
+                ),
+                request,
+            )
+            .await
     }
 
     pub async fn delete_terminal(&self, location_id: &str, terminal_id: &str) -> Result<()> {
-        self.execute_request(
-            Method::DELETE,
-            &format!(
+        self.adapter
+            .delete(&format!(
                 "accounts/current/locations/{}/terminals/{}",
                 location_id, terminal_id
-            ),
-            None::<&()>,
-        )
-        .await
+            ))
+            .await
     }
 }
@@ -1,7 +1,6 @@
 //! Module implementation.
 
 use crate::error::Result;
-use reqwest::Method;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -80,42 +79,37 @@ pub struct TerminateMerchantRequest {
     pub effective_date: Option<String>,
 }
 
-impl crate::client::AccountsClient {
+impl<A: crate::client::AccountsAdapter> crate::client::AccountsClient<A> {
     pub async fn create_onboarding_link(
         &self,
         request: &CreateOnboardingLinkRequest,
     ) -> Result<OnboardingLink> {
-        self.execute_request(Method::POST, "partners/onboarding-links", Some(request)).await
+        self.adapter.post_json("partners/onboarding-links", request).await
     }
 
     pub async fn get_onboarding_link(&self, link_id: &str) -> Result<OnboardingLink> {
-        self.execute_request(
-            Method::GET,
-            &format!("partners/onboarding-links/{}", link_id),
-            None::<&()>,
-        )
-        .await
+        self.adapter.get_json(&format!("partners/onboarding-links/{}", link_id)).await
     }
 
     pub async fn create_account_signup(
         &self,
         request: &CreateAccountSignupRequest,
     ) -> Result<AccountSignup> {
-        self.execute_request(Method::POST, "accounts/signup", Some(request)).await
+        self.adapter.post_json("accounts/signup", request).await
     }
 
     pub async fn verify_signup(&self, request: &VerifySignupRequest) -> Result<AccountSignup> {
-        self.execute_request(Method::POST, "accounts/signup/verify", Some(request)).await
+        self.adapter.post_json("accounts/signup/verify", request).await
     }
 
     pub async fn search_merchants(
         &self,
         request: &SearchMerchantsRequest,
     ) -> Result<Vec<MerchantMatch>> {
-        self.execute_request(Method::POST, "partners/merchants/search", Some(request)).await
+        self.adapter.post_json("partners/merchants/search", request).await
     }
 
     pub async fn terminate_merchant(&self, request: &TerminateMerchantRequest) -> Result<()> {
-        self.execute_request(Method::POST, "partners/merchants/terminate", Some(request)).await
+        self.adapter.post_json("partners/merchants/terminate", request).await
     }
 }
@@ -1,7 +1,6 @@
 //! Module implementation.
 
 use crate::error::Result;
-use reqwest::Method;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -71,64 +70,48 @@ pub struct ResetPasswordRequest {
     pub email: String,
 }
 
-impl crate::client::AccountsClient {
+impl<A: crate::client::AccountsAdapter> crate::client::AccountsClient<A> {
+    /// Lists the users of the current account.
+    ///
+    /// The endpoint is not paginated and returns every user in one response, so there is no
+    /// stream counterpart.
     pub async fn list_users(&self) -> Result<Vec<User>> {
-        self.execute_request(Method::GET, "accounts/current/users", None::<&()>).await
+        self.adapter.get_json("accounts/current/users").await
     }
 
     pub async fn get_user(&self, user_id: &str) -> Result<User> {
-        self.execute_request(
-            Method::GET,
-            &format!("accounts/current/users/{}", user_id),
-            None::<&()>,
-        )
-        .await
+        self.adapter.get_json(&format!("accounts/current/users/{}", user_id)).await
     }
 
     pub async fn create_user(&self, request: &CreateUserRequest) -> Result<User> {
-        self.execute_request(Method::POST, "accounts/current/users", Some(request)).await
+        self.adapter.post_json("accounts/current/users", request).await
     }
 
     pub async fn update_user(&self, user_id: &str, request: &UpdateUserRequest) -> Result<User> {
-        self.execute_request(
-            Method::PUT,
-            &format!("accounts/current/users/{}", user_id),
-            Some(request),
-        )
-        .await
+        self.adapter.put_json(&format!("accounts/current/users/{}", user_id), request).await
     }
 
     pub async fn delete_user(&self, user_id: &str) -> Result<()> {
-        self.execute_request(
-            Method::DELETE,
-            &format!("accounts/current/users/{}", user_id),
-            None::<&()>,
-        )
-        .await
+        self.adapter.delete(&format!("accounts/current/users/{}", user_id)).await
     }
 
     pub async fn list_user_activities(&self, user_id: &str) -> Result<Vec<UserActivity>> {
-        self.execute_request(
-            Method::GET,
-            &format!("accounts/current/users/{}/activities", user_id),
-            None::<&()>,
-        )
-        .await
+        self.adapter.get_json(&format!("accounts/current/users/{}/activities", user_id)).await
     }
 
     pub async fn list_permissions(&self) -> Result<Vec<Permission>> {
-        self.execute_request(Method::GET, "accounts/current/permissions", None::<&()>).await
+        self.adapter.get_json("accounts/current/permissions").await
     }
 
     pub async fn list_roles(&self) -> Result<Vec<Role>> {
-        self.execute_request(Method::GET, "accounts/current/roles", None::<&()>).await
+        self.adapter.get_json("accounts/current/roles").await
     }
 
     pub async fn update_password(&self, request: &UpdatePasswordRequest) -> Result<()> {
-        self.execute_request(Method::POST, "accounts/current/password", Some(request)).await
+        self.adapter.post_json("accounts/current/password", request).await
     }
 
     pub async fn reset_password(&self, request: &ResetPasswordRequest) -> Result<()> {
-        self.execute_request(Method::POST, "accounts/password/reset", Some(request)).await
+        self.adapter.post_json("accounts/password/reset", request).await
     }
 }
@@ -11,7 +11,7 @@ pub struct CardTokenListResponse {
     pub next_page_token: Option<String>,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct ListCardTokensParams {
     pub limit: Option<u32>,
     pub page_token: Option<String>,
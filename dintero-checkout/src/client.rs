@@ -18,27 +18,47 @@ use crate::transactions::{
     TransactionListResponse, UpdateTransactionRequest, VoidRequest,
 };
 use async_trait::async_trait;
+use dintero_core::{paginate, Endpoint, Idempotent, Page, PageRequest, Paginator, RequestOptions};
 
 /// Result type for checkout operations.
 pub type Result<T> = std::result::Result<T, CheckoutError>;
 
 /// Errors that can occur during checkout operations.
-#[derive(Debug, thiserror::Error)]
-pub enum CheckoutError {
-    /// Serialization or deserialization error.
-    #[error("Serialization error: {0}")]
-    Serialization(String),
-
-    /// General client error.
-    #[error("Client error: {0}")]
-    Client(String),
-}
-
-impl From<serde_json::Error> for CheckoutError {
-    fn from(err: serde_json::Error) -> Self {
-        CheckoutError::Serialization(err.to_string())
-    }
-}
+pub type CheckoutError = dintero_core::DinteroError;
+
+/// Endpoints called by the Checkout API client, used to name the spans and metrics
+/// of its calls.
+#[rustfmt::skip]
+pub const ENDPOINTS: &[Endpoint] = &[
+    Endpoint::new("POST", "accounts/{account_id}/sessions", "checkout.create_session"),
+    Endpoint::new("GET", "accounts/{account_id}/sessions/{session_id}", "checkout.get_session"),
+    Endpoint::new("PUT", "accounts/{account_id}/sessions/{session_id}", "checkout.update_session"),
+    Endpoint::new("GET", "accounts/{account_id}/sessions", "checkout.list_sessions"),
+    Endpoint::new("POST", "accounts/{account_id}/sessions/{session_id}/cancel", "checkout.cancel_session"),
+    Endpoint::new("POST", "accounts/{account_id}/session_profile", "checkout.create_profile"),
+    Endpoint::new("GET", "accounts/{account_id}/session_profile/{profile_id}", "checkout.get_profile"),
+    Endpoint::new("PUT", "accounts/{account_id}/session_profile/{profile_id}", "checkout.update_profile"),
+    Endpoint::new("DELETE", "accounts/{account_id}/session_profile/{profile_id}", "checkout.delete_profile"),
+    Endpoint::new("GET", "accounts/{account_id}/session_profile", "checkout.list_profiles"),
+    Endpoint::new("GET", "accounts/{account_id}/transactions/{transaction_id}", "checkout.get_transaction"),
+    Endpoint::new("GET", "accounts/{account_id}/transactions", "checkout.list_transactions"),
+    Endpoint::new("PUT", "accounts/{account_id}/transactions/{transaction_id}", "checkout.update_transaction"),
+    Endpoint::new("POST", "accounts/{account_id}/transactions/{transaction_id}/extend_authorization", "checkout.extend_authorization"),
+    Endpoint::new("POST", "accounts/{account_id}/transactions/{transaction_id}/capture", "checkout.capture_transaction").with_idempotency_key(),
+    Endpoint::new("POST", "accounts/{account_id}/transactions/{transaction_id}/refund", "checkout.refund_transaction").with_idempotency_key(),
+    Endpoint::new("POST", "accounts/{account_id}/transactions/{transaction_id}/void", "checkout.void_transaction").with_idempotency_key(),
+    Endpoint::new("GET", "accounts/{account_id}/card-tokens/{token_id}", "checkout.get_card_token"),
+    Endpoint::new("GET", "accounts/{account_id}/card-tokens", "checkout.list_card_tokens"),
+    Endpoint::new("DELETE", "accounts/{account_id}/card-tokens/{token_id}", "checkout.delete_card_token"),
+    Endpoint::new("POST", "accounts/{account_id}/api_keys", "checkout.create_api_key"),
+    Endpoint::new("GET", "accounts/{account_id}/api_keys", "checkout.list_api_keys"),
+    Endpoint::new("DELETE", "accounts/{account_id}/api_keys/{api_key_id}", "checkout.delete_api_key"),
+    Endpoint::new("POST", "accounts/{account_id}/api_keys/{api_key_id}/rotate", "checkout.rotate_api_key"),
+    Endpoint::new("POST", "accounts/{account_id}/signature", "checkout.create_signature_secret"),
+    Endpoint::new("GET", "accounts/{account_id}/signature", "checkout.get_signature_secret"),
+    Endpoint::new("POST", "accounts/{account_id}/sessions/{session_id}/qr", "checkout.generate_qr_code"),
+    Endpoint::new("POST", "creditchecks", "checkout.perform_credit_check"),
+];
 
 /// Trait defining all checkout operations.
 ///
@@ -176,6 +196,153 @@ impl<C> CheckoutClient<C> {
     }
 }
 
+impl<C: HttpClient> CheckoutClient<C> {
+    /// Streams all checkout sessions, fetching further pages as needed.
+    ///
+    /// `params.page_token` sets the page to start from.
+    pub fn sessions_stream(&self, params: ListSessionsParams) -> Paginator<'_, CheckoutSession> {
+        paginate(move |page: PageRequest| {
+            let params = ListSessionsParams {
+                limit: page.page_size.or(params.limit),
+                page_token: page.page_token().or_else(|| params.page_token.clone()),
+            };
+            async move {
+                let response = self.list_sessions(params).await?;
+                Ok(Page::from_token(
+                    response.sessions,
+                    response.next_page_token,
+                ))
+            }
+        })
+    }
+
+    /// Streams all transactions matching `params`, fetching further pages as needed.
+    pub fn transactions_stream(
+        &self,
+        params: ListTransactionsParams,
+    ) -> Paginator<'_, Transaction> {
+        paginate(move |page: PageRequest| {
+            let params = ListTransactionsParams {
+                limit: page.page_size.or(params.limit),
+                page_token: page.page_token().or_else(|| params.page_token.clone()),
+                ..params.clone()
+            };
+            async move {
+                let response = self.list_transactions(params).await?;
+                Ok(Page::from_token(
+                    response.transactions,
+                    response.next_page_token,
+                ))
+            }
+        })
+    }
+
+    /// Streams all card tokens matching `params`, fetching further pages as needed.
+    pub fn card_tokens_stream(&self, params: ListCardTokensParams) -> Paginator<'_, CardToken> {
+        paginate(move |page: PageRequest| {
+            let params = ListCardTokensParams {
+                limit: page.page_size.or(params.limit),
+                page_token: page.page_token().or_else(|| params.page_token.clone()),
+                ..params.clone()
+            };
+            async move {
+                let response = self.list_card_tokens(params).await?;
+                Ok(Page::from_token(response.tokens, response.next_page_token))
+            }
+        })
+    }
+
+    /// Like [`CheckoutOperations::capture_transaction`], but also returns the idempotency key
+    /// the request was sent with and whether the API replayed an earlier result.
+    pub async fn capture_transaction_with_meta(
+        &self,
+        transaction_id: &str,
+        request: CaptureRequest,
+    ) -> Result<Idempotent<Transaction>> {
+        self.capture_transaction_with_options(transaction_id, &request, &RequestOptions::default())
+            .await
+    }
+
+    /// Like [`capture_transaction_with_meta`](Self::capture_transaction_with_meta), but sends
+    /// the request with `options`, e.g. an idempotency key of the caller's choosing or a retry
+    /// policy.
+    ///
+    /// A key is generated if `options` has none.
+    pub async fn capture_transaction_with_options(
+        &self,
+        transaction_id: &str,
+        request: &CaptureRequest,
+        options: &RequestOptions,
+    ) -> Result<Idempotent<Transaction>> {
+        let path = format!(
+            "accounts/{}/transactions/{}/capture",
+            self.account_id, transaction_id
+        );
+        let options = options.clone().ensure_idempotency_key();
+        self.client.post_json_idempotent(&path, request, &options).await
+    }
+
+    /// Like [`CheckoutOperations::refund_transaction`], but also returns the idempotency key
+    /// the request was sent with and whether the API replayed an earlier result.
+    pub async fn refund_transaction_with_meta(
+        &self,
+        transaction_id: &str,
+        request: RefundRequest,
+    ) -> Result<Idempotent<Transaction>> {
+        self.refund_transaction_with_options(transaction_id, &request, &RequestOptions::default())
+            .await
+    }
+
+    /// Like [`refund_transaction_with_meta`](Self::refund_transaction_with_meta), but sends the
+    /// request with `options`, e.g. an idempotency key of the caller's choosing or a retry
+    /// policy.
+    ///
+    /// A key is generated if `options` has none.
+    pub async fn refund_transaction_with_options(
+        &self,
+        transaction_id: &str,
+        request: &RefundRequest,
+        options: &RequestOptions,
+    ) -> Result<Idempotent<Transaction>> {
+        let path = format!(
+            "accounts/{}/transactions/{}/refund",
+            self.account_id, transaction_id
+        );
+        let options = options.clone().ensure_idempotency_key();
+        self.client.post_json_idempotent(&path, request, &options).await
+    }
+
+    /// Like [`CheckoutOperations::void_transaction`], but also returns the idempotency key
+    /// the request was sent with and whether the API replayed an earlier result.
+    pub async fn void_transaction_with_meta(
+        &self,
+        transaction_id: &str,
+        request: VoidRequest,
+    ) -> Result<Idempotent<Transaction>> {
+        self.void_transaction_with_options(transaction_id, &request, &RequestOptions::default())
+            .await
+    }
+
+    /// Like [`void_transaction_with_meta`](Self::void_transaction_with_meta), but sends the
+    /// request with `options`, e.g. an idempotency key of the caller's choosing or a retry
+    /// policy.
+    ///
+    /// A key is generated if `options` has none.
+    pub async fn void_transaction_with_options(
+        &self,
+        transaction_id: &str,
+        request: &VoidRequest,
+        options: &RequestOptions,
+    ) -> Result<Idempotent<Transaction>> {
+        let path = format!(
+            "accounts/{}/transactions/{}/void",
+            self.account_id, transaction_id
+        );
+        let options = options.clone().ensure_idempotency_key();
+        self.client.post_json_idempotent(&path, request, &options).await
+    }
+}
+
 #[async_trait]
 pub trait HttpClient: Send + Sync {
     async fn post_json<B: serde::Serialize + Send + Sync, T: serde::de::DeserializeOwned>(
@@ -193,6 +360,80 @@ pub trait HttpClient: Send + Sync {
     ) -> Result<T>;
 
     async fn delete(&self, path: &str) -> Result<()>;
+
+    /// Sends a `PATCH` request. Adapters that predate PATCH support get a default that
+    /// returns [`DinteroError::Config`](dintero_core::DinteroError::Config).
+    async fn patch_json<B: serde::Serialize + Send + Sync, T: serde::de::DeserializeOwned>(
+        &self,
+        path: &str,
+        _body: &B,
+    ) -> Result<T> {
+        Err(dintero_core::DinteroError::Config(format!(
+            "PATCH {} is not supported by this adapter",
+            path
+        )))
+    }
+
+    /// Sends a `GET` request with query parameters serialized from `query`, e.g. a
+    /// struct or a slice of pairs.
+    async fn get_json_with_query<
+        T: serde::de::DeserializeOwned,
+        Q: serde::Serialize + Send + Sync + ?Sized,
+    >(
+        &self,
+        path: &str,
+        query: &Q,
+    ) -> Result<T> {
+        self.get_json(&dintero_core::path::with_query(path, query)?).await
+    }
+
+    /// Sends a `POST` request with per-request options such as an idempotency key.
+    ///
+    /// The default implementation cannot send headers, so it returns
+    /// [`DinteroError::Config`](dintero_core::DinteroError::Config) unless the options
+    /// are empty.
+    async fn post_json_with_options<
+        B: serde::Serialize + Send + Sync,
+        T: serde::de::DeserializeOwned,
+    >(
+        &self,
+        path: &str,
+        body: &B,
+        options: &RequestOptions,
+    ) -> Result<T> {
+        if options.idempotency_key.is_some() || options.retry_policy.is_some() {
+            return Err(dintero_core::DinteroError::Config(format!(
+                "POST {} with request options is not supported by this adapter",
+                path
+            )));
+        }
+        self.post_json(path, body).await
+    }
+
+    /// Sends a `POST` request with the idempotency key from `options` and reports
+    /// whether the API replayed an earlier result with the same key.
+    ///
+    /// The default implementation forwards to
+    /// [`post_json_with_options`](Self::post_json_with_options) and cannot see the
+    /// response headers, so it never reports a replay.
+    async fn post_json_idempotent<
+        B: serde::Serialize + Send + Sync,
+        T: serde::de::DeserializeOwned,
+    >(
+        &self,
+        path: &str,
+        body: &B,
+        options: &RequestOptions,
+    ) -> Result<Idempotent<T>> {
+        let Some(idempotency_key) = options.idempotency_key.clone() else {
+            return Err(dintero_core::DinteroError::Config(format!(
+                "POST {} requires an idempotency key",
+                path
+            )));
+        };
+        let value = self.post_json_with_options(path, body, options).await?;
+        Ok(Idempotent { value, idempotency_key, replayed: false })
+    }
 }
 
 #[async_trait]
@@ -219,22 +460,8 @@ impl<C: HttpClient> CheckoutOperations for CheckoutClient<C> {
     }
 
     async fn list_sessions(&self, params: ListSessionsParams) -> Result<SessionListResponse> {
-        let mut path = format!("accounts/{}/sessions", self.account_id);
-
-        let mut query_params = Vec::new();
-        if let Some(limit) = params.limit {
-            query_params.push(format!("limit={}", limit));
-        }
-        if let Some(token) = params.page_token {
-            query_params.push(format!("page_token={}", token));
-        }
-
-        if !query_params.is_empty() {
-            path.push('?');
-            path.push_str(&query_params.join("&"));
-        }
-
-        self.client.get_json(&path).await
+        let path = format!("accounts/{}/sessions", self.account_id);
+        self.client.get_json_with_query(&path, &params).await
     }
 
     async fn cancel_session(&self, session_id: &str) -> Result<CheckoutSession> {
@@ -295,25 +522,8 @@ impl<C: HttpClient> CheckoutOperations for CheckoutClient<C> {
         &self,
         params: ListTransactionsParams,
     ) -> Result<TransactionListResponse> {
-        let mut path = format!("accounts/{}/transactions", self.account_id);
-
-        let mut query_params = Vec::new();
-        if let Some(limit) = params.limit {
-            query_params.push(format!("limit={}", limit));
-        }
-        if let Some(token) = params.page_token {
-            query_params.push(format!("page_token={}", token));
-        }
-        if let Some(status) = params.status {
-            query_params.push(format!("status={:?}", status));
-        }
-
-        if !query_params.is_empty() {
-            path.push('?');
-            path.push_str(&query_params.join("&"));
-        }
-
-        self.client.get_json(&path).await
+        let path = format!("accounts/{}/transactions", self.account_id);
+        self.client.get_json_with_query(&path, &params).await
     }
 
     async fn update_transaction(
@@ -342,11 +552,9 @@ impl<C: HttpClient> CheckoutOperations for CheckoutClient<C> {
         transaction_id: &str,
         request: CaptureRequest,
     ) -> Result<Transaction> {
-        let path = format!(
-            "accounts/{}/transactions/{}/capture",
-            self.account_id, transaction_id
-        );
-        self.client.post_json(&path, &request).await
+        self.capture_transaction_with_meta(transaction_id, request)
+            .await
+            .map(Idempotent::into_inner)
     }
 
     async fn refund_transaction(
@@ -354,11 +562,7 @@ impl<C: HttpClient> CheckoutOperations for CheckoutClient<C> {
         transaction_id: &str,
         request: RefundRequest,
     ) -> Result<Transaction> {
-        let path = format!(
-            "accounts/{}/transactions/{}/refund",
-            self.account_id, transaction_id
-        );
-        self.client.post_json(&path, &request).await
+        self.refund_transaction_with_meta(transaction_id, request).await.map(Idempotent::into_inner)
     }
 
     async fn void_transaction(
@@ -366,11 +570,7 @@ impl<C: HttpClient> CheckoutOperations for CheckoutClient<C> {
         transaction_id: &str,
         request: VoidRequest,
     ) -> Result<Transaction> {
-        let path = format!(
-            "accounts/{}/transactions/{}/void",
-            self.account_id, transaction_id
-        );
-        self.client.post_json(&path, &request).await
+        self.void_transaction_with_meta(transaction_id, request).await.map(Idempotent::into_inner)
     }
 
     async fn get_card_token(&self, token_id: &str) -> Result<CardToken> {
@@ -382,25 +582,8 @@ impl<C: HttpClient> CheckoutOperations for CheckoutClient<C> {
         &self,
         params: ListCardTokensParams,
     ) -> Result<CardTokenListResponse> {
-        let mut path = format!("accounts/{}/card-tokens", self.account_id);
-
-        let mut query_params = Vec::new();
-        if let Some(limit) = params.limit {
-            query_params.push(format!("limit={}", limit));
-        }
-        if let Some(token) = params.page_token {
-            query_params.push(format!("page_token={}", token));
-        }
-        if let Some(status) = params.status {
-            query_params.push(format!("status={:?}", status));
-        }
-
-        if !query_params.is_empty() {
-            path.push('?');
-            path.push_str(&query_params.join("&"));
-        }
-
-        self.client.get_json(&path).await
+        let path = format!("accounts/{}/card-tokens", self.account_id);
+        self.client.get_json_with_query(&path, &params).await
     }
 
     async fn delete_card_token(&self, token_id: &str) -> Result<()> {
@@ -1,5 +1,6 @@
 //! Credit check operations.
 
+use dintero_core::{Currency, Money};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -47,12 +48,19 @@ pub struct CreditCheckAddress {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreditCheckOrder {
     pub amount: i64,
-    pub currency: String,
+    pub currency: Currency,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub items: Option<Vec<CreditCheckItem>>,
 }
 
+impl CreditCheckOrder {
+    /// Returns the amount together with its currency.
+    pub fn money(&self) -> Money {
+        Money::new(self.amount, self.currency)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreditCheckItem {
     pub id: String,
@@ -15,3 +15,8 @@ pub mod sessions;
 pub mod transactions;
 
 pub use client::*;
+
+pub use dintero_core::{
+    ApiError, Currency, DinteroError, ErrorCode, FieldError, Money, MoneyError, RequestOptions,
+    RoundingMode,
+};
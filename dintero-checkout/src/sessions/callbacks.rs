@@ -0,0 +1,281 @@
+//! Express Checkout callbacks: shipping options and discount codes.
+//!
+//! During Express Checkout, Dintero posts the session to the
+//! `shipping_address_callback_url` whenever the customer changes the shipping address,
+//! and to the `discount_codes_callback_url` whenever they enter discount codes. The
+//! merchant answers with the shipping options for the address, or with the order
+//! recalculated for the codes.
+//!
+//! Implement [`ExpressCallbackHandler`] and pass each callback body to
+//! [`handle_shipping_address_callback`] or [`handle_discount_codes_callback`]. They
+//! parse the session, call the handler and check that the response is consistent
+//! before it is sent back:
+//!
+//! ```
+//! use async_trait::async_trait;
+//! use dintero_checkout::sessions::{
+//!     handle_shipping_address_callback, ExpressCallbackHandler, ExpressCallbackSession,
+//!     ShippingOption,
+//! };
+//!
+//! struct Shipping;
+//!
+//! #[async_trait]
+//! impl ExpressCallbackHandler for Shipping {
+//!     async fn shipping_options(
+//!         &self,
+//!         session: &ExpressCallbackSession,
+//!     ) -> dintero_checkout::Result<Vec<ShippingOption>> {
+//!         let country = session.shipping_address().and_then(|a| a.country.as_deref());
+//!         let price = if country == Some("NO") { 4900 } else { 14900 };
+//!         Ok(vec![ShippingOption::new("standard", "shipping", "Posten", "Standard", price)])
+//!     }
+//! }
+//!
+//! # async fn example(body: &[u8]) -> dintero_checkout::Result<()> {
+//! let response = handle_shipping_address_callback(&Shipping, body).await?;
+//! let body = serde_json::to_vec(&response)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use super::express::{validate_shipping_options, ExpressCheckout, ShippingOption};
+use super::types::{Customer, Order, ShippingAddress};
+use crate::client::Result;
+use async_trait::async_trait;
+use dintero_core::totals::Totals;
+use dintero_core::DinteroError;
+use serde::{Deserialize, Serialize};
+
+/// The session Dintero sends with an Express Checkout callback.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExpressCallbackSession {
+    /// The session id.
+    #[serde(default)]
+    pub id: String,
+
+    pub order: Order,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customer: Option<Customer>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub express: Option<ExpressCheckout>,
+}
+
+impl ExpressCallbackSession {
+    /// Returns the shipping address the customer entered.
+    pub fn shipping_address(&self) -> Option<&ShippingAddress> {
+        self.order.shipping_address.as_ref()
+    }
+
+    /// Returns the discount codes the customer entered.
+    pub fn discount_codes(&self) -> &[String] {
+        self.order.discount_codes.as_deref().unwrap_or_default()
+    }
+}
+
+/// Response to a shipping address callback.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShippingOptionsResponse {
+    pub shipping_options: Vec<ShippingOption>,
+}
+
+impl ShippingOptionsResponse {
+    /// Checks the shipping options.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DinteroError::Validation`] if an option is invalid or two options
+    /// share an id.
+    pub fn validate(&self) -> Result<()> {
+        validate_shipping_options(&self.shipping_options).map_err(DinteroError::Validation)
+    }
+}
+
+/// Response to a discount codes callback.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscountCodesResponse {
+    /// The order with the discounts applied.
+    pub order: Order,
+
+    /// Shipping options, if the discounts change them.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shipping_options: Option<Vec<ShippingOption>>,
+}
+
+impl DiscountCodesResponse {
+    /// Checks that the recalculated order is consistent with itself and with the
+    /// order in `session`.
+    ///
+    /// The order must keep the session's currency, its amount must be the sum of the
+    /// item amounts and the shipping option amount, and its VAT amount, if set, the
+    /// sum of their VAT amounts.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DinteroError::Validation`] describing the first inconsistency.
+    pub fn validate(&self, session: &ExpressCallbackSession) -> Result<()> {
+        self.check(session).map_err(DinteroError::Validation)
+    }
+
+    fn check(&self, session: &ExpressCallbackSession) -> std::result::Result<(), String> {
+        let order = &self.order;
+        if order.currency != session.order.currency {
+            return Err(format!(
+                "order currency changed from {} to {}",
+                session.order.currency, order.currency
+            ));
+        }
+        if order.amount < 0 {
+            return Err("order amount is negative".to_string());
+        }
+        check_totals(order)?;
+        order.validate()?;
+
+        if let Some(options) = &self.shipping_options {
+            validate_shipping_options(options)?;
+        }
+        Ok(())
+    }
+}
+
+/// Answers the Express Checkout callbacks of a merchant.
+#[async_trait]
+pub trait ExpressCallbackHandler: Send + Sync {
+    /// Returns the shipping options for the session's shipping address.
+    async fn shipping_options(
+        &self,
+        session: &ExpressCallbackSession,
+    ) -> Result<Vec<ShippingOption>>;
+
+    /// Returns the order recalculated for the session's discount codes.
+    ///
+    /// The default implementation applies no discounts and returns the order as is.
+    async fn discount_codes(
+        &self,
+        session: &ExpressCallbackSession,
+    ) -> Result<DiscountCodesResponse> {
+        Ok(DiscountCodesResponse {
+            order: session.order.clone(),
+            shipping_options: None,
+        })
+    }
+}
+
+/// Handles a shipping address callback body with `handler`.
+///
+/// # Errors
+///
+/// Returns [`DinteroError::Serialization`] if the body is not a session, the
+/// handler's error, or [`DinteroError::Validation`] if the returned options are
+/// invalid.
+pub async fn handle_shipping_address_callback<H: ExpressCallbackHandler + ?Sized>(
+    handler: &H,
+    body: &[u8],
+) -> Result<ShippingOptionsResponse> {
+    let session: ExpressCallbackSession = serde_json::from_slice(body)?;
+    let response = ShippingOptionsResponse {
+        shipping_options: handler.shipping_options(&session).await?,
+    };
+    response.validate()?;
+    Ok(response)
+}
+
+/// Handles a discount codes callback body with `handler`.
+///
+/// # Errors
+///
+/// Returns [`DinteroError::Serialization`] if the body is not a session, the
+/// handler's error, or [`DinteroError::Validation`] if the returned order is
+/// inconsistent, see [`DiscountCodesResponse::validate`].
+pub async fn handle_discount_codes_callback<H: ExpressCallbackHandler + ?Sized>(
+    handler: &H,
+    body: &[u8],
+) -> Result<DiscountCodesResponse> {
+    let session: ExpressCallbackSession = serde_json::from_slice(body)?;
+    let response = handler.discount_codes(&session).await?;
+    response.validate(&session)?;
+    Ok(response)
+}
+
+/// Checks the order amount and VAT amount against the items and the shipping option.
+fn check_totals(order: &Order) -> std::result::Result<(), String> {
+    if order.items.as_ref().is_none_or(Vec::is_empty) {
+        return Ok(());
+    }
+
+    let totals = Totals::of(order.lines());
+    if totals.amount != order.amount {
+        return Err(format!(
+            "order amount {} does not match the item and shipping total {}",
+            order.amount, totals.amount
+        ));
+    }
+    match order.vat_amount {
+        Some(vat_amount) if vat_amount != totals.vat_amount => Err(format!(
+            "order VAT amount {} does not match the item and shipping total {}",
+            vat_amount, totals.vat_amount
+        )),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sessions::OrderItem;
+    use serde_json::json;
+
+    struct TenPercentOff;
+
+    #[async_trait]
+    impl ExpressCallbackHandler for TenPercentOff {
+        async fn shipping_options(
+            &self,
+            _session: &ExpressCallbackSession,
+        ) -> Result<Vec<ShippingOption>> {
+            Ok(vec![ShippingOption::new(
+                "home", "shipping", "Bring", "Home", 9900,
+            )])
+        }
+
+        async fn discount_codes(
+            &self,
+            session: &ExpressCallbackSession,
+        ) -> Result<DiscountCodesResponse> {
+            let mut order = session.order.clone();
+            if session.discount_codes().iter().any(|code| code == "TENOFF") {
+                for item in order.items.iter_mut().flatten() {
+                    item.amount -= item.amount / 10;
+                }
+            }
+            Ok(DiscountCodesResponse { order, shipping_options: None })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_discount_response_must_keep_totals_consistent() {
+        let order = Order::builder()
+            .amount(10000)
+            .currency(crate::Currency::Nok)
+            .add_item(OrderItem::new("1", "1", "Shirt", 1, 10000, 2000, 25))
+            .add_discount_code("TENOFF")
+            .build();
+        let body = serde_json::to_vec(&json!({ "id": "T1.session", "order": order })).unwrap();
+
+        let options = handle_shipping_address_callback(&TenPercentOff, &body).await.unwrap();
+        assert_eq!(options.shipping_options.len(), 1);
+
+        // The handler discounts the item but forgets to update the order amount.
+        let result = handle_discount_codes_callback(&TenPercentOff, &body).await;
+        assert!(matches!(result, Err(DinteroError::Validation(_))));
+
+        let session: ExpressCallbackSession = serde_json::from_slice(&body).unwrap();
+        let mut response = TenPercentOff.discount_codes(&session).await.unwrap();
+        response.order.amount = 9000;
+        assert!(response.validate(&session).is_ok());
+        response.order.currency = crate::Currency::Sek;
+        assert!(response.validate(&session).is_err());
+    }
+}
@@ -0,0 +1,313 @@
+//! Payment configuration of a session.
+
+use serde::de::Error as _;
+use serde::ser::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Sales channel of a session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Channel {
+    Ecommerce,
+    InStore,
+}
+
+/// A payment product, named `{provider}.{product}` or just `{provider}` by the API.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PaymentProductType {
+    PayexCreditcard,
+    PayexMobilepay,
+    Vipps,
+    Swish,
+    CollectorInvoice,
+    CollectorInvoiceB2b,
+    CollectorInstallmentB2cPreapproved,
+    BamboraCreditcard,
+    BamboraMobilepay,
+    BamboraVipps,
+    DinteroPspCreditcard,
+    NetaxeptCreditcard,
+    KlarnaKlarna,
+    KlarnaBillie,
+    InstabankFinance,
+    InstabankInvoice,
+    /// Any other payment product, as named by the API.
+    Other(String),
+}
+
+impl PaymentProductType {
+    /// Parses a payment product name sent by the API.
+    pub fn parse(name: &str) -> Self {
+        match name {
+            "payex.creditcard" => Self::PayexCreditcard,
+            "payex.mobilepay" => Self::PayexMobilepay,
+            "vipps" => Self::Vipps,
+            "swish.swish" => Self::Swish,
+            "collector.invoice" => Self::CollectorInvoice,
+            "collector.invoice_b2b" => Self::CollectorInvoiceB2b,
+            "collector.installment_b2c_preapproved" => Self::CollectorInstallmentB2cPreapproved,
+            "bambora.creditcard" => Self::BamboraCreditcard,
+            "bambora.mobilepay" => Self::BamboraMobilepay,
+            "bambora.vipps" => Self::BamboraVipps,
+            "dintero_psp.creditcard" => Self::DinteroPspCreditcard,
+            "netaxept.creditcard" => Self::NetaxeptCreditcard,
+            "klarna.klarna" => Self::KlarnaKlarna,
+            "klarna.billie" => Self::KlarnaBillie,
+            "instabank.finance" => Self::InstabankFinance,
+            "instabank.invoice" => Self::InstabankInvoice,
+            other => Self::Other(other.to_string()),
+        }
+    }
+
+    /// Returns the name used by the API, e.g. `payex.creditcard`.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::PayexCreditcard => "payex.creditcard",
+            Self::PayexMobilepay => "payex.mobilepay",
+            Self::Vipps => "vipps",
+            Self::Swish => "swish.swish",
+            Self::CollectorInvoice => "collector.invoice",
+            Self::CollectorInvoiceB2b => "collector.invoice_b2b",
+            Self::CollectorInstallmentB2cPreapproved => "collector.installment_b2c_preapproved",
+            Self::BamboraCreditcard => "bambora.creditcard",
+            Self::BamboraMobilepay => "bambora.mobilepay",
+            Self::BamboraVipps => "bambora.vipps",
+            Self::DinteroPspCreditcard => "dintero_psp.creditcard",
+            Self::NetaxeptCreditcard => "netaxept.creditcard",
+            Self::KlarnaKlarna => "klarna.klarna",
+            Self::KlarnaBillie => "klarna.billie",
+            Self::InstabankFinance => "instabank.finance",
+            Self::InstabankInvoice => "instabank.invoice",
+            Self::Other(name) => name,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        let mut parts = self.as_str().split('.');
+        let valid = parts.by_ref().take(2).all(|part| !part.is_empty());
+        valid && parts.next().is_none()
+    }
+}
+
+impl fmt::Display for PaymentProductType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for PaymentProductType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for PaymentProductType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Self::parse(&String::deserialize(deserializer)?))
+    }
+}
+
+/// Payment configuration of a session: capture, channel and payment products.
+///
+/// The API nests product toggles by provider, e.g.
+/// `{"payex": {"creditcard": {"enabled": true}}, "vipps": {"enabled": true}}`;
+/// here they are kept in [`payment_products`](Self::payment_products).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionConfiguration {
+    /// Captures the payment as soon as it is authorized.
+    pub auto_capture: Option<bool>,
+    pub channel: Option<Channel>,
+    /// Payment product selected when the checkout opens.
+    pub default_payment_type: Option<PaymentProductType>,
+    /// Payment products enabled (`true`) or disabled (`false`) for the session.
+    pub payment_products: BTreeMap<PaymentProductType, bool>,
+}
+
+impl SessionConfiguration {
+    pub fn builder() -> SessionConfigurationBuilder {
+        SessionConfigurationBuilder::default()
+    }
+
+    /// Returns `true` if the product is enabled for the session.
+    pub fn is_enabled(&self, product: &PaymentProductType) -> bool {
+        self.payment_products.get(product).copied().unwrap_or(false)
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if let Some(product) = self.payment_products.keys().find(|product| !product.is_valid()) {
+            return Err(format!("invalid payment product {:?}", product.as_str()));
+        }
+        if let Some(default) = &self.default_payment_type {
+            let any_enabled = self.payment_products.values().any(|enabled| *enabled);
+            if self.payment_products.get(default) == Some(&false)
+                || (any_enabled && !self.is_enabled(default))
+            {
+                return Err(format!("default payment type {} is not enabled", default));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for SessionConfiguration {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = Map::new();
+        if let Some(auto_capture) = self.auto_capture {
+            map.insert("auto_capture".into(), auto_capture.into());
+        }
+        if let Some(channel) = self.channel {
+            map.insert(
+                "channel".into(),
+                serde_json::to_value(channel).map_err(S::Error::custom)?,
+            );
+        }
+        if let Some(default) = &self.default_payment_type {
+            map.insert("default_payment_type".into(), default.as_str().into());
+        }
+
+        for (product, enabled) in &self.payment_products {
+            let toggle = Value::from(Map::from_iter([("enabled".to_string(), (*enabled).into())]));
+            let (provider, name) = match product.as_str().split_once('.') {
+                Some((provider, name)) => (provider, Some(name)),
+                None => (product.as_str(), None),
+            };
+            let entry = map.entry(provider).or_insert_with(|| Value::Object(Map::new()));
+            match (entry.as_object_mut(), name) {
+                (Some(products), Some(name)) => {
+                    products.insert(name.to_string(), toggle);
+                }
+                (Some(products), None) => {
+                    products.insert("enabled".to_string(), (*enabled).into());
+                }
+                (None, _) => {
+                    return Err(S::Error::custom(format!(
+                        "payment product {} clashes with a configuration field",
+                        product
+                    )));
+                }
+            }
+        }
+
+        map.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SessionConfiguration {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let map = Map::deserialize(deserializer)?;
+        let mut configuration = SessionConfiguration::default();
+
+        for (key, value) in map {
+            match key.as_str() {
+                "auto_capture" => configuration.auto_capture = value.as_bool(),
+                "channel" => {
+                    configuration.channel =
+                        serde_json::from_value(value).map_err(D::Error::custom)?
+                }
+                "default_payment_type" => {
+                    configuration.default_payment_type =
+                        value.as_str().map(PaymentProductType::parse)
+                }
+                _ => {
+                    let Value::Object(provider) = value else { continue };
+                    for (name, setting) in provider {
+                        if name == "enabled" {
+                            if let Some(enabled) = setting.as_bool() {
+                                let product = PaymentProductType::parse(&key);
+                                configuration.payment_products.insert(product, enabled);
+                            }
+                        } else if let Some(enabled) =
+                            setting.get("enabled").and_then(Value::as_bool)
+                        {
+                            let product = PaymentProductType::parse(&format!("{}.{}", key, name));
+                            configuration.payment_products.insert(product, enabled);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(configuration)
+    }
+}
+
+#[derive(Default)]
+pub struct SessionConfigurationBuilder {
+    configuration: SessionConfiguration,
+}
+
+impl SessionConfigurationBuilder {
+    pub fn auto_capture(mut self, auto_capture: bool) -> Self {
+        self.configuration.auto_capture = Some(auto_capture);
+        self
+    }
+
+    pub fn channel(mut self, channel: Channel) -> Self {
+        self.configuration.channel = Some(channel);
+        self
+    }
+
+    pub fn default_payment_type(mut self, product: PaymentProductType) -> Self {
+        self.configuration.default_payment_type = Some(product);
+        self
+    }
+
+    pub fn enable(mut self, product: PaymentProductType) -> Self {
+        self.configuration.payment_products.insert(product, true);
+        self
+    }
+
+    pub fn disable(mut self, product: PaymentProductType) -> Self {
+        self.configuration.payment_products.insert(product, false);
+        self
+    }
+
+    pub fn build(self) -> SessionConfiguration {
+        self.configuration
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_payment_products_nest_by_provider() {
+        let configuration = SessionConfiguration::builder()
+            .auto_capture(true)
+            .channel(Channel::Ecommerce)
+            .enable(PaymentProductType::PayexCreditcard)
+            .enable(PaymentProductType::Vipps)
+            .disable(PaymentProductType::CollectorInvoice)
+            .default_payment_type(PaymentProductType::Vipps)
+            .build();
+        assert!(configuration.validate().is_ok());
+
+        let value = serde_json::to_value(&configuration).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "auto_capture": true,
+                "channel": "ecommerce",
+                "default_payment_type": "vipps",
+                "payex": { "creditcard": { "enabled": true } },
+                "vipps": { "enabled": true },
+                "collector": { "invoice": { "enabled": false } },
+            })
+        );
+        assert_eq!(
+            serde_json::from_value::<SessionConfiguration>(value).unwrap(),
+            configuration
+        );
+
+        let configuration = SessionConfiguration::builder()
+            .enable(PaymentProductType::Swish)
+            .default_payment_type(PaymentProductType::KlarnaKlarna)
+            .build();
+        assert!(configuration.validate().is_err());
+    }
+}
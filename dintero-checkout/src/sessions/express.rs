@@ -0,0 +1,424 @@
+//! Express Checkout settings: shipping options and callback URLs.
+
+use chrono::{DateTime, Utc};
+use dintero_core::totals::{LineItem, VatRate};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// How the order reaches the customer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryMethod {
+    Delivery,
+    PickUp,
+    Unspecified,
+    None,
+}
+
+/// Customer types offered in Express Checkout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CustomerType {
+    B2c,
+    B2b,
+}
+
+/// Whether the customer must give a shipping address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShippingMode {
+    ShippingRequired,
+    ShippingNotRequired,
+}
+
+/// Estimated time of arrival.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Eta {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub starts_at: Option<DateTime<Utc>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ends_at: Option<DateTime<Utc>>,
+}
+
+/// Address of a pickup point.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PickUpAddress {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub business_name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address_line: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address_line_2: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub postal_code: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub postal_place: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latitude: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub longitude: Option<f64>,
+
+    /// Distance from the shipping address, in kilometers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distance: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+}
+
+/// A shipping option the customer can choose.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShippingOption {
+    pub id: String,
+    pub line_id: String,
+    pub amount: i64,
+    pub operator: String,
+    pub title: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vat_amount: Option<i64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vat: Option<i64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operator_product_id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delivery_method: Option<DeliveryMethod>,
+
+    /// Countries the option ships to, as ISO 3166-1 alpha-2 codes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub countries: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub eta: Option<Eta>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pick_up_address: Option<PickUpAddress>,
+}
+
+impl ShippingOption {
+    pub fn new(
+        id: impl Into<String>,
+        line_id: impl Into<String>,
+        operator: impl Into<String>,
+        title: impl Into<String>,
+        amount: i64,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            line_id: line_id.into(),
+            amount,
+            operator: operator.into(),
+            title: title.into(),
+            vat_amount: None,
+            vat: None,
+            description: None,
+            operator_product_id: None,
+            delivery_method: None,
+            countries: None,
+            eta: None,
+            pick_up_address: None,
+        }
+    }
+
+    /// Sets the VAT included in the amount and the VAT rate in percent.
+    pub fn with_vat(mut self, vat_amount: i64, vat: i64) -> Self {
+        self.vat_amount = Some(vat_amount);
+        self.vat = Some(vat);
+        self
+    }
+
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn with_operator_product_id(mut self, id: impl Into<String>) -> Self {
+        self.operator_product_id = Some(id.into());
+        self
+    }
+
+    pub fn with_delivery_method(mut self, method: DeliveryMethod) -> Self {
+        self.delivery_method = Some(method);
+        self
+    }
+
+    pub fn with_countries(mut self, countries: Vec<String>) -> Self {
+        self.countries = Some(countries);
+        self
+    }
+
+    pub fn with_eta(mut self, starts_at: DateTime<Utc>, ends_at: DateTime<Utc>) -> Self {
+        self.eta = Some(Eta {
+            starts_at: Some(starts_at),
+            ends_at: Some(ends_at),
+        });
+        self
+    }
+
+    /// Makes this a pickup point option.
+    pub fn with_pick_up_address(mut self, address: PickUpAddress) -> Self {
+        self.delivery_method = Some(DeliveryMethod::PickUp);
+        self.pick_up_address = Some(address);
+        self
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        let name = format!("shipping option {:?}", self.id);
+        if self.id.is_empty() || self.line_id.is_empty() {
+            return Err("shipping option id and line_id are required".to_string());
+        }
+        if self.amount < 0 {
+            return Err(format!("{} has a negative amount", name));
+        }
+        if let Some(vat_amount) = self.vat_amount {
+            if !(0..=self.amount).contains(&vat_amount) {
+                return Err(format!(
+                    "{} has a VAT amount outside 0..={}",
+                    name, self.amount
+                ));
+            }
+        }
+        if let Some(eta) = &self.eta {
+            if let (Some(starts_at), Some(ends_at)) = (eta.starts_at, eta.ends_at) {
+                if ends_at < starts_at {
+                    return Err(format!("{} has an ETA that ends before it starts", name));
+                }
+            }
+        }
+        if self.delivery_method == Some(DeliveryMethod::PickUp) && self.pick_up_address.is_none() {
+            return Err(format!(
+                "{} is a pickup option without a pick_up_address",
+                name
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// A chosen shipping option is a line of the order, of quantity one.
+impl LineItem for ShippingOption {
+    fn line_id(&self) -> &str {
+        &self.line_id
+    }
+
+    fn quantity(&self) -> i64 {
+        1
+    }
+
+    fn amount(&self) -> i64 {
+        self.amount
+    }
+
+    fn vat_amount(&self) -> i64 {
+        self.vat_amount.unwrap_or(0)
+    }
+
+    fn vat_rate(&self) -> VatRate {
+        VatRate::from_percent(self.vat.unwrap_or(0))
+    }
+}
+
+/// Express Checkout settings of a session.
+///
+/// The customer picks a shipping option in the checkout. Dintero calls
+/// `shipping_address_callback_url` when the shipping address changes, to get the
+/// options for the new address, and `discount_codes_callback_url` when discount
+/// codes are entered.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExpressCheckout {
+    #[serde(default)]
+    pub shipping_options: Vec<ShippingOption>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shipping_address_callback_url: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub discount_codes_callback_url: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customer_types: Option<Vec<CustomerType>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shipping_mode: Option<ShippingMode>,
+}
+
+impl ExpressCheckout {
+    pub fn builder() -> ExpressCheckoutBuilder {
+        ExpressCheckoutBuilder::default()
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        validate_shipping_options(&self.shipping_options)?;
+
+        if let Some(url) = &self.shipping_address_callback_url {
+            validate_url("shipping_address_callback_url", url)?;
+        }
+        if let Some(url) = &self.discount_codes_callback_url {
+            validate_url("discount_codes_callback_url", url)?;
+        }
+
+        if self.shipping_mode != Some(ShippingMode::ShippingNotRequired)
+            && self.shipping_options.is_empty()
+            && self.shipping_address_callback_url.is_none()
+        {
+            return Err(
+                "express checkout needs shipping options or a shipping_address_callback_url"
+                    .to_string(),
+            );
+        }
+        if self.customer_types.as_ref().is_some_and(Vec::is_empty) {
+            return Err("express customer_types must not be empty".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct ExpressCheckoutBuilder {
+    express: ExpressCheckout,
+}
+
+impl ExpressCheckoutBuilder {
+    pub fn shipping_option(mut self, option: ShippingOption) -> Self {
+        self.express.shipping_options.push(option);
+        self
+    }
+
+    pub fn shipping_address_callback_url(mut self, url: impl Into<String>) -> Self {
+        self.express.shipping_address_callback_url = Some(url.into());
+        self
+    }
+
+    pub fn discount_codes_callback_url(mut self, url: impl Into<String>) -> Self {
+        self.express.discount_codes_callback_url = Some(url.into());
+        self
+    }
+
+    pub fn customer_types(mut self, types: Vec<CustomerType>) -> Self {
+        self.express.customer_types = Some(types);
+        self
+    }
+
+    pub fn shipping_mode(mut self, mode: ShippingMode) -> Self {
+        self.express.shipping_mode = Some(mode);
+        self
+    }
+
+    pub fn build(self) -> ExpressCheckout {
+        self.express
+    }
+}
+
+/// Checks each shipping option and that their ids are unique.
+pub(crate) fn validate_shipping_options(options: &[ShippingOption]) -> Result<(), String> {
+    let mut ids = HashSet::new();
+    for option in options {
+        option.validate()?;
+        if !ids.insert(option.id.as_str()) {
+            return Err(format!("duplicate shipping option id {:?}", option.id));
+        }
+    }
+    Ok(())
+}
+
+/// Checks that `value` is an absolute `http` or `https` URL.
+pub(crate) fn validate_url(field: &str, value: &str) -> Result<(), String> {
+    match url::Url::parse(value) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") => Ok(()),
+        _ => Err(format!(
+            "{} must be an absolute http(s) URL, got {:?}",
+            field, value
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn option(id: &str) -> ShippingOption {
+        ShippingOption::new(id, format!("line-{}", id), "Posten", "Home delivery", 9900)
+    }
+
+    #[test]
+    fn test_shipping_option_validate() {
+        assert!(option("home").validate().is_ok());
+        assert!(option("home").with_vat(1980, 25).validate().is_ok());
+
+        assert!(option("").validate().is_err());
+        assert!(ShippingOption::new("home", "", "Posten", "Home", 9900).validate().is_err());
+        assert!(ShippingOption::new("home", "1", "Posten", "Home", -1).validate().is_err());
+        assert!(option("home").with_vat(9901, 25).validate().is_err());
+        assert!(option("home").with_vat(-1, 25).validate().is_err());
+
+        let now = Utc::now();
+        assert!(option("home").with_eta(now, now + Duration::days(2)).validate().is_ok());
+        let error = option("home").with_eta(now, now - Duration::days(2)).validate();
+        assert!(error.unwrap_err().contains("ends before it starts"));
+
+        let pickup = option("pickup").with_delivery_method(DeliveryMethod::PickUp);
+        assert!(pickup.validate().unwrap_err().contains("without a pick_up_address"));
+        let pickup = option("pickup").with_pick_up_address(PickUpAddress::default());
+        assert!(pickup.validate().is_ok());
+
+        assert!(validate_shipping_options(&[option("home"), option("pickup")]).is_ok());
+        let error = validate_shipping_options(&[option("home"), option("home")]);
+        assert_eq!(error.unwrap_err(), r#"duplicate shipping option id "home""#);
+    }
+
+    #[test]
+    fn test_express_checkout_validate() {
+        let express = ExpressCheckout::builder().shipping_option(option("home")).build();
+        assert!(express.validate().is_ok());
+
+        let callback = ExpressCheckout::builder()
+            .shipping_address_callback_url("https://example.com/shipping")
+            .discount_codes_callback_url("https://example.com/discounts")
+            .build();
+        assert!(callback.validate().is_ok());
+
+        let error = ExpressCheckout::builder().build().validate().unwrap_err();
+        assert!(error.contains("needs shipping options"));
+        let no_shipping =
+            ExpressCheckout::builder().shipping_mode(ShippingMode::ShippingNotRequired).build();
+        assert!(no_shipping.validate().is_ok());
+
+        let relative =
+            ExpressCheckout::builder().shipping_address_callback_url("/shipping").build();
+        assert!(relative.validate().unwrap_err().contains("shipping_address_callback_url"));
+        let insecure = ExpressCheckout::builder()
+            .shipping_option(option("home"))
+            .discount_codes_callback_url("javascript:alert(1)")
+            .build();
+        assert!(insecure.validate().unwrap_err().contains("discount_codes_callback_url"));
+
+        let no_types = ExpressCheckout::builder()
+            .shipping_option(option("home"))
+            .customer_types(Vec::new())
+            .build();
+        assert!(no_types.validate().is_err());
+
+        let duplicates = ExpressCheckout::builder()
+            .shipping_option(option("home"))
+            .shipping_option(option("home"))
+            .build();
+        assert!(duplicates.validate().is_err());
+    }
+}
@@ -1,9 +1,17 @@
 //! Module exports and organization.
 
+pub mod callbacks;
+pub mod configuration;
+pub mod express;
 pub mod operations;
 pub mod profiles;
 pub mod types;
+pub mod watcher;
 
+pub use callbacks::*;
+pub use configuration::*;
+pub use express::*;
 pub use operations::*;
 pub use profiles::*;
 pub use types::*;
+pub use watcher::*;
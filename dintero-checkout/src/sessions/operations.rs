@@ -1,6 +1,9 @@
 //! Module implementation.
 
+use super::configuration::SessionConfiguration;
+use super::express::ExpressCheckout;
 use super::types::*;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize)]
@@ -16,6 +19,18 @@ pub struct CreateSessionRequestPayload {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub merchant_terms_url: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customer: Option<Customer>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub express: Option<ExpressCheckout>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub configuration: Option<SessionConfiguration>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
 }
 
 impl From<CreateSessionRequest> for CreateSessionRequestPayload {
@@ -26,6 +41,10 @@ impl From<CreateSessionRequest> for CreateSessionRequestPayload {
             profile_id: req.profile_id,
             return_url: req.return_url,
             merchant_terms_url: req.merchant_terms_url,
+            customer: req.customer,
+            express: req.express,
+            configuration: req.configuration,
+            expires_at: req.expires_at,
         }
     }
 }
@@ -38,7 +57,7 @@ pub struct SessionListResponse {
     pub next_page_token: Option<String>,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct ListSessionsParams {
     pub limit: Option<u32>,
     pub page_token: Option<String>,
@@ -1,6 +1,10 @@
 //! Type definitions and data structures.
 
+use super::configuration::SessionConfiguration;
+use super::express::{validate_url, ExpressCheckout, ShippingOption};
 use chrono::{DateTime, Utc};
+use dintero_core::totals::{LineItem, Totals, TotalsErrors, TotalsMode, TotalsValidator, VatRate};
+use dintero_core::{Currency, Money, RoundingMode};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -36,12 +40,21 @@ pub struct CheckoutSession {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub transaction_id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customer: Option<Customer>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub express: Option<ExpressCheckout>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub configuration: Option<SessionConfiguration>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Order {
     pub amount: i64,
-    pub currency: String,
+    pub currency: Currency,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub merchant_reference: Option<String>,
@@ -57,6 +70,16 @@ pub struct Order {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub billing_address: Option<BillingAddress>,
+
+    /// The shipping option chosen for the order.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shipping_option: Option<ShippingOption>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gift_cards: Option<Vec<GiftCard>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub discount_codes: Option<Vec<String>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -102,6 +125,53 @@ pub struct ShippingAddress {
 
 pub type BillingAddress = ShippingAddress;
 
+/// Customer details used to prefill the checkout.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Customer {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customer_id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phone_number: Option<String>,
+}
+
+/// A gift card paying part of the order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GiftCard {
+    pub card_id: String,
+    pub amount: i64,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub masked_card_number: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line_id: Option<String>,
+}
+
+impl GiftCard {
+    pub fn new(card_id: impl Into<String>, amount: i64) -> Self {
+        Self {
+            card_id: card_id.into(),
+            amount,
+            masked_card_number: None,
+            line_id: None,
+        }
+    }
+
+    pub fn with_masked_card_number(mut self, number: impl Into<String>) -> Self {
+        self.masked_card_number = Some(number.into());
+        self
+    }
+
+    pub fn with_line_id(mut self, line_id: impl Into<String>) -> Self {
+        self.line_id = Some(line_id.into());
+        self
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct CreateSessionRequest {
     pub url: SessionUrl,
@@ -109,6 +179,10 @@ pub struct CreateSessionRequest {
     pub profile_id: Option<String>,
     pub return_url: Option<String>,
     pub merchant_terms_url: Option<String>,
+    pub customer: Option<Customer>,
+    pub express: Option<ExpressCheckout>,
+    pub configuration: Option<SessionConfiguration>,
+    pub expires_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -130,6 +204,12 @@ pub struct CreateSessionRequestBuilder {
     profile_id: Option<String>,
     return_url: Option<String>,
     merchant_terms_url: Option<String>,
+    customer: Option<Customer>,
+    express: Option<ExpressCheckout>,
+    configuration: Option<SessionConfiguration>,
+    expires_at: Option<DateTime<Utc>>,
+    totals: TotalsMode,
+    validator: TotalsValidator,
 }
 
 impl CreateSessionRequestBuilder {
@@ -158,8 +238,70 @@ impl CreateSessionRequestBuilder {
         self
     }
 
+    /// Prefills the customer's details.
+    pub fn customer(mut self, customer: Customer) -> Self {
+        self.customer = Some(customer);
+        self
+    }
+
+    /// Enables Express Checkout.
+    pub fn express(mut self, express: ExpressCheckout) -> Self {
+        self.express = Some(express);
+        self
+    }
+
+    pub fn configuration(mut self, configuration: SessionConfiguration) -> Self {
+        self.configuration = Some(configuration);
+        self
+    }
+
+    /// Sets when the session expires.
+    pub fn expires_at(mut self, expires_at: DateTime<Utc>) -> Self {
+        self.expires_at = Some(expires_at);
+        self
+    }
+
+    /// Sets how the order amounts are checked, see [`OrderBuilder::totals`].
+    pub fn totals(mut self, mode: TotalsMode) -> Self {
+        self.totals = mode;
+        self
+    }
+
+    /// Sets the rounding and VAT tolerance the order lines are checked with.
+    pub fn totals_validator(mut self, validator: TotalsValidator) -> Self {
+        self.validator = validator;
+        self
+    }
+
     pub fn build(self) -> Result<CreateSessionRequest, String> {
-        let order = self.order.ok_or("order is required")?;
+        let mut order = self.order.ok_or("order is required")?;
+        order.apply_totals(self.totals, &self.validator).map_err(|errors| errors.to_string())?;
+        order.validate()?;
+
+        let urls = [
+            ("return_url", &self.url.return_url),
+            ("callback_url", &self.url.callback_url),
+            ("merchant_terms_url", &self.merchant_terms_url),
+        ];
+        for (field, url) in urls {
+            if let Some(url) = url {
+                validate_url(field, url)?;
+            }
+        }
+        if let Some(email) = self.customer.as_ref().and_then(|c| c.email.as_deref()) {
+            if !is_valid_email(email) {
+                return Err(format!("invalid customer email {:?}", email));
+            }
+        }
+        if let Some(express) = &self.express {
+            express.validate()?;
+        }
+        if let Some(configuration) = &self.configuration {
+            configuration.validate()?;
+        }
+        if self.expires_at.is_some_and(|expires_at| expires_at <= Utc::now()) {
+            return Err("expires_at must be in the future".to_string());
+        }
 
         Ok(CreateSessionRequest {
             url: self.url,
@@ -167,25 +309,126 @@ impl CreateSessionRequestBuilder {
             profile_id: self.profile_id,
             return_url: self.return_url,
             merchant_terms_url: self.merchant_terms_url,
+            customer: self.customer,
+            express: self.express,
+            configuration: self.configuration,
+            expires_at: self.expires_at,
         })
     }
 }
 
+/// Checks the shape of an email address: a local part and a domain of at least two
+/// dot-separated labels, joined by a single `@`, without whitespace.
+fn is_valid_email(email: &str) -> bool {
+    let Some((local, domain)) = email.split_once('@') else {
+        return false;
+    };
+    let valid_local = !local.is_empty()
+        && local.len() <= 64
+        && !local.starts_with('.')
+        && !local.ends_with('.')
+        && !local.contains("..")
+        && !local.chars().any(|c| c.is_whitespace() || c.is_control() || c == '@');
+    let valid_label = |label: &str| {
+        (1..=63).contains(&label.len())
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label.chars().all(|c| c.is_alphanumeric() || c == '-')
+    };
+
+    email.len() <= 254
+        && valid_local
+        && domain.split('.').count() >= 2
+        && domain.split('.').all(valid_label)
+}
+
 impl Order {
     pub fn builder() -> OrderBuilder {
         OrderBuilder::default()
     }
+
+    /// Returns the amount together with its currency.
+    pub fn money(&self) -> Money {
+        Money::new(self.amount, self.currency)
+    }
+
+    /// Returns the items and the shipping option, the lines the order amount is made of.
+    pub fn lines(&self) -> impl Iterator<Item = &dyn LineItem> {
+        let items = self.items.iter().flatten().map(|item| item as &dyn LineItem);
+        items.chain(self.shipping_option.iter().map(|option| option as &dyn LineItem))
+    }
+
+    /// Checks each line, and that the amount and VAT amount are their sums.
+    ///
+    /// An order without items is not checked, as its amount need not be itemized.
+    pub fn validate_totals(&self, validator: &TotalsValidator) -> Result<Totals, TotalsErrors> {
+        if !self.has_items() {
+            return Ok(Totals {
+                amount: self.amount,
+                vat_amount: self.vat_amount.unwrap_or(0),
+            });
+        }
+        validator.validate(self.lines(), self.amount, self.vat_amount)
+    }
+
+    fn has_items(&self) -> bool {
+        self.items.as_ref().is_some_and(|items| !items.is_empty())
+    }
+
+    /// Calculates or checks the amounts. An order without items keeps its amounts, as
+    /// in [`validate_totals`](Self::validate_totals).
+    fn apply_totals(
+        &mut self,
+        mode: TotalsMode,
+        validator: &TotalsValidator,
+    ) -> Result<(), TotalsErrors> {
+        if !self.has_items() {
+            return Ok(());
+        }
+        match mode {
+            TotalsMode::AsGiven => {}
+            TotalsMode::Calculate => {
+                let totals = validator.check_lines(self.lines())?;
+                self.amount = totals.amount;
+                self.vat_amount = Some(totals.vat_amount);
+            }
+            TotalsMode::Strict => {
+                self.validate_totals(validator)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if let Some(option) = &self.shipping_option {
+            option.validate()?;
+        }
+        if let Some(gift_cards) = &self.gift_cards {
+            if gift_cards.iter().any(|card| card.amount <= 0) {
+                return Err("gift card amounts must be positive".to_string());
+            }
+            if gift_cards.iter().map(|card| card.amount).sum::<i64>() > self.amount {
+                return Err("gift cards exceed the order amount".to_string());
+            }
+        }
+        Ok(())
+    }
 }
 
 #[derive(Default)]
 pub struct OrderBuilder {
     amount: i64,
-    currency: String,
+    currency: Currency,
     merchant_reference: Option<String>,
     items: Vec<OrderItem>,
     vat_amount: Option<i64>,
     shipping_address: Option<ShippingAddress>,
     billing_address: Option<BillingAddress>,
+    shipping_option: Option<ShippingOption>,
+    gift_cards: Vec<GiftCard>,
+    discount_codes: Vec<String>,
+    totals: TotalsMode,
+    validator: TotalsValidator,
 }
 
 impl OrderBuilder {
@@ -194,8 +437,16 @@ impl OrderBuilder {
         self
     }
 
-    pub fn currency(mut self, currency: impl Into<String>) -> Self {
-        self.currency = currency.into();
+    /// Sets the currency, e.g. `Currency::Nok`. Parse a code with `Currency::try_from`.
+    pub fn currency(mut self, currency: Currency) -> Self {
+        self.currency = currency;
+        self
+    }
+
+    /// Sets the amount and the currency.
+    pub fn money(mut self, money: Money) -> Self {
+        self.amount = money.amount();
+        self.currency = money.currency();
         self
     }
 
@@ -224,7 +475,80 @@ impl OrderBuilder {
         self
     }
 
+    pub fn shipping_option(mut self, option: ShippingOption) -> Self {
+        self.shipping_option = Some(option);
+        self
+    }
+
+    pub fn add_gift_card(mut self, gift_card: GiftCard) -> Self {
+        self.gift_cards.push(gift_card);
+        self
+    }
+
+    pub fn add_discount_code(mut self, code: impl Into<String>) -> Self {
+        self.discount_codes.push(code.into());
+        self
+    }
+
+    /// Sets how the order amounts are treated.
+    ///
+    /// With [`TotalsMode::Calculate`], the amount and VAT amount are the sums of the
+    /// items and the shipping option, and need not be set. With [`TotalsMode::Strict`],
+    /// they are checked against the lines. An order without items keeps the amounts
+    /// it was given in either mode.
+    pub fn totals(mut self, mode: TotalsMode) -> Self {
+        self.totals = mode;
+        self
+    }
+
+    /// Sets the rounding and VAT tolerance the lines are checked with.
+    pub fn totals_validator(mut self, validator: TotalsValidator) -> Self {
+        self.validator = validator;
+        self
+    }
+
+    /// Builds the order, checking its amounts as set by [`totals`](Self::totals).
+    ///
+    /// # Errors
+    ///
+    /// Returns every line-level and order-level mistake found.
+    pub fn try_build(self) -> Result<Order, TotalsErrors> {
+        let (mode, validator) = (self.totals, self.validator);
+        let mut order = self.build_as_given();
+        order.apply_totals(mode, &validator)?;
+        Ok(order)
+    }
+
+    /// Builds the order. With [`TotalsMode::Calculate`], the amounts are calculated but
+    /// the lines are not checked; use [`try_build`](Self::try_build) to check them.
+    ///
+    /// # Panics
+    ///
+    /// Panics with [`TotalsMode::Strict`] if the amounts do not match the lines. Use
+    /// [`try_build`](Self::try_build) to handle the mistakes instead.
     pub fn build(self) -> Order {
+        let (mode, validator) = (self.totals, self.validator);
+        let mut order = self.build_as_given();
+        if !order.has_items() {
+            return order;
+        }
+        match mode {
+            TotalsMode::AsGiven => {}
+            TotalsMode::Calculate => {
+                let totals = Totals::of(order.lines());
+                order.amount = totals.amount;
+                order.vat_amount = Some(totals.vat_amount);
+            }
+            TotalsMode::Strict => {
+                if let Err(errors) = order.validate_totals(&validator) {
+                    panic!("order totals do not match its lines: {}", errors);
+                }
+            }
+        }
+        order
+    }
+
+    fn build_as_given(self) -> Order {
         Order {
             amount: self.amount,
             currency: self.currency,
@@ -233,6 +557,13 @@ impl OrderBuilder {
             vat_amount: self.vat_amount,
             shipping_address: self.shipping_address,
             billing_address: self.billing_address,
+            shipping_option: self.shipping_option,
+            gift_cards: if self.gift_cards.is_empty() { None } else { Some(self.gift_cards) },
+            discount_codes: if self.discount_codes.is_empty() {
+                None
+            } else {
+                Some(self.discount_codes)
+            },
         }
     }
 }
@@ -259,8 +590,177 @@ impl OrderItem {
         }
     }
 
+    /// Creates an item from its unit price, with `unit_price × quantity` as the amount
+    /// and the VAT included in it rounded half up.
+    pub fn from_unit_price(
+        id: impl Into<String>,
+        line_id: impl Into<String>,
+        description: impl Into<String>,
+        quantity: i64,
+        unit_price: i64,
+        vat: VatRate,
+    ) -> Self {
+        let totals = Totals::line(unit_price, quantity, 0, vat, RoundingMode::HalfUp);
+        Self::new(
+            id,
+            line_id,
+            description,
+            quantity,
+            totals.amount,
+            totals.vat_amount,
+            vat.percent(),
+        )
+    }
+
     pub fn with_discount(mut self, discount_amount: i64) -> Self {
         self.discount_amount = Some(discount_amount);
         self
     }
+
+    /// Takes `discount_amount` off the amount, recalculates the VAT and adds it to the
+    /// discount of the item.
+    pub fn apply_discount(mut self, discount_amount: i64) -> Self {
+        self.amount = self.amount.saturating_sub(discount_amount);
+        self.vat_amount = self.vat_rate().vat_included(self.amount, RoundingMode::HalfUp);
+        self.discount_amount = Some(self.discount_amount.unwrap_or(0) + discount_amount);
+        self
+    }
+}
+
+impl LineItem for OrderItem {
+    fn line_id(&self) -> &str {
+        &self.line_id
+    }
+
+    fn quantity(&self) -> i64 {
+        self.quantity
+    }
+
+    fn amount(&self) -> i64 {
+        self.amount
+    }
+
+    fn vat_amount(&self) -> i64 {
+        self.vat_amount
+    }
+
+    fn vat_rate(&self) -> VatRate {
+        VatRate::from_percent(self.vat)
+    }
+
+    fn discount_amount(&self) -> i64 {
+        self.discount_amount.unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dintero_core::TotalsError;
+
+    #[test]
+    fn test_order_builder_calculates_or_checks_totals() {
+        let vat = VatRate::from_percent(25);
+        let items = || {
+            Order::builder()
+                .currency(Currency::Nok)
+                .add_item(OrderItem::from_unit_price(
+                    "sku-1", "1", "T-shirt", 2, 19900, vat,
+                ))
+                .add_item(
+                    OrderItem::from_unit_price("sku-2", "2", "Cap", 1, 14900, vat)
+                        .apply_discount(2000),
+                )
+        };
+
+        let order = items().totals(TotalsMode::Calculate).try_build().unwrap();
+        assert_eq!((order.amount, order.vat_amount), (52700, Some(10540)));
+
+        let errors = items().amount(54700).vat_amount(10540).totals(TotalsMode::Strict).try_build();
+        assert_eq!(
+            errors.unwrap_err().errors(),
+            [TotalsError::AmountMismatch { amount: 54700, expected: 52700 }]
+        );
+
+        let request = CreateSessionRequest::builder()
+            .order(items().amount(1).build())
+            .totals(TotalsMode::Strict)
+            .build();
+        assert!(request.unwrap_err().contains("does not match the line total"));
+
+        // Without items, the given amount is kept.
+        let order = Order::builder().amount(5000).totals(TotalsMode::Calculate).build();
+        assert_eq!(order.amount, 5000);
+        let order = Order::builder().amount(5000).totals(TotalsMode::Calculate).try_build();
+        assert_eq!(order.unwrap().amount, 5000);
+
+        // VAT rounded per unit is one øre off the line VAT.
+        let per_unit = || {
+            Order::builder()
+                .add_item(OrderItem::new("sku-3", "3", "Socks", 3, 2997, 600, 25))
+                .totals(TotalsMode::Calculate)
+        };
+        assert!(per_unit().try_build().is_err());
+        let validator = TotalsValidator::new().vat_tolerance(1);
+        let order = per_unit().totals_validator(validator).try_build().unwrap();
+        assert_eq!(order.amount, 2997);
+    }
+
+    #[test]
+    fn test_session_builder_checks_urls_email_expiry_and_gift_cards() {
+        let order = || Order::builder().amount(10000).currency(Currency::Nok).build();
+        let builder = || CreateSessionRequest::builder().order(order());
+        assert!(builder().return_url("https://example.com/return").build().is_ok());
+
+        let error = builder().return_url("/return").build().unwrap_err();
+        assert!(error.contains("return_url"));
+        assert!(builder().callback_url("ftp://example.com").build().is_err());
+        assert!(builder().merchant_terms_url("example.com/terms").build().is_err());
+
+        let email = |email: &str| {
+            let customer = Customer {
+                email: Some(email.to_string()),
+                ..Default::default()
+            };
+            builder().customer(customer).build()
+        };
+        assert!(email("kari.nordmann@example.no").is_ok());
+        for invalid in ["kari", "@example.no", "kari@", "kari@example", "kari@@example.no"] {
+            assert!(email(invalid).is_err(), "{} should be rejected", invalid);
+        }
+        for invalid in ["kari nordmann@example.no", "kari@-example.no", "kari..n@example.no"] {
+            assert!(email(invalid).is_err(), "{} should be rejected", invalid);
+        }
+
+        let expires_at = |offset: chrono::Duration| builder().expires_at(Utc::now() + offset);
+        assert!(expires_at(chrono::Duration::hours(1)).build().is_ok());
+        let error = expires_at(-chrono::Duration::seconds(1)).build().unwrap_err();
+        assert_eq!(error, "expires_at must be in the future");
+
+        let gift_cards = |amounts: &[i64]| {
+            let order = amounts.iter().fold(Order::builder().amount(10000), |order, amount| {
+                order.add_gift_card(GiftCard::new("card", *amount))
+            });
+            CreateSessionRequest::builder().order(order.build()).build()
+        };
+        assert!(gift_cards(&[4000, 6000]).is_ok());
+        assert_eq!(
+            gift_cards(&[0]).unwrap_err(),
+            "gift card amounts must be positive"
+        );
+        assert_eq!(
+            gift_cards(&[6000, 4001]).unwrap_err(),
+            "gift cards exceed the order amount"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "order totals do not match its lines")]
+    fn test_strict_build_panics_on_mismatch() {
+        Order::builder()
+            .amount(1)
+            .add_item(OrderItem::new("sku-1", "1", "Cap", 1, 14900, 2980, 25))
+            .totals(TotalsMode::Strict)
+            .build();
+    }
 }
@@ -0,0 +1,474 @@
+//! Waiting for the customer to finish a checkout session.
+//!
+//! After a session is created, e.g. for a POS terminal or a QR code from
+//! [`generate_qr_code`](crate::CheckoutOperations::generate_qr_code), the customer pays
+//! outside of the merchant's control. [`SessionWatcher`] polls the session with backoff
+//! and yields every status change as a [`Stream`], checking each change against the
+//! transitions a session can make, see [`SessionStatus::can_transition_to`]:
+//!
+//! ```no_run
+//! use dintero_checkout::sessions::SessionWatcher;
+//! use dintero_checkout::CheckoutOperations;
+//! use std::time::Duration;
+//!
+//! # async fn example(checkout: &impl CheckoutOperations, session_id: &str) -> dintero_checkout::Result<()> {
+//! let outcome = SessionWatcher::new(checkout, session_id)
+//!     .timeout(Duration::from_secs(300))
+//!     .wait()
+//!     .await?;
+//!
+//! match outcome.transaction {
+//!     Some(transaction) => println!("paid: {}", transaction.id),
+//!     None => println!("session ended as {:?}", outcome.session.status),
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use super::types::{CheckoutSession, SessionStatus};
+use crate::client::{CheckoutOperations, Result};
+use crate::transactions::Transaction;
+use dintero_core::DinteroError;
+use futures_core::future::BoxFuture;
+use futures_core::Stream;
+use std::fmt;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+impl SessionStatus {
+    /// Returns `true` if the session cannot change status any more.
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, Self::Completed | Self::Failed | Self::Cancelled)
+    }
+
+    /// Returns `true` if the customer has paid, i.e. the session has a transaction.
+    pub fn is_paid(&self) -> bool {
+        matches!(self, Self::Authorized | Self::Completed)
+    }
+
+    /// Returns `true` if a session can go from this status to `next`.
+    ///
+    /// A session is created, may be started by the customer, and is then authorized,
+    /// failed or cancelled. An authorized session is completed once the payment is
+    /// captured, or cancelled. Statuses may be skipped, as a poll can miss the
+    /// statuses in between; staying in the same status is always allowed.
+    pub fn can_transition_to(&self, next: &SessionStatus) -> bool {
+        use SessionStatus::*;
+
+        self == next
+            || matches!(
+                (self, next),
+                (Created, _)
+                    | (Started, Authorized | Completed | Failed | Cancelled)
+                    | (Authorized, Completed | Cancelled)
+            )
+    }
+}
+
+/// Shortest interval a [`SessionWatcher`] waits between polls.
+pub const MIN_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Largest factor a [`SessionWatcher`]'s poll interval grows by.
+pub const MAX_BACKOFF_MULTIPLIER: f64 = 10.0;
+
+/// A status change observed by a [`SessionWatcher`].
+#[derive(Debug, Clone)]
+pub struct SessionChange {
+    /// The previous status, or `None` for the first status observed.
+    pub from: Option<SessionStatus>,
+    /// The new status.
+    pub to: SessionStatus,
+    /// The session as fetched when the change was observed.
+    pub session: CheckoutSession,
+}
+
+/// The final state of a watched session.
+#[derive(Debug, Clone)]
+pub struct SessionOutcome {
+    /// The session as last fetched.
+    pub session: CheckoutSession,
+    /// The session's transaction, if the customer paid.
+    pub transaction: Option<Transaction>,
+}
+
+/// Polls a checkout session until the customer is done with it.
+///
+/// The watcher is a [`Stream`] of [`SessionChange`]s. It ends after the session reaches
+/// a terminal status, or is authorized unless [`stop_at_authorized`] is disabled.
+/// The first poll is sent immediately; the interval between polls then grows by the
+/// backoff multiplier up to the maximum interval.
+///
+/// If the session makes a transition that is not allowed, the stream yields a
+/// [`DinteroError::Validation`] and ends. If the timeout passes first, it yields a
+/// [`DinteroError::Timeout`] and ends. Errors fetching the session end the stream too;
+/// the client retries transient failures before they reach the watcher.
+///
+/// [`stop_at_authorized`]: Self::stop_at_authorized
+pub struct SessionWatcher<'a, C: ?Sized> {
+    client: &'a C,
+    session_id: String,
+    interval: Duration,
+    max_interval: Duration,
+    backoff_multiplier: f64,
+    timeout: Option<Duration>,
+    stop_at_authorized: bool,
+    started: Option<Instant>,
+    next_interval: Duration,
+    status: Option<SessionStatus>,
+    last: Option<CheckoutSession>,
+    in_flight: Option<BoxFuture<'a, Result<CheckoutSession>>>,
+    done: bool,
+}
+
+impl<'a, C: CheckoutOperations + ?Sized> SessionWatcher<'a, C> {
+    /// Creates a watcher polling every second at first, backing off to every 10
+    /// seconds, without a timeout.
+    pub fn new(client: &'a C, session_id: impl Into<String>) -> Self {
+        Self {
+            client,
+            session_id: session_id.into(),
+            interval: Duration::from_secs(1),
+            max_interval: Duration::from_secs(10),
+            backoff_multiplier: 1.5,
+            timeout: None,
+            stop_at_authorized: true,
+            started: None,
+            next_interval: Duration::ZERO,
+            status: None,
+            last: None,
+            in_flight: None,
+            done: false,
+        }
+    }
+
+    /// Sets the interval between the first polls, at least [`MIN_POLL_INTERVAL`].
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval.max(MIN_POLL_INTERVAL);
+        self
+    }
+
+    /// Sets the longest interval between polls, at least [`MIN_POLL_INTERVAL`].
+    pub fn max_interval(mut self, max_interval: Duration) -> Self {
+        self.max_interval = max_interval.max(MIN_POLL_INTERVAL);
+        self
+    }
+
+    /// Sets the factor the interval grows by after every poll, between `1.0`, which
+    /// polls at a fixed interval, and [`MAX_BACKOFF_MULTIPLIER`]. A NaN factor is taken
+    /// as `1.0`.
+    pub fn backoff_multiplier(mut self, multiplier: f64) -> Self {
+        self.backoff_multiplier =
+            if multiplier.is_nan() { 1.0 } else { multiplier.clamp(1.0, MAX_BACKOFF_MULTIPLIER) };
+        self
+    }
+
+    /// Sets how long to watch the session, counted from the first poll.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets whether watching stops once the session is authorized. Disable it to wait
+    /// until the payment is captured and the session completed.
+    pub fn stop_at_authorized(mut self, stop: bool) -> Self {
+        self.stop_at_authorized = stop;
+        self
+    }
+
+    /// Watches the session until it is done and returns it with its transaction.
+    ///
+    /// The transaction is fetched if the session ends paid and links one.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error of the stream, see [`SessionWatcher`], or the error
+    /// fetching the transaction.
+    pub async fn wait(mut self) -> Result<SessionOutcome> {
+        while let Some(change) = std::future::poll_fn(|cx| Pin::new(&mut self).poll_next(cx)).await
+        {
+            change?;
+        }
+
+        let Some(session) = self.last.take() else {
+            return Err(DinteroError::Validation(format!(
+                "session {} has no status",
+                self.session_id
+            )));
+        };
+        let transaction = match (&session.status, &session.transaction_id) {
+            (Some(status), Some(transaction_id)) if status.is_paid() => {
+                Some(self.client.get_transaction(transaction_id).await?)
+            }
+            _ => None,
+        };
+        Ok(SessionOutcome { session, transaction })
+    }
+
+    /// Returns the interval after a poll that waited `interval`, zero for the first
+    /// poll.
+    fn backoff(&self, interval: Duration) -> Duration {
+        if interval.is_zero() {
+            return self.interval;
+        }
+        let next = interval.as_secs_f64() * self.backoff_multiplier;
+        Duration::try_from_secs_f64(next)
+            .map_or(self.max_interval, |next| next.min(self.max_interval))
+    }
+
+    fn is_done(&self, status: &SessionStatus) -> bool {
+        status.is_terminal() || (self.stop_at_authorized && *status == SessionStatus::Authorized)
+    }
+
+    fn start_poll(&mut self) -> Result<()> {
+        let started = *self.started.get_or_insert_with(Instant::now);
+        let mut delay = self.next_interval;
+        if let Some(timeout) = self.timeout {
+            let remaining = timeout.saturating_sub(started.elapsed());
+            if remaining.is_zero() {
+                return Err(DinteroError::Timeout(
+                    format!(
+                        "session {} was not done within {:?}",
+                        self.session_id, timeout
+                    )
+                    .into(),
+                ));
+            }
+            delay = delay.min(remaining);
+        }
+        self.next_interval = self.backoff(self.next_interval);
+
+        let client = self.client;
+        let session_id = self.session_id.clone();
+        self.in_flight = Some(Box::pin(async move {
+            if !delay.is_zero() {
+                tokio::time::sleep(delay).await;
+            }
+            client.get_session(&session_id).await
+        }));
+        Ok(())
+    }
+
+    fn observe(&mut self, session: CheckoutSession) -> Result<Option<SessionChange>> {
+        let Some(to) = session.status.clone() else {
+            return Ok(None);
+        };
+        let from = self.status.clone();
+        if let Some(from) = &from {
+            if !from.can_transition_to(&to) {
+                return Err(DinteroError::Validation(format!(
+                    "session {} went from {:?} to {:?}",
+                    self.session_id, from, to
+                )));
+            }
+        }
+
+        self.done = self.is_done(&to);
+        self.status = Some(to.clone());
+        self.last = Some(session.clone());
+        if from.as_ref() == Some(&to) {
+            return Ok(None);
+        }
+        Ok(Some(SessionChange { from, to, session }))
+    }
+}
+
+impl<C: CheckoutOperations + ?Sized> Stream for SessionWatcher<'_, C> {
+    type Item = Result<SessionChange>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+
+        loop {
+            if this.done {
+                return Poll::Ready(None);
+            }
+
+            if this.in_flight.is_none() {
+                if let Err(e) = this.start_poll() {
+                    this.done = true;
+                    return Poll::Ready(Some(Err(e)));
+                }
+            }
+
+            let Some(in_flight) = &mut this.in_flight else { continue };
+            let result = match in_flight.as_mut().poll(cx) {
+                Poll::Ready(result) => result,
+                Poll::Pending => return Poll::Pending,
+            };
+            this.in_flight = None;
+
+            match result.and_then(|session| this.observe(session)) {
+                Ok(Some(change)) => return Poll::Ready(Some(Ok(change))),
+                Ok(None) => {}
+                Err(e) => {
+                    this.done = true;
+                    return Poll::Ready(Some(Err(e)));
+                }
+            }
+        }
+    }
+}
+
+impl<C: ?Sized> fmt::Debug for SessionWatcher<'_, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SessionWatcher")
+            .field("session_id", &self.session_id)
+            .field("status", &self.status)
+            .field("interval", &self.interval)
+            .field("max_interval", &self.max_interval)
+            .field("timeout", &self.timeout)
+            .field("stop_at_authorized", &self.stop_at_authorized)
+            .field("in_flight", &self.in_flight.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{CheckoutClient, HttpClient};
+    use async_trait::async_trait;
+    use serde_json::{json, Value};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    /// Answers session fetches with the scripted statuses, repeating the last one.
+    struct Script {
+        statuses: Mutex<Vec<&'static str>>,
+        polls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl HttpClient for Script {
+        async fn post_json<B: serde::Serialize + Send + Sync, T: serde::de::DeserializeOwned>(
+            &self,
+            _path: &str,
+            _body: &B,
+        ) -> Result<T> {
+            unreachable!()
+        }
+
+        async fn get_json<T: serde::de::DeserializeOwned>(&self, path: &str) -> Result<T> {
+            let body = if path.contains("/transactions/") {
+                json!({ "id": "T1.tx", "session_id": "T1.s", "status": "AUTHORIZED", "amount": 100, "currency": "NOK" })
+            } else {
+                self.polls.fetch_add(1, Ordering::SeqCst);
+                let mut statuses = self.statuses.lock().unwrap();
+                let status = if statuses.len() > 1 { statuses.remove(0) } else { statuses[0] };
+                let mut session = json!({ "id": "T1.s", "url": "", "status": status });
+                if status == "authorized" {
+                    session["transaction_id"] = Value::from("T1.tx");
+                }
+                session
+            };
+            Ok(serde_json::from_value(body)?)
+        }
+
+        async fn put_json<B: serde::Serialize + Send + Sync, T: serde::de::DeserializeOwned>(
+            &self,
+            _path: &str,
+            _body: &B,
+        ) -> Result<T> {
+            unreachable!()
+        }
+
+        async fn delete(&self, _path: &str) -> Result<()> {
+            unreachable!()
+        }
+    }
+
+    fn checkout(statuses: Vec<&'static str>) -> CheckoutClient<Script> {
+        counting_checkout(statuses, Arc::default())
+    }
+
+    fn counting_checkout(
+        statuses: Vec<&'static str>,
+        polls: Arc<AtomicUsize>,
+    ) -> CheckoutClient<Script> {
+        CheckoutClient::new(Script { statuses: Mutex::new(statuses), polls }, "T1")
+    }
+
+    #[tokio::test]
+    async fn test_watcher_follows_session_to_transaction() {
+        let client = checkout(vec!["created", "created", "started", "authorized"]);
+        let mut watcher = SessionWatcher::new(&client, "T1.s").interval(Duration::from_millis(1));
+        let mut changes = Vec::new();
+        while let Some(change) =
+            std::future::poll_fn(|cx| Pin::new(&mut watcher).poll_next(cx)).await
+        {
+            let change = change.unwrap();
+            changes.push((change.from, change.to));
+        }
+        assert_eq!(
+            changes,
+            vec![
+                (None, SessionStatus::Created),
+                (Some(SessionStatus::Created), SessionStatus::Started),
+                (Some(SessionStatus::Started), SessionStatus::Authorized),
+            ]
+        );
+
+        let client = checkout(vec!["started", "authorized"]);
+        let outcome = SessionWatcher::new(&client, "T1.s")
+            .interval(Duration::from_millis(1))
+            .wait()
+            .await
+            .unwrap();
+        assert_eq!(outcome.transaction.unwrap().id, "T1.tx");
+
+        let client = checkout(vec!["authorized", "started"]);
+        let result = SessionWatcher::new(&client, "T1.s")
+            .interval(Duration::from_millis(1))
+            .stop_at_authorized(false)
+            .wait()
+            .await;
+        assert!(matches!(result, Err(DinteroError::Validation(_))));
+
+        let client = checkout(vec!["created"]);
+        let result = SessionWatcher::new(&client, "T1.s")
+            .interval(Duration::from_millis(5))
+            .timeout(Duration::from_millis(20))
+            .wait()
+            .await;
+        assert!(matches!(result, Err(DinteroError::Timeout(_))));
+    }
+
+    #[tokio::test]
+    async fn test_zero_interval_still_times_out() {
+        let polls = Arc::new(AtomicUsize::new(0));
+        let client = counting_checkout(vec!["created"], polls.clone());
+        let result = SessionWatcher::new(&client, "T1.s")
+            .interval(Duration::ZERO)
+            .backoff_multiplier(1.0)
+            .timeout(Duration::from_millis(50))
+            .wait()
+            .await;
+        assert!(matches!(result, Err(DinteroError::Timeout(_))));
+        // One poll per minimum interval at most, not a busy loop.
+        let max_polls = 50 / MIN_POLL_INTERVAL.as_millis() as usize + 2;
+        assert!(polls.load(Ordering::SeqCst) <= max_polls);
+    }
+
+    #[test]
+    fn test_backoff_is_capped() {
+        let client = checkout(vec!["created"]);
+        let watcher = SessionWatcher::new(&client, "T1.s")
+            .interval(Duration::from_secs(1))
+            .max_interval(Duration::from_secs(5))
+            .backoff_multiplier(f64::INFINITY);
+        assert_eq!(watcher.backoff_multiplier, MAX_BACKOFF_MULTIPLIER);
+        assert_eq!(watcher.backoff(Duration::ZERO), Duration::from_secs(1));
+        assert_eq!(
+            watcher.backoff(Duration::from_secs(1)),
+            Duration::from_secs(5)
+        );
+        assert_eq!(watcher.backoff(Duration::MAX), Duration::from_secs(5));
+
+        let watcher = SessionWatcher::new(&client, "T1.s").backoff_multiplier(f64::NAN);
+        assert_eq!(
+            watcher.backoff(Duration::from_secs(2)),
+            Duration::from_secs(2)
+        );
+    }
+}
@@ -1,7 +1,9 @@
 //! Module exports and organization.
 
 pub mod operations;
+pub mod planner;
 pub mod types;
 
 pub use operations::*;
+pub use planner::*;
 pub use types::*;
@@ -0,0 +1,730 @@
+//! Planning captures, refunds and voids against what a transaction allows.
+//!
+//! The API rejects a capture above the remaining authorization, a capture after a
+//! refund, a refund of more than was captured or a void after a capture, but only once
+//! the request is sent. [`TransactionLedger`] replays the
+//! transaction's event history to know, per order line, how much can still be captured,
+//! refunded or voided, and checks requests before they are sent. [`TransactionPlanner`]
+//! sends checked requests through a client and keeps the ledger up to date:
+//!
+//! ```no_run
+//! use dintero_checkout::transactions::TransactionPlanner;
+//! use dintero_checkout::CheckoutOperations;
+//!
+//! # async fn example(checkout: &impl CheckoutOperations) -> dintero_checkout::Result<()> {
+//! let mut planner = TransactionPlanner::load(checkout, "T12345678.abc").await?;
+//! println!("capturable: {}", planner.ledger().capturable_amount());
+//!
+//! planner.capture_remaining().await?;
+//! planner.refund_line("2").await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use super::types::{
+    CaptureRequest, RefundRequest, Transaction, TransactionEventType, TransactionStatus,
+    VoidRequest,
+};
+use super::{CaptureItem, RefundItem};
+use crate::client::{CheckoutOperations, Result};
+use dintero_core::DinteroError;
+use std::collections::HashSet;
+
+/// Captured and refunded amounts of an order line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineBalance {
+    /// Id of the order line.
+    pub line_id: String,
+    /// Quantity of the line.
+    pub quantity: i64,
+    /// Authorized amount of the line.
+    pub amount: i64,
+    /// Amount of the line captured so far.
+    pub captured_amount: i64,
+    /// Quantity of the line captured so far.
+    pub captured_quantity: i64,
+    /// Amount of the line refunded so far.
+    pub refunded_amount: i64,
+    /// Quantity of the line refunded so far.
+    pub refunded_quantity: i64,
+    /// Whether the transaction was voided. A void releases the whole authorization and
+    /// is only possible before anything is captured.
+    pub voided: bool,
+}
+
+impl LineBalance {
+    fn new(line_id: &str, quantity: i64, amount: i64) -> Self {
+        Self {
+            line_id: line_id.to_string(),
+            quantity,
+            amount,
+            captured_amount: 0,
+            captured_quantity: 0,
+            refunded_amount: 0,
+            refunded_quantity: 0,
+            voided: false,
+        }
+    }
+
+    /// Returns the amount of the line that can still be captured.
+    pub fn capturable_amount(&self) -> i64 {
+        if self.voided {
+            return 0;
+        }
+        (self.amount - self.captured_amount).max(0)
+    }
+
+    /// Returns the quantity of the line that can still be captured.
+    pub fn capturable_quantity(&self) -> i64 {
+        if self.capturable_amount() == 0 {
+            return 0;
+        }
+        (self.quantity - self.captured_quantity).max(0)
+    }
+
+    /// Returns the captured amount of the line that can still be refunded.
+    pub fn refundable_amount(&self) -> i64 {
+        (self.captured_amount - self.refunded_amount).max(0)
+    }
+
+    /// Returns the captured quantity of the line that can still be refunded.
+    pub fn refundable_quantity(&self) -> i64 {
+        if self.refundable_amount() == 0 {
+            return 0;
+        }
+        (self.captured_quantity - self.refunded_quantity).max(0)
+    }
+
+    fn capture(&mut self, amount: i64, quantity: Option<i64>) {
+        let quantity = quantity.unwrap_or_else(|| {
+            if amount >= self.capturable_amount() {
+                self.capturable_quantity()
+            } else {
+                0
+            }
+        });
+        self.captured_amount += amount;
+        self.captured_quantity += quantity;
+    }
+
+    fn refund(&mut self, amount: i64, quantity: Option<i64>) {
+        let quantity = quantity.unwrap_or_else(|| {
+            if amount >= self.refundable_amount() {
+                self.refundable_quantity()
+            } else {
+                0
+            }
+        });
+        self.refunded_amount += amount;
+        self.refunded_quantity += quantity;
+    }
+}
+
+/// The amounts a transaction has authorized, captured, refunded and voided, in total
+/// and per order line.
+///
+/// Lines come from the transaction's items. Captures and refunds made without items
+/// count towards the totals only; the totals always bound what a line allows.
+#[derive(Debug, Clone)]
+pub struct TransactionLedger {
+    transaction: Transaction,
+    captured: i64,
+    refunded: i64,
+    voided: bool,
+    lines: Vec<LineBalance>,
+}
+
+impl TransactionLedger {
+    /// Builds the ledger of a transaction from its items and event history.
+    ///
+    /// Failed events are skipped. A transaction without event history is accepted
+    /// only if its status tells the amounts, i.e. it is not partially captured,
+    /// refunded or settled.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DinteroError::Validation`] if the amounts cannot be determined.
+    pub fn new(transaction: Transaction) -> Result<Self> {
+        let lines = transaction
+            .items
+            .iter()
+            .flatten()
+            .map(|item| LineBalance::new(&item.line_id, item.quantity, item.amount))
+            .collect();
+        let mut ledger = Self {
+            transaction,
+            captured: 0,
+            refunded: 0,
+            voided: false,
+            lines,
+        };
+
+        let Some(events) = ledger.transaction.events.take() else {
+            ledger.assume_from_status()?;
+            return Ok(ledger);
+        };
+        for event in events.iter().filter(|event| event.is_success()) {
+            let items: Vec<_> = event
+                .items
+                .iter()
+                .flatten()
+                .map(|item| (item.line_id.as_str(), item.amount, item.quantity))
+                .collect();
+            let amount =
+                event.amount.unwrap_or_else(|| items.iter().map(|(_, amount, _)| amount).sum());
+            match event.event {
+                TransactionEventType::Capture => ledger.record_capture(amount, &items),
+                TransactionEventType::Refund => ledger.record_refund(amount, &items),
+                TransactionEventType::Void => ledger.record_void(),
+                _ => {}
+            }
+        }
+        ledger.transaction.events = Some(events);
+        Ok(ledger)
+    }
+
+    fn assume_from_status(&mut self) -> Result<()> {
+        match self.transaction.status {
+            TransactionStatus::OnHold | TransactionStatus::Authorized => {}
+            TransactionStatus::Captured | TransactionStatus::Settled => {
+                self.record_capture(self.transaction.amount, &[]);
+                for line in &mut self.lines {
+                    line.capture(line.amount, None);
+                }
+            }
+            TransactionStatus::Refunded => {
+                self.record_capture(self.transaction.amount, &[]);
+                self.record_refund(self.transaction.amount, &[]);
+                for line in &mut self.lines {
+                    line.capture(line.amount, None);
+                    line.refund(line.amount, None);
+                }
+            }
+            TransactionStatus::Voided
+            | TransactionStatus::Cancelled
+            | TransactionStatus::Failed => self.record_void(),
+            TransactionStatus::PartiallyCaptured
+            | TransactionStatus::PartiallyRefunded
+            | TransactionStatus::PartiallySettled => {
+                return Err(DinteroError::Validation(format!(
+                    "transaction {} is {:?} but has no event history",
+                    self.transaction.id, self.transaction.status
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the transaction the ledger was built from.
+    pub fn transaction(&self) -> &Transaction {
+        &self.transaction
+    }
+
+    /// Returns the authorized amount.
+    pub fn authorized_amount(&self) -> i64 {
+        self.transaction.amount
+    }
+
+    /// Returns the amount captured so far.
+    pub fn captured_amount(&self) -> i64 {
+        self.captured
+    }
+
+    /// Returns the amount refunded so far.
+    pub fn refunded_amount(&self) -> i64 {
+        self.refunded
+    }
+
+    /// Returns the authorized amount that can still be captured.
+    ///
+    /// Nothing can be captured from a transaction that is on hold, voided, cancelled
+    /// or failed, or once any part of it has been refunded.
+    pub fn capturable_amount(&self) -> i64 {
+        if self.is_closed()
+            || self.refunded > 0
+            || self.transaction.status == TransactionStatus::OnHold
+        {
+            return 0;
+        }
+        (self.transaction.amount - self.captured).max(0)
+    }
+
+    /// Returns the captured amount that can still be refunded.
+    pub fn refundable_amount(&self) -> i64 {
+        (self.captured - self.refunded).max(0)
+    }
+
+    /// Returns the authorized amount a void releases.
+    ///
+    /// Only a transaction with nothing captured can be voided.
+    pub fn voidable_amount(&self) -> i64 {
+        if self.is_closed() || self.captured > 0 {
+            return 0;
+        }
+        self.transaction.amount
+    }
+
+    fn is_closed(&self) -> bool {
+        self.voided
+            || matches!(
+                self.transaction.status,
+                TransactionStatus::Voided
+                    | TransactionStatus::Cancelled
+                    | TransactionStatus::Failed
+            )
+    }
+
+    /// Returns the balances of the order lines.
+    pub fn lines(&self) -> &[LineBalance] {
+        &self.lines
+    }
+
+    /// Returns the balance of an order line.
+    pub fn line(&self, line_id: &str) -> Option<&LineBalance> {
+        self.lines.iter().find(|line| line.line_id == line_id)
+    }
+
+    /// Checks a capture request against the capturable amounts.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DinteroError::Validation`] if the amount is not positive or exceeds
+    /// the capturable amount, or if an item refers to an unknown line, exceeds what
+    /// the line allows, or the items do not add up to the amount.
+    pub fn validate_capture(&self, request: &CaptureRequest) -> Result<()> {
+        self.check_status("captured", self.capturable_amount())?;
+        check_amount("capture", request.amount, self.capturable_amount())?;
+
+        let items = request.items.iter().flatten();
+        let items = items.map(|item| (item.line_id.as_str(), item.amount, item.quantity));
+        self.check_items("capture", request.amount, items, |line| {
+            (line.capturable_amount(), line.capturable_quantity())
+        })
+    }
+
+    /// Checks a refund request against the refundable amounts.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DinteroError::Validation`] if the amount is not positive or exceeds
+    /// the refundable amount, or if an item refers to an unknown line, exceeds what
+    /// the line allows, or the items do not add up to the amount.
+    pub fn validate_refund(&self, request: &RefundRequest) -> Result<()> {
+        if self.refundable_amount() == 0 {
+            return Err(DinteroError::Validation(format!(
+                "transaction {} has nothing captured to refund",
+                self.transaction.id
+            )));
+        }
+        check_amount("refund", request.amount, self.refundable_amount())?;
+
+        let items = request.items.iter().flatten();
+        let items = items.map(|item| (item.line_id.as_str(), item.amount, item.quantity));
+        self.check_items("refund", request.amount, items, |line| {
+            (line.refundable_amount(), line.refundable_quantity())
+        })
+    }
+
+    /// Checks that the transaction can be voided, i.e. nothing of it is captured.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DinteroError::Validation`] if part of the transaction is captured or
+    /// it is already voided, cancelled or failed.
+    pub fn validate_void(&self) -> Result<()> {
+        self.check_status("voided", self.voidable_amount())
+    }
+
+    /// Checks that the authorization can be extended by `days`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DinteroError::Validation`] if `days` is zero or nothing is left to
+    /// capture.
+    pub fn validate_extend_authorization(&self, days: u32) -> Result<()> {
+        if days == 0 {
+            return Err(DinteroError::Validation(
+                "authorization must be extended by at least one day".to_string(),
+            ));
+        }
+        self.check_status("extended", self.capturable_amount())
+    }
+
+    /// Returns a request capturing everything left to capture, by line when the lines
+    /// account for the whole remainder.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DinteroError::Validation`] if nothing is left to capture.
+    pub fn capture_remaining(&self) -> Result<CaptureRequest> {
+        let amount = self.capturable_amount();
+        self.check_status("captured", amount)?;
+
+        let items: Vec<_> = self
+            .lines
+            .iter()
+            .filter(|line| line.capturable_amount() > 0)
+            .map(|line| CaptureItem {
+                line_id: line.line_id.clone(),
+                amount: line.capturable_amount(),
+                quantity: Some(line.capturable_quantity()).filter(|quantity| *quantity > 0),
+            })
+            .collect();
+
+        let request = CaptureRequest::new(amount);
+        if !items.is_empty() && items.iter().map(|item| item.amount).sum::<i64>() == amount {
+            return Ok(request.with_items(items));
+        }
+        Ok(request)
+    }
+
+    /// Returns a request refunding everything refundable of an order line.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DinteroError::Validation`] if the line is unknown or has nothing to
+    /// refund.
+    pub fn refund_line(&self, line_id: &str) -> Result<RefundRequest> {
+        let line = self.line(line_id).ok_or_else(|| self.unknown_line(line_id))?;
+        let amount = line.refundable_amount().min(self.refundable_amount());
+        if amount == 0 {
+            return Err(DinteroError::Validation(format!(
+                "line {} has nothing captured to refund",
+                line_id
+            )));
+        }
+
+        let item = RefundItem {
+            line_id: line.line_id.clone(),
+            amount,
+            quantity: Some(line.refundable_quantity()).filter(|quantity| *quantity > 0),
+        };
+        Ok(RefundRequest::new(amount).with_items(vec![item]))
+    }
+
+    /// Returns a request voiding the authorization.
+    ///
+    /// A void releases the whole authorization, so the API only accepts it before
+    /// anything is captured. After a partial capture, the uncaptured remainder cannot be
+    /// released this way.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DinteroError::Validation`] if the transaction cannot be voided, see
+    /// [`validate_void`](Self::validate_void).
+    pub fn void_authorization(&self) -> Result<VoidRequest> {
+        self.validate_void()?;
+        Ok(VoidRequest::new())
+    }
+
+    fn record_capture(&mut self, amount: i64, items: &[(&str, i64, Option<i64>)]) {
+        self.captured += amount;
+        for (line_id, amount, quantity) in items {
+            if let Some(line) = self.lines.iter_mut().find(|line| line.line_id == *line_id) {
+                line.capture(*amount, *quantity);
+            }
+        }
+    }
+
+    fn record_refund(&mut self, amount: i64, items: &[(&str, i64, Option<i64>)]) {
+        self.refunded += amount;
+        for (line_id, amount, quantity) in items {
+            if let Some(line) = self.lines.iter_mut().find(|line| line.line_id == *line_id) {
+                line.refund(*amount, *quantity);
+            }
+        }
+    }
+
+    fn record_void(&mut self) {
+        self.voided = true;
+        for line in &mut self.lines {
+            line.voided = true;
+        }
+    }
+
+    /// Replaces the transaction with the one returned by an operation. Without event
+    /// history in `transaction`, the operation is recorded with `record`.
+    fn update(&mut self, transaction: Transaction, record: impl FnOnce(&mut Self)) -> Result<()> {
+        if transaction.events.is_some() {
+            *self = Self::new(transaction)?;
+            return Ok(());
+        }
+
+        record(self);
+        let items = self.transaction.items.take();
+        self.transaction = Transaction {
+            items: transaction.items.clone().or(items),
+            ..transaction
+        };
+        Ok(())
+    }
+
+    fn check_status(&self, operation: &str, available: i64) -> Result<()> {
+        if available > 0 {
+            return Ok(());
+        }
+        Err(DinteroError::Validation(format!(
+            "transaction {} is {:?} and has nothing left to be {}",
+            self.transaction.id, self.transaction.status, operation
+        )))
+    }
+
+    fn check_items<'i>(
+        &self,
+        operation: &str,
+        amount: i64,
+        items: impl Iterator<Item = (&'i str, i64, Option<i64>)>,
+        available: impl Fn(&LineBalance) -> (i64, i64),
+    ) -> Result<()> {
+        let mut seen = HashSet::new();
+        let mut total = 0;
+        let mut any = false;
+        for (line_id, item_amount, quantity) in items {
+            any = true;
+            let line = self.line(line_id).ok_or_else(|| self.unknown_line(line_id))?;
+            if !seen.insert(line_id) {
+                return Err(DinteroError::Validation(format!(
+                    "line {} appears twice in the {}",
+                    line_id, operation
+                )));
+            }
+
+            let (available_amount, available_quantity) = available(line);
+            if !(0..=available_amount).contains(&item_amount) {
+                return Err(DinteroError::Validation(format!(
+                    "{} of {} on line {} is outside 0..={}",
+                    operation, item_amount, line_id, available_amount
+                )));
+            }
+            if let Some(quantity) = quantity {
+                if !(0..=available_quantity).contains(&quantity) {
+                    return Err(DinteroError::Validation(format!(
+                        "{} of quantity {} on line {} is outside 0..={}",
+                        operation, quantity, line_id, available_quantity
+                    )));
+                }
+            }
+            total += item_amount;
+        }
+
+        if any && total != amount {
+            return Err(DinteroError::Validation(format!(
+                "{} items add up to {}, not the amount {}",
+                operation, total, amount
+            )));
+        }
+        Ok(())
+    }
+
+    fn unknown_line(&self, line_id: &str) -> DinteroError {
+        DinteroError::Validation(format!(
+            "transaction {} has no line {}",
+            self.transaction.id, line_id
+        ))
+    }
+}
+
+fn check_amount(operation: &str, amount: i64, available: i64) -> Result<()> {
+    if amount <= 0 {
+        return Err(DinteroError::Validation(format!(
+            "{} amount must be positive",
+            operation
+        )));
+    }
+    if amount > available {
+        return Err(DinteroError::Validation(format!(
+            "{} amount {} exceeds the {} available",
+            operation, amount, available
+        )));
+    }
+    Ok(())
+}
+
+/// Sends captures, refunds and voids of a transaction after checking them against its
+/// [`TransactionLedger`], and keeps the ledger up to date.
+#[derive(Debug)]
+pub struct TransactionPlanner<'a, C: ?Sized> {
+    client: &'a C,
+    ledger: TransactionLedger,
+}
+
+impl<'a, C: CheckoutOperations + ?Sized> TransactionPlanner<'a, C> {
+    /// Fetches a transaction and builds its ledger.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error fetching the transaction, or [`DinteroError::Validation`] if
+    /// its amounts cannot be determined, see [`TransactionLedger::new`].
+    pub async fn load(client: &'a C, transaction_id: &str) -> Result<Self> {
+        let transaction = client.get_transaction(transaction_id).await?;
+        Ok(Self::new(client, TransactionLedger::new(transaction)?))
+    }
+
+    /// Creates a planner for a ledger that is already built.
+    pub fn new(client: &'a C, ledger: TransactionLedger) -> Self {
+        Self { client, ledger }
+    }
+
+    pub fn ledger(&self) -> &TransactionLedger {
+        &self.ledger
+    }
+
+    /// Checks and sends a capture.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DinteroError::Validation`] without sending anything if the capture is
+    /// not allowed, see [`TransactionLedger::validate_capture`], or the API error.
+    pub async fn capture(&mut self, request: CaptureRequest) -> Result<Transaction> {
+        self.ledger.validate_capture(&request)?;
+        let transaction =
+            self.client.capture_transaction(&self.ledger.transaction.id, request.clone()).await?;
+        self.ledger.update(transaction.clone(), |ledger| {
+            let items = request.items.iter().flatten();
+            let items: Vec<_> =
+                items.map(|item| (item.line_id.as_str(), item.amount, item.quantity)).collect();
+            ledger.record_capture(request.amount, &items);
+        })?;
+        Ok(transaction)
+    }
+
+    /// Checks and sends a refund.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DinteroError::Validation`] without sending anything if the refund is
+    /// not allowed, see [`TransactionLedger::validate_refund`], or the API error.
+    pub async fn refund(&mut self, request: RefundRequest) -> Result<Transaction> {
+        self.ledger.validate_refund(&request)?;
+        let transaction =
+            self.client.refund_transaction(&self.ledger.transaction.id, request.clone()).await?;
+        self.ledger.update(transaction.clone(), |ledger| {
+            let items = request.items.iter().flatten();
+            let items: Vec<_> =
+                items.map(|item| (item.line_id.as_str(), item.amount, item.quantity)).collect();
+            ledger.record_refund(request.amount, &items);
+        })?;
+        Ok(transaction)
+    }
+
+    /// Checks and sends a void of the authorization.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DinteroError::Validation`] without sending anything if the void is not
+    /// allowed, see [`TransactionLedger::validate_void`], or the API error.
+    pub async fn void(&mut self, request: VoidRequest) -> Result<Transaction> {
+        self.ledger.validate_void()?;
+        let transaction =
+            self.client.void_transaction(&self.ledger.transaction.id, request).await?;
+        self.ledger.update(transaction.clone(), TransactionLedger::record_void)?;
+        Ok(transaction)
+    }
+
+    /// Checks and sends an extension of the authorization by `days`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DinteroError::Validation`] without sending anything if the
+    /// authorization cannot be extended, or the API error.
+    pub async fn extend_authorization(&mut self, days: u32) -> Result<Transaction> {
+        self.ledger.validate_extend_authorization(days)?;
+        let transaction =
+            self.client.extend_authorization(&self.ledger.transaction.id, days).await?;
+        self.ledger.update(transaction.clone(), |_| {})?;
+        Ok(transaction)
+    }
+
+    /// Captures everything left to capture, see [`TransactionLedger::capture_remaining`].
+    pub async fn capture_remaining(&mut self) -> Result<Transaction> {
+        let request = self.ledger.capture_remaining()?;
+        self.capture(request).await
+    }
+
+    /// Refunds everything refundable of an order line, see
+    /// [`TransactionLedger::refund_line`].
+    pub async fn refund_line(&mut self, line_id: &str) -> Result<Transaction> {
+        let request = self.ledger.refund_line(line_id)?;
+        self.refund(request).await
+    }
+
+    /// Voids the authorization, see [`TransactionLedger::void_authorization`].
+    pub async fn void_authorization(&mut self) -> Result<Transaction> {
+        let request = self.ledger.void_authorization()?;
+        self.void(request).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_ledger_replays_events_per_line() {
+        let ledger = |status: &str, refunded: bool| {
+            let mut events = vec![
+                json!({ "event": "AUTHORIZE", "amount": 30000, "success": true }),
+                json!({ "event": "CAPTURE", "amount": 5000, "success": false }),
+                json!({ "event": "CAPTURE", "amount": 20000, "items": [
+                    { "line_id": "1", "amount": 10000, "quantity": 1 },
+                    { "line_id": "2", "amount": 10000 },
+                ] }),
+            ];
+            if refunded {
+                events.push(json!({ "event": "REFUND", "amount": 10000, "items": [{ "line_id": "2", "amount": 10000 }] }));
+            }
+            let transaction: Transaction = serde_json::from_value(json!({
+                "id": "T1.tx",
+                "session_id": "T1.s",
+                "status": status,
+                "amount": 30000,
+                "currency": "NOK",
+                "items": [
+                    { "id": "a", "line_id": "1", "description": "Shirt", "quantity": 2, "amount": 20000, "vat_amount": 4000, "vat": 25 },
+                    { "id": "b", "line_id": "2", "description": "Cap", "quantity": 1, "amount": 10000, "vat_amount": 2000, "vat": 25 },
+                ],
+                "events": events,
+            }))
+            .unwrap();
+            TransactionLedger::new(transaction).unwrap()
+        };
+
+        let captured = ledger("PARTIALLY_CAPTURED", false);
+        assert_eq!(captured.capturable_amount(), 10000);
+        let shirt = captured.line("1").unwrap();
+        assert_eq!(
+            (shirt.capturable_amount(), shirt.capturable_quantity()),
+            (10000, 1)
+        );
+        let remaining = captured.capture_remaining().unwrap();
+        assert_eq!(remaining.amount, 10000);
+        assert!(captured.validate_capture(&remaining).is_ok());
+        assert!(captured.validate_capture(&CaptureRequest::new(10001)).is_err());
+
+        let over = CaptureRequest::new(10000).with_items(vec![CaptureItem {
+            line_id: "2".into(),
+            amount: 10000,
+            quantity: None,
+        }]);
+        assert!(captured.validate_capture(&over).is_err());
+        assert_eq!(captured.voidable_amount(), 0);
+        assert!(captured.void_authorization().is_err());
+
+        // Like the API, nothing more can be captured once part of it is refunded.
+        let refunded = ledger("PARTIALLY_REFUNDED", true);
+        assert_eq!(refunded.capturable_amount(), 0);
+        assert!(refunded.capture_remaining().is_err());
+        assert!(refunded.validate_capture(&CaptureRequest::new(100)).is_err());
+        assert_eq!(refunded.refundable_amount(), 10000);
+        let cap = refunded.line("2").unwrap();
+        assert_eq!(cap.refundable_amount(), 0);
+        assert_eq!(cap.refunded_quantity, 1);
+
+        let refund = refunded.refund_line("1").unwrap();
+        assert_eq!(refund.amount, 10000);
+        assert!(refunded.validate_refund(&refund).is_ok());
+        assert!(refunded.refund_line("2").is_err());
+        assert!(refunded.refund_line("3").is_err());
+    }
+}
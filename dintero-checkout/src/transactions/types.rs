@@ -1,6 +1,8 @@
 //! Type definitions and data structures.
 
+use crate::sessions::OrderItem;
 use chrono::{DateTime, Utc};
+use dintero_core::{Currency, Money};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -8,6 +10,12 @@ use serde::{Deserialize, Serialize};
 pub enum TransactionStatus {
     OnHold,
     Authorized,
+    PartiallyCaptured,
+    Captured,
+    PartiallyRefunded,
+    Refunded,
+    #[serde(alias = "AUTHORIZATION_VOIDED")]
+    Voided,
     PartiallySettled,
     Settled,
     Cancelled,
@@ -20,7 +28,7 @@ pub struct Transaction {
     pub session_id: String,
     pub status: TransactionStatus,
     pub amount: i64,
-    pub currency: String,
+    pub currency: Currency,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub created_at: Option<DateTime<Utc>>,
@@ -33,6 +41,76 @@ pub struct Transaction {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub payment_product: Option<String>,
+
+    /// The order lines the transaction pays for.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub items: Option<Vec<OrderItem>>,
+
+    /// Operations made on the transaction, oldest first.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub events: Option<Vec<TransactionEvent>>,
+}
+
+impl Transaction {
+    /// Returns the amount together with its currency.
+    pub fn money(&self) -> Money {
+        Money::new(self.amount, self.currency)
+    }
+}
+
+/// Kind of a transaction event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TransactionEventType {
+    Initialize,
+    Authorize,
+    Capture,
+    Refund,
+    #[serde(alias = "AUTHORIZATION_VOIDED")]
+    Void,
+    ExtendAuthorization,
+    /// Any other event, e.g. an update of the merchant reference.
+    #[serde(other)]
+    Other,
+}
+
+/// An operation made on a transaction.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionEvent {
+    pub event: TransactionEventType,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    /// Whether the operation succeeded. Events without it are taken as successful.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub success: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<i64>,
+
+    /// The lines the operation applied to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub items: Option<Vec<TransactionEventItem>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl TransactionEvent {
+    /// Returns `true` unless the event records a failed operation.
+    pub fn is_success(&self) -> bool {
+        self.success.unwrap_or(true)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionEventItem {
+    pub line_id: String,
+    pub amount: i64,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quantity: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -0,0 +1,414 @@
+//! Error types shared by all Dintero API clients.
+//!
+//! Every client returns [`DinteroError`]. Errors reported by the API itself are
+//! carried as a structured [`ApiError`], so callers can branch on the HTTP status,
+//! the Dintero error [`ErrorCode`], field-level validation errors and the request id
+//! without matching on message strings.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
+
+/// Boxed error used for transport failures.
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Response headers that may carry the id Dintero assigned to a request.
+pub const REQUEST_ID_HEADERS: [&str; 2] = ["request-id", "x-request-id"];
+
+/// Result type for Dintero SDK operations.
+pub type Result<T> = std::result::Result<T, DinteroError>;
+
+/// Error type returned by all Dintero SDK operations.
+#[derive(Debug, thiserror::Error)]
+pub enum DinteroError {
+    /// The API responded with an error status.
+    #[error(transparent)]
+    Api(Box<ApiError>),
+
+    /// The request could not be sent or the response could not be read.
+    #[error("HTTP request failed: {0}")]
+    Http(#[source] BoxError),
+
+    /// The request timed out.
+    #[error("Request timed out: {0}")]
+    Timeout(#[source] BoxError),
+
+    /// Authentication failed.
+    #[error("Authentication failed: {0}")]
+    Auth(String),
+
+    /// Validation error for input parameters.
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    /// Serialization/deserialization error.
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    /// Invalid URL.
+    #[error("Invalid URL: {0}")]
+    InvalidUrl(#[from] url::ParseError),
+
+    /// Configuration error.
+    #[error("Configuration error: {0}")]
+    Config(String),
+}
+
+impl DinteroError {
+    /// Returns the API error details, if the API rejected the request.
+    pub fn api_error(&self) -> Option<&ApiError> {
+        match self {
+            DinteroError::Api(err) => Some(err),
+            _ => None,
+        }
+    }
+
+    /// Returns the HTTP status code returned by the API, if any.
+    pub fn status(&self) -> Option<u16> {
+        self.api_error().map(|err| err.status)
+    }
+
+    /// Returns the Dintero error code, if the API rejected the request.
+    pub fn code(&self) -> Option<&ErrorCode> {
+        self.api_error().map(|err| &err.code)
+    }
+
+    /// Returns the request id reported by the API, if any.
+    pub fn request_id(&self) -> Option<&str> {
+        self.api_error().and_then(|err| err.request_id.as_deref())
+    }
+
+    /// Returns `true` if retrying the same request may succeed.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            DinteroError::Api(err) => err.retryable,
+            DinteroError::Timeout(_) => true,
+            _ => false,
+        }
+    }
+}
+
+impl From<ApiError> for DinteroError {
+    fn from(err: ApiError) -> Self {
+        DinteroError::Api(Box::new(err))
+    }
+}
+
+#[cfg(feature = "reqwest")]
+impl From<reqwest::Error> for DinteroError {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_timeout() {
+            DinteroError::Timeout(Box::new(err))
+        } else {
+            DinteroError::Http(Box::new(err))
+        }
+    }
+}
+
+/// Error details returned by the Dintero API.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiError {
+    /// HTTP status code.
+    pub status: u16,
+    /// Dintero error code.
+    pub code: ErrorCode,
+    /// Human-readable error message.
+    pub message: String,
+    /// Field-level validation errors.
+    pub field_errors: Vec<FieldError>,
+    /// Id of the failed request, useful when contacting Dintero support.
+    pub request_id: Option<String>,
+    /// How long the API asked the client to wait before retrying.
+    pub retry_after: Option<Duration>,
+    /// Whether retrying the same request may succeed.
+    pub retryable: bool,
+}
+
+impl ApiError {
+    /// Creates an error for the given HTTP status and message.
+    pub fn new(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            code: ErrorCode::from_status(status),
+            message: message.into(),
+            field_errors: Vec::new(),
+            request_id: None,
+            retry_after: None,
+            retryable: is_retryable_status(status),
+        }
+    }
+
+    /// Creates an error from an error response body.
+    ///
+    /// Understands the Dintero `{"error": {"code", "message", "errors"}}` format and
+    /// falls back to using the raw body as the message.
+    pub fn from_body(status: u16, body: &[u8]) -> Self {
+        let parsed = serde_json::from_slice::<serde_json::Value>(body).ok().and_then(|value| {
+            let error = value.get("error").cloned().unwrap_or(value);
+            match error {
+                serde_json::Value::String(message) => Some(ErrorBody {
+                    message: Some(message),
+                    ..Default::default()
+                }),
+                serde_json::Value::Object(_) => serde_json::from_value(error).ok(),
+                _ => None,
+            }
+        });
+
+        let Some(parsed) = parsed else {
+            return Self::new(status, String::from_utf8_lossy(body).trim());
+        };
+
+        let mut error = Self::new(status, parsed.message.unwrap_or_default());
+        if let Some(code) = parsed.code.as_deref().filter(|code| !code.is_empty()) {
+            error.code = ErrorCode::parse(code);
+        }
+        error.field_errors = parsed.errors.into_iter().map(FieldError::from_value).collect();
+        error
+    }
+
+    /// Sets the request id.
+    pub fn with_request_id(mut self, request_id: impl Into<String>) -> Self {
+        self.request_id = Some(request_id.into());
+        self
+    }
+
+    /// Sets the delay the API asked for before retrying.
+    pub fn with_retry_after(mut self, retry_after: Duration) -> Self {
+        self.retry_after = Some(retry_after);
+        self
+    }
+
+    /// Creates an error from a failed HTTP response, consuming its body.
+    #[cfg(feature = "reqwest")]
+    pub async fn from_response(response: reqwest::Response) -> Self {
+        let status = response.status().as_u16();
+        let headers = response.headers();
+
+        let request_id = REQUEST_ID_HEADERS
+            .iter()
+            .find_map(|name| headers.get(*name))
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+
+        let retry_after = headers
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(parse_retry_after);
+
+        let body = response.bytes().await.unwrap_or_default();
+
+        let mut error = Self::from_body(status, &body);
+        error.request_id = request_id;
+        error.retry_after = retry_after;
+        error
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "API error ({} {}): {}",
+            self.status, self.code, self.message
+        )?;
+        if let Some(request_id) = &self.request_id {
+            write!(f, " [request id: {}]", request_id)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+/// Parses a `Retry-After` header given in seconds or as an HTTP date.
+///
+/// A date in the past means the request can be retried right away.
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = httpdate::parse_http_date(value).ok()?;
+    Some(date.duration_since(std::time::SystemTime::now()).unwrap_or_default())
+}
+
+fn is_retryable_status(status: u16) -> bool {
+    matches!(status, 408 | 429 | 500 | 502 | 503 | 504)
+}
+
+#[derive(Default, Deserialize)]
+struct ErrorBody {
+    #[serde(default)]
+    code: Option<String>,
+    #[serde(default)]
+    message: Option<String>,
+    #[serde(default)]
+    errors: Vec<serde_json::Value>,
+}
+
+/// A validation error for a single request field.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct FieldError {
+    /// Path of the offending field, e.g. `order.items[0].amount`.
+    #[serde(default, alias = "path", alias = "property", skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    /// Description of the problem.
+    #[serde(default)]
+    pub message: String,
+    /// Machine-readable error code for the field.
+    #[serde(default, alias = "type", skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+}
+
+impl FieldError {
+    fn from_value(value: serde_json::Value) -> Self {
+        match value {
+            serde_json::Value::String(message) => Self { message, ..Default::default() },
+            other => serde_json::from_value(other.clone()).unwrap_or_else(|_| Self {
+                message: other.to_string(),
+                ..Default::default()
+            }),
+        }
+    }
+}
+
+/// Dintero error code.
+///
+/// Known codes are mapped to dedicated variants. When the API does not send a code,
+/// the variant is derived from the HTTP status.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    /// The request was malformed or failed validation.
+    InvalidRequest,
+    /// Credentials are missing, invalid or expired.
+    Unauthorized,
+    /// The credentials do not grant access to the resource.
+    Forbidden,
+    /// The resource does not exist.
+    NotFound,
+    /// The request conflicts with the current state of the resource.
+    Conflict,
+    /// The payment was declined.
+    Declined,
+    /// The payment instrument has insufficient funds.
+    InsufficientFunds,
+    /// Too many requests were sent.
+    RateLimited,
+    /// Dintero failed to process the request.
+    ServerError,
+    /// The service is temporarily unavailable.
+    ServiceUnavailable,
+    /// Any other error code, as sent by the API.
+    Other(String),
+}
+
+impl ErrorCode {
+    /// Parses an error code sent by the API.
+    pub fn parse(code: &str) -> Self {
+        match code.trim().to_ascii_uppercase().replace('-', "_").as_str() {
+            "INVALID_REQUEST" | "BAD_REQUEST" | "VALIDATION_ERROR" | "INVALID_INPUT" => {
+                ErrorCode::InvalidRequest
+            }
+            "UNAUTHORIZED" | "INVALID_TOKEN" | "TOKEN_EXPIRED" => ErrorCode::Unauthorized,
+            "FORBIDDEN" | "ACCESS_DENIED" => ErrorCode::Forbidden,
+            "NOT_FOUND" => ErrorCode::NotFound,
+            "CONFLICT" | "DUPLICATE" => ErrorCode::Conflict,
+            "DECLINED" | "CARD_DECLINED" | "PAYMENT_DECLINED" => ErrorCode::Declined,
+            "INSUFFICIENT_FUNDS" => ErrorCode::InsufficientFunds,
+            "RATE_LIMITED" | "RATE_LIMIT_EXCEEDED" | "TOO_MANY_REQUESTS" => ErrorCode::RateLimited,
+            "INTERNAL_ERROR" | "SERVER_ERROR" => ErrorCode::ServerError,
+            "SERVICE_UNAVAILABLE" => ErrorCode::ServiceUnavailable,
+            _ => ErrorCode::Other(code.to_string()),
+        }
+    }
+
+    /// Derives an error code from an HTTP status.
+    pub fn from_status(status: u16) -> Self {
+        match status {
+            400 | 422 => ErrorCode::InvalidRequest,
+            401 => ErrorCode::Unauthorized,
+            403 => ErrorCode::Forbidden,
+            404 => ErrorCode::NotFound,
+            409 => ErrorCode::Conflict,
+            402 => ErrorCode::Declined,
+            429 => ErrorCode::RateLimited,
+            503 => ErrorCode::ServiceUnavailable,
+            500..=599 => ErrorCode::ServerError,
+            _ => ErrorCode::Other(status.to_string()),
+        }
+    }
+
+    /// Returns the code as a string.
+    pub fn as_str(&self) -> &str {
+        match self {
+            ErrorCode::InvalidRequest => "INVALID_REQUEST",
+            ErrorCode::Unauthorized => "UNAUTHORIZED",
+            ErrorCode::Forbidden => "FORBIDDEN",
+            ErrorCode::NotFound => "NOT_FOUND",
+            ErrorCode::Conflict => "CONFLICT",
+            ErrorCode::Declined => "DECLINED",
+            ErrorCode::InsufficientFunds => "INSUFFICIENT_FUNDS",
+            ErrorCode::RateLimited => "RATE_LIMITED",
+            ErrorCode::ServerError => "SERVER_ERROR",
+            ErrorCode::ServiceUnavailable => "SERVICE_UNAVAILABLE",
+            ErrorCode::Other(code) => code,
+        }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_error_from_dintero_body() {
+        let body = br#"{
+            "error": {
+                "code": "insufficient_funds",
+                "message": "Payment failed",
+                "errors": [{"path": "order.amount", "message": "too large"}, "bad item"]
+            }
+        }"#;
+
+        let error = ApiError::from_body(402, body).with_request_id("req-1");
+        assert_eq!(error.code, ErrorCode::InsufficientFunds);
+        assert_eq!(error.message, "Payment failed");
+        assert_eq!(error.field_errors.len(), 2);
+        assert_eq!(error.field_errors[0].field.as_deref(), Some("order.amount"));
+        assert_eq!(error.field_errors[1].message, "bad item");
+        assert!(!error.retryable);
+
+        let error = DinteroError::from(error);
+        assert_eq!(error.status(), Some(402));
+        assert_eq!(error.request_id(), Some("req-1"));
+    }
+
+    #[test]
+    fn test_api_error_from_plain_body() {
+        let error = ApiError::from_body(503, b"upstream unavailable");
+        assert_eq!(error.code, ErrorCode::ServiceUnavailable);
+        assert_eq!(error.message, "upstream unavailable");
+        assert!(DinteroError::from(error).is_retryable());
+    }
+
+    #[test]
+    fn test_parse_retry_after_seconds_and_dates() {
+        assert_eq!(parse_retry_after(" 120 "), Some(Duration::from_secs(120)));
+        assert_eq!(
+            parse_retry_after("Sun, 06 Nov 1994 08:49:37 GMT"),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon"), None);
+
+        let later = std::time::SystemTime::now() + Duration::from_secs(90);
+        let delay = parse_retry_after(&httpdate::fmt_http_date(later)).unwrap();
+        assert!(delay > Duration::from_secs(85) && delay <= Duration::from_secs(90));
+    }
+}
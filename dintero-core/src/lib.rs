@@ -0,0 +1,26 @@
+//! Copyright (c) 2024 Budna Marketplace AB
+//! Author: Marcus Cvjeticanin
+//!
+//! Shared building blocks for the Dintero SDK crates.
+//!
+//! This crate provides the error type, the money type, order line totals, the per-request
+//! options, API paths, the retry policies and the auto-pagination stream shared by every
+//! Dintero API client.
+
+pub mod error;
+pub mod money;
+pub mod pagination;
+pub mod path;
+pub mod request;
+pub mod retry;
+pub mod totals;
+
+pub use error::{ApiError, BoxError, DinteroError, ErrorCode, FieldError, Result};
+pub use money::{Currency, Money, MoneyError, RoundingMode};
+pub use pagination::{paginate, Cursor, Page, PageRequest, Paginator};
+pub use path::{ApiPath, Endpoint, PathTemplate};
+pub use request::{Idempotent, RequestOptions};
+pub use retry::{Jitter, RetryAttempt, RetryBudget, RetryConfig, RetryFailure, RetryPolicy};
+pub use totals::{
+    LineItem, Totals, TotalsError, TotalsErrors, TotalsMode, TotalsValidator, VatRate,
+};
@@ -0,0 +1,813 @@
+//! Currency-tagged amounts of money.
+//!
+//! A [`Money`] value is a whole number of minor units (øre, cents, fils, ...) together with
+//! its [`Currency`]. Arithmetic is checked: mixing currencies or overflowing returns a
+//! [`MoneyError`] instead of a wrong amount. Decimal strings are parsed and formatted
+//! exactly, using the number of minor units defined by ISO 4217, so `"0.1"` never turns
+//! into `0.09999`.
+//!
+//! ```
+//! use dintero_core::{Currency, Money, RoundingMode};
+//!
+//! let total = Money::parse("100.00", Currency::Nok)?;
+//! let shares = total.split(3, RoundingMode::HalfEven)?;
+//! assert_eq!(shares[0].to_string(), "33.34 NOK");
+//! assert_eq!(shares[1].to_string(), "33.33 NOK");
+//!
+//! let vat = total.mul_ratio(25, 125, RoundingMode::HalfUp)?;
+//! assert_eq!(vat.to_decimal_string(), "20.00");
+//! # Ok::<(), dintero_core::MoneyError>(())
+//! ```
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Mutex, PoisonError};
+
+/// Errors returned by [`Money`] operations.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum MoneyError {
+    /// The operands have different currencies.
+    #[error("currency mismatch: expected {expected}, found {found}")]
+    CurrencyMismatch {
+        /// Currency of the left-hand operand.
+        expected: Currency,
+        /// Currency of the right-hand operand.
+        found: Currency,
+    },
+
+    /// The result does not fit in an `i64` number of minor units.
+    #[error("amount overflow")]
+    Overflow,
+
+    /// The string is not a decimal amount.
+    #[error("invalid amount: {0:?}")]
+    InvalidAmount(String),
+
+    /// The amount has more decimals than the currency allows.
+    #[error("{currency} amounts have at most {} decimals", currency.minor_units())]
+    TooManyDecimals {
+        /// Currency of the amount.
+        currency: Currency,
+    },
+
+    /// The string is not an ISO 4217 currency code.
+    #[error("unknown currency code: {0:?}")]
+    UnknownCurrency(String),
+
+    /// The ratios of an allocation are empty or sum to zero.
+    #[error("invalid allocation: {0}")]
+    InvalidAllocation(&'static str),
+}
+
+impl From<MoneyError> for crate::DinteroError {
+    fn from(err: MoneyError) -> Self {
+        crate::DinteroError::Validation(err.to_string())
+    }
+}
+
+macro_rules! currencies {
+    ($($(#[$attr:meta])* $variant:ident = ($code:literal, $numeric:literal, $minor:literal),)*) => {
+        /// An ISO 4217 currency.
+        ///
+        /// Serialized as its three-letter code, e.g. `"NOK"`.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+        #[non_exhaustive]
+        pub enum Currency {
+            $(
+                #[doc = $code]
+                $(#[$attr])*
+                $variant,
+            )*
+            /// A currency code missing from the ISO 4217 table of this version, e.g. one
+            /// introduced after its release, so responses using it still deserialize.
+            ///
+            /// Assumed to have 2 decimals. Create it with [`Currency::try_from`] so known codes
+            /// map to their variant.
+            Other(&'static str),
+        }
+
+        impl Currency {
+            /// Every currency in the ISO 4217 table.
+            pub const ALL: &'static [Currency] = &[$(Currency::$variant,)*];
+
+            /// Returns the three-letter code, e.g. `"NOK"`.
+            pub fn code(&self) -> &'static str {
+                match self {
+                    $(Currency::$variant => $code,)*
+                    Currency::Other(code) => code,
+                }
+            }
+
+            /// Returns the numeric code, e.g. `578` for NOK, or 0 for
+            /// [`Currency::Other`].
+            pub fn numeric_code(&self) -> u16 {
+                match self {
+                    $(Currency::$variant => $numeric,)*
+                    Currency::Other(_) => 0,
+                }
+            }
+
+            /// Returns the number of decimals of the minor unit: 2 for NOK, 0 for JPY and
+            /// 3 for KWD.
+            pub fn minor_units(&self) -> u32 {
+                match self {
+                    $(Currency::$variant => $minor,)*
+                    Currency::Other(_) => 2,
+                }
+            }
+
+            /// Looks up a currency in the ISO 4217 table by its three-letter code, ignoring
+            /// case.
+            pub fn from_code(code: &str) -> Option<Self> {
+                match code.to_ascii_uppercase().as_str() {
+                    $($code => Some(Currency::$variant),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+currencies! {
+    Aed = ("AED", 784, 2),
+    Afn = ("AFN", 971, 2),
+    All = ("ALL", 8, 2),
+    Amd = ("AMD", 51, 2),
+    Ang = ("ANG", 532, 2),
+    Aoa = ("AOA", 973, 2),
+    Ars = ("ARS", 32, 2),
+    Aud = ("AUD", 36, 2),
+    Awg = ("AWG", 533, 2),
+    Azn = ("AZN", 944, 2),
+    Bam = ("BAM", 977, 2),
+    Bbd = ("BBD", 52, 2),
+    Bdt = ("BDT", 50, 2),
+    Bgn = ("BGN", 975, 2),
+    Bhd = ("BHD", 48, 3),
+    Bif = ("BIF", 108, 0),
+    Bmd = ("BMD", 60, 2),
+    Bnd = ("BND", 96, 2),
+    Bob = ("BOB", 68, 2),
+    Bov = ("BOV", 984, 2),
+    Brl = ("BRL", 986, 2),
+    Bsd = ("BSD", 44, 2),
+    Btn = ("BTN", 64, 2),
+    Bwp = ("BWP", 72, 2),
+    Byn = ("BYN", 933, 2),
+    Bzd = ("BZD", 84, 2),
+    Cad = ("CAD", 124, 2),
+    Cdf = ("CDF", 976, 2),
+    Che = ("CHE", 947, 2),
+    Chf = ("CHF", 756, 2),
+    Chw = ("CHW", 948, 2),
+    Clf = ("CLF", 990, 4),
+    Clp = ("CLP", 152, 0),
+    Cny = ("CNY", 156, 2),
+    Cop = ("COP", 170, 2),
+    Cou = ("COU", 970, 2),
+    Crc = ("CRC", 188, 2),
+    Cup = ("CUP", 192, 2),
+    Cve = ("CVE", 132, 2),
+    Czk = ("CZK", 203, 2),
+    Djf = ("DJF", 262, 0),
+    Dkk = ("DKK", 208, 2),
+    Dop = ("DOP", 214, 2),
+    Dzd = ("DZD", 12, 2),
+    Egp = ("EGP", 818, 2),
+    Ern = ("ERN", 232, 2),
+    Etb = ("ETB", 230, 2),
+    Eur = ("EUR", 978, 2),
+    Fjd = ("FJD", 242, 2),
+    Fkp = ("FKP", 238, 2),
+    Gbp = ("GBP", 826, 2),
+    Gel = ("GEL", 981, 2),
+    Ghs = ("GHS", 936, 2),
+    Gip = ("GIP", 292, 2),
+    Gmd = ("GMD", 270, 2),
+    Gnf = ("GNF", 324, 0),
+    Gtq = ("GTQ", 320, 2),
+    Gyd = ("GYD", 328, 2),
+    Hkd = ("HKD", 344, 2),
+    Hnl = ("HNL", 340, 2),
+    Htg = ("HTG", 332, 2),
+    Huf = ("HUF", 348, 2),
+    Idr = ("IDR", 360, 2),
+    Ils = ("ILS", 376, 2),
+    Inr = ("INR", 356, 2),
+    Iqd = ("IQD", 368, 3),
+    Irr = ("IRR", 364, 2),
+    Isk = ("ISK", 352, 0),
+    Jmd = ("JMD", 388, 2),
+    Jod = ("JOD", 400, 3),
+    Jpy = ("JPY", 392, 0),
+    Kes = ("KES", 404, 2),
+    Kgs = ("KGS", 417, 2),
+    Khr = ("KHR", 116, 2),
+    Kmf = ("KMF", 174, 0),
+    Kpw = ("KPW", 408, 2),
+    Krw = ("KRW", 410, 0),
+    Kwd = ("KWD", 414, 3),
+    Kyd = ("KYD", 136, 2),
+    Kzt = ("KZT", 398, 2),
+    Lak = ("LAK", 418, 2),
+    Lbp = ("LBP", 422, 2),
+    Lkr = ("LKR", 144, 2),
+    Lrd = ("LRD", 430, 2),
+    Lsl = ("LSL", 426, 2),
+    Lyd = ("LYD", 434, 3),
+    Mad = ("MAD", 504, 2),
+    Mdl = ("MDL", 498, 2),
+    Mga = ("MGA", 969, 2),
+    Mkd = ("MKD", 807, 2),
+    Mmk = ("MMK", 104, 2),
+    Mnt = ("MNT", 496, 2),
+    Mop = ("MOP", 446, 2),
+    Mru = ("MRU", 929, 2),
+    Mur = ("MUR", 480, 2),
+    Mvr = ("MVR", 462, 2),
+    Mwk = ("MWK", 454, 2),
+    Mxn = ("MXN", 484, 2),
+    Mxv = ("MXV", 979, 2),
+    Myr = ("MYR", 458, 2),
+    Mzn = ("MZN", 943, 2),
+    Nad = ("NAD", 516, 2),
+    Ngn = ("NGN", 566, 2),
+    Nio = ("NIO", 558, 2),
+    #[default]
+    Nok = ("NOK", 578, 2),
+    Npr = ("NPR", 524, 2),
+    Nzd = ("NZD", 554, 2),
+    Omr = ("OMR", 512, 3),
+    Pab = ("PAB", 590, 2),
+    Pen = ("PEN", 604, 2),
+    Pgk = ("PGK", 598, 2),
+    Php = ("PHP", 608, 2),
+    Pkr = ("PKR", 586, 2),
+    Pln = ("PLN", 985, 2),
+    Pyg = ("PYG", 600, 0),
+    Qar = ("QAR", 634, 2),
+    Ron = ("RON", 946, 2),
+    Rsd = ("RSD", 941, 2),
+    Rub = ("RUB", 643, 2),
+    Rwf = ("RWF", 646, 0),
+    Sar = ("SAR", 682, 2),
+    Sbd = ("SBD", 90, 2),
+    Scr = ("SCR", 690, 2),
+    Sdg = ("SDG", 938, 2),
+    Sek = ("SEK", 752, 2),
+    Sgd = ("SGD", 702, 2),
+    Shp = ("SHP", 654, 2),
+    Sle = ("SLE", 925, 2),
+    Sll = ("SLL", 694, 2),
+    Sos = ("SOS", 706, 2),
+    Srd = ("SRD", 968, 2),
+    Ssp = ("SSP", 728, 2),
+    Stn = ("STN", 930, 2),
+    Svc = ("SVC", 222, 2),
+    Syp = ("SYP", 760, 2),
+    Szl = ("SZL", 748, 2),
+    Thb = ("THB", 764, 2),
+    Tjs = ("TJS", 972, 2),
+    Tmt = ("TMT", 934, 2),
+    Tnd = ("TND", 788, 3),
+    Top = ("TOP", 776, 2),
+    Try = ("TRY", 949, 2),
+    Ttd = ("TTD", 780, 2),
+    Twd = ("TWD", 901, 2),
+    Tzs = ("TZS", 834, 2),
+    Uah = ("UAH", 980, 2),
+    Ugx = ("UGX", 800, 0),
+    Usd = ("USD", 840, 2),
+    Usn = ("USN", 997, 2),
+    Uyi = ("UYI", 940, 0),
+    Uyu = ("UYU", 858, 2),
+    Uyw = ("UYW", 927, 4),
+    Uzs = ("UZS", 860, 2),
+    Ved = ("VED", 926, 2),
+    Ves = ("VES", 928, 2),
+    Vnd = ("VND", 704, 0),
+    Vuv = ("VUV", 548, 0),
+    Wst = ("WST", 882, 2),
+    Xaf = ("XAF", 950, 0),
+    Xcd = ("XCD", 951, 2),
+    Xof = ("XOF", 952, 0),
+    Xpf = ("XPF", 953, 0),
+    Yer = ("YER", 886, 2),
+    Zar = ("ZAR", 710, 2),
+    Zmw = ("ZMW", 967, 2),
+    Zwg = ("ZWG", 924, 2),
+    Zwl = ("ZWL", 932, 2),
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl FromStr for Currency {
+    type Err = MoneyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Currency::from_code(s.trim()).ok_or_else(|| MoneyError::UnknownCurrency(s.to_string()))
+    }
+}
+
+impl TryFrom<&str> for Currency {
+    type Error = MoneyError;
+
+    /// Looks up a currency by its code, ignoring case, and returns [`Currency::Other`]
+    /// for a code of three uppercase letters missing from the ISO 4217 table.
+    ///
+    /// Unknown codes are stored for the lifetime of the process, once per distinct code.
+    fn try_from(code: &str) -> Result<Self, Self::Error> {
+        let trimmed = code.trim();
+        if let Some(currency) = Currency::from_code(trimmed) {
+            Ok(currency)
+        } else if trimmed.len() == 3 && trimmed.bytes().all(|b| b.is_ascii_uppercase()) {
+            Ok(Currency::Other(intern(trimmed)))
+        } else {
+            Err(MoneyError::UnknownCurrency(code.to_string()))
+        }
+    }
+}
+
+impl TryFrom<String> for Currency {
+    type Error = MoneyError;
+
+    fn try_from(code: String) -> Result<Self, Self::Error> {
+        Currency::try_from(code.as_str())
+    }
+}
+
+/// Returns a `'static` copy of an unknown currency code.
+fn intern(code: &str) -> &'static str {
+    static CODES: Mutex<BTreeSet<&'static str>> = Mutex::new(BTreeSet::new());
+
+    let mut codes = CODES.lock().unwrap_or_else(PoisonError::into_inner);
+    match codes.get(code) {
+        Some(interned) => interned,
+        None => {
+            let interned: &'static str = Box::leak(code.to_string().into_boxed_str());
+            codes.insert(interned);
+            interned
+        }
+    }
+}
+
+impl Serialize for Currency {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.code())
+    }
+}
+
+impl<'de> Deserialize<'de> for Currency {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let code = String::deserialize(deserializer)?;
+        Currency::try_from(code).map_err(serde::de::Error::custom)
+    }
+}
+
+/// How to round a result that falls between two minor units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum RoundingMode {
+    /// Towards zero.
+    Down,
+    /// Away from zero.
+    Up,
+    /// Towards negative infinity.
+    Floor,
+    /// Towards positive infinity.
+    Ceiling,
+    /// To the nearest minor unit, with halves rounded away from zero.
+    HalfUp,
+    /// To the nearest minor unit, with halves rounded towards zero.
+    HalfDown,
+    /// To the nearest minor unit, with halves rounded to the even neighbour.
+    #[default]
+    HalfEven,
+}
+
+impl RoundingMode {
+    /// Divides `numerator` by a positive `denominator`, rounding the quotient.
+    pub(crate) fn div(self, numerator: i128, denominator: i128) -> i128 {
+        let quotient = numerator / denominator;
+        let remainder = numerator % denominator;
+        if remainder == 0 {
+            return quotient;
+        }
+
+        let away = quotient + numerator.signum();
+        let half = (remainder.abs() * 2).cmp(&denominator);
+        match (self, half) {
+            (RoundingMode::Down, _) => quotient,
+            (RoundingMode::Up, _) => away,
+            (RoundingMode::Floor, _) => quotient.min(away),
+            (RoundingMode::Ceiling, _) => quotient.max(away),
+            (_, Ordering::Greater) => away,
+            (_, Ordering::Less) => quotient,
+            (RoundingMode::HalfUp, Ordering::Equal) => away,
+            (RoundingMode::HalfDown, Ordering::Equal) => quotient,
+            (RoundingMode::HalfEven, Ordering::Equal) => {
+                if quotient % 2 == 0 {
+                    quotient
+                } else {
+                    away
+                }
+            }
+        }
+    }
+}
+
+/// An amount of money in the minor unit of its currency.
+///
+/// Serialized as `{"amount": 12345, "currency": "NOK"}`, the shape used by the Dintero
+/// API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Money {
+    amount: i64,
+    currency: Currency,
+}
+
+impl Money {
+    /// Creates an amount of `amount` minor units, e.g. øre for NOK.
+    pub const fn new(amount: i64, currency: Currency) -> Self {
+        Self { amount, currency }
+    }
+
+    /// Creates a zero amount.
+    pub const fn zero(currency: Currency) -> Self {
+        Self { amount: 0, currency }
+    }
+
+    /// Creates an amount of `major` whole units, e.g. kroner for NOK.
+    pub fn from_major(major: i64, currency: Currency) -> Result<Self, MoneyError> {
+        let amount = major.checked_mul(scale(currency)).ok_or(MoneyError::Overflow)?;
+        Ok(Self { amount, currency })
+    }
+
+    /// Parses a decimal amount such as `"123.45"` or `"-0.5"`.
+    ///
+    /// Fails if the amount has more significant decimals than the currency allows.
+    pub fn parse(amount: &str, currency: Currency) -> Result<Self, MoneyError> {
+        let (numerator, extra_digits) = parse_decimal(amount, currency)?;
+        let denominator = 10_i128.pow(extra_digits);
+        if numerator % denominator != 0 {
+            return Err(MoneyError::TooManyDecimals { currency });
+        }
+        Self::from_i128(numerator / denominator, currency)
+    }
+
+    /// Parses a decimal amount, rounding it to the minor unit of the currency.
+    pub fn parse_rounded(
+        amount: &str,
+        currency: Currency,
+        mode: RoundingMode,
+    ) -> Result<Self, MoneyError> {
+        let (numerator, extra_digits) = parse_decimal(amount, currency)?;
+        Self::from_i128(mode.div(numerator, 10_i128.pow(extra_digits)), currency)
+    }
+
+    fn from_i128(amount: i128, currency: Currency) -> Result<Self, MoneyError> {
+        let amount = i64::try_from(amount).map_err(|_| MoneyError::Overflow)?;
+        Ok(Self { amount, currency })
+    }
+
+    /// Returns the amount in minor units.
+    pub fn amount(&self) -> i64 {
+        self.amount
+    }
+
+    /// Returns the currency.
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    /// Returns `true` if the amount is zero.
+    pub fn is_zero(&self) -> bool {
+        self.amount == 0
+    }
+
+    /// Returns `true` if the amount is greater than zero.
+    pub fn is_positive(&self) -> bool {
+        self.amount > 0
+    }
+
+    /// Returns `true` if the amount is less than zero.
+    pub fn is_negative(&self) -> bool {
+        self.amount < 0
+    }
+
+    /// Formats the amount as a decimal string without the currency, e.g. `"123.45"`.
+    pub fn to_decimal_string(&self) -> String {
+        let decimals = self.currency.minor_units() as usize;
+        let sign = if self.amount < 0 { "-" } else { "" };
+        let magnitude = self.amount.unsigned_abs();
+        if decimals == 0 {
+            return format!("{}{}", sign, magnitude);
+        }
+
+        let scale = scale(self.currency).unsigned_abs();
+        format!(
+            "{}{}.{:0decimals$}",
+            sign,
+            magnitude / scale,
+            magnitude % scale
+        )
+    }
+
+    fn check_currency(&self, other: &Money) -> Result<(), MoneyError> {
+        if self.currency == other.currency {
+            Ok(())
+        } else {
+            Err(MoneyError::CurrencyMismatch {
+                expected: self.currency,
+                found: other.currency,
+            })
+        }
+    }
+
+    /// Adds two amounts of the same currency.
+    pub fn checked_add(self, other: Money) -> Result<Money, MoneyError> {
+        self.check_currency(&other)?;
+        let amount = self.amount.checked_add(other.amount).ok_or(MoneyError::Overflow)?;
+        Ok(Money { amount, ..self })
+    }
+
+    /// Subtracts an amount of the same currency.
+    pub fn checked_sub(self, other: Money) -> Result<Money, MoneyError> {
+        self.check_currency(&other)?;
+        let amount = self.amount.checked_sub(other.amount).ok_or(MoneyError::Overflow)?;
+        Ok(Money { amount, ..self })
+    }
+
+    /// Multiplies the amount by a whole number, e.g. a quantity.
+    pub fn checked_mul(self, factor: i64) -> Result<Money, MoneyError> {
+        let amount = self.amount.checked_mul(factor).ok_or(MoneyError::Overflow)?;
+        Ok(Money { amount, ..self })
+    }
+
+    /// Multiplies the amount by `numerator / denominator`, rounding the result.
+    ///
+    /// For example, the VAT included in a gross amount at 25% is
+    /// `gross.mul_ratio(25, 125, mode)`.
+    pub fn mul_ratio(
+        self,
+        numerator: i64,
+        denominator: i64,
+        mode: RoundingMode,
+    ) -> Result<Money, MoneyError> {
+        if denominator == 0 {
+            return Err(MoneyError::InvalidAllocation("denominator is zero"));
+        }
+        let (numerator, denominator) = if denominator < 0 {
+            (-(numerator as i128), -(denominator as i128))
+        } else {
+            (numerator as i128, denominator as i128)
+        };
+        Self::from_i128(
+            mode.div(self.amount as i128 * numerator, denominator),
+            self.currency,
+        )
+    }
+
+    /// Sums amounts of the same currency. An empty iterator sums to zero.
+    pub fn sum<I>(currency: Currency, amounts: I) -> Result<Money, MoneyError>
+    where
+        I: IntoIterator<Item = Money>,
+    {
+        amounts.into_iter().try_fold(Money::zero(currency), Money::checked_add)
+    }
+
+    /// Splits the amount into shares proportional to `ratios`.
+    ///
+    /// Each share is first rounded with `mode`. The shares are then adjusted by one minor
+    /// unit at a time, starting with the share whose rounding was furthest off, so they
+    /// always add up to the original amount.
+    pub fn allocate(&self, ratios: &[u64], mode: RoundingMode) -> Result<Vec<Money>, MoneyError> {
+        let total: i128 = ratios.iter().map(|&ratio| ratio as i128).sum();
+        if total == 0 {
+            return Err(MoneyError::InvalidAllocation("ratios must not all be zero"));
+        }
+
+        let exact: Vec<i128> =
+            ratios.iter().map(|&ratio| self.amount as i128 * ratio as i128).collect();
+        let mut shares: Vec<i128> = exact.iter().map(|&n| mode.div(n, total)).collect();
+
+        // Remainders in units of 1/total minor unit: positive if the share was rounded down.
+        let mut order: Vec<usize> = (0..shares.len()).collect();
+        let remainder = |i: usize, shares: &[i128]| exact[i] - shares[i] * total;
+
+        let mut difference = self.amount as i128 - shares.iter().sum::<i128>();
+        if difference > 0 {
+            order.sort_by_key(|&i| std::cmp::Reverse(remainder(i, &shares)));
+        } else {
+            order.sort_by_key(|&i| remainder(i, &shares));
+        }
+        for &i in order.iter().cycle() {
+            if difference == 0 {
+                break;
+            }
+            shares[i] += difference.signum();
+            difference -= difference.signum();
+        }
+
+        shares.into_iter().map(|share| Self::from_i128(share, self.currency)).collect()
+    }
+
+    /// Splits the amount into `parts` equal shares that add up to the original amount.
+    pub fn split(&self, parts: usize, mode: RoundingMode) -> Result<Vec<Money>, MoneyError> {
+        if parts == 0 {
+            return Err(MoneyError::InvalidAllocation(
+                "cannot split into zero parts",
+            ));
+        }
+        self.allocate(&vec![1; parts], mode)
+    }
+}
+
+impl PartialOrd for Money {
+    /// Amounts of different currencies are not comparable.
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        (self.currency == other.currency).then(|| self.amount.cmp(&other.amount))
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.to_decimal_string(), self.currency)
+    }
+}
+
+impl FromStr for Money {
+    type Err = MoneyError;
+
+    /// Parses an amount followed or preceded by its currency code, e.g. `"123.45 NOK"` or
+    /// `"NOK 123.45"`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || MoneyError::InvalidAmount(s.to_string());
+        let (first, second) = s.trim().split_once(char::is_whitespace).ok_or_else(invalid)?;
+        let second = second.trim();
+
+        match Currency::from_code(first) {
+            Some(currency) => Money::parse(second, currency),
+            None => Money::parse(first, second.parse()?),
+        }
+    }
+}
+
+/// Returns 10^minor_units of the currency.
+fn scale(currency: Currency) -> i64 {
+    10_i64.pow(currency.minor_units())
+}
+
+/// Parses a decimal string into a number of `10^-(minor_units + extra_digits)` units.
+/// Returns the number and `extra_digits`.
+fn parse_decimal(amount: &str, currency: Currency) -> Result<(i128, u32), MoneyError> {
+    let invalid = || MoneyError::InvalidAmount(amount.to_string());
+    let trimmed = amount.trim();
+
+    let (negative, unsigned) = match trimmed.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, trimmed.strip_prefix('+').unwrap_or(trimmed)),
+    };
+    let (whole, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+
+    let all_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    if whole.is_empty() && fraction.is_empty() || !all_digits(whole) || !all_digits(fraction) {
+        return Err(invalid());
+    }
+    // Enough for any i64 amount; longer strings overflow anyway.
+    if whole.len() + fraction.len() > 30 {
+        return Err(MoneyError::Overflow);
+    }
+
+    let minor_units = currency.minor_units() as usize;
+    let extra_digits = fraction.len().saturating_sub(minor_units);
+    let digits = format!(
+        "{}{}{}",
+        whole,
+        fraction,
+        "0".repeat(minor_units - fraction.len().min(minor_units))
+    );
+    let value: i128 = if digits.is_empty() { 0 } else { digits.parse().map_err(|_| invalid())? };
+
+    Ok((if negative { -value } else { value }, extra_digits as u32))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_format_follow_minor_units() {
+        let nok = Money::parse("123.4", Currency::Nok).unwrap();
+        assert_eq!(nok.amount(), 12340);
+        assert_eq!(nok.to_string(), "123.40 NOK");
+
+        assert_eq!(
+            Money::parse("-0.05", Currency::Eur).unwrap().to_decimal_string(),
+            "-0.05"
+        );
+        assert_eq!(
+            Money::parse("1500", Currency::Jpy).unwrap().to_string(),
+            "1500 JPY"
+        );
+        assert_eq!(Money::parse("1.234", Currency::Kwd).unwrap().amount(), 1234);
+        assert_eq!(
+            "NOK 10.00".parse::<Money>().unwrap(),
+            Money::new(1000, Currency::Nok)
+        );
+
+        assert_eq!(
+            Money::parse("1.005", Currency::Nok),
+            Err(MoneyError::TooManyDecimals { currency: Currency::Nok })
+        );
+        assert_eq!(Money::parse("1.010", Currency::Nok).unwrap().amount(), 101);
+        assert_eq!(
+            Money::parse_rounded("1.005", Currency::Nok, RoundingMode::HalfEven).unwrap().amount(),
+            100
+        );
+        assert!(Money::parse("1.2.3", Currency::Nok).is_err());
+        assert!(Money::parse("99999999999999999999", Currency::Nok).is_err());
+    }
+
+    #[test]
+    fn test_checked_arithmetic() {
+        let a = Money::new(1000, Currency::Nok);
+        assert_eq!(
+            a.checked_add(Money::new(250, Currency::Nok)).unwrap().amount(),
+            1250
+        );
+        assert_eq!(
+            a.checked_sub(Money::new(250, Currency::Nok)).unwrap().amount(),
+            750
+        );
+        assert_eq!(a.checked_mul(3).unwrap().amount(), 3000);
+        assert_eq!(
+            a.checked_add(Money::new(1, Currency::Sek)),
+            Err(MoneyError::CurrencyMismatch {
+                expected: Currency::Nok,
+                found: Currency::Sek
+            })
+        );
+        assert_eq!(
+            Money::new(i64::MAX, Currency::Nok).checked_mul(2),
+            Err(MoneyError::Overflow)
+        );
+        assert_eq!(a.partial_cmp(&Money::new(1, Currency::Sek)), None);
+        assert_eq!(
+            Money::from_major(100, Currency::Nok).unwrap().amount(),
+            10000
+        );
+    }
+
+    #[test]
+    fn test_allocation_preserves_total() {
+        let total = Money::new(100, Currency::Nok);
+        let amounts = |shares: Vec<Money>| shares.iter().map(Money::amount).collect::<Vec<_>>();
+
+        assert_eq!(
+            amounts(total.split(3, RoundingMode::HalfEven).unwrap()),
+            [34, 33, 33]
+        );
+        assert_eq!(
+            amounts(total.allocate(&[1, 1, 1], RoundingMode::Up).unwrap()),
+            [33, 33, 34]
+        );
+        assert_eq!(
+            amounts(total.allocate(&[70, 20, 10], RoundingMode::Down).unwrap()),
+            [70, 20, 10]
+        );
+        assert_eq!(
+            amounts(Money::new(-5, Currency::Nok).split(2, RoundingMode::HalfUp).unwrap()),
+            [-2, -3]
+        );
+        assert!(total.allocate(&[0, 0], RoundingMode::HalfEven).is_err());
+
+        let vat = Money::new(12500, Currency::Nok).mul_ratio(25, 125, RoundingMode::HalfUp);
+        assert_eq!(vat.unwrap().amount(), 2500);
+    }
+
+    #[test]
+    fn test_serde_uses_currency_codes() {
+        let money = Money::new(12345, Currency::Nok);
+        let json = serde_json::to_string(&money).unwrap();
+        assert_eq!(json, r#"{"amount":12345,"currency":"NOK"}"#);
+        assert_eq!(serde_json::from_str::<Money>(&json).unwrap(), money);
+
+        let other: Currency = serde_json::from_str(r#""XYZ""#).unwrap();
+        assert_eq!(other, Currency::Other("XYZ"));
+        assert_eq!(other.code(), "XYZ");
+        assert_eq!(serde_json::to_string(&other).unwrap(), r#""XYZ""#);
+        assert_eq!(Currency::try_from("nok").unwrap(), Currency::Nok);
+        assert!(serde_json::from_str::<Currency>(r#""kroner""#).is_err());
+        assert!(serde_json::from_str::<Currency>(r#""xyz""#).is_err());
+        assert!(Currency::try_from("X1Z").is_err());
+        assert!("XYZ".parse::<Currency>().is_err());
+        assert!(Currency::ALL.iter().all(|c| Currency::from_code(c.code()) == Some(*c)));
+    }
+}
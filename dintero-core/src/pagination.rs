@@ -0,0 +1,312 @@
+//! Lazy auto-pagination of list endpoints.
+//!
+//! Dintero list endpoints page their results either with a `next_page_token` or with
+//! `limit`/`offset` parameters. [`Paginator`] hides both behind a [`Stream`] of items that
+//! fetches the next page only when the current one has been consumed:
+//!
+//! ```ignore
+//! use futures::TryStreamExt;
+//!
+//! client
+//!     .orders()
+//!     .orders_stream(ListOrdersParams::default())
+//!     .page_size(100)
+//!     .max_items(1_000)
+//!     .try_for_each(|order| async move {
+//!         println!("{}", order.id);
+//!         Ok(())
+//!     })
+//!     .await?;
+//! ```
+
+use crate::error::Result;
+use futures_core::future::BoxFuture;
+use futures_core::Stream;
+use std::collections::VecDeque;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Position of a page in a list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Cursor {
+    /// Opaque token returned by endpoints paging with `next_page_token`.
+    Token(String),
+    /// Offset of the first item, for endpoints paging with `limit`/`offset`.
+    Offset(u64),
+}
+
+/// Request for a single page.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PageRequest {
+    /// Position of the page, or `None` for the first page.
+    pub cursor: Option<Cursor>,
+    /// Requested number of items, or `None` for the endpoint's default.
+    pub page_size: Option<u32>,
+}
+
+impl PageRequest {
+    /// Returns the page token, if the cursor is token-based.
+    pub fn page_token(&self) -> Option<String> {
+        match &self.cursor {
+            Some(Cursor::Token(token)) => Some(token.clone()),
+            _ => None,
+        }
+    }
+
+    /// Returns the offset, if the cursor is offset-based.
+    pub fn offset(&self) -> Option<u64> {
+        match self.cursor {
+            Some(Cursor::Offset(offset)) => Some(offset),
+            _ => None,
+        }
+    }
+}
+
+/// A page of items together with the cursor of the next page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page<T> {
+    /// Items on this page.
+    pub items: Vec<T>,
+    /// Cursor of the next page, or `None` if this is the last page.
+    pub next: Option<Cursor>,
+}
+
+impl<T> Page<T> {
+    /// Creates the only page of an endpoint that does not page its results.
+    pub fn single(items: Vec<T>) -> Self {
+        Self { items, next: None }
+    }
+
+    /// Creates a page of a token-paged endpoint.
+    pub fn from_token(items: Vec<T>, next_page_token: Option<String>) -> Self {
+        let next = next_page_token.filter(|token| !token.is_empty()).map(Cursor::Token);
+        Self { items, next }
+    }
+
+    /// Creates a page of an offset-paged endpoint.
+    ///
+    /// The page is the last one if it holds fewer items than requested, or if
+    /// `total_count` is known and reached.
+    pub fn from_offset(items: Vec<T>, request: &PageRequest, total_count: Option<u64>) -> Self {
+        let end = request.offset().unwrap_or(0) + items.len() as u64;
+        let short = request.page_size.is_some_and(|size| items.len() < size as usize);
+        let complete = total_count.is_some_and(|total| end >= total);
+
+        let next = (!short && !complete).then_some(Cursor::Offset(end));
+        Self { items, next }
+    }
+}
+
+type FetchPage<'a, T> = Box<dyn FnMut(PageRequest) -> BoxFuture<'a, Result<Page<T>>> + Send + 'a>;
+
+/// A [`Stream`] of the items of a paged list endpoint.
+///
+/// Pages are fetched lazily as items are consumed. With [`prefetch`](Self::prefetch)
+/// enabled, the request for the next page is sent as soon as the current page arrives,
+/// so it is in flight while the current page is processed.
+///
+/// If fetching a page fails, the error is yielded after the items already received and
+/// the stream ends.
+pub struct Paginator<'a, T> {
+    fetch: FetchPage<'a, T>,
+    in_flight: Option<BoxFuture<'a, Result<Page<T>>>>,
+    next: Option<PageRequest>,
+    buffer: VecDeque<T>,
+    error: Option<crate::DinteroError>,
+    page_size: Option<u32>,
+    prefetch: bool,
+    remaining: Option<usize>,
+    last_page_len: usize,
+}
+
+/// Creates a [`Paginator`] that fetches pages with `fetch`.
+pub fn paginate<'a, T, F, Fut>(mut fetch: F) -> Paginator<'a, T>
+where
+    F: FnMut(PageRequest) -> Fut + Send + 'a,
+    Fut: Future<Output = Result<Page<T>>> + Send + 'a,
+{
+    Paginator {
+        fetch: Box::new(move |request| Box::pin(fetch(request))),
+        in_flight: None,
+        next: Some(PageRequest::default()),
+        buffer: VecDeque::new(),
+        error: None,
+        page_size: None,
+        prefetch: false,
+        remaining: None,
+        last_page_len: 0,
+    }
+}
+
+impl<'a, T> Paginator<'a, T> {
+    /// Sets the number of items requested per page.
+    pub fn page_size(mut self, page_size: u32) -> Self {
+        self.page_size = Some(page_size);
+        self
+    }
+
+    /// Requests the next page while the current one is being consumed.
+    pub fn prefetch(mut self, prefetch: bool) -> Self {
+        self.prefetch = prefetch;
+        self
+    }
+
+    /// Ends the stream after `max_items` items. Pages are shrunk to avoid fetching items
+    /// beyond the cap.
+    pub fn max_items(mut self, max_items: usize) -> Self {
+        self.remaining = Some(max_items);
+        self
+    }
+
+    fn start_fetch(&mut self) {
+        if let Some(mut request) = self.next.take() {
+            request.page_size = self.page_size;
+            if let Some(remaining) = self.remaining {
+                let remaining = remaining.saturating_sub(self.buffer.len()).max(1);
+                let capped = u32::try_from(remaining).unwrap_or(u32::MAX);
+                request.page_size = Some(self.page_size.map_or(capped, |size| size.min(capped)));
+            }
+            self.in_flight = Some((self.fetch)(request));
+        }
+    }
+
+    /// Whether to fetch the next page while items are still buffered: at most one page
+    /// ahead, and not beyond the item cap.
+    fn should_prefetch(&self) -> bool {
+        self.prefetch
+            && self.buffer.len() <= self.last_page_len
+            && self.remaining.is_none_or(|remaining| self.buffer.len() < remaining)
+    }
+}
+
+// The paginator never pins its items or the fetch closure.
+impl<T> Unpin for Paginator<'_, T> {}
+
+impl<T> Stream for Paginator<'_, T> {
+    type Item = Result<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+
+        loop {
+            if this.remaining == Some(0) {
+                this.in_flight = None;
+                return Poll::Ready(None);
+            }
+
+            if this.in_flight.is_none()
+                && this.error.is_none()
+                && (this.buffer.is_empty() || this.should_prefetch())
+            {
+                this.start_fetch();
+            }
+
+            if let Some(in_flight) = &mut this.in_flight {
+                match in_flight.as_mut().poll(cx) {
+                    Poll::Ready(Ok(page)) => {
+                        this.in_flight = None;
+                        // An empty page ends the list even if it carries a cursor.
+                        if !page.items.is_empty() {
+                            this.next = page.next.map(|cursor| PageRequest {
+                                cursor: Some(cursor),
+                                page_size: None,
+                            });
+                        }
+                        this.last_page_len = page.items.len();
+                        this.buffer.extend(page.items);
+                        continue;
+                    }
+                    Poll::Ready(Err(e)) => {
+                        this.in_flight = None;
+                        this.next = None;
+                        this.error = Some(e);
+                    }
+                    Poll::Pending if this.buffer.is_empty() => return Poll::Pending,
+                    Poll::Pending => {}
+                }
+            }
+
+            if let Some(item) = this.buffer.pop_front() {
+                if let Some(remaining) = &mut this.remaining {
+                    *remaining -= 1;
+                }
+                return Poll::Ready(Some(Ok(item)));
+            }
+
+            if let Some(e) = this.error.take() {
+                return Poll::Ready(Some(Err(e)));
+            }
+
+            if this.in_flight.is_none() && this.next.is_none() {
+                return Poll::Ready(None);
+            }
+        }
+    }
+}
+
+impl<T> fmt::Debug for Paginator<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Paginator")
+            .field("next", &self.next)
+            .field("buffered", &self.buffer.len())
+            .field("in_flight", &self.in_flight.is_some())
+            .field("page_size", &self.page_size)
+            .field("prefetch", &self.prefetch)
+            .field("remaining", &self.remaining)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+    use futures::TryStreamExt;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_paginates_offset_pages_up_to_max_items() {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&requests);
+
+        let items: Vec<u64> = block_on(
+            paginate(move |request: PageRequest| {
+                seen.lock().unwrap().push(request.clone());
+                async move {
+                    let offset = request.offset().unwrap_or(0);
+                    let size = request.page_size.unwrap_or(10) as u64;
+                    let items = (offset..(offset + size).min(25)).collect();
+                    Ok(Page::from_offset(items, &request, Some(25)))
+                }
+            })
+            .page_size(10)
+            .prefetch(true)
+            .max_items(22)
+            .try_collect(),
+        )
+        .unwrap();
+
+        assert_eq!(items, (0..22).collect::<Vec<_>>());
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[2].cursor, Some(Cursor::Offset(20)));
+        assert_eq!(requests[2].page_size, Some(2));
+    }
+
+    #[test]
+    fn test_error_ends_stream_after_buffered_items() {
+        let results: Vec<Result<&str>> = block_on(futures::StreamExt::collect(paginate(
+            |request: PageRequest| async move {
+                match request.page_token().as_deref() {
+                    None => Ok(Page::from_token(vec!["a", "b"], Some("next".to_string()))),
+                    Some(_) => Err(crate::DinteroError::Validation("boom".to_string())),
+                }
+            },
+        )));
+
+        assert_eq!(results.len(), 3);
+        assert!(results[2].is_err());
+    }
+}
@@ -0,0 +1,380 @@
+//! API paths with percent-encoded segments, and query strings.
+
+use crate::error::{DinteroError, Result};
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+use serde::Serialize;
+use std::fmt;
+
+/// Characters encoded in a path segment: everything that could end the segment or the
+/// path, or is not allowed in a URL path.
+const SEGMENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'/')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'[')
+    .add(b'\\')
+    .add(b']')
+    .add(b'^')
+    .add(b'`')
+    .add(b'{')
+    .add(b'|')
+    .add(b'}');
+
+/// A path below the API version, e.g. `accounts/T12345678/transactions/T12345678.abc`.
+///
+/// Segments added with [`segment`](Self::segment) are percent-encoded, so IDs and
+/// other values taken from user input cannot change the path structure or add a
+/// query string.
+///
+/// ```
+/// use dintero_core::ApiPath;
+///
+/// let path = ApiPath::new().segment("accounts").segment("T12345678").segment("a/b?c");
+/// assert_eq!(path.as_str(), "accounts/T12345678/a%2Fb%3Fc");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ApiPath {
+    path: String,
+}
+
+impl ApiPath {
+    /// Creates an empty path.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts a [`PathTemplate`], e.g. `accounts/{account_id}/transactions/{id}`.
+    pub fn template(template: impl Into<String>) -> PathTemplate {
+        PathTemplate::new(template)
+    }
+
+    /// Appends a percent-encoded segment.
+    pub fn segment(mut self, segment: impl AsRef<str>) -> Self {
+        if !self.path.is_empty() {
+            self.path.push('/');
+        }
+        let segment = segment.as_ref();
+        if !segment.is_empty() && segment.bytes().all(|b| b == b'.') {
+            // `.` and `..` would be resolved as relative path segments.
+            self.path.push_str(&"%2E".repeat(segment.len()));
+        } else {
+            self.path.extend(utf8_percent_encode(segment, SEGMENT));
+        }
+        self
+    }
+
+    /// Returns the encoded path.
+    pub fn as_str(&self) -> &str {
+        &self.path
+    }
+}
+
+impl fmt::Display for ApiPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.path)
+    }
+}
+
+impl AsRef<str> for ApiPath {
+    fn as_ref(&self) -> &str {
+        &self.path
+    }
+}
+
+/// A path with `{name}` placeholders, filled with percent-encoded parameters.
+///
+/// Placeholders must make up a whole segment. Literal segments are used as written.
+///
+/// ```
+/// use dintero_core::ApiPath;
+///
+/// let path = ApiPath::template("accounts/{account_id}/transactions/{id}/capture")
+///     .param("account_id", "T12345678")
+///     .param("id", "T12345678.4Xd 2")
+///     .build()
+///     .unwrap();
+/// assert_eq!(path.as_str(), "accounts/T12345678/transactions/T12345678.4Xd%202/capture");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathTemplate {
+    template: String,
+    params: Vec<(String, String)>,
+}
+
+impl PathTemplate {
+    /// Creates a template.
+    pub fn new(template: impl Into<String>) -> Self {
+        Self {
+            template: template.into(),
+            params: Vec::new(),
+        }
+    }
+
+    /// Sets the value of a placeholder.
+    pub fn param(mut self, name: impl Into<String>, value: impl fmt::Display) -> Self {
+        let name = name.into();
+        self.params.retain(|(existing, _)| *existing != name);
+        self.params.push((name, value.to_string()));
+        self
+    }
+
+    /// Returns `true` if the template has a placeholder named `name`.
+    pub fn has_placeholder(&self, name: &str) -> bool {
+        self.placeholders().any(|placeholder| placeholder == name)
+    }
+
+    /// Returns the template, e.g. `accounts/{account_id}/transactions/{id}`.
+    pub fn as_str(&self) -> &str {
+        &self.template
+    }
+
+    /// Returns `true` if the template has any placeholder.
+    pub fn has_placeholders(&self) -> bool {
+        self.placeholders().next().is_some()
+    }
+
+    /// Returns `true` if a value is set for `name`.
+    pub fn has_param(&self, name: &str) -> bool {
+        self.params.iter().any(|(existing, _)| existing == name)
+    }
+
+    fn placeholders(&self) -> impl Iterator<Item = &str> {
+        self.template.split('/').filter_map(placeholder)
+    }
+
+    /// Fills the placeholders.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DinteroError::Validation`] if a placeholder has no value, a value has
+    /// no placeholder, or the template contains a query string or fragment.
+    pub fn build(&self) -> Result<ApiPath> {
+        if self.template.contains(['?', '#']) {
+            return Err(DinteroError::Validation(format!(
+                "path template {:?} must not contain a query string or fragment",
+                self.template
+            )));
+        }
+        if let Some((name, _)) = self.params.iter().find(|(name, _)| !self.has_placeholder(name)) {
+            return Err(DinteroError::Validation(format!(
+                "path template {:?} has no placeholder {{{}}}",
+                self.template, name
+            )));
+        }
+
+        let mut path = String::with_capacity(self.template.len());
+        for segment in self.template.trim_start_matches('/').split('/') {
+            if !path.is_empty() {
+                path.push('/');
+            }
+            match placeholder(segment) {
+                Some(name) => {
+                    let (_, value) =
+                        self.params.iter().find(|(param, _)| param == name).ok_or_else(|| {
+                            DinteroError::Validation(format!(
+                                "missing value for path parameter {{{}}}",
+                                name
+                            ))
+                        })?;
+                    path.push_str(ApiPath::new().segment(value).as_str());
+                }
+                None => path.push_str(segment),
+            }
+        }
+
+        Ok(ApiPath { path })
+    }
+}
+
+fn placeholder(segment: &str) -> Option<&str> {
+    segment.strip_prefix('{')?.strip_suffix('}')
+}
+
+impl From<&str> for PathTemplate {
+    fn from(template: &str) -> Self {
+        Self::new(template)
+    }
+}
+
+impl From<String> for PathTemplate {
+    fn from(template: String) -> Self {
+        Self::new(template)
+    }
+}
+
+impl From<ApiPath> for PathTemplate {
+    fn from(path: ApiPath) -> Self {
+        // Braces are encoded in segments, so an encoded path has no placeholders.
+        Self::new(path.path)
+    }
+}
+
+/// An API endpoint: its method, route template and operation name.
+///
+/// Each API crate lists the endpoints its client calls in an `ENDPOINTS` table, which
+/// the transport uses to name the spans and metrics of a call instead of guessing the
+/// route from the URL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Endpoint {
+    /// HTTP method, e.g. `POST`.
+    pub method: &'static str,
+    /// Route below the API version, e.g.
+    /// `accounts/{account_id}/transactions/{transaction_id}/capture`.
+    pub route: &'static str,
+    /// Operation name, e.g. `checkout.capture_transaction`.
+    pub operation: &'static str,
+    /// Whether the API deduplicates requests to this endpoint by their `Idempotency-Key`
+    /// header, which makes them safe to retry.
+    pub idempotency_key: bool,
+}
+
+impl Endpoint {
+    /// Creates an endpoint.
+    pub const fn new(method: &'static str, route: &'static str, operation: &'static str) -> Self {
+        Self {
+            method,
+            route,
+            operation,
+            idempotency_key: false,
+        }
+    }
+
+    /// Marks the endpoint as deduplicating requests by their `Idempotency-Key` header.
+    pub const fn with_idempotency_key(mut self) -> Self {
+        self.idempotency_key = true;
+        self
+    }
+
+    /// Returns the endpoint serving a request for `path`, which may be an encoded path
+    /// or a route template.
+    ///
+    /// A placeholder matches any single segment. When several endpoints match, the one
+    /// with the most literal segments wins, so `accounts/current` is preferred over
+    /// `accounts/{account_id}`.
+    pub fn find<'a>(
+        endpoints: impl IntoIterator<Item = &'a Endpoint>,
+        method: &str,
+        path: &str,
+    ) -> Option<&'a Endpoint> {
+        endpoints
+            .into_iter()
+            .filter(|endpoint| endpoint.method.eq_ignore_ascii_case(method))
+            .filter_map(|endpoint| endpoint.literal_matches(path).map(|count| (count, endpoint)))
+            .max_by_key(|(count, _)| *count)
+            .map(|(_, endpoint)| endpoint)
+    }
+
+    /// Returns the number of literal segments if the route matches `path`.
+    fn literal_matches(&self, path: &str) -> Option<usize> {
+        let path = path.split(['?', '#']).next().unwrap_or_default().trim_matches('/');
+        let mut route = self.route.trim_matches('/').split('/');
+        let mut segments = path.split('/');
+        let mut literals = 0;
+
+        loop {
+            match (route.next(), segments.next()) {
+                (None, None) => return Some(literals),
+                (Some(expected), Some(segment)) => {
+                    if placeholder(expected).is_some() {
+                        if segment.is_empty() {
+                            return None;
+                        }
+                    } else if expected == segment {
+                        literals += 1;
+                    } else {
+                        return None;
+                    }
+                }
+                _ => return None,
+            }
+        }
+    }
+}
+
+/// Encodes query parameters, e.g. a struct or a slice of pairs, as a query string.
+///
+/// `None` fields are left out.
+pub fn encode_query<Q: Serialize + ?Sized>(query: &Q) -> Result<String> {
+    serde_urlencoded::to_string(query)
+        .map_err(|e| DinteroError::Validation(format!("invalid query parameters: {}", e)))
+}
+
+/// Appends encoded query parameters to a path.
+pub fn with_query<Q: Serialize + ?Sized>(path: &str, query: &Q) -> Result<String> {
+    let query = encode_query(query)?;
+    if query.is_empty() {
+        return Ok(path.to_string());
+    }
+    let separator = if path.contains('?') { '&' } else { '?' };
+    Ok(format!("{}{}{}", path, separator, query))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template_encodes_params_and_rejects_mistakes() {
+        let template = ApiPath::template("/accounts/{account_id}/orders/{id}");
+        let path = template.clone().param("account_id", "T1").param("id", "..").build().unwrap();
+        assert_eq!(path.as_str(), "accounts/T1/orders/%2E%2E");
+
+        assert!(template.clone().param("account_id", "T1").build().is_err());
+        assert!(template.param("account_id", "T1").param("id", 1).param("x", 2).build().is_err());
+        assert!(ApiPath::template("orders?limit=1").build().is_err());
+
+        #[derive(Serialize)]
+        struct Query {
+            limit: u32,
+            status: Option<&'static str>,
+        }
+        assert_eq!(
+            with_query("orders", &Query { limit: 10, status: None }).unwrap(),
+            "orders?limit=10"
+        );
+        assert_eq!(
+            with_query("orders?a=1", &[("b", "x y")]).unwrap(),
+            "orders?a=1&b=x+y"
+        );
+        assert_eq!(
+            with_query("customers", &(("limit", Some(5)), ("query", None::<&str>))).unwrap(),
+            "customers?limit=5"
+        );
+    }
+
+    #[test]
+    fn test_endpoint_matches_segments_and_prefers_literals() {
+        const ENDPOINTS: &[Endpoint] = &[
+            Endpoint::new("GET", "accounts/{account_id}", "accounts.get_account"),
+            Endpoint::new("GET", "accounts/current", "accounts.get_account_details"),
+            Endpoint::new(
+                "GET",
+                "accounts/{account_id}/customers/{customer_id}",
+                "loyalty.get_customer",
+            ),
+        ];
+        let find = |method, path| Endpoint::find(ENDPOINTS, method, path).map(|e| e.operation);
+
+        assert_eq!(
+            find("GET", "accounts/current"),
+            Some("accounts.get_account_details")
+        );
+        assert_eq!(find("get", "accounts/T1"), Some("accounts.get_account"));
+        assert_eq!(
+            find("GET", "accounts/T1/customers/john-doe?limit=1"),
+            Some("loyalty.get_customer")
+        );
+        assert_eq!(
+            find("GET", "accounts/{account_id}/customers/{id}"),
+            Some("loyalty.get_customer")
+        );
+        assert_eq!(find("PUT", "accounts/T1"), None);
+        assert_eq!(find("GET", "accounts/T1/customers"), None);
+        assert_eq!(find("GET", "accounts/T1/customers//"), None);
+    }
+}
@@ -0,0 +1,92 @@
+//! Per-request options shared by all Dintero API clients.
+
+use crate::retry::RetryPolicy;
+use std::sync::Arc;
+
+/// Header carrying the idempotency key of a mutating request.
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+/// Response header set when the API replayed the stored result of an earlier request
+/// with the same idempotency key.
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "Idempotent-Replayed";
+
+/// Options that apply to a single API request.
+#[derive(Debug, Clone, Default)]
+pub struct RequestOptions {
+    /// Idempotency key sent with the request.
+    ///
+    /// When unset, operations the API deduplicates, such as captures and refunds, get a
+    /// generated key and other requests are sent without one. The same key is reused for
+    /// every retry of the request.
+    pub idempotency_key: Option<String>,
+    /// Retry policy used instead of the client's policy for this request.
+    pub retry_policy: Option<Arc<dyn RetryPolicy>>,
+    /// Operation name for the span and metrics of the request, e.g.
+    /// `payments.initiate_fund_transfer`, used instead of the name in the endpoint table.
+    pub operation: Option<&'static str>,
+}
+
+impl RequestOptions {
+    /// Creates empty request options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates request options using the given idempotency key and retry policy, if any.
+    pub fn from_parts(
+        idempotency_key: Option<String>,
+        retry_policy: Option<Arc<dyn RetryPolicy>>,
+    ) -> Self {
+        Self {
+            idempotency_key,
+            retry_policy,
+            operation: None,
+        }
+    }
+
+    /// Sets the idempotency key.
+    pub fn with_idempotency_key(mut self, idempotency_key: impl Into<String>) -> Self {
+        self.idempotency_key = Some(idempotency_key.into());
+        self
+    }
+
+    /// Returns the options with an idempotency key, generating a random key if none is
+    /// set.
+    pub fn ensure_idempotency_key(mut self) -> Self {
+        if self.idempotency_key.is_none() {
+            self.idempotency_key = Some(uuid::Uuid::new_v4().to_string());
+        }
+        self
+    }
+
+    /// Sets the operation name the request is reported under.
+    pub fn with_operation(mut self, operation: &'static str) -> Self {
+        self.operation = Some(operation);
+        self
+    }
+
+    /// Overrides the client's retry policy for this request.
+    pub fn with_retry_policy(mut self, retry_policy: impl RetryPolicy + 'static) -> Self {
+        self.retry_policy = Some(Arc::new(retry_policy));
+        self
+    }
+}
+
+/// Response of a request sent with an idempotency key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Idempotent<T> {
+    /// The deserialized response body.
+    pub value: T,
+    /// The idempotency key the request was sent with.
+    pub idempotency_key: String,
+    /// `true` if the API returned the stored result of an earlier request with the
+    /// same key instead of performing the operation again.
+    pub replayed: bool,
+}
+
+impl<T> Idempotent<T> {
+    /// Returns the response body, discarding the idempotency details.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
@@ -0,0 +1,346 @@
+//! Retry policies shared by all Dintero API clients.
+//!
+//! A [`RetryPolicy`] decides whether a failed attempt is retried and how long to wait
+//! first. [`RetryConfig`] is the standard policy: exponential backoff with jitter,
+//! limited to failures that are safe to retry. A [`RetryBudget`] caps the share of
+//! retries across all requests of a client, so a degraded API is not flooded with
+//! retried traffic.
+
+use rand::Rng;
+use std::fmt;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Decides whether and when a failed request is retried.
+pub trait RetryPolicy: Send + Sync + fmt::Debug {
+    /// Returns how long to wait before retrying the failed attempt, or `None` to give up.
+    fn retry_delay(&self, attempt: &RetryAttempt<'_>) -> Option<Duration>;
+}
+
+/// Why an attempt failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryFailure {
+    /// The API responded with a non-success status code.
+    Status(u16),
+    /// The request timed out. It may or may not have been processed.
+    Timeout,
+    /// No connection could be established, so the request was never sent.
+    Connect,
+}
+
+/// A failed attempt, as seen by a [`RetryPolicy`].
+#[derive(Debug, Clone)]
+pub struct RetryAttempt<'a> {
+    /// HTTP method of the request, e.g. `"POST"`.
+    pub method: &'a str,
+    /// Whether the request carries an idempotency key that its endpoint deduplicates
+    /// requests by.
+    pub has_idempotency_key: bool,
+    /// Why the attempt failed.
+    pub failure: RetryFailure,
+    /// Wait time requested by the API through a `Retry-After` header.
+    pub retry_after: Option<Duration>,
+    /// Number of retries already made for this request.
+    pub retries: u32,
+    /// Time since the first attempt was sent.
+    pub elapsed: Duration,
+    /// Delay before the previous retry, if any.
+    pub previous_delay: Option<Duration>,
+}
+
+impl RetryAttempt<'_> {
+    /// Returns `true` if sending the request again cannot repeat its side effects.
+    ///
+    /// This holds for idempotent HTTP methods and for requests carrying an idempotency
+    /// key their endpoint honours.
+    pub fn is_idempotent(&self) -> bool {
+        self.has_idempotency_key
+            || matches!(self.method, "GET" | "HEAD" | "OPTIONS" | "PUT" | "DELETE")
+    }
+
+    /// Returns `true` if the API did not process the failed attempt.
+    ///
+    /// Such attempts can be retried regardless of the method.
+    pub fn was_rejected(&self) -> bool {
+        matches!(
+            self.failure,
+            RetryFailure::Connect | RetryFailure::Status(429)
+        )
+    }
+}
+
+/// Randomization applied to the exponential backoff.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Jitter {
+    /// Wait exactly the computed backoff.
+    None,
+    /// Wait a random duration between zero and the computed backoff.
+    #[default]
+    Full,
+    /// Wait a random duration between the initial backoff and three times the previous
+    /// delay, capped at the maximum backoff.
+    Decorrelated,
+}
+
+/// Exponential backoff retry policy.
+///
+/// Retries timeouts, connection failures and the configured status codes. Requests
+/// that are not idempotent are only retried when the API did not process them, unless
+/// `retry_non_idempotent` is set.
+#[derive(Debug, Clone)]
+pub struct RetryConfig {
+    /// Maximum number of retry attempts.
+    pub max_retries: u32,
+    /// Initial backoff duration in milliseconds.
+    pub initial_backoff_ms: u64,
+    /// Maximum backoff duration in milliseconds.
+    pub max_backoff_ms: u64,
+    /// Backoff multiplier for exponential backoff.
+    pub backoff_multiplier: f64,
+    /// Randomization applied to the backoff.
+    pub jitter: Jitter,
+    /// Maximum time in milliseconds from the first attempt after which no retry is
+    /// started.
+    pub max_elapsed_ms: Option<u64>,
+    /// Status codes that are retried.
+    pub retryable_statuses: Vec<u16>,
+    /// Whether requests that are not idempotent are retried after they may have been
+    /// processed.
+    pub retry_non_idempotent: bool,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff_ms: 100,
+            max_backoff_ms: 10_000,
+            backoff_multiplier: 2.0,
+            jitter: Jitter::default(),
+            max_elapsed_ms: Some(30_000),
+            retryable_statuses: vec![408, 429, 500, 502, 503, 504],
+            retry_non_idempotent: false,
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Returns a policy that never retries.
+    pub fn disabled() -> Self {
+        Self { max_retries: 0, ..Self::default() }
+    }
+
+    /// Sets the maximum number of retry attempts.
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Sets the jitter strategy.
+    pub fn with_jitter(mut self, jitter: Jitter) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Sets the maximum time from the first attempt after which no retry is started.
+    pub fn with_max_elapsed(mut self, max_elapsed: Duration) -> Self {
+        self.max_elapsed_ms = Some(max_elapsed.as_millis() as u64);
+        self
+    }
+
+    /// Sets the status codes that are retried.
+    pub fn with_retryable_statuses(mut self, statuses: Vec<u16>) -> Self {
+        self.retryable_statuses = statuses;
+        self
+    }
+
+    fn backoff(&self, attempt: &RetryAttempt<'_>) -> Duration {
+        let max = self.max_backoff_ms as f64;
+        let exponential = (self.initial_backoff_ms as f64
+            * self.backoff_multiplier.powi(attempt.retries as i32))
+        .min(max);
+
+        let millis = match self.jitter {
+            Jitter::None => exponential,
+            Jitter::Full => rand::thread_rng().gen_range(0.0..=exponential),
+            Jitter::Decorrelated => {
+                let low = self.initial_backoff_ms as f64;
+                let previous = attempt.previous_delay.map_or(low, |delay| delay.as_millis() as f64);
+                rand::thread_rng().gen_range(low..=(previous * 3.0).max(low)).min(max)
+            }
+        };
+
+        Duration::from_millis(millis as u64)
+    }
+}
+
+impl RetryPolicy for RetryConfig {
+    fn retry_delay(&self, attempt: &RetryAttempt<'_>) -> Option<Duration> {
+        if attempt.retries >= self.max_retries {
+            return None;
+        }
+
+        let retryable = match attempt.failure {
+            RetryFailure::Status(status) => self.retryable_statuses.contains(&status),
+            RetryFailure::Timeout | RetryFailure::Connect => true,
+        };
+        let safe = attempt.is_idempotent() || attempt.was_rejected() || self.retry_non_idempotent;
+
+        if !retryable || !safe {
+            return None;
+        }
+
+        let delay = attempt.retry_after.unwrap_or_else(|| self.backoff(attempt));
+
+        match self.max_elapsed_ms {
+            Some(max) if attempt.elapsed + delay > Duration::from_millis(max) => None,
+            _ => Some(delay),
+        }
+    }
+}
+
+/// Token bucket limiting the number of retries across the requests of a client.
+///
+/// Every retry withdraws one token and every successful request deposits a fraction of
+/// a token, up to the bucket's capacity. With the default settings a client makes at
+/// most 10 retries in a burst, and about one retry per 10 successful requests after that.
+#[derive(Debug)]
+pub struct RetryBudget {
+    capacity: f64,
+    deposit: f64,
+    tokens: Mutex<f64>,
+}
+
+impl RetryBudget {
+    /// Creates a full budget with the given capacity, depositing `deposit` tokens for
+    /// every successful request.
+    pub fn new(capacity: u32, deposit: f64) -> Self {
+        Self {
+            capacity: capacity as f64,
+            deposit,
+            tokens: Mutex::new(capacity as f64),
+        }
+    }
+
+    /// Withdraws a token for a retry. Returns `false` if the budget is exhausted.
+    pub fn try_withdraw(&self) -> bool {
+        let mut tokens = self.tokens.lock().unwrap_or_else(|e| e.into_inner());
+        if *tokens >= 1.0 {
+            *tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Records a successful request.
+    pub fn deposit(&self) {
+        let mut tokens = self.tokens.lock().unwrap_or_else(|e| e.into_inner());
+        *tokens = (*tokens + self.deposit).min(self.capacity);
+    }
+
+    /// Returns the number of tokens currently available.
+    pub fn available(&self) -> f64 {
+        *self.tokens.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for RetryBudget {
+    fn default() -> Self {
+        Self::new(10, 0.1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attempt(method: &str, failure: RetryFailure) -> RetryAttempt<'_> {
+        RetryAttempt {
+            method,
+            has_idempotency_key: false,
+            failure,
+            retry_after: None,
+            retries: 0,
+            elapsed: Duration::ZERO,
+            previous_delay: None,
+        }
+    }
+
+    #[test]
+    fn test_non_idempotent_requests_retried_only_when_rejected() {
+        let policy = RetryConfig::default().with_jitter(Jitter::None);
+
+        assert_eq!(
+            policy.retry_delay(&attempt("GET", RetryFailure::Status(503))),
+            Some(Duration::from_millis(100))
+        );
+        assert_eq!(
+            policy.retry_delay(&attempt("PATCH", RetryFailure::Status(503))),
+            None
+        );
+        assert!(policy.retry_delay(&attempt("PATCH", RetryFailure::Status(429))).is_some());
+        assert!(policy.retry_delay(&attempt("PATCH", RetryFailure::Connect)).is_some());
+
+        let keyed = RetryAttempt {
+            has_idempotency_key: true,
+            ..attempt("POST", RetryFailure::Timeout)
+        };
+        assert!(policy.retry_delay(&keyed).is_some());
+    }
+
+    #[test]
+    fn test_retry_limits() {
+        let policy = RetryConfig::default().with_max_elapsed(Duration::from_secs(1));
+
+        let exhausted = RetryAttempt {
+            retries: 3,
+            ..attempt("GET", RetryFailure::Timeout)
+        };
+        assert_eq!(policy.retry_delay(&exhausted), None);
+
+        let late = RetryAttempt {
+            elapsed: Duration::from_millis(900),
+            retry_after: Some(Duration::from_millis(200)),
+            ..attempt("GET", RetryFailure::Status(429))
+        };
+        assert_eq!(policy.retry_delay(&late), None);
+
+        assert_eq!(
+            policy.retry_delay(&attempt("GET", RetryFailure::Status(400))),
+            None
+        );
+    }
+
+    #[test]
+    fn test_jitter_stays_within_bounds() {
+        let full = RetryConfig::default();
+        let decorrelated = RetryConfig::default().with_jitter(Jitter::Decorrelated);
+        let previous = RetryAttempt {
+            previous_delay: Some(Duration::from_millis(400)),
+            ..attempt("GET", RetryFailure::Timeout)
+        };
+
+        for _ in 0..100 {
+            let delay = full.retry_delay(&attempt("GET", RetryFailure::Timeout)).unwrap();
+            assert!(delay <= Duration::from_millis(100));
+
+            let delay = decorrelated.retry_delay(&previous).unwrap();
+            assert!(delay >= Duration::from_millis(100) && delay <= Duration::from_millis(1200));
+        }
+    }
+
+    #[test]
+    fn test_retry_budget() {
+        let budget = RetryBudget::new(2, 0.5);
+
+        assert!(budget.try_withdraw());
+        assert!(budget.try_withdraw());
+        assert!(!budget.try_withdraw());
+
+        budget.deposit();
+        budget.deposit();
+        assert!(budget.try_withdraw());
+        assert_eq!(budget.available(), 0.0);
+    }
+}
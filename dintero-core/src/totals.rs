@@ -0,0 +1,551 @@
+//! Order line totals and VAT.
+//!
+//! Dintero rejects orders whose amount is not the sum of the line amounts, or whose
+//! VAT amounts do not match the lines. [`TotalsValidator`] finds these mistakes before
+//! the request is sent, and [`Totals::line`] computes a line from its unit price.
+//!
+//! Line amounts include VAT and are net of discounts, as in the API. The VAT included
+//! in an amount at rate `r` is `amount × r / (1 + r)`, rounded with a
+//! [`RoundingMode`].
+//!
+//! ```
+//! use dintero_core::totals::{Totals, TotalsValidator, VatRate};
+//! use dintero_core::RoundingMode;
+//!
+//! // 3 × 99.90 NOK with 10.00 NOK off, at 25% VAT.
+//! let line = Totals::line(9990, 3, 1000, VatRate::from_percent(25), RoundingMode::HalfUp);
+//! assert_eq!(line, Totals { amount: 28970, vat_amount: 5794 });
+//! ```
+
+use crate::error::DinteroError;
+use crate::money::RoundingMode;
+use std::collections::HashSet;
+use std::fmt;
+use std::ops::{Add, AddAssign};
+
+/// Basis points in 100%.
+const FULL_RATE: i64 = 10_000;
+
+/// A VAT rate in basis points, e.g. 2500 for 25%.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct VatRate(i64);
+
+impl VatRate {
+    /// No VAT.
+    pub const ZERO: VatRate = VatRate(0);
+
+    /// Creates a rate from basis points.
+    pub const fn from_basis_points(basis_points: i64) -> Self {
+        Self(basis_points)
+    }
+
+    /// Creates a rate from a whole percentage, as in the `vat` field of order lines.
+    pub const fn from_percent(percent: i64) -> Self {
+        Self(percent.saturating_mul(100))
+    }
+
+    /// Returns the rate in basis points.
+    pub const fn basis_points(self) -> i64 {
+        self.0
+    }
+
+    /// Returns the rate as a whole percentage, rounded down.
+    pub const fn percent(self) -> i64 {
+        self.0 / 100
+    }
+
+    /// Returns `true` if the rate is between 0% and 100%.
+    pub fn is_valid(self) -> bool {
+        (0..=FULL_RATE).contains(&self.0)
+    }
+
+    /// Returns the VAT included in `amount`.
+    ///
+    /// Rates outside 0%..=100% are clamped to that range. [`TotalsValidator`] reports
+    /// such rates as [`TotalsError::InvalidVatRate`].
+    pub fn vat_included(self, amount: i64, mode: RoundingMode) -> i64 {
+        let rate = self.0.clamp(0, FULL_RATE) as i128;
+        let vat = mode.div(amount as i128 * rate, FULL_RATE as i128 + rate);
+        vat as i64
+    }
+}
+
+impl fmt::Display for VatRate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 % 100 {
+            0 => write!(f, "{}%", self.0 / 100),
+            rest => write!(f, "{}.{:02}%", self.0 / 100, rest.abs()),
+        }
+    }
+}
+
+/// The amounts of an order line, as sent to the API.
+///
+/// Implemented by the order line types of the API crates, so orders of every API
+/// are checked by the same [`TotalsValidator`].
+pub trait LineItem {
+    /// The line's id, used to report errors.
+    fn line_id(&self) -> &str;
+
+    /// The number of units.
+    fn quantity(&self) -> i64;
+
+    /// The line total, including VAT and net of discounts.
+    fn amount(&self) -> i64;
+
+    /// The VAT included in [`amount`](Self::amount).
+    fn vat_amount(&self) -> i64;
+
+    /// The VAT rate of the line.
+    fn vat_rate(&self) -> VatRate;
+
+    /// The discount already taken off [`amount`](Self::amount).
+    fn discount_amount(&self) -> i64 {
+        0
+    }
+}
+
+impl<T: LineItem + ?Sized> LineItem for &T {
+    fn line_id(&self) -> &str {
+        (**self).line_id()
+    }
+
+    fn quantity(&self) -> i64 {
+        (**self).quantity()
+    }
+
+    fn amount(&self) -> i64 {
+        (**self).amount()
+    }
+
+    fn vat_amount(&self) -> i64 {
+        (**self).vat_amount()
+    }
+
+    fn vat_rate(&self) -> VatRate {
+        (**self).vat_rate()
+    }
+
+    fn discount_amount(&self) -> i64 {
+        (**self).discount_amount()
+    }
+}
+
+/// An amount and the VAT included in it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Totals {
+    pub amount: i64,
+    pub vat_amount: i64,
+}
+
+impl Totals {
+    /// Computes a line from its unit price: `unit_price × quantity - discount_amount`,
+    /// and the VAT included in that amount. Saturates instead of overflowing.
+    ///
+    /// The rate is not checked; see [`VatRate::vat_included`] for rates out of range.
+    pub fn line(
+        unit_price: i64,
+        quantity: i64,
+        discount_amount: i64,
+        rate: VatRate,
+        mode: RoundingMode,
+    ) -> Self {
+        let amount = unit_price.saturating_mul(quantity).saturating_sub(discount_amount);
+        Self {
+            amount,
+            vat_amount: rate.vat_included(amount, mode),
+        }
+    }
+
+    /// Sums the amounts of `lines`.
+    pub fn of<I>(lines: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: LineItem,
+    {
+        lines.into_iter().fold(Self::default(), |totals, line| {
+            totals
+                + Totals {
+                    amount: line.amount(),
+                    vat_amount: line.vat_amount(),
+                }
+        })
+    }
+}
+
+impl Add for Totals {
+    type Output = Totals;
+
+    fn add(self, other: Totals) -> Totals {
+        Totals {
+            amount: self.amount.saturating_add(other.amount),
+            vat_amount: self.vat_amount.saturating_add(other.vat_amount),
+        }
+    }
+}
+
+impl AddAssign for Totals {
+    fn add_assign(&mut self, other: Totals) {
+        *self = *self + other;
+    }
+}
+
+/// How an order builder treats the order amount and the line amounts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TotalsMode {
+    /// Sends the amounts as given.
+    #[default]
+    AsGiven,
+    /// Sets the order amount and VAT amount to the sums of the lines, after checking
+    /// each line.
+    Calculate,
+    /// Checks each line and that the order amounts are the sums of the lines.
+    Strict,
+}
+
+/// A mistake in the amounts of an order.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum TotalsError {
+    /// Two lines have the same id.
+    #[error("line {line_id:?}: duplicate line id")]
+    DuplicateLineId { line_id: String },
+
+    /// The quantity is zero or negative.
+    #[error("line {line_id:?}: quantity {quantity} is not positive")]
+    InvalidQuantity { line_id: String, quantity: i64 },
+
+    /// The line amount is negative.
+    #[error("line {line_id:?}: amount {amount} is negative")]
+    NegativeAmount { line_id: String, amount: i64 },
+
+    /// The discount is negative.
+    #[error("line {line_id:?}: discount {discount_amount} is negative")]
+    NegativeDiscount { line_id: String, discount_amount: i64 },
+
+    /// The VAT rate is outside 0%..=100%.
+    #[error("line {line_id:?}: VAT rate {rate} is out of range")]
+    InvalidVatRate { line_id: String, rate: VatRate },
+
+    /// The VAT amount does not match the line amount and VAT rate.
+    #[error("line {line_id:?}: VAT amount {vat_amount} should be {expected} at {rate}")]
+    VatMismatch { line_id: String, vat_amount: i64, expected: i64, rate: VatRate },
+
+    /// The order amount is not the sum of the line amounts.
+    #[error("order amount {amount} does not match the line total {expected}")]
+    AmountMismatch { amount: i64, expected: i64 },
+
+    /// The order VAT amount is not the sum of the line VAT amounts.
+    #[error("order VAT amount {vat_amount} does not match the line total {expected}")]
+    VatAmountMismatch { vat_amount: i64, expected: i64 },
+}
+
+impl TotalsError {
+    /// Returns the id of the line the error is about, if any.
+    pub fn line_id(&self) -> Option<&str> {
+        match self {
+            Self::DuplicateLineId { line_id }
+            | Self::InvalidQuantity { line_id, .. }
+            | Self::NegativeAmount { line_id, .. }
+            | Self::NegativeDiscount { line_id, .. }
+            | Self::InvalidVatRate { line_id, .. }
+            | Self::VatMismatch { line_id, .. } => Some(line_id),
+            Self::AmountMismatch { .. } | Self::VatAmountMismatch { .. } => None,
+        }
+    }
+}
+
+/// All mistakes found in the amounts of an order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TotalsErrors(Vec<TotalsError>);
+
+impl TotalsErrors {
+    /// Returns the errors, in line order, with the order-level errors last.
+    pub fn errors(&self) -> &[TotalsError] {
+        &self.0
+    }
+
+    /// Returns the errors about the line `line_id`.
+    pub fn for_line<'a>(&'a self, line_id: &'a str) -> impl Iterator<Item = &'a TotalsError> {
+        self.0.iter().filter(move |error| error.line_id() == Some(line_id))
+    }
+
+    pub fn into_vec(self) -> Vec<TotalsError> {
+        self.0
+    }
+}
+
+impl fmt::Display for TotalsErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, error) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str("; ")?;
+            }
+            write!(f, "{}", error)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for TotalsErrors {}
+
+impl From<TotalsErrors> for DinteroError {
+    fn from(errors: TotalsErrors) -> Self {
+        DinteroError::Validation(errors.to_string())
+    }
+}
+
+/// Checks order lines and order amounts.
+///
+/// Each line must have a positive quantity, a non-negative amount and discount, a VAT
+/// rate between 0% and 100%, a VAT amount matching its amount and rate, and a unique
+/// line id.
+///
+/// ```
+/// use dintero_core::totals::{LineItem, TotalsError, TotalsValidator, VatRate};
+///
+/// struct Line(&'static str, i64, i64);
+///
+/// impl LineItem for Line {
+///     fn line_id(&self) -> &str { self.0 }
+///     fn quantity(&self) -> i64 { 1 }
+///     fn amount(&self) -> i64 { self.1 }
+///     fn vat_amount(&self) -> i64 { self.2 }
+///     fn vat_rate(&self) -> VatRate { VatRate::from_percent(25) }
+/// }
+///
+/// let lines = [Line("1", 10000, 2000), Line("2", 5000, 900)];
+/// let errors = TotalsValidator::new().validate(&lines, 15000, Some(2900)).unwrap_err();
+/// assert_eq!(
+///     errors.errors(),
+///     [TotalsError::VatMismatch {
+///         line_id: "2".into(),
+///         vat_amount: 900,
+///         expected: 1000,
+///         rate: VatRate::from_percent(25),
+///     }]
+/// );
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct TotalsValidator {
+    rounding: RoundingMode,
+    vat_tolerance: i64,
+}
+
+impl Default for TotalsValidator {
+    fn default() -> Self {
+        Self {
+            rounding: RoundingMode::HalfUp,
+            vat_tolerance: 0,
+        }
+    }
+}
+
+impl TotalsValidator {
+    /// Creates a validator rounding VAT half up, with no tolerance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how the expected VAT of a line is rounded.
+    pub fn rounding(mut self, mode: RoundingMode) -> Self {
+        self.rounding = mode;
+        self
+    }
+
+    /// Accepts line VAT amounts off by up to `minor_units`, e.g. when VAT is rounded
+    /// per unit rather than per line.
+    pub fn vat_tolerance(mut self, minor_units: i64) -> Self {
+        self.vat_tolerance = minor_units.max(0);
+        self
+    }
+
+    /// Computes a line from its unit price with this validator's rounding.
+    pub fn line(&self, unit_price: i64, quantity: i64, discount: i64, rate: VatRate) -> Totals {
+        Totals::line(unit_price, quantity, discount, rate, self.rounding)
+    }
+
+    /// Checks each line and returns their sums.
+    ///
+    /// # Errors
+    ///
+    /// Returns every line-level mistake found.
+    pub fn check_lines<I>(&self, lines: I) -> Result<Totals, TotalsErrors>
+    where
+        I: IntoIterator,
+        I::Item: LineItem,
+    {
+        let mut errors = Vec::new();
+        let mut totals = Totals::default();
+        let mut line_ids = HashSet::new();
+
+        for line in lines {
+            self.check_line(&line, &mut errors);
+            if !line_ids.insert(line.line_id().to_string()) {
+                errors.push(TotalsError::DuplicateLineId { line_id: line.line_id().to_string() });
+            }
+            totals += Totals {
+                amount: line.amount(),
+                vat_amount: line.vat_amount(),
+            };
+        }
+
+        if errors.is_empty() {
+            Ok(totals)
+        } else {
+            Err(TotalsErrors(errors))
+        }
+    }
+
+    /// Checks each line, and that `amount` and `vat_amount`, if given, are their sums.
+    ///
+    /// # Errors
+    ///
+    /// Returns every mistake found.
+    pub fn validate<I>(
+        &self,
+        lines: I,
+        amount: i64,
+        vat_amount: Option<i64>,
+    ) -> Result<Totals, TotalsErrors>
+    where
+        I: IntoIterator,
+        I::Item: LineItem,
+    {
+        let lines: Vec<_> = lines.into_iter().collect();
+        let expected = Totals::of(&lines);
+        let mut errors = match self.check_lines(&lines) {
+            Ok(_) => Vec::new(),
+            Err(errors) => errors.0,
+        };
+
+        if amount != expected.amount {
+            errors.push(TotalsError::AmountMismatch { amount, expected: expected.amount });
+        }
+        if let Some(vat_amount) = vat_amount.filter(|vat| *vat != expected.vat_amount) {
+            errors.push(TotalsError::VatAmountMismatch {
+                vat_amount,
+                expected: expected.vat_amount,
+            });
+        }
+
+        if errors.is_empty() {
+            Ok(expected)
+        } else {
+            Err(TotalsErrors(errors))
+        }
+    }
+
+    fn check_line<L: LineItem>(&self, line: &L, errors: &mut Vec<TotalsError>) {
+        let line_id = || line.line_id().to_string();
+        let (quantity, amount, rate) = (line.quantity(), line.amount(), line.vat_rate());
+
+        if quantity <= 0 {
+            errors.push(TotalsError::InvalidQuantity { line_id: line_id(), quantity });
+        }
+        if amount < 0 {
+            errors.push(TotalsError::NegativeAmount { line_id: line_id(), amount });
+        }
+        if line.discount_amount() < 0 {
+            errors.push(TotalsError::NegativeDiscount {
+                line_id: line_id(),
+                discount_amount: line.discount_amount(),
+            });
+        }
+        if !rate.is_valid() {
+            errors.push(TotalsError::InvalidVatRate { line_id: line_id(), rate });
+            return;
+        }
+
+        let expected = rate.vat_included(amount, self.rounding);
+        let vat_amount = line.vat_amount();
+        if (vat_amount - expected).abs() > self.vat_tolerance {
+            errors.push(TotalsError::VatMismatch {
+                line_id: line_id(),
+                vat_amount,
+                expected,
+                rate,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Line {
+        id: &'static str,
+        quantity: i64,
+        amount: i64,
+        vat_amount: i64,
+        vat: i64,
+    }
+
+    impl LineItem for Line {
+        fn line_id(&self) -> &str {
+            self.id
+        }
+
+        fn quantity(&self) -> i64 {
+            self.quantity
+        }
+
+        fn amount(&self) -> i64 {
+            self.amount
+        }
+
+        fn vat_amount(&self) -> i64 {
+            self.vat_amount
+        }
+
+        fn vat_rate(&self) -> VatRate {
+            VatRate::from_percent(self.vat)
+        }
+    }
+
+    #[test]
+    fn test_validator_reports_every_line_and_order_error() {
+        let rate = VatRate::from_basis_points(1250);
+        assert_eq!(rate.to_string(), "12.50%");
+        assert_eq!(rate.vat_included(1125, RoundingMode::HalfUp), 125);
+        assert_eq!(
+            VatRate::from_percent(25).vat_included(3, RoundingMode::HalfUp),
+            1
+        );
+        assert_eq!(
+            VatRate::from_percent(25).vat_included(3, RoundingMode::Down),
+            0
+        );
+        for (percent, vat) in [(-100, 0), (-250, 0), (300, 500)] {
+            let rate = VatRate::from_percent(percent);
+            assert_eq!(rate.vat_included(1000, RoundingMode::HalfUp), vat);
+            assert_eq!(
+                Totals::line(500, 2, 0, rate, RoundingMode::HalfUp).vat_amount,
+                vat
+            );
+        }
+
+        let line =
+            |id, quantity, amount, vat_amount, vat| Line { id, quantity, amount, vat_amount, vat };
+        let lines = [
+            line("1", 2, 20000, 4000, 25),
+            line("2", 0, 1500, 300, 25),
+            line("2", 1, 999, 200, 25),
+            line("3", 1, 1000, 0, 120),
+        ];
+        let errors = TotalsValidator::new().validate(&lines, 20000, Some(4000)).unwrap_err();
+        assert_eq!(errors.for_line("1").count(), 0);
+        assert_eq!(errors.for_line("2").count(), 2);
+        assert!(errors
+            .errors()
+            .contains(&TotalsError::AmountMismatch { amount: 20000, expected: 23499 }));
+        assert!(matches!(
+            errors.errors()[2],
+            TotalsError::InvalidVatRate { .. }
+        ));
+
+        let lenient = TotalsValidator::new().vat_tolerance(1);
+        let totals = lenient.check_lines([line("1", 1, 999, 199, 25)]).unwrap();
+        assert_eq!(totals, Totals { amount: 999, vat_amount: 199 });
+    }
+}
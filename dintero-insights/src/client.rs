@@ -1,29 +1,88 @@
 //! Insights API client implementation.
 
-use reqwest::Client;
-use std::sync::Arc;
+use crate::error::Result;
+use async_trait::async_trait;
+use dintero_core::Endpoint;
 
-#[derive(Clone)]
-pub struct InsightsClient {
-    pub(crate) http_client: Arc<Client>,
-    pub(crate) base_url: String,
+/// Endpoints called by the Insights API client, used to name the spans and metrics
+/// of its calls.
+#[rustfmt::skip]
+pub const ENDPOINTS: &[Endpoint] = &[
+    Endpoint::new("GET", "accounts/{account_id}/insight/kpi/checkout-transaction-status", "insights.get_checkout_transaction_status"),
+    Endpoint::new("GET", "accounts/{account_id}/insight/kpi/transactions", "insights.get_transactions"),
+    Endpoint::new("GET", "accounts/{account_id}/insight/kpi/payment-methods", "insights.get_payment_methods"),
+    Endpoint::new("GET", "accounts/{account_id}/insight/kpi/revenue", "insights.get_revenue"),
+    Endpoint::new("POST", "accounts/{account_id}/reports/configuration", "insights.create_report_config"),
+    Endpoint::new("GET", "accounts/{account_id}/reports/configuration/{config_id}", "insights.get_report_config"),
+    Endpoint::new("GET", "accounts/{account_id}/reports/configuration", "insights.list_report_configs"),
+    Endpoint::new("PUT", "accounts/{account_id}/reports/configuration/{config_id}", "insights.update_report_config"),
+    Endpoint::new("DELETE", "accounts/{account_id}/reports/configuration/{config_id}", "insights.delete_report_config"),
+    Endpoint::new("GET", "accounts/{account_id}/insight/reports/configuration", "insights.list_configurations"),
+    Endpoint::new("GET", "accounts/{account_id}/insight/reports/configuration/{configuration_id}", "insights.get_configuration"),
+    Endpoint::new("POST", "accounts/{account_id}/insight/reports/configuration", "insights.create_configuration"),
+    Endpoint::new("PUT", "accounts/{account_id}/insight/reports/configuration/{configuration_id}", "insights.update_configuration"),
+    Endpoint::new("DELETE", "accounts/{account_id}/insight/reports/configuration/{configuration_id}", "insights.delete_configuration"),
+];
+
+#[async_trait]
+pub trait InsightsAdapter: Send + Sync {
+    async fn get_json<T: serde::de::DeserializeOwned>(&self, path: &str) -> Result<T>;
+    async fn post_json<T: serde::de::DeserializeOwned, B: serde::Serialize + Send + Sync>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<T>;
+    async fn put_json<T: serde::de::DeserializeOwned, B: serde::Serialize + Send + Sync>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<T>;
+    async fn delete(&self, path: &str) -> Result<()>;
+    /// Sends a `PATCH` request. Adapters that predate PATCH support get a default that
+    /// returns [`DinteroError::Config`](dintero_core::DinteroError::Config).
+    async fn patch_json<T: serde::de::DeserializeOwned, B: serde::Serialize + Send + Sync>(
+        &self,
+        path: &str,
+        _body: &B,
+    ) -> Result<T> {
+        Err(dintero_core::DinteroError::Config(format!(
+            "PATCH {} is not supported by this adapter",
+            path
+        )))
+    }
+    /// Sends a `GET` request with query parameters serialized from `query`, e.g. a
+    /// struct or a slice of pairs.
+    async fn get_json_with_query<
+        T: serde::de::DeserializeOwned,
+        Q: serde::Serialize + Send + Sync + ?Sized,
+    >(
+        &self,
+        path: &str,
+        query: &Q,
+    ) -> Result<T> {
+        self.get_json(&dintero_core::path::with_query(path, query)?).await
+    }
+}
+
+pub struct InsightsClient<A: InsightsAdapter> {
+    pub(crate) adapter: A,
     pub(crate) account_id: String,
 }
 
-impl InsightsClient {
-    pub fn new(http_client: Arc<Client>, base_url: String, account_id: String) -> Self {
-        Self { http_client, base_url, account_id }
+impl<A: InsightsAdapter> InsightsClient<A> {
+    pub fn new(adapter: A, account_id: String) -> Self {
+        Self { adapter, account_id }
     }
 
-    pub fn kpis(&self) -> crate::kpis::KpisClient {
-        crate::kpis::KpisClient { client: self.clone() }
+    pub fn kpis(&self) -> crate::kpis::KpisClient<'_, A> {
+        crate::kpis::KpisClient { client: self }
     }
 
-    pub fn reports(&self) -> crate::reports::ReportsClient {
-        crate::reports::ReportsClient { client: self.clone() }
+    pub fn reports(&self) -> crate::reports::ReportsClient<'_, A> {
+        crate::reports::ReportsClient { client: self }
     }
 
-    pub fn report_configs(&self) -> crate::report_configs::ReportConfigsClient {
-        crate::report_configs::ReportConfigsClient { client: self.clone() }
+    pub fn report_configs(&self) -> crate::report_configs::ReportConfigsClient<'_, A> {
+        crate::report_configs::ReportConfigsClient { client: self }
     }
 }
@@ -0,0 +1,7 @@
+//! Error types and handling.
+
+pub use dintero_core::{ApiError, DinteroError};
+
+pub type InsightsError = DinteroError;
+
+pub type Result<T> = std::result::Result<T, InsightsError>;
@@ -6,10 +6,14 @@
 //! This crate provides types and clients for analytics and insights in the Dintero platform.
 
 pub mod client;
+pub mod error;
 pub mod kpis;
 pub mod report_configs;
 pub mod reports;
 pub mod types;
 
-pub use client::InsightsClient;
+pub use client::{InsightsAdapter, InsightsClient, ENDPOINTS};
+pub use error::{InsightsError, Result};
 pub use types::*;
+
+pub use dintero_core::{ApiError, DinteroError, ErrorCode, FieldError};
@@ -1,75 +1,57 @@
 //! Module implementation.
 
+use crate::client::{InsightsAdapter, InsightsClient};
+use crate::error::Result;
 use crate::types::*;
 
-pub struct ReportConfigsClient {
-    pub(crate) client: crate::client::InsightsClient,
+pub struct ReportConfigsClient<'a, A: InsightsAdapter> {
+    pub(crate) client: &'a InsightsClient<A>,
 }
 
-impl ReportConfigsClient {
+impl<A: InsightsAdapter> ReportConfigsClient<'_, A> {
     pub async fn create(
         &self,
         request: CreateReportConfigurationRequest,
-    ) -> Result<ReportConfiguration, Box<dyn std::error::Error>> {
-        let url = format!(
-            "{}/accounts/{}/reports/configuration",
-            self.client.base_url, self.client.account_id
-        );
-
-        let response = self.client.http_client.post(&url).json(&request).send().await?;
+    ) -> Result<ReportConfiguration> {
+        let path = format!("accounts/{}/reports/configuration", self.client.account_id);
 
-        let config = response.json().await?;
-        Ok(config)
+        self.client.adapter.post_json(&path, &request).await
     }
 
-    pub async fn get(
-        &self,
-        config_id: &str,
-    ) -> Result<ReportConfiguration, Box<dyn std::error::Error>> {
-        let url = format!(
-            "{}/accounts/{}/reports/configuration/{}",
-            self.client.base_url, self.client.account_id, config_id
+    pub async fn get(&self, config_id: &str) -> Result<ReportConfiguration> {
+        let path = format!(
+            "accounts/{}/reports/configuration/{}",
+            self.client.account_id, config_id
         );
 
-        let response = self.client.http_client.get(&url).send().await?;
-        let config = response.json().await?;
-        Ok(config)
+        self.client.adapter.get_json(&path).await
     }
 
-    pub async fn list(&self) -> Result<Vec<ReportConfiguration>, Box<dyn std::error::Error>> {
-        let url = format!(
-            "{}/accounts/{}/reports/configuration",
-            self.client.base_url, self.client.account_id
-        );
+    pub async fn list(&self) -> Result<Vec<ReportConfiguration>> {
+        let path = format!("accounts/{}/reports/configuration", self.client.account_id);
 
-        let response = self.client.http_client.get(&url).send().await?;
-        let configs = response.json().await?;
-        Ok(configs)
+        self.client.adapter.get_json(&path).await
     }
 
     pub async fn update(
         &self,
         config_id: &str,
         request: UpdateReportConfigurationRequest,
-    ) -> Result<ReportConfiguration, Box<dyn std::error::Error>> {
-        let url = format!(
-            "{}/accounts/{}/reports/configuration/{}",
-            self.client.base_url, self.client.account_id, config_id
+    ) -> Result<ReportConfiguration> {
+        let path = format!(
+            "accounts/{}/reports/configuration/{}",
+            self.client.account_id, config_id
         );
 
-        let response = self.client.http_client.put(&url).json(&request).send().await?;
-
-        let config = response.json().await?;
-        Ok(config)
+        self.client.adapter.put_json(&path, &request).await
     }
 
-    pub async fn delete(&self, config_id: &str) -> Result<(), Box<dyn std::error::Error>> {
-        let url = format!(
-            "{}/accounts/{}/reports/configuration/{}",
-            self.client.base_url, self.client.account_id, config_id
+    pub async fn delete(&self, config_id: &str) -> Result<()> {
+        let path = format!(
+            "accounts/{}/reports/configuration/{}",
+            self.client.account_id, config_id
         );
 
-        self.client.http_client.delete(&url).send().await?;
-        Ok(())
+        self.client.adapter.delete(&path).await
     }
 }
@@ -1,100 +1,67 @@
 //! Module implementation.
 
-use crate::client::InsightsClient;
+use crate::client::{InsightsAdapter, InsightsClient};
+use crate::error::Result;
 use crate::types::*;
 
-pub struct ReportsClient {
-    pub(crate) client: InsightsClient,
+pub struct ReportsClient<'a, A: InsightsAdapter> {
+    pub(crate) client: &'a InsightsClient<A>,
 }
 
-impl ReportsClient {
-    pub async fn list_configurations(
-        &self,
-    ) -> Result<Vec<ReportConfiguration>, Box<dyn std::error::Error>> {
-        let url = format!(
-            "{}/v1/accounts/{}/insight/reports/configuration",
-            self.client.base_url, self.client.account_id
+impl<A: InsightsAdapter> ReportsClient<'_, A> {
+    /// Lists the report configurations of the account.
+    ///
+    /// The endpoint is not paginated and returns every configuration in one response, so there
+    /// is no stream counterpart.
+    pub async fn list_configurations(&self) -> Result<Vec<ReportConfiguration>> {
+        let path = format!(
+            "accounts/{}/insight/reports/configuration",
+            self.client.account_id
         );
 
-        let response =
-            self.client.http_client.get(&url).send().await?.error_for_status()?.json().await?;
-
-        Ok(response)
+        self.client.adapter.get_json(&path).await
     }
 
-    pub async fn get_configuration(
-        &self,
-        configuration_id: &str,
-    ) -> Result<ReportConfiguration, Box<dyn std::error::Error>> {
-        let url = format!(
-            "{}/v1/accounts/{}/insight/reports/configuration/{}",
-            self.client.base_url, self.client.account_id, configuration_id
+    pub async fn get_configuration(&self, configuration_id: &str) -> Result<ReportConfiguration> {
+        let path = format!(
+            "accounts/{}/insight/reports/configuration/{}",
+            self.client.account_id, configuration_id
         );
 
-        let response =
-            self.client.http_client.get(&url).send().await?.error_for_status()?.json().await?;
-
-        Ok(response)
+        self.client.adapter.get_json(&path).await
     }
 
     pub async fn create_configuration(
         &self,
         request: CreateReportConfigurationRequest,
-    ) -> Result<ReportConfiguration, Box<dyn std::error::Error>> {
-        let url = format!(
-            "{}/v1/accounts/{}/insight/reports/configuration",
-            self.client.base_url, self.client.account_id
+    ) -> Result<ReportConfiguration> {
+        let path = format!(
+            "accounts/{}/insight/reports/configuration",
+            self.client.account_id
         );
 
-        let response = self
-            .client
-            .http_client
-            .post(&url)
-            .json(&request)
-            .send()
-            .await?
-            .error_for_status()?
-            .json()
-            .await?;
-
-        Ok(response)
+        self.client.adapter.post_json(&path, &request).await
     }
 
     pub async fn update_configuration(
         &self,
         configuration_id: &str,
         request: UpdateReportConfigurationRequest,
-    ) -> Result<ReportConfiguration, Box<dyn std::error::Error>> {
-        let url = format!(
-            "{}/v1/accounts/{}/insight/reports/configuration/{}",
-            self.client.base_url, self.client.account_id, configuration_id
+    ) -> Result<ReportConfiguration> {
+        let path = format!(
+            "accounts/{}/insight/reports/configuration/{}",
+            self.client.account_id, configuration_id
         );
 
-        let response = self
-            .client
-            .http_client
-            .put(&url)
-            .json(&request)
-            .send()
-            .await?
-            .error_for_status()?
-            .json()
-            .await?;
-
-        Ok(response)
+        self.client.adapter.put_json(&path, &request).await
     }
 
-    pub async fn delete_configuration(
-        &self,
-        configuration_id: &str,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        let url = format!(
-            "{}/v1/accounts/{}/insight/reports/configuration/{}",
-            self.client.base_url, self.client.account_id, configuration_id
+    pub async fn delete_configuration(&self, configuration_id: &str) -> Result<()> {
+        let path = format!(
+            "accounts/{}/insight/reports/configuration/{}",
+            self.client.account_id, configuration_id
         );
 
-        self.client.http_client.delete(&url).send().await?.error_for_status()?;
-
-        Ok(())
+        self.client.adapter.delete(&path).await
     }
 }
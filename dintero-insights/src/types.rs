@@ -120,7 +120,7 @@ pub struct RevenueKpiResponse {
     pub currency: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct KpiQueryParams {
     pub from_date: DateTime<Utc>,
     pub to_date: DateTime<Utc>,
@@ -1,9 +1,10 @@
 //! Automation rules for loyalty programs.
 
-use crate::client::LoyaltyClient;
+use crate::client::{LoyaltyAdapter, LoyaltyClient};
 use crate::error::Result;
-use crate::types::PaginatedResponse;
+use crate::types::{page_offset, resolve_page, PaginatedResponse};
 use chrono::{DateTime, Utc};
+use dintero_core::{paginate, PageRequest, Paginator};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -66,36 +67,18 @@ pub struct ListAutomationRulesRequest {
     pub offset: Option<u32>,
 }
 
-impl LoyaltyClient {
+impl<A: LoyaltyAdapter> LoyaltyClient<A> {
     pub async fn create_automation_rule(
         &self,
         req: CreateAutomationRuleRequest,
     ) -> Result<AutomationRule> {
-        let url = self.url("/automations/rules");
-        let response = self.http().post(&url).json(&req).send().await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path("/automations/rules");
+        self.adapter.post_json(&path, &req).await
     }
 
     pub async fn get_automation_rule(&self, rule_id: &Uuid) -> Result<AutomationRule> {
-        let url = self.url(&format!("/automations/rules/{}", rule_id));
-        let response = self.http().get(&url).send().await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path(&format!("/automations/rules/{}", rule_id));
+        self.adapter.get_json(&path).await
     }
 
     pub async fn update_automation_rule(
@@ -103,61 +86,36 @@ impl LoyaltyClient {
         rule_id: &Uuid,
         req: UpdateAutomationRuleRequest,
     ) -> Result<AutomationRule> {
-        let url = self.url(&format!("/automations/rules/{}", rule_id));
-        let response = self.http().put(&url).json(&req).send().await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path(&format!("/automations/rules/{}", rule_id));
+        self.adapter.put_json(&path, &req).await
     }
 
     pub async fn delete_automation_rule(&self, rule_id: &Uuid) -> Result<()> {
-        let url = self.url(&format!("/automations/rules/{}", rule_id));
-        let response = self.http().delete(&url).send().await?;
-
-        if response.status().is_success() {
-            Ok(())
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path(&format!("/automations/rules/{}", rule_id));
+        self.adapter.delete(&path).await
     }
 
     pub async fn list_automation_rules(
         &self,
         req: ListAutomationRulesRequest,
     ) -> Result<PaginatedResponse<AutomationRule>> {
-        let mut url = self.url("/automations/rules");
-        let mut params = vec![];
-
-        if let Some(limit) = req.limit {
-            params.push(format!("limit={}", limit));
-        }
-        if let Some(offset) = req.offset {
-            params.push(format!("offset={}", offset));
-        }
-
-        if !params.is_empty() {
-            url.push('?');
-            url.push_str(&params.join("&"));
-        }
-
-        let response = self.http().get(&url).send().await?;
+        let path = self.path("/automations/rules");
+        let query = (("limit", req.limit), ("offset", req.offset));
+        self.adapter.get_json_with_query(&path, &query).await
+    }
 
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+    /// Streams all automation rules matching `req`, fetching further pages as needed.
+    pub fn automation_rules_stream(
+        &self,
+        req: ListAutomationRulesRequest,
+    ) -> Paginator<'_, AutomationRule> {
+        paginate(move |page: PageRequest| {
+            let page = resolve_page(page, req.limit, req.offset);
+            let req = ListAutomationRulesRequest {
+                limit: page.page_size,
+                offset: page_offset(&page),
+            };
+            async move { Ok(self.list_automation_rules(req).await?.into_page(&page)) }
+        })
     }
 }
@@ -1,23 +1,126 @@
 //! Loyalty API client implementation.
 
-use reqwest::Client;
+use crate::error::Result;
+use async_trait::async_trait;
+use dintero_core::Endpoint;
 
-pub struct LoyaltyClient {
-    http: Client,
-    base_url: String,
-    account_id: String,
-}
+/// Endpoints called by the Loyalty API client, used to name the spans and metrics
+/// of its calls.
+#[rustfmt::skip]
+pub const ENDPOINTS: &[Endpoint] = &[
+    Endpoint::new("POST", "accounts/{account_id}/automations/rules", "loyalty.create_automation_rule"),
+    Endpoint::new("GET", "accounts/{account_id}/automations/rules/{rule_id}", "loyalty.get_automation_rule"),
+    Endpoint::new("PUT", "accounts/{account_id}/automations/rules/{rule_id}", "loyalty.update_automation_rule"),
+    Endpoint::new("DELETE", "accounts/{account_id}/automations/rules/{rule_id}", "loyalty.delete_automation_rule"),
+    Endpoint::new("GET", "accounts/{account_id}/automations/rules", "loyalty.list_automation_rules"),
+    Endpoint::new("POST", "accounts/{account_id}/customers", "loyalty.create_customer"),
+    Endpoint::new("GET", "accounts/{account_id}/customers/{customer_id}", "loyalty.get_customer"),
+    Endpoint::new("PUT", "accounts/{account_id}/customers/{customer_id}", "loyalty.update_customer"),
+    Endpoint::new("DELETE", "accounts/{account_id}/customers/{customer_id}", "loyalty.delete_customer"),
+    Endpoint::new("GET", "accounts/{account_id}/customers", "loyalty.list_customers"),
+    Endpoint::new("POST", "accounts/{account_id}/customers/{customer_id}/tokens", "loyalty.create_customer_token"),
+    Endpoint::new("POST", "accounts/{account_id}/customers/{customer_id}/terms", "loyalty.accept_terms"),
+    Endpoint::new("GET", "accounts/{account_id}/customers/{customer_id}/settings", "loyalty.get_customer_settings"),
+    Endpoint::new("PUT", "accounts/{account_id}/customers/{customer_id}/settings", "loyalty.update_customer_settings"),
+    Endpoint::new("POST", "accounts/{account_id}/discounts/rules", "loyalty.create_discount_rule"),
+    Endpoint::new("GET", "accounts/{account_id}/discounts/rules/{rule_id}", "loyalty.get_discount_rule"),
+    Endpoint::new("PUT", "accounts/{account_id}/discounts/rules/{rule_id}", "loyalty.update_discount_rule"),
+    Endpoint::new("DELETE", "accounts/{account_id}/discounts/rules/{rule_id}", "loyalty.delete_discount_rule"),
+    Endpoint::new("GET", "accounts/{account_id}/discounts/rules", "loyalty.list_discount_rules"),
+    Endpoint::new("POST", "accounts/{account_id}/discounts/campaigns", "loyalty.create_discount_campaign"),
+    Endpoint::new("GET", "accounts/{account_id}/discounts/campaigns/{campaign_id}", "loyalty.get_discount_campaign"),
+    Endpoint::new("PUT", "accounts/{account_id}/discounts/campaigns/{campaign_id}", "loyalty.update_discount_campaign"),
+    Endpoint::new("DELETE", "accounts/{account_id}/discounts/campaigns/{campaign_id}", "loyalty.delete_discount_campaign"),
+    Endpoint::new("GET", "accounts/{account_id}/discounts/campaigns", "loyalty.list_discount_campaigns"),
+    Endpoint::new("POST", "accounts/{account_id}/customers/{customer_id}/discounts", "loyalty.assign_discount_to_customer"),
+    Endpoint::new("GET", "accounts/{account_id}/customers/{customer_id}/discounts", "loyalty.list_customer_discounts"),
+    Endpoint::new("POST", "accounts/{account_id}/locations", "loyalty.create_location"),
+    Endpoint::new("GET", "accounts/{account_id}/locations/{location_id}", "loyalty.get_location"),
+    Endpoint::new("PUT", "accounts/{account_id}/locations/{location_id}", "loyalty.update_location"),
+    Endpoint::new("DELETE", "accounts/{account_id}/locations/{location_id}", "loyalty.delete_location"),
+    Endpoint::new("GET", "accounts/{account_id}/locations", "loyalty.list_locations"),
+    Endpoint::new("POST", "accounts/{account_id}/products/catalogs", "loyalty.create_product_catalog"),
+    Endpoint::new("GET", "accounts/{account_id}/products/catalogs/{catalog_id}", "loyalty.get_product_catalog"),
+    Endpoint::new("PUT", "accounts/{account_id}/products/catalogs/{catalog_id}", "loyalty.update_product_catalog"),
+    Endpoint::new("DELETE", "accounts/{account_id}/products/catalogs/{catalog_id}", "loyalty.delete_product_catalog"),
+    Endpoint::new("GET", "accounts/{account_id}/products/catalogs", "loyalty.list_product_catalogs"),
+    Endpoint::new("POST", "accounts/{account_id}/products/items", "loyalty.create_product_item"),
+    Endpoint::new("GET", "accounts/{account_id}/products/items/{item_id}", "loyalty.get_product_item"),
+    Endpoint::new("PUT", "accounts/{account_id}/products/items/{item_id}", "loyalty.update_product_item"),
+    Endpoint::new("DELETE", "accounts/{account_id}/products/items/{item_id}", "loyalty.delete_product_item"),
+    Endpoint::new("GET", "accounts/{account_id}/products/items", "loyalty.list_product_items"),
+    Endpoint::new("POST", "accounts/{account_id}/receipts", "loyalty.create_receipt"),
+    Endpoint::new("GET", "accounts/{account_id}/receipts/{receipt_id}", "loyalty.get_receipt"),
+    Endpoint::new("GET", "accounts/{account_id}/receipts", "loyalty.list_receipts"),
+    Endpoint::new("POST", "accounts/{account_id}/wallets/cards", "loyalty.create_virtual_card"),
+    Endpoint::new("GET", "accounts/{account_id}/wallets/cards/{card_id}", "loyalty.get_virtual_card"),
+    Endpoint::new("PUT", "accounts/{account_id}/wallets/cards/{card_id}", "loyalty.update_virtual_card"),
+    Endpoint::new("DELETE", "accounts/{account_id}/wallets/cards/{card_id}", "loyalty.delete_virtual_card"),
+    Endpoint::new("GET", "accounts/{account_id}/wallets/cards", "loyalty.list_virtual_cards"),
+    Endpoint::new("POST", "accounts/{account_id}/wallets/transactions", "loyalty.create_card_transaction"),
+    Endpoint::new("GET", "accounts/{account_id}/wallets/transactions/{transaction_id}", "loyalty.get_card_transaction"),
+    Endpoint::new("GET", "accounts/{account_id}/wallets/transactions", "loyalty.list_card_transactions"),
+    Endpoint::new("POST", "accounts/{account_id}/webhooks/subscriptions", "loyalty.create_webhook_subscription"),
+    Endpoint::new("GET", "accounts/{account_id}/webhooks/subscriptions/{subscription_id}", "loyalty.get_webhook_subscription"),
+    Endpoint::new("PUT", "accounts/{account_id}/webhooks/subscriptions/{subscription_id}", "loyalty.update_webhook_subscription"),
+    Endpoint::new("DELETE", "accounts/{account_id}/webhooks/subscriptions/{subscription_id}", "loyalty.delete_webhook_subscription"),
+    Endpoint::new("GET", "accounts/{account_id}/webhooks/subscriptions", "loyalty.list_webhook_subscriptions"),
+    Endpoint::new("GET", "accounts/{account_id}/webhooks/deliveries/{delivery_id}", "loyalty.get_webhook_delivery"),
+    Endpoint::new("GET", "accounts/{account_id}/webhooks/deliveries", "loyalty.list_webhook_deliveries"),
+    Endpoint::new("POST", "accounts/{account_id}/webhooks/deliveries/{delivery_id}/retry", "loyalty.retry_webhook_delivery"),
+];
 
-impl LoyaltyClient {
-    pub fn new(http: Client, base_url: String, account_id: String) -> Self {
-        Self { http, base_url, account_id }
+#[async_trait]
+pub trait LoyaltyAdapter: Send + Sync {
+    async fn get_json<T: serde::de::DeserializeOwned>(&self, path: &str) -> Result<T>;
+    async fn post_json<T: serde::de::DeserializeOwned, B: serde::Serialize + Send + Sync>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<T>;
+    async fn put_json<T: serde::de::DeserializeOwned, B: serde::Serialize + Send + Sync>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<T>;
+    async fn delete(&self, path: &str) -> Result<()>;
+    /// Sends a `PATCH` request. Adapters that predate PATCH support get a default that
+    /// returns [`DinteroError::Config`](dintero_core::DinteroError::Config).
+    async fn patch_json<T: serde::de::DeserializeOwned, B: serde::Serialize + Send + Sync>(
+        &self,
+        path: &str,
+        _body: &B,
+    ) -> Result<T> {
+        Err(dintero_core::DinteroError::Config(format!(
+            "PATCH {} is not supported by this adapter",
+            path
+        )))
     }
+    /// Sends a `GET` request with query parameters serialized from `query`, e.g. a
+    /// struct or a slice of pairs.
+    async fn get_json_with_query<
+        T: serde::de::DeserializeOwned,
+        Q: serde::Serialize + Send + Sync + ?Sized,
+    >(
+        &self,
+        path: &str,
+        query: &Q,
+    ) -> Result<T> {
+        self.get_json(&dintero_core::path::with_query(path, query)?).await
+    }
+}
+
+pub struct LoyaltyClient<A: LoyaltyAdapter> {
+    pub(crate) adapter: A,
+    account_id: String,
+}
 
-    pub(crate) fn url(&self, path: &str) -> String {
-        format!("{}/accounts/{}{}", self.base_url, self.account_id, path)
+impl<A: LoyaltyAdapter> LoyaltyClient<A> {
+    pub fn new(adapter: A, account_id: String) -> Self {
+        Self { adapter, account_id }
     }
 
-    pub(crate) fn http(&self) -> &Client {
-        &self.http
+    pub(crate) fn path(&self, path: &str) -> String {
+        format!("accounts/{}{}", self.account_id, path)
     }
 }
@@ -1,9 +1,13 @@
 //! Customer management in loyalty programs.
 
-use crate::client::LoyaltyClient;
+use crate::client::{LoyaltyAdapter, LoyaltyClient};
 use crate::error::Result;
-use crate::types::{Address, CustomerStatus, CustomerType, PaginatedResponse, PhoneNumber};
+use crate::types::{
+    page_offset, resolve_page, Address, CustomerStatus, CustomerType, PaginatedResponse,
+    PhoneNumber,
+};
 use chrono::{DateTime, Utc};
+use dintero_core::{paginate, PageRequest, Paginator};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -107,33 +111,15 @@ pub struct UpdateCustomerSettingsRequest {
     pub language: Option<String>,
 }
 
-impl LoyaltyClient {
+impl<A: LoyaltyAdapter> LoyaltyClient<A> {
     pub async fn create_customer(&self, req: CreateCustomerRequest) -> Result<Customer> {
-        let url = self.url("/customers");
-        let response = self.http().post(&url).json(&req).send().await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path("/customers");
+        self.adapter.post_json(&path, &req).await
     }
 
     pub async fn get_customer(&self, customer_id: &Uuid) -> Result<Customer> {
-        let url = self.url(&format!("/customers/{}", customer_id));
-        let response = self.http().get(&url).send().await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path(&format!("/customers/{}", customer_id));
+        self.adapter.get_json(&path).await
     }
 
     pub async fn update_customer(
@@ -141,65 +127,39 @@ impl LoyaltyClient {
         customer_id: &Uuid,
         req: UpdateCustomerRequest,
     ) -> Result<Customer> {
-        let url = self.url(&format!("/customers/{}", customer_id));
-        let response = self.http().put(&url).json(&req).send().await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path(&format!("/customers/{}", customer_id));
+        self.adapter.put_json(&path, &req).await
     }
 
     pub async fn delete_customer(&self, customer_id: &Uuid) -> Result<()> {
-        let url = self.url(&format!("/customers/{}", customer_id));
-        let response = self.http().delete(&url).send().await?;
-
-        if response.status().is_success() {
-            Ok(())
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path(&format!("/customers/{}", customer_id));
+        self.adapter.delete(&path).await
     }
 
     pub async fn list_customers(
         &self,
         req: ListCustomersRequest,
     ) -> Result<PaginatedResponse<Customer>> {
-        let mut url = self.url("/customers");
-        let mut params = vec![];
-
-        if let Some(limit) = req.limit {
-            params.push(format!("limit={}", limit));
-        }
-        if let Some(offset) = req.offset {
-            params.push(format!("offset={}", offset));
-        }
-        if let Some(query) = &req.query {
-            params.push(format!("query={}", urlencoding::encode(query)));
-        }
-
-        if !params.is_empty() {
-            url.push('?');
-            url.push_str(&params.join("&"));
-        }
-
-        let response = self.http().get(&url).send().await?;
+        let path = self.path("/customers");
+        let query = (
+            ("limit", req.limit),
+            ("offset", req.offset),
+            ("query", req.query),
+        );
+        self.adapter.get_json_with_query(&path, &query).await
+    }
 
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+    /// Streams all customers matching `req`, fetching further pages as needed.
+    pub fn customers_stream(&self, req: ListCustomersRequest) -> Paginator<'_, Customer> {
+        paginate(move |page: PageRequest| {
+            let page = resolve_page(page, req.limit, req.offset);
+            let req = ListCustomersRequest {
+                limit: page.page_size,
+                offset: page_offset(&page),
+                ..req.clone()
+            };
+            async move { Ok(self.list_customers(req).await?.into_page(&page)) }
+        })
     }
 
     pub async fn create_customer_token(
@@ -207,17 +167,8 @@ impl LoyaltyClient {
         customer_id: &Uuid,
         req: CreateCustomerTokenRequest,
     ) -> Result<CustomerToken> {
-        let url = self.url(&format!("/customers/{}/tokens", customer_id));
-        let response = self.http().post(&url).json(&req).send().await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path(&format!("/customers/{}/tokens", customer_id));
+        self.adapter.post_json(&path, &req).await
     }
 
     pub async fn accept_terms(
@@ -225,31 +176,13 @@ impl LoyaltyClient {
         customer_id: &Uuid,
         req: AcceptTermsRequest,
     ) -> Result<CustomerTerms> {
-        let url = self.url(&format!("/customers/{}/terms", customer_id));
-        let response = self.http().post(&url).json(&req).send().await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path(&format!("/customers/{}/terms", customer_id));
+        self.adapter.post_json(&path, &req).await
     }
 
     pub async fn get_customer_settings(&self, customer_id: &Uuid) -> Result<CustomerSettings> {
-        let url = self.url(&format!("/customers/{}/settings", customer_id));
-        let response = self.http().get(&url).send().await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path(&format!("/customers/{}/settings", customer_id));
+        self.adapter.get_json(&path).await
     }
 
     pub async fn update_customer_settings(
@@ -257,22 +190,7 @@ impl LoyaltyClient {
         customer_id: &Uuid,
         req: UpdateCustomerSettingsRequest,
     ) -> Result<CustomerSettings> {
-        let url = self.url(&format!("/customers/{}/settings", customer_id));
-        let response = self.http().put(&url).json(&req).send().await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
-    }
-}
-
-mod urlencoding {
-    pub fn encode(s: &str) -> String {
-        url::form_urlencoded::byte_serialize(s.as_bytes()).collect()
+        let path = self.path(&format!("/customers/{}/settings", customer_id));
+        self.adapter.put_json(&path, &req).await
     }
 }
@@ -1,9 +1,10 @@
 //! Discount management.
 
-use crate::client::LoyaltyClient;
+use crate::client::{LoyaltyAdapter, LoyaltyClient};
 use crate::error::Result;
-use crate::types::PaginatedResponse;
+use crate::types::{page_offset, resolve_page, PaginatedResponse};
 use chrono::{DateTime, Utc};
+use dintero_core::{paginate, PageRequest, Paginator};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -117,36 +118,18 @@ pub struct ListDiscountsRequest {
     pub offset: Option<u32>,
 }
 
-impl LoyaltyClient {
+impl<A: LoyaltyAdapter> LoyaltyClient<A> {
     pub async fn create_discount_rule(
         &self,
         req: CreateDiscountRuleRequest,
     ) -> Result<DiscountRule> {
-        let url = self.url("/discounts/rules");
-        let response = self.http().post(&url).json(&req).send().await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path("/discounts/rules");
+        self.adapter.post_json(&path, &req).await
     }
 
     pub async fn get_discount_rule(&self, rule_id: &Uuid) -> Result<DiscountRule> {
-        let url = self.url(&format!("/discounts/rules/{}", rule_id));
-        let response = self.http().get(&url).send().await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path(&format!("/discounts/rules/{}", rule_id));
+        self.adapter.get_json(&path).await
     }
 
     pub async fn update_discount_rule(
@@ -154,93 +137,47 @@ impl LoyaltyClient {
         rule_id: &Uuid,
         req: UpdateDiscountRuleRequest,
     ) -> Result<DiscountRule> {
-        let url = self.url(&format!("/discounts/rules/{}", rule_id));
-        let response = self.http().put(&url).json(&req).send().await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path(&format!("/discounts/rules/{}", rule_id));
+        self.adapter.put_json(&path, &req).await
     }
 
     pub async fn delete_discount_rule(&self, rule_id: &Uuid) -> Result<()> {
-        let url = self.url(&format!("/discounts/rules/{}", rule_id));
-        let response = self.http().delete(&url).send().await?;
-
-        if response.status().is_success() {
-            Ok(())
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path(&format!("/discounts/rules/{}", rule_id));
+        self.adapter.delete(&path).await
     }
 
     pub async fn list_discount_rules(
         &self,
         req: ListDiscountsRequest,
     ) -> Result<PaginatedResponse<DiscountRule>> {
-        let mut url = self.url("/discounts/rules");
-        let mut params = vec![];
-
-        if let Some(limit) = req.limit {
-            params.push(format!("limit={}", limit));
-        }
-        if let Some(offset) = req.offset {
-            params.push(format!("offset={}", offset));
-        }
-
-        if !params.is_empty() {
-            url.push('?');
-            url.push_str(&params.join("&"));
-        }
-
-        let response = self.http().get(&url).send().await?;
+        let path = self.path("/discounts/rules");
+        let query = (("limit", req.limit), ("offset", req.offset));
+        self.adapter.get_json_with_query(&path, &query).await
+    }
 
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+    /// Streams all discount rules matching `req`, fetching further pages as needed.
+    pub fn discount_rules_stream(&self, req: ListDiscountsRequest) -> Paginator<'_, DiscountRule> {
+        paginate(move |page: PageRequest| {
+            let page = resolve_page(page, req.limit, req.offset);
+            let req = ListDiscountsRequest {
+                limit: page.page_size,
+                offset: page_offset(&page),
+            };
+            async move { Ok(self.list_discount_rules(req).await?.into_page(&page)) }
+        })
     }
 
     pub async fn create_discount_campaign(
         &self,
         req: CreateDiscountCampaignRequest,
     ) -> Result<DiscountCampaign> {
-        let url = self.url("/discounts/campaigns");
-        let response = self.http().post(&url).json(&req).send().await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path("/discounts/campaigns");
+        self.adapter.post_json(&path, &req).await
     }
 
     pub async fn get_discount_campaign(&self, campaign_id: &Uuid) -> Result<DiscountCampaign> {
-        let url = self.url(&format!("/discounts/campaigns/{}", campaign_id));
-        let response = self.http().get(&url).send().await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path(&format!("/discounts/campaigns/{}", campaign_id));
+        self.adapter.get_json(&path).await
     }
 
     pub async fn update_discount_campaign(
@@ -248,62 +185,37 @@ impl LoyaltyClient {
         campaign_id: &Uuid,
         req: UpdateDiscountCampaignRequest,
     ) -> Result<DiscountCampaign> {
-        let url = self.url(&format!("/discounts/campaigns/{}", campaign_id));
-        let response = self.http().put(&url).json(&req).send().await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path(&format!("/discounts/campaigns/{}", campaign_id));
+        self.adapter.put_json(&path, &req).await
     }
 
     pub async fn delete_discount_campaign(&self, campaign_id: &Uuid) -> Result<()> {
-        let url = self.url(&format!("/discounts/campaigns/{}", campaign_id));
-        let response = self.http().delete(&url).send().await?;
-
-        if response.status().is_success() {
-            Ok(())
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path(&format!("/discounts/campaigns/{}", campaign_id));
+        self.adapter.delete(&path).await
     }
 
     pub async fn list_discount_campaigns(
         &self,
         req: ListDiscountsRequest,
     ) -> Result<PaginatedResponse<DiscountCampaign>> {
-        let mut url = self.url("/discounts/campaigns");
-        let mut params = vec![];
-
-        if let Some(limit) = req.limit {
-            params.push(format!("limit={}", limit));
-        }
-        if let Some(offset) = req.offset {
-            params.push(format!("offset={}", offset));
-        }
-
-        if !params.is_empty() {
-            url.push('?');
-            url.push_str(&params.join("&"));
-        }
-
-        let response = self.http().get(&url).send().await?;
+        let path = self.path("/discounts/campaigns");
+        let query = (("limit", req.limit), ("offset", req.offset));
+        self.adapter.get_json_with_query(&path, &query).await
+    }
 
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+    /// Streams all discount campaigns matching `req`, fetching further pages as needed.
+    pub fn discount_campaigns_stream(
+        &self,
+        req: ListDiscountsRequest,
+    ) -> Paginator<'_, DiscountCampaign> {
+        paginate(move |page: PageRequest| {
+            let page = resolve_page(page, req.limit, req.offset);
+            let req = ListDiscountsRequest {
+                limit: page.page_size,
+                offset: page_offset(&page),
+            };
+            async move { Ok(self.list_discount_campaigns(req).await?.into_page(&page)) }
+        })
     }
 
     pub async fn assign_discount_to_customer(
@@ -311,33 +223,19 @@ impl LoyaltyClient {
         customer_id: &Uuid,
         req: AssignDiscountRequest,
     ) -> Result<CustomerDiscount> {
-        let url = self.url(&format!("/customers/{}/discounts", customer_id));
-        let response = self.http().post(&url).json(&req).send().await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path(&format!("/customers/{}/discounts", customer_id));
+        self.adapter.post_json(&path, &req).await
     }
 
+    /// Lists the discounts assigned to a customer.
+    ///
+    /// The endpoint is not paginated and returns every discount in one response, so there is
+    /// no stream counterpart.
     pub async fn list_customer_discounts(
         &self,
         customer_id: &Uuid,
     ) -> Result<Vec<CustomerDiscount>> {
-        let url = self.url(&format!("/customers/{}/discounts", customer_id));
-        let response = self.http().get(&url).send().await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path(&format!("/customers/{}/discounts", customer_id));
+        self.adapter.get_json(&path).await
     }
 }
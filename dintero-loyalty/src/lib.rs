@@ -17,5 +17,7 @@ pub mod types;
 pub mod wallets;
 pub mod webhooks;
 
-pub use client::LoyaltyClient;
+pub use client::{LoyaltyAdapter, LoyaltyClient, ENDPOINTS};
 pub use error::{LoyaltyError, Result};
+
+pub use dintero_core::{ApiError, DinteroError, ErrorCode, FieldError};
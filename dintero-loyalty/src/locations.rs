@@ -1,9 +1,10 @@
 //! Location management.
 
-use crate::client::LoyaltyClient;
+use crate::client::{LoyaltyAdapter, LoyaltyClient};
 use crate::error::Result;
-use crate::types::{Address, PaginatedResponse};
+use crate::types::{page_offset, resolve_page, Address, PaginatedResponse};
 use chrono::{DateTime, Utc};
+use dintero_core::{paginate, PageRequest, Paginator};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -54,33 +55,15 @@ pub struct ListLocationsRequest {
     pub active: Option<bool>,
 }
 
-impl LoyaltyClient {
+impl<A: LoyaltyAdapter> LoyaltyClient<A> {
     pub async fn create_location(&self, req: CreateLocationRequest) -> Result<Location> {
-        let url = self.url("/locations");
-        let response = self.http().post(&url).json(&req).send().await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path("/locations");
+        self.adapter.post_json(&path, &req).await
     }
 
     pub async fn get_location(&self, location_id: &Uuid) -> Result<Location> {
-        let url = self.url(&format!("/locations/{}", location_id));
-        let response = self.http().get(&url).send().await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path(&format!("/locations/{}", location_id));
+        self.adapter.get_json(&path).await
     }
 
     pub async fn update_location(
@@ -88,64 +71,38 @@ impl LoyaltyClient {
         location_id: &Uuid,
         req: UpdateLocationRequest,
     ) -> Result<Location> {
-        let url = self.url(&format!("/locations/{}", location_id));
-        let response = self.http().put(&url).json(&req).send().await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path(&format!("/locations/{}", location_id));
+        self.adapter.put_json(&path, &req).await
     }
 
     pub async fn delete_location(&self, location_id: &Uuid) -> Result<()> {
-        let url = self.url(&format!("/locations/{}", location_id));
-        let response = self.http().delete(&url).send().await?;
-
-        if response.status().is_success() {
-            Ok(())
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path(&format!("/locations/{}", location_id));
+        self.adapter.delete(&path).await
     }
 
     pub async fn list_locations(
         &self,
         req: ListLocationsRequest,
     ) -> Result<PaginatedResponse<Location>> {
-        let mut url = self.url("/locations");
-        let mut params = vec![];
-
-        if let Some(limit) = req.limit {
-            params.push(format!("limit={}", limit));
-        }
-        if let Some(offset) = req.offset {
-            params.push(format!("offset={}", offset));
-        }
-        if let Some(active) = req.active {
-            params.push(format!("active={}", active));
-        }
-
-        if !params.is_empty() {
-            url.push('?');
-            url.push_str(&params.join("&"));
-        }
-
-        let response = self.http().get(&url).send().await?;
+        let path = self.path("/locations");
+        let query = (
+            ("limit", req.limit),
+            ("offset", req.offset),
+            ("active", req.active),
+        );
+        self.adapter.get_json_with_query(&path, &query).await
+    }
 
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+    /// Streams all locations matching `req`, fetching further pages as needed.
+    pub fn locations_stream(&self, req: ListLocationsRequest) -> Paginator<'_, Location> {
+        paginate(move |page: PageRequest| {
+            let page = resolve_page(page, req.limit, req.offset);
+            let req = ListLocationsRequest {
+                limit: page.page_size,
+                offset: page_offset(&page),
+                ..req.clone()
+            };
+            async move { Ok(self.list_locations(req).await?.into_page(&page)) }
+        })
     }
 }
@@ -1,9 +1,10 @@
 //! Product management in loyalty programs.
 
-use crate::client::LoyaltyClient;
+use crate::client::{LoyaltyAdapter, LoyaltyClient};
 use crate::error::Result;
-use crate::types::PaginatedResponse;
+use crate::types::{page_offset, resolve_page, PaginatedResponse};
 use chrono::{DateTime, Utc};
+use dintero_core::{paginate, PageRequest, Paginator};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -82,36 +83,18 @@ pub struct ListProductsRequest {
     pub catalog_id: Option<Uuid>,
 }
 
-impl LoyaltyClient {
+impl<A: LoyaltyAdapter> LoyaltyClient<A> {
     pub async fn create_product_catalog(
         &self,
         req: CreateProductCatalogRequest,
     ) -> Result<ProductCatalog> {
-        let url = self.url("/products/catalogs");
-        let response = self.http().post(&url).json(&req).send().await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path("/products/catalogs");
+        self.adapter.post_json(&path, &req).await
     }
 
     pub async fn get_product_catalog(&self, catalog_id: &Uuid) -> Result<ProductCatalog> {
-        let url = self.url(&format!("/products/catalogs/{}", catalog_id));
-        let response = self.http().get(&url).send().await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path(&format!("/products/catalogs/{}", catalog_id));
+        self.adapter.get_json(&path).await
     }
 
     pub async fn update_product_catalog(
@@ -119,90 +102,48 @@ impl LoyaltyClient {
         catalog_id: &Uuid,
         req: UpdateProductCatalogRequest,
     ) -> Result<ProductCatalog> {
-        let url = self.url(&format!("/products/catalogs/{}", catalog_id));
-        let response = self.http().put(&url).json(&req).send().await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path(&format!("/products/catalogs/{}", catalog_id));
+        self.adapter.put_json(&path, &req).await
     }
 
     pub async fn delete_product_catalog(&self, catalog_id: &Uuid) -> Result<()> {
-        let url = self.url(&format!("/products/catalogs/{}", catalog_id));
-        let response = self.http().delete(&url).send().await?;
-
-        if response.status().is_success() {
-            Ok(())
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path(&format!("/products/catalogs/{}", catalog_id));
+        self.adapter.delete(&path).await
     }
 
     pub async fn list_product_catalogs(
         &self,
         req: ListProductsRequest,
     ) -> Result<PaginatedResponse<ProductCatalog>> {
-        let mut url = self.url("/products/catalogs");
-        let mut params = vec![];
-
-        if let Some(limit) = req.limit {
-            params.push(format!("limit={}", limit));
-        }
-        if let Some(offset) = req.offset {
-            params.push(format!("offset={}", offset));
-        }
-
-        if !params.is_empty() {
-            url.push('?');
-            url.push_str(&params.join("&"));
-        }
-
-        let response = self.http().get(&url).send().await?;
+        let path = self.path("/products/catalogs");
+        let query = (("limit", req.limit), ("offset", req.offset));
+        self.adapter.get_json_with_query(&path, &query).await
+    }
 
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+    /// Streams all product catalogs matching `req`, fetching further pages as needed.
+    pub fn product_catalogs_stream(
+        &self,
+        req: ListProductsRequest,
+    ) -> Paginator<'_, ProductCatalog> {
+        paginate(move |page: PageRequest| {
+            let page = resolve_page(page, req.limit, req.offset);
+            let req = ListProductsRequest {
+                limit: page.page_size,
+                offset: page_offset(&page),
+                ..req.clone()
+            };
+            async move { Ok(self.list_product_catalogs(req).await?.into_page(&page)) }
+        })
     }
 
     pub async fn create_product_item(&self, req: CreateProductItemRequest) -> Result<ProductItem> {
-        let url = self.url("/products/items");
-        let response = self.http().post(&url).json(&req).send().await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path("/products/items");
+        self.adapter.post_json(&path, &req).await
     }
 
     pub async fn get_product_item(&self, item_id: &Uuid) -> Result<ProductItem> {
-        let url = self.url(&format!("/products/items/{}", item_id));
-        let response = self.http().get(&url).send().await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path(&format!("/products/items/{}", item_id));
+        self.adapter.get_json(&path).await
     }
 
     pub async fn update_product_item(
@@ -210,64 +151,38 @@ impl LoyaltyClient {
         item_id: &Uuid,
         req: UpdateProductItemRequest,
     ) -> Result<ProductItem> {
-        let url = self.url(&format!("/products/items/{}", item_id));
-        let response = self.http().put(&url).json(&req).send().await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path(&format!("/products/items/{}", item_id));
+        self.adapter.put_json(&path, &req).await
     }
 
     pub async fn delete_product_item(&self, item_id: &Uuid) -> Result<()> {
-        let url = self.url(&format!("/products/items/{}", item_id));
-        let response = self.http().delete(&url).send().await?;
-
-        if response.status().is_success() {
-            Ok(())
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path(&format!("/products/items/{}", item_id));
+        self.adapter.delete(&path).await
     }
 
     pub async fn list_product_items(
         &self,
         req: ListProductsRequest,
     ) -> Result<PaginatedResponse<ProductItem>> {
-        let mut url = self.url("/products/items");
-        let mut params = vec![];
-
-        if let Some(limit) = req.limit {
-            params.push(format!("limit={}", limit));
-        }
-        if let Some(offset) = req.offset {
-            params.push(format!("offset={}", offset));
-        }
-        if let Some(catalog_id) = req.catalog_id {
-            params.push(format!("catalog_id={}", catalog_id));
-        }
-
-        if !params.is_empty() {
-            url.push('?');
-            url.push_str(&params.join("&"));
-        }
-
-        let response = self.http().get(&url).send().await?;
+        let path = self.path("/products/items");
+        let query = (
+            ("limit", req.limit),
+            ("offset", req.offset),
+            ("catalog_id", req.catalog_id),
+        );
+        self.adapter.get_json_with_query(&path, &query).await
+    }
 
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+    /// Streams all product items matching `req`, fetching further pages as needed.
+    pub fn product_items_stream(&self, req: ListProductsRequest) -> Paginator<'_, ProductItem> {
+        paginate(move |page: PageRequest| {
+            let page = resolve_page(page, req.limit, req.offset);
+            let req = ListProductsRequest {
+                limit: page.page_size,
+                offset: page_offset(&page),
+                ..req.clone()
+            };
+            async move { Ok(self.list_product_items(req).await?.into_page(&page)) }
+        })
     }
 }
@@ -1,8 +1,10 @@
 //! Receipt management.
 
-use crate::client::LoyaltyClient;
+use crate::client::{LoyaltyAdapter, LoyaltyClient};
 use crate::error::Result;
+use crate::types::{page_offset, resolve_page};
 use chrono::{DateTime, Utc};
+use dintero_core::{paginate, Page, PageRequest, Paginator};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -65,66 +67,41 @@ pub struct ListReceiptsRequest {
     pub to: Option<DateTime<Utc>>,
 }
 
-impl LoyaltyClient {
+impl<A: LoyaltyAdapter> LoyaltyClient<A> {
     pub async fn create_receipt(&self, req: CreateReceiptRequest) -> Result<Receipt> {
-        let url = self.url("/receipts");
-        let response = self.http().post(&url).json(&req).send().await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path("/receipts");
+        self.adapter.post_json(&path, &req).await
     }
 
     pub async fn get_receipt(&self, receipt_id: &Uuid) -> Result<Receipt> {
-        let url = self.url(&format!("/receipts/{}", receipt_id));
-        let response = self.http().get(&url).send().await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path(&format!("/receipts/{}", receipt_id));
+        self.adapter.get_json(&path).await
     }
 
     pub async fn list_receipts(&self, req: ListReceiptsRequest) -> Result<Vec<Receipt>> {
-        let mut url = self.url("/receipts");
-        let mut params = vec![];
-
-        if let Some(limit) = req.limit {
-            params.push(format!("limit={}", limit));
-        }
-        if let Some(offset) = req.offset {
-            params.push(format!("offset={}", offset));
-        }
-        if let Some(customer_id) = req.customer_id {
-            params.push(format!("customer_id={}", customer_id));
-        }
-        if let Some(location_id) = req.location_id {
-            params.push(format!("location_id={}", location_id));
-        }
-
-        if !params.is_empty() {
-            url.push('?');
-            url.push_str(&params.join("&"));
-        }
-
-        let response = self.http().get(&url).send().await?;
+        let path = self.path("/receipts");
+        let query = (
+            ("limit", req.limit),
+            ("offset", req.offset),
+            ("customer_id", req.customer_id),
+            ("location_id", req.location_id),
+        );
+        self.adapter.get_json_with_query(&path, &query).await
+    }
 
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+    /// Streams all receipts matching `req`, fetching further pages as needed.
+    pub fn receipts_stream(&self, req: ListReceiptsRequest) -> Paginator<'_, Receipt> {
+        paginate(move |page: PageRequest| {
+            let page = resolve_page(page, req.limit, req.offset);
+            let req = ListReceiptsRequest {
+                limit: page.page_size,
+                offset: page_offset(&page),
+                ..req.clone()
+            };
+            async move {
+                let receipts = self.list_receipts(req).await?;
+                Ok(Page::from_offset(receipts, &page, None))
+            }
+        })
     }
 }
@@ -1,6 +1,7 @@
 //! Type definitions for loyalty programs.
 
 use chrono::{DateTime, Utc};
+use dintero_core::{Cursor, Page, PageRequest};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -36,6 +37,32 @@ pub struct PaginatedResponse<T> {
     pub offset: Option<u32>,
 }
 
+impl<T> PaginatedResponse<T> {
+    /// Converts the response into a [`Page`] of the stream that requested it.
+    pub fn into_page(self, request: &PageRequest) -> Page<T> {
+        Page::from_offset(self.items, request, self.total_count)
+    }
+}
+
+/// Resolves a page requested by a stream against the `limit` and `offset` of the list
+/// request the stream was created with, which apply to the first page.
+pub(crate) fn resolve_page(
+    page: PageRequest,
+    limit: Option<u32>,
+    offset: Option<u32>,
+) -> PageRequest {
+    let offset = page.offset().unwrap_or(offset.map_or(0, u64::from));
+    PageRequest {
+        cursor: Some(Cursor::Offset(offset)),
+        page_size: page.page_size.or(limit),
+    }
+}
+
+/// Returns the `offset` parameter of a resolved page.
+pub(crate) fn page_offset(page: &PageRequest) -> Option<u32> {
+    page.offset().map(|offset| u32::try_from(offset).unwrap_or(u32::MAX))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TimeRange {
     pub from: Option<DateTime<Utc>>,
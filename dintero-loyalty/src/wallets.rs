@@ -1,9 +1,10 @@
 //! Wallet management for loyalty points.
 
-use crate::client::LoyaltyClient;
+use crate::client::{LoyaltyAdapter, LoyaltyClient};
 use crate::error::Result;
-use crate::types::PaginatedResponse;
+use crate::types::{page_offset, resolve_page, PaginatedResponse};
 use chrono::{DateTime, Utc};
+use dintero_core::{paginate, PageRequest, Paginator};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -103,33 +104,15 @@ pub struct ListTransactionsRequest {
     pub transaction_type: Option<TransactionType>,
 }
 
-impl LoyaltyClient {
+impl<A: LoyaltyAdapter> LoyaltyClient<A> {
     pub async fn create_virtual_card(&self, req: CreateVirtualCardRequest) -> Result<VirtualCard> {
-        let url = self.url("/wallets/cards");
-        let response = self.http().post(&url).json(&req).send().await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path("/wallets/cards");
+        self.adapter.post_json(&path, &req).await
     }
 
     pub async fn get_virtual_card(&self, card_id: &Uuid) -> Result<VirtualCard> {
-        let url = self.url(&format!("/wallets/cards/{}", card_id));
-        let response = self.http().get(&url).send().await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path(&format!("/wallets/cards/{}", card_id));
+        self.adapter.get_json(&path).await
     }
 
     pub async fn update_virtual_card(
@@ -137,129 +120,80 @@ impl LoyaltyClient {
         card_id: &Uuid,
         req: UpdateVirtualCardRequest,
     ) -> Result<VirtualCard> {
-        let url = self.url(&format!("/wallets/cards/{}", card_id));
-        let response = self.http().put(&url).json(&req).send().await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path(&format!("/wallets/cards/{}", card_id));
+        self.adapter.put_json(&path, &req).await
     }
 
     pub async fn delete_virtual_card(&self, card_id: &Uuid) -> Result<()> {
-        let url = self.url(&format!("/wallets/cards/{}", card_id));
-        let response = self.http().delete(&url).send().await?;
-
-        if response.status().is_success() {
-            Ok(())
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path(&format!("/wallets/cards/{}", card_id));
+        self.adapter.delete(&path).await
     }
 
     pub async fn list_virtual_cards(
         &self,
         req: ListCardsRequest,
     ) -> Result<PaginatedResponse<VirtualCard>> {
-        let mut url = self.url("/wallets/cards");
-        let mut params = vec![];
-
-        if let Some(limit) = req.limit {
-            params.push(format!("limit={}", limit));
-        }
-        if let Some(offset) = req.offset {
-            params.push(format!("offset={}", offset));
-        }
-        if let Some(customer_id) = req.customer_id {
-            params.push(format!("customer_id={}", customer_id));
-        }
-
-        if !params.is_empty() {
-            url.push('?');
-            url.push_str(&params.join("&"));
-        }
-
-        let response = self.http().get(&url).send().await?;
+        let path = self.path("/wallets/cards");
+        let query = (
+            ("limit", req.limit),
+            ("offset", req.offset),
+            ("customer_id", req.customer_id),
+        );
+        self.adapter.get_json_with_query(&path, &query).await
+    }
 
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+    /// Streams all virtual cards matching `req`, fetching further pages as needed.
+    pub fn virtual_cards_stream(&self, req: ListCardsRequest) -> Paginator<'_, VirtualCard> {
+        paginate(move |page: PageRequest| {
+            let page = resolve_page(page, req.limit, req.offset);
+            let req = ListCardsRequest {
+                limit: page.page_size,
+                offset: page_offset(&page),
+                ..req.clone()
+            };
+            async move { Ok(self.list_virtual_cards(req).await?.into_page(&page)) }
+        })
     }
 
     pub async fn create_card_transaction(
         &self,
         req: CreateCardTransactionRequest,
     ) -> Result<CardTransaction> {
-        let url = self.url("/wallets/transactions");
-        let response = self.http().post(&url).json(&req).send().await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path("/wallets/transactions");
+        self.adapter.post_json(&path, &req).await
     }
 
     pub async fn get_card_transaction(&self, transaction_id: &Uuid) -> Result<CardTransaction> {
-        let url = self.url(&format!("/wallets/transactions/{}", transaction_id));
-        let response = self.http().get(&url).send().await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path(&format!("/wallets/transactions/{}", transaction_id));
+        self.adapter.get_json(&path).await
     }
 
     pub async fn list_card_transactions(
         &self,
         req: ListTransactionsRequest,
     ) -> Result<PaginatedResponse<CardTransaction>> {
-        let mut url = self.url("/wallets/transactions");
-        let mut params = vec![];
-
-        if let Some(limit) = req.limit {
-            params.push(format!("limit={}", limit));
-        }
-        if let Some(offset) = req.offset {
-            params.push(format!("offset={}", offset));
-        }
-        if let Some(card_id) = req.card_id {
-            params.push(format!("card_id={}", card_id));
-        }
-
-        if !params.is_empty() {
-            url.push('?');
-            url.push_str(&params.join("&"));
-        }
-
-        let response = self.http().get(&url).send().await?;
+        let path = self.path("/wallets/transactions");
+        let query = (
+            ("limit", req.limit),
+            ("offset", req.offset),
+            ("card_id", req.card_id),
+        );
+        self.adapter.get_json_with_query(&path, &query).await
+    }
 
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+    /// Streams all card transactions matching `req`, fetching further pages as needed.
+    pub fn card_transactions_stream(
+        &self,
+        req: ListTransactionsRequest,
+    ) -> Paginator<'_, CardTransaction> {
+        paginate(move |page: PageRequest| {
+            let page = resolve_page(page, req.limit, req.offset);
+            let req = ListTransactionsRequest {
+                limit: page.page_size,
+                offset: page_offset(&page),
+                ..req.clone()
+            };
+            async move { Ok(self.list_card_transactions(req).await?.into_page(&page)) }
+        })
     }
 }
@@ -1,9 +1,10 @@
 //! Webhook management for loyalty events.
 
-use crate::client::LoyaltyClient;
+use crate::client::{LoyaltyAdapter, LoyaltyClient};
 use crate::error::Result;
-use crate::types::PaginatedResponse;
+use crate::types::{page_offset, resolve_page, PaginatedResponse};
 use chrono::{DateTime, Utc};
+use dintero_core::{paginate, PageRequest, Paginator};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -86,39 +87,21 @@ pub struct ListDeliveriesRequest {
     pub status: Option<DeliveryStatus>,
 }
 
-impl LoyaltyClient {
+impl<A: LoyaltyAdapter> LoyaltyClient<A> {
     pub async fn create_webhook_subscription(
         &self,
         req: CreateWebhookSubscriptionRequest,
     ) -> Result<WebhookSubscription> {
-        let url = self.url("/webhooks/subscriptions");
-        let response = self.http().post(&url).json(&req).send().await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path("/webhooks/subscriptions");
+        self.adapter.post_json(&path, &req).await
     }
 
     pub async fn get_webhook_subscription(
         &self,
         subscription_id: &Uuid,
     ) -> Result<WebhookSubscription> {
-        let url = self.url(&format!("/webhooks/subscriptions/{}", subscription_id));
-        let response = self.http().get(&url).send().await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path(&format!("/webhooks/subscriptions/{}", subscription_id));
+        self.adapter.get_json(&path).await
     }
 
     pub async fn update_webhook_subscription(
@@ -126,123 +109,75 @@ impl LoyaltyClient {
         subscription_id: &Uuid,
         req: UpdateWebhookSubscriptionRequest,
     ) -> Result<WebhookSubscription> {
-        let url = self.url(&format!("/webhooks/subscriptions/{}", subscription_id));
-        let response = self.http().put(&url).json(&req).send().await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path(&format!("/webhooks/subscriptions/{}", subscription_id));
+        self.adapter.put_json(&path, &req).await
     }
 
     pub async fn delete_webhook_subscription(&self, subscription_id: &Uuid) -> Result<()> {
-        let url = self.url(&format!("/webhooks/subscriptions/{}", subscription_id));
-        let response = self.http().delete(&url).send().await?;
-
-        if response.status().is_success() {
-            Ok(())
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path(&format!("/webhooks/subscriptions/{}", subscription_id));
+        self.adapter.delete(&path).await
     }
 
     pub async fn list_webhook_subscriptions(
         &self,
         req: ListWebhooksRequest,
     ) -> Result<PaginatedResponse<WebhookSubscription>> {
-        let mut url = self.url("/webhooks/subscriptions");
-        let mut params = vec![];
-
-        if let Some(limit) = req.limit {
-            params.push(format!("limit={}", limit));
-        }
-        if let Some(offset) = req.offset {
-            params.push(format!("offset={}", offset));
-        }
-
-        if !params.is_empty() {
-            url.push('?');
-            url.push_str(&params.join("&"));
-        }
-
-        let response = self.http().get(&url).send().await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path("/webhooks/subscriptions");
+        let query = (("limit", req.limit), ("offset", req.offset));
+        self.adapter.get_json_with_query(&path, &query).await
+    }
+
+    /// Streams all webhook subscriptions matching `req`, fetching further pages as needed.
+    pub fn webhook_subscriptions_stream(
+        &self,
+        req: ListWebhooksRequest,
+    ) -> Paginator<'_, WebhookSubscription> {
+        paginate(move |page: PageRequest| {
+            let page = resolve_page(page, req.limit, req.offset);
+            let req = ListWebhooksRequest {
+                limit: page.page_size,
+                offset: page_offset(&page),
+            };
+            async move { Ok(self.list_webhook_subscriptions(req).await?.into_page(&page)) }
+        })
     }
 
     pub async fn get_webhook_delivery(&self, delivery_id: &Uuid) -> Result<WebhookDelivery> {
-        let url = self.url(&format!("/webhooks/deliveries/{}", delivery_id));
-        let response = self.http().get(&url).send().await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path(&format!("/webhooks/deliveries/{}", delivery_id));
+        self.adapter.get_json(&path).await
     }
 
     pub async fn list_webhook_deliveries(
         &self,
         req: ListDeliveriesRequest,
     ) -> Result<PaginatedResponse<WebhookDelivery>> {
-        let mut url = self.url("/webhooks/deliveries");
-        let mut params = vec![];
-
-        if let Some(limit) = req.limit {
-            params.push(format!("limit={}", limit));
-        }
-        if let Some(offset) = req.offset {
-            params.push(format!("offset={}", offset));
-        }
-        if let Some(subscription_id) = req.subscription_id {
-            params.push(format!("subscription_id={}", subscription_id));
-        }
-
-        if !params.is_empty() {
-            url.push('?');
-            url.push_str(&params.join("&"));
-        }
-
-        let response = self.http().get(&url).send().await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path("/webhooks/deliveries");
+        let query = (
+            ("limit", req.limit),
+            ("offset", req.offset),
+            ("subscription_id", req.subscription_id),
+        );
+        self.adapter.get_json_with_query(&path, &query).await
+    }
+
+    /// Streams all webhook deliveries matching `req`, fetching further pages as needed.
+    pub fn webhook_deliveries_stream(
+        &self,
+        req: ListDeliveriesRequest,
+    ) -> Paginator<'_, WebhookDelivery> {
+        paginate(move |page: PageRequest| {
+            let page = resolve_page(page, req.limit, req.offset);
+            let req = ListDeliveriesRequest {
+                limit: page.page_size,
+                offset: page_offset(&page),
+                ..req.clone()
+            };
+            async move { Ok(self.list_webhook_deliveries(req).await?.into_page(&page)) }
+        })
     }
 
     pub async fn retry_webhook_delivery(&self, delivery_id: &Uuid) -> Result<WebhookDelivery> {
-        let url = self.url(&format!("/webhooks/deliveries/{}/retry", delivery_id));
-        let response = self.http().post(&url).send().await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(crate::error::LoyaltyError::Api {
-                status: response.status().as_u16(),
-                message: response.text().await?,
-            })
-        }
+        let path = self.path(&format!("/webhooks/deliveries/{}/retry", delivery_id));
+        self.adapter.post_json(&path, &serde_json::json!({})).await
     }
 }
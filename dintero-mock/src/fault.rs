@@ -0,0 +1,128 @@
+//! Injected failures and latency.
+
+use axum::http::Method;
+use dintero_core::ErrorCode;
+use std::time::Duration;
+
+/// A failure or delay injected into the responses of a [`MockServer`].
+///
+/// Faults apply to every request unless narrowed with [`method`](Self::method) and
+/// [`path`](Self::path), and stay active until removed unless limited with
+/// [`times`](Self::times):
+///
+/// ```
+/// use dintero_mock::Fault;
+/// use std::time::Duration;
+///
+/// // The next two captures fail with 503, then captures succeed again.
+/// let fault = Fault::status(503).path("/capture").times(2);
+///
+/// // Every request is rate limited.
+/// let fault = Fault::rate_limited(Duration::from_secs(1));
+///
+/// // Session requests take half a second, then succeed.
+/// let fault = Fault::latency(Duration::from_millis(500)).path("/sessions");
+/// ```
+///
+/// [`MockServer`]: crate::MockServer
+#[derive(Debug, Clone)]
+pub struct Fault {
+    pub(crate) method: Option<Method>,
+    pub(crate) path: Option<String>,
+    pub(crate) remaining: Option<usize>,
+    pub(crate) latency: Duration,
+    pub(crate) response: Option<FaultResponse>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct FaultResponse {
+    pub(crate) status: u16,
+    pub(crate) code: String,
+    pub(crate) message: String,
+    pub(crate) retry_after: Option<Duration>,
+}
+
+impl Fault {
+    /// Fails matching requests with `status` and a Dintero error body.
+    pub fn status(status: u16) -> Self {
+        let code = ErrorCode::from_status(status);
+        Self::new(Some(FaultResponse {
+            status,
+            code: code.as_str().to_string(),
+            message: format!("injected {} error", status),
+            retry_after: None,
+        }))
+    }
+
+    /// Fails matching requests with `429 Too Many Requests` and a `Retry-After` header.
+    pub fn rate_limited(retry_after: Duration) -> Self {
+        let mut fault = Self::status(429);
+        if let Some(response) = &mut fault.response {
+            response.retry_after = Some(retry_after);
+        }
+        fault
+    }
+
+    /// Delays matching requests by `latency`, then handles them normally.
+    pub fn latency(latency: Duration) -> Self {
+        let mut fault = Self::new(None);
+        fault.latency = latency;
+        fault
+    }
+
+    fn new(response: Option<FaultResponse>) -> Self {
+        Self {
+            method: None,
+            path: None,
+            remaining: None,
+            latency: Duration::ZERO,
+            response,
+        }
+    }
+
+    /// Sets the error code in the error body.
+    pub fn code(mut self, code: impl Into<String>) -> Self {
+        if let Some(response) = &mut self.response {
+            response.code = code.into();
+        }
+        self
+    }
+
+    /// Sets the message in the error body.
+    pub fn message(mut self, message: impl Into<String>) -> Self {
+        if let Some(response) = &mut self.response {
+            response.message = message.into();
+        }
+        self
+    }
+
+    /// Also delays the failing response by `latency`.
+    pub fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    /// Only applies to requests with `method`.
+    pub fn method(mut self, method: Method) -> Self {
+        self.method = Some(method);
+        self
+    }
+
+    /// Only applies to requests whose path contains `path`.
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Applies to the next `times` matching requests only.
+    pub fn times(mut self, times: usize) -> Self {
+        self.remaining = Some(times);
+        self
+    }
+
+    pub(crate) fn matches(&self, method: &Method, path: &str) -> bool {
+        self.remaining != Some(0)
+            && self.method.as_ref().is_none_or(|m| m == method)
+            && self.path.as_deref().is_none_or(|p| path.contains(p))
+    }
+}
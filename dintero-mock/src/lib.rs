@@ -0,0 +1,36 @@
+//! # Dintero Mock
+//!
+//! An in-process stand-in for the Dintero API, for integration tests that run offline.
+//!
+//! [`MockServer`] serves the checkout session and transaction endpoints used by
+//! `CheckoutClient` and `PaymentsClient`, and the order endpoints used by `OrdersClient`,
+//! on a local port. State is kept in memory, so a test can walk a payment through its
+//! whole life: create a session, [`authorize`](MockServer::authorize_session) it as if
+//! the customer paid, then capture, refund or void the transaction. [`Fault`]s inject
+//! errors, rate limiting and latency.
+//!
+//! ```ignore
+//! use dintero_mock::{Fault, MockServer};
+//! use std::time::Duration;
+//!
+//! let server = MockServer::start().await;
+//! let client = DinteroClient::new(
+//!     Config::builder("T12345678").api_key("test").base_url(server.url()).build()?,
+//! )?;
+//!
+//! let session = client.checkout().create_session(request).await?;
+//! let transaction_id = server.authorize_session(&session.id).unwrap();
+//!
+//! // The first capture is rate limited.
+//! server.inject(Fault::rate_limited(Duration::from_secs(1)).path("/capture").times(1));
+//! client.checkout().capture_transaction(&transaction_id, CaptureRequest::new(10000)).await?;
+//! ```
+
+mod fault;
+mod routes;
+mod server;
+pub mod state;
+
+pub use axum::http::Method;
+pub use fault::Fault;
+pub use server::{MockServer, RecordedRequest};
@@ -0,0 +1,286 @@
+//! Checkout sessions and payment transactions.
+
+use super::{amount, page, parse_body, status_matches, Params, Result};
+use crate::server::{App, MockError};
+use crate::state::{session_expiry, Session, SessionStatus, Transaction};
+use axum::body::Bytes;
+use axum::extract::{Path, Query, State};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use chrono::{Duration as ChronoDuration, Utc};
+use dintero_core::Currency;
+use serde_json::{json, Value};
+
+pub(super) fn router() -> Router<App> {
+    Router::new()
+        .route(
+            "/v1/accounts/{aid}/sessions",
+            get(list_sessions).post(create_session),
+        )
+        .route(
+            "/v1/accounts/{aid}/sessions/{id}",
+            get(get_session).put(update_session),
+        )
+        .route(
+            "/v1/accounts/{aid}/sessions/{id}/cancel",
+            post(cancel_session),
+        )
+        .route("/v1/accounts/{aid}/transactions", get(list_transactions))
+        .route(
+            "/v1/accounts/{aid}/transactions/{id}",
+            get(get_transaction).put(update_transaction),
+        )
+        .route(
+            "/v1/accounts/{aid}/transactions/{id}/capture",
+            post(capture),
+        )
+        .route("/v1/accounts/{aid}/transactions/{id}/refund", post(refund))
+        .route("/v1/accounts/{aid}/transactions/{id}/void", post(void))
+        .route(
+            "/v1/accounts/{aid}/transactions/{id}/extend_authorization",
+            post(extend_authorization),
+        )
+}
+
+/// Checks that a session request carries an order with an amount and a known currency.
+fn session_order(body: &Value) -> Result<Value> {
+    let order = &body["order"];
+    amount(order).map_err(|err| MockError::bad_request(format!("order: {}", err.message())))?;
+    let currency = order["currency"].as_str().unwrap_or_default();
+    Currency::from_code(currency)
+        .ok_or_else(|| MockError::bad_request(format!("order: unknown currency {:?}", currency)))?;
+    Ok(order.clone())
+}
+
+async fn create_session(
+    State(app): State<App>,
+    Path(aid): Path<String>,
+    body: Bytes,
+) -> Result<Json<Session>> {
+    let body = parse_body(&body)?;
+    let order = session_order(&body)?;
+
+    let mut state = app.state();
+    let id = state.next_id(&aid, "session");
+    let now = Utc::now();
+    let session = Session {
+        url: format!("{}/v1/view/{}", app.base_url, id),
+        id: id.clone(),
+        status: SessionStatus::Created,
+        created_at: now,
+        updated_at: now,
+        expires_at: session_expiry(now),
+        order,
+        transaction_id: None,
+        callback_url: body["url"]["callback_url"].as_str().map(str::to_string),
+    };
+    state.sessions.insert(id, session.clone());
+    Ok(Json(session))
+}
+
+async fn get_session(
+    State(app): State<App>,
+    Path((_aid, id)): Path<(String, String)>,
+) -> Result<Json<Session>> {
+    let state = app.state();
+    let session = state.sessions.get(&id).ok_or_else(|| MockError::not_found("session", &id))?;
+    Ok(Json(session.clone()))
+}
+
+async fn update_session(
+    State(app): State<App>,
+    Path((_aid, id)): Path<(String, String)>,
+    body: Bytes,
+) -> Result<Json<Session>> {
+    let body = parse_body(&body)?;
+    let order = session_order(&body)?;
+
+    let mut state = app.state();
+    let session =
+        state.sessions.get_mut(&id).ok_or_else(|| MockError::not_found("session", &id))?;
+    if session.status != SessionStatus::Created {
+        return Err(MockError::conflict(
+            "only sessions that are not paid can be updated",
+        ));
+    }
+    session.order = order;
+    if let Some(callback_url) = body["url"]["callback_url"].as_str() {
+        session.callback_url = Some(callback_url.to_string());
+    }
+    session.updated_at = Utc::now();
+    Ok(Json(session.clone()))
+}
+
+async fn list_sessions(
+    State(app): State<App>,
+    Path(_aid): Path<String>,
+    Query(query): Query<Params>,
+) -> Json<Value> {
+    let state = app.state();
+    let (sessions, next_page_token) = page(state.sessions.iter(), &query);
+    Json(json!({ "sessions": sessions, "next_page_token": next_page_token }))
+}
+
+async fn cancel_session(
+    State(app): State<App>,
+    Path((_aid, id)): Path<(String, String)>,
+) -> Result<Json<Session>> {
+    let mut state = app.state();
+    let session =
+        state.sessions.get_mut(&id).ok_or_else(|| MockError::not_found("session", &id))?;
+    match session.status {
+        SessionStatus::Created => {
+            session.status = SessionStatus::Cancelled;
+            session.updated_at = Utc::now();
+        }
+        SessionStatus::Cancelled => {}
+        SessionStatus::Authorized => {
+            return Err(MockError::conflict("a paid session cannot be cancelled"));
+        }
+    }
+    Ok(Json(session.clone()))
+}
+
+async fn get_transaction(
+    State(app): State<App>,
+    Path((_aid, id)): Path<(String, String)>,
+) -> Result<Json<Transaction>> {
+    let state = app.state();
+    let transaction =
+        state.transactions.get(&id).ok_or_else(|| MockError::not_found("transaction", &id))?;
+    Ok(Json(transaction.clone()))
+}
+
+async fn list_transactions(
+    State(app): State<App>,
+    Path(_aid): Path<String>,
+    Query(query): Query<Params>,
+) -> Result<Json<Value>> {
+    let state = app.state();
+    let status = query.get("status");
+    let transactions = state.transactions.iter().filter(|(_, transaction)| {
+        let value = serde_json::to_value(transaction.status).unwrap_or_default();
+        status_matches(status, value.as_str().unwrap_or_default())
+    });
+    let (transactions, next_page_token) = page(transactions, &query);
+    Ok(Json(
+        json!({ "transactions": transactions, "next_page_token": next_page_token }),
+    ))
+}
+
+async fn update_transaction(
+    State(app): State<App>,
+    Path((_aid, id)): Path<(String, String)>,
+    body: Bytes,
+) -> Result<Json<Transaction>> {
+    let body = parse_body(&body)?;
+    let mut state = app.state();
+    let transaction =
+        state.transactions.get_mut(&id).ok_or_else(|| MockError::not_found("transaction", &id))?;
+
+    if let Some(reference) = body["merchant_reference"].as_str() {
+        transaction.merchant_reference = Some(reference.to_string());
+    }
+    if let Some(reference) = body["merchant_reference_2"].as_str() {
+        transaction.merchant_reference_2 = Some(reference.to_string());
+    }
+    if !body["metadata"].is_null() {
+        transaction.metadata = Some(body["metadata"].clone());
+    }
+    transaction.updated_at = Utc::now();
+    Ok(Json(transaction.clone()))
+}
+
+/// Applies an operation to a transaction and returns the updated transaction.
+fn operate(
+    app: &App,
+    id: &str,
+    operation: impl FnOnce(&mut Transaction) -> Result<()>,
+) -> Result<Json<Transaction>> {
+    let mut state = app.state();
+    let transaction =
+        state.transactions.get_mut(id).ok_or_else(|| MockError::not_found("transaction", id))?;
+    if transaction.voided {
+        return Err(MockError::conflict("the transaction is voided"));
+    }
+    operation(transaction)?;
+    transaction.update_status(Utc::now());
+    Ok(Json(transaction.clone()))
+}
+
+async fn capture(
+    State(app): State<App>,
+    Path((_aid, id)): Path<(String, String)>,
+    body: Bytes,
+) -> Result<Json<Transaction>> {
+    let amount = amount(&parse_body(&body)?)?;
+    operate(&app, &id, |transaction| {
+        let capturable = transaction.amount - transaction.captured_amount;
+        if transaction.refunded_amount > 0 || amount > capturable {
+            return Err(MockError::bad_request(format!(
+                "cannot capture {}, {} is capturable",
+                amount, capturable
+            )));
+        }
+        transaction.captured_amount += amount;
+        Ok(())
+    })
+}
+
+async fn refund(
+    State(app): State<App>,
+    Path((_aid, id)): Path<(String, String)>,
+    body: Bytes,
+) -> Result<Json<Transaction>> {
+    let amount = amount(&parse_body(&body)?)?;
+    operate(&app, &id, |transaction| {
+        let refundable = transaction.captured_amount - transaction.refunded_amount;
+        if amount > refundable {
+            return Err(MockError::bad_request(format!(
+                "cannot refund {}, {} is refundable",
+                amount, refundable
+            )));
+        }
+        transaction.refunded_amount += amount;
+        Ok(())
+    })
+}
+
+async fn void(
+    State(app): State<App>,
+    Path((_aid, id)): Path<(String, String)>,
+) -> Result<Json<Transaction>> {
+    operate(&app, &id, |transaction| {
+        if transaction.captured_amount > 0 {
+            return Err(MockError::conflict(
+                "a captured transaction cannot be voided",
+            ));
+        }
+        transaction.voided = true;
+        Ok(())
+    })
+}
+
+/// Extends the authorization by `days` (checkout) or `extend_days` (payments), counted
+/// from the current expiry or from now if it has passed.
+async fn extend_authorization(
+    State(app): State<App>,
+    Path((_aid, id)): Path<(String, String)>,
+    body: Bytes,
+) -> Result<Json<Transaction>> {
+    let body = parse_body(&body)?;
+    let days = body["days"].as_i64().or_else(|| body["extend_days"].as_i64()).unwrap_or(7);
+    if days <= 0 {
+        return Err(MockError::bad_request("days must be positive"));
+    }
+    operate(&app, &id, |transaction| {
+        if transaction.captured_amount >= transaction.amount || transaction.refunded_amount > 0 {
+            return Err(MockError::conflict(
+                "only an authorized amount can be extended",
+            ));
+        }
+        let from = transaction.authorization_expires_at.max(Utc::now());
+        transaction.authorization_expires_at = from + ChronoDuration::days(days);
+        Ok(())
+    })
+}
@@ -0,0 +1,101 @@
+//! Endpoints implemented by the mock server.
+
+mod checkout;
+mod orders;
+
+use crate::server::{App, MockError};
+use axum::extract::Path;
+use axum::http::{Method, Uri};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+pub(crate) type Result<T> = std::result::Result<T, MockError>;
+
+/// Query parameters of a request.
+pub(crate) type Params = HashMap<String, String>;
+
+/// Number of items returned by list endpoints when the request sets no `limit`.
+const DEFAULT_LIMIT: usize = 25;
+
+pub(crate) fn router() -> Router<App> {
+    Router::new()
+        .route("/v1/accounts/{aid}/auth/token", post(token))
+        .merge(checkout::router())
+        .merge(orders::router())
+        .route("/v1/accounts/{aid}/settlements", get(settlements))
+        .fallback(fallback)
+}
+
+async fn token(Path(_aid): Path<String>) -> Json<Value> {
+    Json(json!({
+        "access_token": "dintero-mock-token",
+        "token_type": "Bearer",
+        "expires_in": 3600,
+    }))
+}
+
+async fn settlements(Path(_aid): Path<String>) -> Json<Value> {
+    Json(json!({ "settlements": [] }))
+}
+
+async fn fallback(method: Method, uri: Uri) -> MockError {
+    MockError::new(
+        axum::http::StatusCode::NOT_FOUND,
+        format!(
+            "{} {} is not implemented by the mock server",
+            method,
+            uri.path()
+        ),
+    )
+}
+
+/// Parses a JSON request body, answering with 400 if it is invalid.
+pub(crate) fn parse_body(body: &[u8]) -> Result<Value> {
+    if body.is_empty() {
+        return Ok(json!({}));
+    }
+    serde_json::from_slice(body)
+        .map_err(|err| MockError::bad_request(format!("invalid JSON body: {}", err)))
+}
+
+/// Returns the positive `amount` field of a request body.
+pub(crate) fn amount(body: &Value) -> Result<i64> {
+    match body["amount"].as_i64() {
+        Some(amount) if amount > 0 => Ok(amount),
+        Some(_) => Err(MockError::bad_request("amount must be positive")),
+        None => Err(MockError::bad_request("amount is required")),
+    }
+}
+
+/// Returns one page of `items`, which are sorted by ID, together with the next page
+/// token. The page token is the ID of the last item on the previous page.
+pub(crate) fn page<'a, T: 'a>(
+    items: impl Iterator<Item = (&'a String, T)>,
+    query: &HashMap<String, String>,
+) -> (Vec<T>, Option<String>) {
+    let limit = query.get("limit").and_then(|limit| limit.parse().ok()).unwrap_or(DEFAULT_LIMIT);
+    let after = query.get("page_token");
+
+    let mut page = Vec::new();
+    let mut last = None;
+    let mut more = false;
+    for (id, item) in items.filter(|(id, _)| after.is_none_or(|after| *id > after)) {
+        if page.len() == limit.max(1) {
+            more = true;
+            break;
+        }
+        last = Some(id.clone());
+        page.push(item);
+    }
+
+    (page, last.filter(|_| more))
+}
+
+/// Compares a status filter such as `PartiallyCaptured` or `PARTIALLY_CAPTURED` with a
+/// status as sent by the API.
+pub(crate) fn status_matches(filter: Option<&String>, status: &str) -> bool {
+    let normalize = |s: &str| s.replace('_', "").to_ascii_lowercase();
+    filter.is_none_or(|filter| normalize(filter) == normalize(status))
+}
@@ -0,0 +1,385 @@
+//! Orders and their captures, refunds, cancellations and events.
+
+use super::{amount, page, parse_body, status_matches, Params, Result};
+use crate::server::{App, MockError};
+use crate::state::Order;
+use axum::body::Bytes;
+use axum::extract::{Path, Query, State};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use chrono::Utc;
+use dintero_core::Currency;
+use serde_json::{json, Map, Value};
+
+pub(super) fn router() -> Router<App> {
+    Router::new()
+        .route(
+            "/v1/accounts/{aid}/orders",
+            get(list_orders).post(create_order),
+        )
+        .route(
+            "/v1/accounts/{aid}/orders/{id}",
+            get(get_order).put(update_order),
+        )
+        .route("/v1/accounts/{aid}/orders/{id}/close", post(close_order))
+        .route("/v1/accounts/{aid}/orders/{id}/open", post(open_order))
+        .route(
+            "/v1/accounts/{aid}/orders/{id}/capture",
+            post(create_capture),
+        )
+        .route(
+            "/v1/accounts/{aid}/orders/{id}/captures",
+            get(list_captures),
+        )
+        .route(
+            "/v1/accounts/{aid}/orders/{id}/captures/{cid}",
+            get(get_capture),
+        )
+        .route(
+            "/v1/accounts/{aid}/orders/{id}/refunds",
+            get(list_refunds).post(create_refund),
+        )
+        .route(
+            "/v1/accounts/{aid}/orders/{id}/refunds/{rid}",
+            get(get_refund),
+        )
+        .route(
+            "/v1/accounts/{aid}/orders/{id}/cancellation",
+            post(create_cancellation),
+        )
+        .route(
+            "/v1/accounts/{aid}/orders/{id}/cancellations",
+            get(list_cancellations),
+        )
+        .route(
+            "/v1/accounts/{aid}/orders/{id}/cancellation/{cid}",
+            get(get_cancellation),
+        )
+        .route(
+            "/v1/accounts/{aid}/orders/{id}/events",
+            get(list_events).post(create_event),
+        )
+}
+
+/// Splits an order request into its amount, currency and remaining fields.
+fn order_fields(body: Value) -> Result<(i64, Currency, Map<String, Value>)> {
+    let amount = amount(&body)?;
+    let code = body["currency"].as_str().unwrap_or_default();
+    let currency = Currency::from_code(code)
+        .ok_or_else(|| MockError::bad_request(format!("unknown currency {:?}", code)))?;
+
+    let Value::Object(mut fields) = body else {
+        return Err(MockError::bad_request("the order must be a JSON object"));
+    };
+    for generated in ["id", "status", "amount", "currency", "created_at", "updated_at"] {
+        fields.remove(generated);
+    }
+    Ok((amount, currency, fields))
+}
+
+async fn create_order(
+    State(app): State<App>,
+    Path(aid): Path<String>,
+    body: Bytes,
+) -> Result<Json<Value>> {
+    let (amount, currency, fields) = order_fields(parse_body(&body)?)?;
+
+    let mut state = app.state();
+    let id = state.next_id(&aid, "order");
+    let now = Utc::now();
+    let mut order = Order {
+        id: id.clone(),
+        amount,
+        currency,
+        captured_amount: 0,
+        refunded_amount: 0,
+        cancelled_amount: 0,
+        closed: false,
+        created_at: now,
+        updated_at: now,
+        fields,
+        captures: Vec::new(),
+        refunds: Vec::new(),
+        cancellations: Vec::new(),
+        events: Vec::new(),
+    };
+    order.record("ORDER_CREATED", json!({ "amount": amount }), now);
+    let response = order.to_json();
+    state.orders.insert(id, order);
+    Ok(Json(response))
+}
+
+/// Applies an operation to an order and returns its result.
+fn with_order<T>(
+    app: &App,
+    id: &str,
+    operation: impl FnOnce(&mut Order) -> Result<T>,
+) -> Result<Json<T>> {
+    let mut state = app.state();
+    let order = state.orders.get_mut(id).ok_or_else(|| MockError::not_found("order", id))?;
+    operation(order).map(Json)
+}
+
+/// Applies an operation that changes the amounts of an open order.
+fn with_open_order<T>(
+    app: &App,
+    id: &str,
+    operation: impl FnOnce(&mut Order) -> Result<T>,
+) -> Result<Json<T>> {
+    with_order(app, id, |order| {
+        if order.closed {
+            return Err(MockError::conflict("the order is closed"));
+        }
+        operation(order)
+    })
+}
+
+async fn get_order(
+    State(app): State<App>,
+    Path((_aid, id)): Path<(String, String)>,
+) -> Result<Json<Value>> {
+    with_order(&app, &id, |order| Ok(order.to_json()))
+}
+
+async fn update_order(
+    State(app): State<App>,
+    Path((_aid, id)): Path<(String, String)>,
+    body: Bytes,
+) -> Result<Json<Value>> {
+    let (amount, currency, fields) = order_fields(parse_body(&body)?)?;
+    with_open_order(&app, &id, |order| {
+        if currency != order.currency {
+            return Err(MockError::bad_request(
+                "the currency of an order cannot change",
+            ));
+        }
+        if amount < order.captured_amount + order.cancelled_amount {
+            return Err(MockError::bad_request(
+                "amount is below the captured amount",
+            ));
+        }
+        order.amount = amount;
+        order.fields = fields;
+        order.record("ORDER_UPDATED", json!({ "amount": amount }), Utc::now());
+        Ok(order.to_json())
+    })
+}
+
+async fn list_orders(
+    State(app): State<App>,
+    Path(_aid): Path<String>,
+    Query(query): Query<Params>,
+) -> Json<Value> {
+    let state = app.state();
+    let status = query.get("status");
+    let reference = query.get("merchant_reference");
+    let orders = state
+        .orders
+        .iter()
+        .filter(|(_, order)| {
+            status_matches(status, order.status())
+                && reference.is_none_or(|reference| {
+                    order.fields.get("merchant_reference").and_then(Value::as_str)
+                        == Some(reference.as_str())
+                })
+        })
+        .map(|(id, order)| (id, order.to_json()));
+    let (orders, next_page_token) = page(orders, &query);
+    Json(json!({ "orders": orders, "next_page_token": next_page_token }))
+}
+
+async fn close_order(
+    State(app): State<App>,
+    Path((_aid, id)): Path<(String, String)>,
+) -> Result<Json<Value>> {
+    with_order(&app, &id, |order| {
+        order.closed = true;
+        order.record("ORDER_CLOSED", json!({}), Utc::now());
+        Ok(order.to_json())
+    })
+}
+
+async fn open_order(
+    State(app): State<App>,
+    Path((_aid, id)): Path<(String, String)>,
+) -> Result<Json<Value>> {
+    with_order(&app, &id, |order| {
+        order.closed = false;
+        order.record("ORDER_OPENED", json!({}), Utc::now());
+        Ok(order.to_json())
+    })
+}
+
+/// Creates a capture, refund or cancellation record of an order.
+fn operation_record(order: &Order, kind: &str, count: usize, amount: i64, body: &Value) -> Value {
+    let mut record = json!({
+        "id": format!("{}.{}{:04}", order.id, kind, count + 1),
+        "order_id": order.id,
+        "amount": amount,
+        "currency": order.currency,
+        "created_at": Utc::now(),
+    });
+    for field in ["items", "reason"] {
+        if !body[field].is_null() {
+            record[field] = body[field].clone();
+        }
+    }
+    record
+}
+
+async fn create_capture(
+    State(app): State<App>,
+    Path((_aid, id)): Path<(String, String)>,
+    body: Bytes,
+) -> Result<Json<Value>> {
+    let body = parse_body(&body)?;
+    let amount = amount(&body)?;
+    with_open_order(&app, &id, |order| {
+        if amount > order.remaining_amount() {
+            return Err(MockError::bad_request(format!(
+                "cannot capture {}, {} is capturable",
+                amount,
+                order.remaining_amount()
+            )));
+        }
+        let capture = operation_record(order, "capture", order.captures.len(), amount, &body);
+        order.captured_amount += amount;
+        order.captures.push(capture.clone());
+        order.record("ORDER_CAPTURED", capture.clone(), Utc::now());
+        Ok(capture)
+    })
+}
+
+async fn create_refund(
+    State(app): State<App>,
+    Path((_aid, id)): Path<(String, String)>,
+    body: Bytes,
+) -> Result<Json<Value>> {
+    let body = parse_body(&body)?;
+    let amount = amount(&body)?;
+    with_open_order(&app, &id, |order| {
+        let refundable = order.captured_amount - order.refunded_amount;
+        if amount > refundable {
+            return Err(MockError::bad_request(format!(
+                "cannot refund {}, {} is refundable",
+                amount, refundable
+            )));
+        }
+        let refund = operation_record(order, "refund", order.refunds.len(), amount, &body);
+        order.refunded_amount += amount;
+        order.refunds.push(refund.clone());
+        order.record("ORDER_REFUNDED", refund.clone(), Utc::now());
+        Ok(refund)
+    })
+}
+
+async fn create_cancellation(
+    State(app): State<App>,
+    Path((_aid, id)): Path<(String, String)>,
+    body: Bytes,
+) -> Result<Json<Value>> {
+    let body = parse_body(&body)?;
+    with_open_order(&app, &id, |order| {
+        let remaining = order.remaining_amount();
+        let amount = if body["amount"].is_null() { remaining } else { amount(&body)? };
+        if amount == 0 || amount > remaining {
+            return Err(MockError::bad_request(format!(
+                "cannot cancel {}, {} is cancellable",
+                amount, remaining
+            )));
+        }
+        let cancellation = operation_record(
+            order,
+            "cancellation",
+            order.cancellations.len(),
+            amount,
+            &body,
+        );
+        order.cancelled_amount += amount;
+        order.cancellations.push(cancellation.clone());
+        order.record("ORDER_CANCELLED", cancellation.clone(), Utc::now());
+        Ok(cancellation)
+    })
+}
+
+/// Finds a capture, refund or cancellation record by ID.
+fn find(records: &[Value], what: &str, id: &str) -> Result<Value> {
+    records
+        .iter()
+        .find(|record| record["id"] == id)
+        .cloned()
+        .ok_or_else(|| MockError::not_found(what, id))
+}
+
+async fn list_captures(
+    State(app): State<App>,
+    Path((_aid, id)): Path<(String, String)>,
+) -> Result<Json<Value>> {
+    with_order(&app, &id, |order| Ok(json!({ "captures": order.captures })))
+}
+
+async fn get_capture(
+    State(app): State<App>,
+    Path((_aid, id, capture_id)): Path<(String, String, String)>,
+) -> Result<Json<Value>> {
+    with_order(&app, &id, |order| {
+        find(&order.captures, "capture", &capture_id)
+    })
+}
+
+async fn list_refunds(
+    State(app): State<App>,
+    Path((_aid, id)): Path<(String, String)>,
+) -> Result<Json<Value>> {
+    with_order(&app, &id, |order| Ok(json!({ "refunds": order.refunds })))
+}
+
+async fn get_refund(
+    State(app): State<App>,
+    Path((_aid, id, refund_id)): Path<(String, String, String)>,
+) -> Result<Json<Value>> {
+    with_order(&app, &id, |order| {
+        find(&order.refunds, "refund", &refund_id)
+    })
+}
+
+async fn list_cancellations(
+    State(app): State<App>,
+    Path((_aid, id)): Path<(String, String)>,
+) -> Result<Json<Value>> {
+    with_order(&app, &id, |order| {
+        Ok(json!({ "cancellations": order.cancellations }))
+    })
+}
+
+async fn get_cancellation(
+    State(app): State<App>,
+    Path((_aid, id, cancellation_id)): Path<(String, String, String)>,
+) -> Result<Json<Value>> {
+    with_order(&app, &id, |order| {
+        find(&order.cancellations, "cancellation", &cancellation_id)
+    })
+}
+
+async fn list_events(
+    State(app): State<App>,
+    Path((_aid, id)): Path<(String, String)>,
+) -> Result<Json<Value>> {
+    with_order(&app, &id, |order| Ok(json!({ "events": order.events })))
+}
+
+async fn create_event(
+    State(app): State<App>,
+    Path((_aid, id)): Path<(String, String)>,
+    body: Bytes,
+) -> Result<Json<Value>> {
+    let body = parse_body(&body)?;
+    let event_type = body["event_type"]
+        .as_str()
+        .ok_or_else(|| MockError::bad_request("event_type is required"))?
+        .to_string();
+    with_order(&app, &id, |order| {
+        order.record(&event_type, body["data"].clone(), Utc::now());
+        Ok(order.events.last().cloned().unwrap_or_default())
+    })
+}
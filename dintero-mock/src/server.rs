@@ -0,0 +1,361 @@
+//! The mock HTTP server.
+
+use crate::fault::Fault;
+use crate::routes;
+use crate::state::{Order, Session, State, Transaction};
+use axum::body::{to_bytes, Body};
+use axum::extract::{Request, State as AxumState};
+use axum::http::header::{AUTHORIZATION, RETRY_AFTER};
+use axum::http::{HeaderValue, Method, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use dintero_core::request::{IDEMPOTENCY_KEY_HEADER, IDEMPOTENT_REPLAYED_HEADER};
+use serde_json::{json, Value};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+
+/// Largest request body the mock server accepts.
+const MAX_BODY: usize = 4 * 1024 * 1024;
+
+/// A request received by the mock server.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedRequest {
+    /// HTTP method.
+    pub method: Method,
+    /// Path and query, e.g. `/v1/accounts/T12345678/sessions?limit=10`.
+    pub path: String,
+    /// JSON body, if the request had one.
+    pub body: Option<Value>,
+    /// Value of the `Idempotency-Key` header.
+    pub idempotency_key: Option<String>,
+}
+
+pub(crate) struct Inner {
+    pub(crate) base_url: String,
+    pub(crate) state: Mutex<State>,
+    faults: Mutex<Vec<Fault>>,
+    requests: Mutex<Vec<RecordedRequest>>,
+}
+
+impl Inner {
+    pub(crate) fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn faults(&self) -> MutexGuard<'_, Vec<Fault>> {
+        self.faults.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn requests(&self) -> MutexGuard<'_, Vec<RecordedRequest>> {
+        self.requests.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+pub(crate) type App = Arc<Inner>;
+
+/// A local HTTP server standing in for the Dintero API.
+///
+/// The server implements the checkout session, transaction and order endpoints used by
+/// the SDK clients and keeps their state in memory: a session is authorized with
+/// [`authorize_session`](Self::authorize_session), which creates its transaction, and
+/// the transaction can then be captured, refunded or voided through the API. Point the
+/// SDK at the server with a custom base URL:
+///
+/// ```no_run
+/// use dintero_mock::MockServer;
+///
+/// # async fn example() {
+/// let server = MockServer::start().await;
+/// // Config::builder("T12345678").api_key("any").base_url(server.url()).build()
+/// # }
+/// ```
+///
+/// Requests must carry an `Authorization` header, but any credentials are accepted,
+/// including OAuth client credentials exchanged at the token endpoint. Endpoints the
+/// mock does not implement answer with `404 Not Found`.
+///
+/// The server shuts down when dropped.
+pub struct MockServer {
+    app: App,
+    addr: SocketAddr,
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl MockServer {
+    /// Starts a server on a free local port.
+    pub async fn start() -> Self {
+        let listener =
+            TcpListener::bind("127.0.0.1:0").await.expect("failed to bind mock server port");
+        let addr = listener.local_addr().expect("mock server has no local address");
+
+        let app = Arc::new(Inner {
+            base_url: format!("http://{}", addr),
+            state: Mutex::new(State::default()),
+            faults: Mutex::new(Vec::new()),
+            requests: Mutex::new(Vec::new()),
+        });
+
+        let router = routes::router()
+            .layer(middleware::from_fn_with_state(Arc::clone(&app), intercept))
+            .with_state(Arc::clone(&app));
+
+        let (shutdown, signal) = oneshot::channel::<()>();
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, router)
+                .with_graceful_shutdown(async {
+                    let _ = signal.await;
+                })
+                .await;
+        });
+
+        Self { app, addr, shutdown: Some(shutdown) }
+    }
+
+    /// Returns the base URL of the server, e.g. `http://127.0.0.1:49152`.
+    pub fn url(&self) -> String {
+        self.app.base_url.clone()
+    }
+
+    /// Returns the address the server listens on.
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Injects a failure or delay into the server's responses.
+    pub fn inject(&self, fault: Fault) {
+        self.app.faults().push(fault);
+    }
+
+    /// Removes all injected faults.
+    pub fn clear_faults(&self) {
+        self.app.faults().clear();
+    }
+
+    /// Returns the requests received so far, oldest first.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.app.requests().clone()
+    }
+
+    /// Authorizes a session as if the customer had completed the payment, and returns
+    /// the ID of the created transaction.
+    ///
+    /// Returns `None` if the session does not exist or was cancelled. Authorizing an
+    /// already authorized session returns its transaction.
+    pub fn authorize_session(&self, session_id: &str) -> Option<String> {
+        self.app.state().authorize_session(session_id)
+    }
+
+    /// Returns a session by ID.
+    pub fn session(&self, session_id: &str) -> Option<Session> {
+        self.app.state().sessions.get(session_id).cloned()
+    }
+
+    /// Returns a transaction by ID.
+    pub fn transaction(&self, transaction_id: &str) -> Option<Transaction> {
+        self.app.state().transactions.get(transaction_id).cloned()
+    }
+
+    /// Returns an order by ID.
+    pub fn order(&self, order_id: &str) -> Option<Order> {
+        self.app.state().orders.get(order_id).cloned()
+    }
+
+    /// Removes all state, faults and recorded requests.
+    pub fn reset(&self) {
+        *self.app.state() = State::default();
+        self.clear_faults();
+        self.app.requests().clear();
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+    }
+}
+
+impl std::fmt::Debug for MockServer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MockServer").field("url", &self.app.base_url).finish_non_exhaustive()
+    }
+}
+
+/// Records the request, then applies faults, the authorization check and idempotent
+/// replays before handing the request to the routes.
+async fn intercept(AxumState(app): AxumState<App>, request: Request, next: Next) -> Response {
+    let (parts, body) = request.into_parts();
+    let Ok(body) = to_bytes(body, MAX_BODY).await else {
+        return MockError::new(StatusCode::PAYLOAD_TOO_LARGE, "request body too large")
+            .into_response();
+    };
+
+    let method = parts.method.clone();
+    let path = parts
+        .uri
+        .path_and_query()
+        .map_or_else(|| parts.uri.path().to_string(), |p| p.as_str().to_string());
+    let idempotency_key = parts
+        .headers
+        .get(IDEMPOTENCY_KEY_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    app.requests().push(RecordedRequest {
+        method: method.clone(),
+        path: path.clone(),
+        body: serde_json::from_slice(&body).ok(),
+        idempotency_key: idempotency_key.clone(),
+    });
+
+    let fault = {
+        let mut faults = app.faults();
+        faults.retain(|fault| fault.remaining != Some(0));
+        faults.iter_mut().find(|fault| fault.matches(&method, &path)).map(|fault| {
+            if let Some(remaining) = &mut fault.remaining {
+                *remaining -= 1;
+            }
+            fault.clone()
+        })
+    };
+    if let Some(fault) = fault {
+        if !fault.latency.is_zero() {
+            tokio::time::sleep(fault.latency).await;
+        }
+        if let Some(response) = fault.response {
+            let status =
+                StatusCode::from_u16(response.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            let mut error = MockError::new(status, response.message);
+            error.code = response.code;
+            let mut error = error.into_response();
+            if let Some(retry_after) = response.retry_after {
+                error.headers_mut().insert(RETRY_AFTER, HeaderValue::from(retry_after.as_secs()));
+            }
+            return error;
+        }
+    }
+
+    let is_token_request = parts.uri.path().ends_with("/auth/token");
+    if !is_token_request && !parts.headers.contains_key(AUTHORIZATION) {
+        return MockError::new(StatusCode::UNAUTHORIZED, "missing authorization header")
+            .into_response();
+    }
+
+    let replay_key = idempotency_key
+        .filter(|_| method == Method::POST)
+        .map(|key| format!("{} {}", key, parts.uri.path()));
+    if let Some(key) = &replay_key {
+        if let Some((status, body)) = app.state().idempotent.get(key).cloned() {
+            let status = StatusCode::from_u16(status).unwrap_or(StatusCode::OK);
+            let mut response = (status, Json(body)).into_response();
+            response
+                .headers_mut()
+                .insert(IDEMPOTENT_REPLAYED_HEADER, HeaderValue::from_static("true"));
+            return response;
+        }
+    }
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+
+    let Some(key) = replay_key.filter(|_| response.status().is_success()) else {
+        return response;
+    };
+    let (parts, body) = response.into_parts();
+    let body = to_bytes(body, MAX_BODY).await.unwrap_or_default();
+    if let Ok(value) = serde_json::from_slice(&body) {
+        app.state().idempotent.insert(key, (parts.status.as_u16(), value));
+    }
+    Response::from_parts(parts, Body::from(body))
+}
+
+/// An error response in the format of the Dintero API.
+#[derive(Debug)]
+pub(crate) struct MockError {
+    status: StatusCode,
+    code: String,
+    message: String,
+}
+
+impl MockError {
+    pub(crate) fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            code: dintero_core::ErrorCode::from_status(status.as_u16()).as_str().to_string(),
+            message: message.into(),
+        }
+    }
+
+    pub(crate) fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub(crate) fn bad_request(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, message)
+    }
+
+    pub(crate) fn not_found(what: &str, id: &str) -> Self {
+        Self::new(StatusCode::NOT_FOUND, format!("{} {} not found", what, id))
+    }
+
+    pub(crate) fn conflict(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::CONFLICT, message)
+    }
+}
+
+impl IntoResponse for MockError {
+    fn into_response(self) -> Response {
+        let body = json!({ "error": { "code": self.code, "message": self.message } });
+        (self.status, Json(body)).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_session_payment_flow_with_faults_and_replays() {
+        let server = MockServer::start().await;
+        let http = reqwest::Client::new();
+        let base = format!("{}/v1/accounts/T12345678", server.url());
+
+        let session: Value = http
+            .post(format!("{}/sessions", base))
+            .header(AUTHORIZATION, "Token test")
+            .json(&json!({ "order": { "amount": 10000, "currency": "NOK" } }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        let transaction_id = server.authorize_session(session["id"].as_str().unwrap()).unwrap();
+
+        server.inject(Fault::status(503).path("/capture").times(1));
+        let capture = |key: &'static str| {
+            http.post(format!("{}/transactions/{}/capture", base, transaction_id))
+                .header(AUTHORIZATION, "Token test")
+                .header(IDEMPOTENCY_KEY_HEADER, key)
+                .json(&json!({ "amount": 4000 }))
+                .send()
+        };
+
+        assert_eq!(capture("key-1").await.unwrap().status(), 503);
+        let first = capture("key-1").await.unwrap();
+        assert_eq!(first.status(), 200);
+        assert!(first.headers().get(IDEMPOTENT_REPLAYED_HEADER).is_none());
+        let replayed = capture("key-1").await.unwrap();
+        assert_eq!(replayed.headers()[IDEMPOTENT_REPLAYED_HEADER], "true");
+
+        let transaction = server.transaction(&transaction_id).unwrap();
+        assert_eq!(transaction.captured_amount, 4000);
+        assert_eq!(server.requests().len(), 4);
+
+        let unauthorized =
+            http.get(format!("{}/transactions/{}", base, transaction_id)).send().await.unwrap();
+        assert_eq!(unauthorized.status(), 401);
+    }
+}
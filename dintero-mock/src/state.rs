@@ -0,0 +1,234 @@
+//! In-memory state of the mock server.
+
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use dintero_core::Currency;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, HashMap};
+
+/// A checkout session held by the mock server.
+#[derive(Debug, Clone, Serialize)]
+pub struct Session {
+    pub id: String,
+    pub url: String,
+    pub status: SessionStatus,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    /// The `order` of the session as sent by the client.
+    pub order: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_id: Option<String>,
+    /// Callback URL from the session `url` object.
+    #[serde(skip)]
+    pub callback_url: Option<String>,
+}
+
+/// Status of a mock checkout session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionStatus {
+    Created,
+    Authorized,
+    Cancelled,
+}
+
+/// A payment transaction held by the mock server.
+#[derive(Debug, Clone, Serialize)]
+pub struct Transaction {
+    pub id: String,
+    pub session_id: String,
+    pub status: TransactionStatus,
+    pub amount: i64,
+    pub currency: Currency,
+    pub captured_amount: i64,
+    pub refunded_amount: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub merchant_reference: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub merchant_reference_2: Option<String>,
+    pub payment_product: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+    /// When the authorized amount expires, unless the authorization is extended.
+    pub authorization_expires_at: DateTime<Utc>,
+    #[serde(skip)]
+    pub voided: bool,
+}
+
+/// Status of a mock transaction, derived from its captured and refunded amounts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TransactionStatus {
+    Authorized,
+    PartiallyCaptured,
+    Captured,
+    PartiallyRefunded,
+    Refunded,
+    Voided,
+}
+
+impl Transaction {
+    pub(crate) fn update_status(&mut self, now: DateTime<Utc>) {
+        self.status = if self.voided {
+            TransactionStatus::Voided
+        } else if self.refunded_amount > 0 {
+            if self.refunded_amount >= self.captured_amount {
+                TransactionStatus::Refunded
+            } else {
+                TransactionStatus::PartiallyRefunded
+            }
+        } else if self.captured_amount >= self.amount {
+            TransactionStatus::Captured
+        } else if self.captured_amount > 0 {
+            TransactionStatus::PartiallyCaptured
+        } else {
+            TransactionStatus::Authorized
+        };
+        self.updated_at = now;
+    }
+}
+
+/// An order held by the mock server.
+#[derive(Debug, Clone)]
+pub struct Order {
+    pub id: String,
+    pub amount: i64,
+    pub currency: Currency,
+    pub captured_amount: i64,
+    pub refunded_amount: i64,
+    pub cancelled_amount: i64,
+    pub closed: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Other fields of the order as sent by the client.
+    pub fields: Map<String, Value>,
+    pub captures: Vec<Value>,
+    pub refunds: Vec<Value>,
+    pub cancellations: Vec<Value>,
+    pub events: Vec<Value>,
+}
+
+impl Order {
+    /// Returns the status the Orders API reports for the order.
+    pub fn status(&self) -> &'static str {
+        if self.closed {
+            "CLOSED"
+        } else if self.refunded_amount > 0 {
+            if self.refunded_amount >= self.captured_amount {
+                "REFUNDED"
+            } else {
+                "PARTIALLY_REFUNDED"
+            }
+        } else if self.captured_amount > 0 {
+            if self.captured_amount + self.cancelled_amount >= self.amount {
+                "CAPTURED"
+            } else {
+                "PARTIALLY_CAPTURED"
+            }
+        } else if self.cancelled_amount >= self.amount {
+            "CANCELLED"
+        } else if self.cancelled_amount > 0 {
+            "PARTIALLY_CANCELLED"
+        } else {
+            "CREATED"
+        }
+    }
+
+    /// Amount that is neither captured nor cancelled.
+    pub fn remaining_amount(&self) -> i64 {
+        self.amount - self.captured_amount - self.cancelled_amount
+    }
+
+    pub(crate) fn to_json(&self) -> Value {
+        let mut value = self.fields.clone();
+        value.insert("id".into(), json!(self.id));
+        value.insert("status".into(), json!(self.status()));
+        value.insert("amount".into(), json!(self.amount));
+        value.insert("currency".into(), json!(self.currency));
+        value.insert("created_at".into(), json!(self.created_at));
+        value.insert("updated_at".into(), json!(self.updated_at));
+        Value::Object(value)
+    }
+
+    pub(crate) fn record(&mut self, event_type: &str, data: Value, now: DateTime<Utc>) {
+        let id = format!("{}.event{:04}", self.id, self.events.len() + 1);
+        self.events.push(json!({
+            "id": id,
+            "order_id": self.id,
+            "event_type": event_type,
+            "created_at": now,
+            "data": data,
+        }));
+        self.updated_at = now;
+    }
+}
+
+/// Everything the mock server stores.
+#[derive(Debug, Default)]
+pub(crate) struct State {
+    pub(crate) sessions: BTreeMap<String, Session>,
+    pub(crate) transactions: BTreeMap<String, Transaction>,
+    pub(crate) orders: BTreeMap<String, Order>,
+    /// Responses of requests sent with an `Idempotency-Key`, by key.
+    pub(crate) idempotent: HashMap<String, (u16, Value)>,
+    sequence: u64,
+}
+
+impl State {
+    /// Returns a new ID. IDs sort in creation order, so listings are stable.
+    pub(crate) fn next_id(&mut self, account_id: &str, kind: &str) -> String {
+        self.sequence += 1;
+        format!("{}.{}{:08}", account_id, kind, self.sequence)
+    }
+
+    /// Authorizes a session as if the customer had paid, creating its transaction.
+    pub(crate) fn authorize_session(&mut self, session_id: &str) -> Option<String> {
+        let session = self.sessions.get(session_id)?;
+        if let Some(transaction_id) = &session.transaction_id {
+            return Some(transaction_id.clone());
+        }
+        if session.status != SessionStatus::Created {
+            return None;
+        }
+
+        let account_id = session_id.split('.').next().unwrap_or_default().to_string();
+        let order = session.order.clone();
+        let id = self.next_id(&account_id, "tx");
+        let now = Utc::now();
+        let transaction = Transaction {
+            id: id.clone(),
+            session_id: session_id.to_string(),
+            status: TransactionStatus::Authorized,
+            amount: order["amount"].as_i64().unwrap_or_default(),
+            currency: serde_json::from_value(order["currency"].clone()).unwrap_or_default(),
+            captured_amount: 0,
+            refunded_amount: 0,
+            created_at: now,
+            updated_at: now,
+            merchant_reference: order["merchant_reference"].as_str().map(str::to_string),
+            merchant_reference_2: None,
+            payment_product: "dintero-mock".to_string(),
+            metadata: None,
+            authorization_expires_at: authorization_expiry(now),
+            voided: false,
+        };
+        self.transactions.insert(id.clone(), transaction);
+
+        let session = self.sessions.get_mut(session_id)?;
+        session.status = SessionStatus::Authorized;
+        session.transaction_id = Some(id.clone());
+        session.updated_at = now;
+        Some(id)
+    }
+}
+
+pub(crate) fn session_expiry(now: DateTime<Utc>) -> DateTime<Utc> {
+    now + ChronoDuration::hours(1)
+}
+
+pub(crate) fn authorization_expiry(now: DateTime<Utc>) -> DateTime<Utc> {
+    now + ChronoDuration::days(7)
+}
@@ -1,6 +1,7 @@
 //! Module implementation.
 
 use chrono::{DateTime, Utc};
+use dintero_core::{Currency, Money};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -8,7 +9,7 @@ pub struct Authorization {
     pub id: String,
     pub order_id: String,
     pub amount: i64,
-    pub currency: String,
+    pub currency: Currency,
     pub status: AuthorizationStatus,
 
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -21,6 +22,13 @@ pub struct Authorization {
     pub payment_product: Option<String>,
 }
 
+impl Authorization {
+    /// Returns the amount together with its currency.
+    pub fn money(&self) -> Money {
+        Money::new(self.amount, self.currency)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum AuthorizationStatus {
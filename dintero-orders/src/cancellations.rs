@@ -1,6 +1,7 @@
 //! Module implementation.
 
 use chrono::{DateTime, Utc};
+use dintero_core::{Currency, Money};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -8,7 +9,7 @@ pub struct Cancellation {
     pub id: String,
     pub order_id: String,
     pub amount: i64,
-    pub currency: String,
+    pub currency: Currency,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub created_at: Option<DateTime<Utc>>,
@@ -17,6 +18,13 @@ pub struct Cancellation {
     pub reason: Option<String>,
 }
 
+impl Cancellation {
+    /// Returns the amount together with its currency.
+    pub fn money(&self) -> Money {
+        Money::new(self.amount, self.currency)
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct CreateCancellationRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -10,23 +10,54 @@ use crate::orders::*;
 use crate::refunds::*;
 use crate::sessions::*;
 use async_trait::async_trait;
+use dintero_core::{paginate, Endpoint, Idempotent, Page, PageRequest, Paginator, RequestOptions};
 
 pub type Result<T> = std::result::Result<T, OrdersError>;
 
-#[derive(Debug, thiserror::Error)]
-pub enum OrdersError {
-    #[error("Serialization error: {0}")]
-    Serialization(String),
-
-    #[error("Client error: {0}")]
-    Client(String),
-}
-
-impl From<serde_json::Error> for OrdersError {
-    fn from(err: serde_json::Error) -> Self {
-        OrdersError::Serialization(err.to_string())
-    }
-}
+pub type OrdersError = dintero_core::DinteroError;
+
+/// Endpoints called by the Orders API client, used to name the spans and metrics
+/// of its calls.
+#[rustfmt::skip]
+pub const ENDPOINTS: &[Endpoint] = &[
+    Endpoint::new("POST", "accounts/{account_id}/orders", "orders.create_order"),
+    Endpoint::new("GET", "accounts/{account_id}/orders/{order_id}", "orders.get_order"),
+    Endpoint::new("PUT", "accounts/{account_id}/orders/{order_id}", "orders.update_order"),
+    Endpoint::new("GET", "accounts/{account_id}/orders", "orders.list_orders"),
+    Endpoint::new("POST", "accounts/{account_id}/orders/{order_id}/close", "orders.close_order"),
+    Endpoint::new("POST", "accounts/{account_id}/orders/{order_id}/open", "orders.open_order"),
+    Endpoint::new("POST", "accounts/{account_id}/draft_orders", "orders.create_draft_order"),
+    Endpoint::new("GET", "accounts/{account_id}/draft_orders/{draft_id}", "orders.get_draft_order"),
+    Endpoint::new("PUT", "accounts/{account_id}/draft_orders/{draft_id}", "orders.update_draft_order"),
+    Endpoint::new("GET", "accounts/{account_id}/draft_orders", "orders.list_draft_orders"),
+    Endpoint::new("PUT", "accounts/{account_id}/draft_orders/{draft_id}/complete", "orders.complete_draft_order"),
+    Endpoint::new("POST", "accounts/{account_id}/draft_orders/{draft_id}/items", "orders.add_draft_order_item"),
+    Endpoint::new("PUT", "accounts/{account_id}/draft_orders/{draft_id}/items/{line_id}", "orders.update_draft_order_item"),
+    Endpoint::new("DELETE", "accounts/{account_id}/draft_orders/{draft_id}/items/{line_id}", "orders.delete_draft_order_item"),
+    Endpoint::new("POST", "accounts/{account_id}/orders/{order_id}/authorization", "orders.create_authorization"),
+    Endpoint::new("GET", "accounts/{account_id}/orders/{order_id}/authorization/{auth_id}", "orders.get_authorization"),
+    Endpoint::new("GET", "accounts/{account_id}/orders/{order_id}/authorizations", "orders.list_authorizations"),
+    Endpoint::new("POST", "accounts/{account_id}/orders/{order_id}/capture", "orders.create_capture").with_idempotency_key(),
+    Endpoint::new("GET", "accounts/{account_id}/orders/{order_id}/captures/{capture_id}", "orders.get_capture"),
+    Endpoint::new("GET", "accounts/{account_id}/orders/{order_id}/captures", "orders.list_captures"),
+    Endpoint::new("POST", "accounts/{account_id}/orders/{order_id}/refunds", "orders.create_refund").with_idempotency_key(),
+    Endpoint::new("GET", "accounts/{account_id}/orders/{order_id}/refunds/{refund_id}", "orders.get_refund"),
+    Endpoint::new("GET", "accounts/{account_id}/orders/{order_id}/refunds", "orders.list_refunds"),
+    Endpoint::new("POST", "accounts/{account_id}/orders/{order_id}/cancellation", "orders.create_cancellation").with_idempotency_key(),
+    Endpoint::new("GET", "accounts/{account_id}/orders/{order_id}/cancellation/{cancellation_id}", "orders.get_cancellation"),
+    Endpoint::new("GET", "accounts/{account_id}/orders/{order_id}/cancellations", "orders.list_cancellations"),
+    Endpoint::new("POST", "accounts/{account_id}/orders/{order_id}/comments", "orders.create_comment"),
+    Endpoint::new("DELETE", "accounts/{account_id}/orders/{order_id}/comments/{comment_id}", "orders.delete_comment"),
+    Endpoint::new("GET", "accounts/{account_id}/orders/{order_id}/events", "orders.get_events"),
+    Endpoint::new("POST", "accounts/{account_id}/orders/{order_id}/events", "orders.create_event"),
+    Endpoint::new("POST", "accounts/{account_id}/orders/{order_id}/session", "orders.create_order_session"),
+    Endpoint::new("GET", "accounts/{account_id}/orders/{order_id}/sessions/{session_id}", "orders.get_order_session"),
+    Endpoint::new("GET", "accounts/{account_id}/orders/{order_id}/sessions", "orders.list_order_sessions"),
+    Endpoint::new("GET", "accounts/{account_id}/customers/{customer_id}/orders", "orders.list_customer_orders"),
+    Endpoint::new("GET", "accounts/{account_id}/customers/{customer_id}/orders/{order_id}", "orders.get_customer_order"),
+    Endpoint::new("GET", "accounts/{account_id}/stores/{store_id}/orders", "orders.list_store_orders"),
+    Endpoint::new("GET", "accounts/{account_id}/stores/{store_id}/orders/{order_id}", "orders.get_store_order"),
+];
 
 #[async_trait]
 pub trait OrdersOperations: Send + Sync {
@@ -44,7 +75,10 @@ pub trait OrdersOperations: Send + Sync {
         draft_id: &str,
         request: CreateDraftOrderRequest,
     ) -> Result<DraftOrder>;
-    async fn list_draft_orders(&self) -> Result<DraftOrderListResponse>;
+    async fn list_draft_orders(
+        &self,
+        params: ListDraftOrdersParams,
+    ) -> Result<DraftOrderListResponse>;
     async fn complete_draft_order(&self, draft_id: &str) -> Result<Order>;
     async fn add_draft_order_item(
         &self,
@@ -128,6 +162,78 @@ pub trait OrdersAdapter: Send + Sync {
         body: &B,
     ) -> Result<T>;
     async fn delete(&self, path: &str) -> Result<()>;
+    /// Sends a `PATCH` request. Adapters that predate PATCH support get a default that
+    /// returns [`DinteroError::Config`](dintero_core::DinteroError::Config).
+    async fn patch_json<T: serde::de::DeserializeOwned, B: serde::Serialize + Send + Sync>(
+        &self,
+        path: &str,
+        _body: &B,
+    ) -> Result<T> {
+        Err(dintero_core::DinteroError::Config(format!(
+            "PATCH {} is not supported by this adapter",
+            path
+        )))
+    }
+    /// Sends a `GET` request with query parameters serialized from `query`, e.g. a
+    /// struct or a slice of pairs.
+    async fn get_json_with_query<
+        T: serde::de::DeserializeOwned,
+        Q: serde::Serialize + Send + Sync + ?Sized,
+    >(
+        &self,
+        path: &str,
+        query: &Q,
+    ) -> Result<T> {
+        self.get_json(&dintero_core::path::with_query(path, query)?).await
+    }
+
+    /// Sends a `POST` request with per-request options such as an idempotency key.
+    ///
+    /// The default implementation cannot send headers, so it returns
+    /// [`DinteroError::Config`](dintero_core::DinteroError::Config) unless the options
+    /// are empty.
+    async fn post_json_with_options<
+        T: serde::de::DeserializeOwned,
+        B: serde::Serialize + Send + Sync,
+    >(
+        &self,
+        path: &str,
+        body: &B,
+        options: &RequestOptions,
+    ) -> Result<T> {
+        if options.idempotency_key.is_some() || options.retry_policy.is_some() {
+            return Err(dintero_core::DinteroError::Config(format!(
+                "POST {} with request options is not supported by this adapter",
+                path
+            )));
+        }
+        self.post_json(path, body).await
+    }
+
+    /// Sends a `POST` request with the idempotency key from `options` and reports
+    /// whether the API replayed an earlier result with the same key.
+    ///
+    /// The default implementation forwards to
+    /// [`post_json_with_options`](Self::post_json_with_options) and cannot see the
+    /// response headers, so it never reports a replay.
+    async fn post_json_idempotent<
+        T: serde::de::DeserializeOwned,
+        B: serde::Serialize + Send + Sync,
+    >(
+        &self,
+        path: &str,
+        body: &B,
+        options: &RequestOptions,
+    ) -> Result<Idempotent<T>> {
+        let Some(idempotency_key) = options.idempotency_key.clone() else {
+            return Err(dintero_core::DinteroError::Config(format!(
+                "POST {} requires an idempotency key",
+                path
+            )));
+        };
+        let value = self.post_json_with_options(path, body, options).await?;
+        Ok(Idempotent { value, idempotency_key, replayed: false })
+    }
 }
 
 pub struct OrdersClient<A: OrdersAdapter> {
@@ -139,6 +245,178 @@ impl<A: OrdersAdapter> OrdersClient<A> {
     pub fn new(adapter: A, account_id: String) -> Self {
         Self { adapter, account_id }
     }
+
+    /// Streams all orders matching `params`, fetching further pages as needed.
+    ///
+    /// `params.page_token` sets the page to start from.
+    pub fn orders_stream(&self, params: ListOrdersParams) -> Paginator<'_, Order> {
+        paginate(move |page: PageRequest| {
+            let params = ListOrdersParams {
+                limit: page.page_size.or(params.limit),
+                page_token: page.page_token().or_else(|| params.page_token.clone()),
+                ..params.clone()
+            };
+            async move {
+                let response = self.list_orders(params).await?;
+                Ok(Page::from_token(response.orders, response.next_page_token))
+            }
+        })
+    }
+
+    /// Streams all draft orders, fetching further pages as needed.
+    pub fn draft_orders_stream(&self, params: ListDraftOrdersParams) -> Paginator<'_, DraftOrder> {
+        paginate(move |page: PageRequest| {
+            let params = ListDraftOrdersParams {
+                limit: page.page_size.or(params.limit),
+                page_token: page.page_token().or_else(|| params.page_token.clone()),
+            };
+            async move {
+                let response = self.list_draft_orders(params).await?;
+                Ok(Page::from_token(
+                    response.draft_orders,
+                    response.next_page_token,
+                ))
+            }
+        })
+    }
+
+    /// Streams all orders of a customer, fetching further pages as needed.
+    pub fn customer_orders_stream(&self, customer_id: &str) -> Paginator<'_, Order> {
+        let path = format!(
+            "accounts/{}/customers/{}/orders",
+            self.account_id, customer_id
+        );
+        self.orders_at(path)
+    }
+
+    /// Streams all orders of a store, fetching further pages as needed.
+    pub fn store_orders_stream(&self, store_id: &str) -> Paginator<'_, Order> {
+        let path = format!("accounts/{}/stores/{}/orders", self.account_id, store_id);
+        self.orders_at(path)
+    }
+
+    /// Streams the authorizations of an order.
+    ///
+    /// The endpoint is not paginated, so the stream yields a single page.
+    pub fn authorizations_stream(&self, order_id: &str) -> Paginator<'_, Authorization> {
+        let order_id = order_id.to_string();
+        paginate(move |_: PageRequest| {
+            let order_id = order_id.clone();
+            async move {
+                let response = self.list_authorizations(&order_id).await?;
+                Ok(Page::single(response.authorizations))
+            }
+        })
+    }
+
+    /// Streams the payment sessions of an order.
+    ///
+    /// The endpoint is not paginated, so the stream yields a single page.
+    pub fn order_sessions_stream(&self, order_id: &str) -> Paginator<'_, OrderSession> {
+        let order_id = order_id.to_string();
+        paginate(move |_: PageRequest| {
+            let order_id = order_id.clone();
+            async move {
+                let response = self.list_order_sessions(&order_id).await?;
+                Ok(Page::single(response.sessions))
+            }
+        })
+    }
+
+    fn orders_at(&self, path: String) -> Paginator<'_, Order> {
+        paginate(move |page: PageRequest| {
+            let path = path.clone();
+            let params = ListOrdersParams {
+                limit: page.page_size,
+                page_token: page.page_token(),
+                ..Default::default()
+            };
+            async move {
+                let response: OrderListResponse =
+                    self.adapter.get_json_with_query(&path, &params).await?;
+                Ok(Page::from_token(response.orders, response.next_page_token))
+            }
+        })
+    }
+
+    /// Like [`OrdersOperations::create_capture`], but also returns the idempotency key
+    /// the request was sent with and whether the API replayed an earlier result.
+    pub async fn create_capture_with_meta(
+        &self,
+        order_id: &str,
+        request: CreateCaptureRequest,
+    ) -> Result<Idempotent<Capture>> {
+        self.create_capture_with_options(order_id, &request, &RequestOptions::default()).await
+    }
+
+    /// Like [`create_capture_with_meta`](Self::create_capture_with_meta), but sends the request
+    /// with `options`, e.g. an idempotency key of the caller's choosing or a retry policy.
+    ///
+    /// A key is generated if `options` has none.
+    pub async fn create_capture_with_options(
+        &self,
+        order_id: &str,
+        request: &CreateCaptureRequest,
+        options: &RequestOptions,
+    ) -> Result<Idempotent<Capture>> {
+        let path = format!("accounts/{}/orders/{}/capture", self.account_id, order_id);
+        let options = options.clone().ensure_idempotency_key();
+        self.adapter.post_json_idempotent(&path, request, &options).await
+    }
+
+    /// Like [`OrdersOperations::create_refund`], but also returns the idempotency key
+    /// the request was sent with and whether the API replayed an earlier result.
+    pub async fn create_refund_with_meta(
+        &self,
+        order_id: &str,
+        request: CreateRefundRequest,
+    ) -> Result<Idempotent<Refund>> {
+        self.create_refund_with_options(order_id, &request, &RequestOptions::default()).await
+    }
+
+    /// Like [`create_refund_with_meta`](Self::create_refund_with_meta), but sends the request
+    /// with `options`, e.g. an idempotency key of the caller's choosing or a retry policy.
+    ///
+    /// A key is generated if `options` has none.
+    pub async fn create_refund_with_options(
+        &self,
+        order_id: &str,
+        request: &CreateRefundRequest,
+        options: &RequestOptions,
+    ) -> Result<Idempotent<Refund>> {
+        let path = format!("accounts/{}/orders/{}/refunds", self.account_id, order_id);
+        let options = options.clone().ensure_idempotency_key();
+        self.adapter.post_json_idempotent(&path, request, &options).await
+    }
+
+    /// Like [`OrdersOperations::create_cancellation`], but also returns the idempotency key
+    /// the request was sent with and whether the API replayed an earlier result.
+    pub async fn create_cancellation_with_meta(
+        &self,
+        order_id: &str,
+        request: CreateCancellationRequest,
+    ) -> Result<Idempotent<Cancellation>> {
+        self.create_cancellation_with_options(order_id, &request, &RequestOptions::default()).await
+    }
+
+    /// Like [`create_cancellation_with_meta`](Self::create_cancellation_with_meta), but sends
+    /// the request with `options`, e.g. an idempotency key of the caller's choosing or a retry
+    /// policy.
+    ///
+    /// A key is generated if `options` has none.
+    pub async fn create_cancellation_with_options(
+        &self,
+        order_id: &str,
+        request: &CreateCancellationRequest,
+        options: &RequestOptions,
+    ) -> Result<Idempotent<Cancellation>> {
+        let path = format!(
+            "accounts/{}/orders/{}/cancellation",
+            self.account_id, order_id
+        );
+        let options = options.clone().ensure_idempotency_key();
+        self.adapter.post_json_idempotent(&path, request, &options).await
+    }
 }
 
 #[async_trait]
@@ -159,28 +437,8 @@ impl<A: OrdersAdapter> OrdersOperations for OrdersClient<A> {
     }
 
     async fn list_orders(&self, params: ListOrdersParams) -> Result<OrderListResponse> {
-        let mut path = format!("accounts/{}/orders", self.account_id);
-
-        let mut query_params = Vec::new();
-        if let Some(limit) = params.limit {
-            query_params.push(format!("limit={}", limit));
-        }
-        if let Some(token) = params.page_token {
-            query_params.push(format!("page_token={}", token));
-        }
-        if let Some(status) = params.status {
-            query_params.push(format!("status={:?}", status));
-        }
-        if let Some(reference) = params.merchant_reference {
-            query_params.push(format!("merchant_reference={}", reference));
-        }
-
-        if !query_params.is_empty() {
-            path.push('?');
-            path.push_str(&query_params.join("&"));
-        }
-
-        self.adapter.get_json(&path).await
+        let path = format!("accounts/{}/orders", self.account_id);
+        self.adapter.get_json_with_query(&path, &params).await
     }
 
     async fn close_order(&self, order_id: &str) -> Result<Order> {
@@ -212,9 +470,12 @@ impl<A: OrdersAdapter> OrdersOperations for OrdersClient<A> {
         self.adapter.put_json(&path, &request).await
     }
 
-    async fn list_draft_orders(&self) -> Result<DraftOrderListResponse> {
+    async fn list_draft_orders(
+        &self,
+        params: ListDraftOrdersParams,
+    ) -> Result<DraftOrderListResponse> {
         let path = format!("accounts/{}/draft_orders", self.account_id);
-        self.adapter.get_json(&path).await
+        self.adapter.get_json_with_query(&path, &params).await
     }
 
     async fn complete_draft_order(&self, draft_id: &str) -> Result<Order> {
@@ -291,8 +552,7 @@ impl<A: OrdersAdapter> OrdersOperations for OrdersClient<A> {
         order_id: &str,
         request: CreateCaptureRequest,
     ) -> Result<Capture> {
-        let path = format!("accounts/{}/orders/{}/capture", self.account_id, order_id);
-        self.adapter.post_json(&path, &request).await
+        self.create_capture_with_meta(order_id, request).await.map(Idempotent::into_inner)
     }
 
     async fn get_capture(&self, order_id: &str, capture_id: &str) -> Result<Capture> {
@@ -309,8 +569,7 @@ impl<A: OrdersAdapter> OrdersOperations for OrdersClient<A> {
     }
 
     async fn create_refund(&self, order_id: &str, request: CreateRefundRequest) -> Result<Refund> {
-        let path = format!("accounts/{}/orders/{}/refunds", self.account_id, order_id);
-        self.adapter.post_json(&path, &request).await
+        self.create_refund_with_meta(order_id, request).await.map(Idempotent::into_inner)
     }
 
     async fn get_refund(&self, order_id: &str, refund_id: &str) -> Result<Refund> {
@@ -331,11 +590,7 @@ impl<A: OrdersAdapter> OrdersOperations for OrdersClient<A> {
         order_id: &str,
         request: CreateCancellationRequest,
     ) -> Result<Cancellation> {
-        let path = format!(
-            "accounts/{}/orders/{}/cancellation",
-            self.account_id, order_id
-        );
-        self.adapter.post_json(&path, &request).await
+        self.create_cancellation_with_meta(order_id, request).await.map(Idempotent::into_inner)
     }
 
     async fn get_cancellation(
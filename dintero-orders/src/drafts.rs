@@ -1,14 +1,17 @@
 //! Module implementation.
 
+use crate::orders::types::resolve_amount;
 use crate::orders::{Address, Customer};
 use chrono::{DateTime, Utc};
+use dintero_core::totals::{LineItem, TotalsMode, TotalsValidator, VatRate};
+use dintero_core::{Currency, Money};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DraftOrder {
     pub id: String,
     pub amount: i64,
-    pub currency: String,
+    pub currency: Currency,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub merchant_reference: Option<String>,
@@ -29,6 +32,13 @@ pub struct DraftOrder {
     pub shipping_address: Option<Address>,
 }
 
+impl DraftOrder {
+    /// Returns the amount together with its currency.
+    pub fn money(&self) -> Money {
+        Money::new(self.amount, self.currency)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DraftOrderItem {
     pub line_id: String,
@@ -42,10 +52,32 @@ pub struct DraftOrderItem {
     pub product_id: Option<String>,
 }
 
+impl LineItem for DraftOrderItem {
+    fn line_id(&self) -> &str {
+        &self.line_id
+    }
+
+    fn quantity(&self) -> i64 {
+        self.quantity
+    }
+
+    fn amount(&self) -> i64 {
+        self.amount
+    }
+
+    fn vat_amount(&self) -> i64 {
+        self.vat_amount
+    }
+
+    fn vat_rate(&self) -> VatRate {
+        VatRate::from_percent(self.vat)
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct CreateDraftOrderRequest {
     pub amount: i64,
-    pub currency: String,
+    pub currency: Currency,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub merchant_reference: Option<String>,
@@ -61,15 +93,22 @@ impl CreateDraftOrderRequest {
     pub fn builder() -> CreateDraftOrderRequestBuilder {
         CreateDraftOrderRequestBuilder::default()
     }
+
+    /// Returns the amount together with its currency.
+    pub fn money(&self) -> Money {
+        Money::new(self.amount, self.currency)
+    }
 }
 
 #[derive(Default)]
 pub struct CreateDraftOrderRequestBuilder {
     amount: Option<i64>,
-    currency: Option<String>,
+    currency: Option<Currency>,
     merchant_reference: Option<String>,
     items: Vec<DraftOrderItem>,
     customer: Option<Customer>,
+    totals: TotalsMode,
+    validator: TotalsValidator,
 }
 
 impl CreateDraftOrderRequestBuilder {
@@ -78,11 +117,19 @@ impl CreateDraftOrderRequestBuilder {
         self
     }
 
-    pub fn currency(mut self, currency: impl Into<String>) -> Self {
+    /// Sets the currency, e.g. `Currency::Nok` or `"NOK"`.
+    pub fn currency(mut self, currency: impl Into<Currency>) -> Self {
         self.currency = Some(currency.into());
         self
     }
 
+    /// Sets the amount and the currency.
+    pub fn money(mut self, money: Money) -> Self {
+        self.amount = Some(money.amount());
+        self.currency = Some(money.currency());
+        self
+    }
+
     pub fn merchant_reference(mut self, reference: impl Into<String>) -> Self {
         self.merchant_reference = Some(reference.into());
         self
@@ -98,9 +145,22 @@ impl CreateDraftOrderRequestBuilder {
         self
     }
 
+    /// Sets how the amount is checked against the items, see
+    /// [`CreateOrderRequestBuilder::totals`](crate::orders::CreateOrderRequestBuilder::totals).
+    pub fn totals(mut self, mode: TotalsMode) -> Self {
+        self.totals = mode;
+        self
+    }
+
+    /// Sets the rounding and VAT tolerance the items are checked with.
+    pub fn totals_validator(mut self, validator: TotalsValidator) -> Self {
+        self.validator = validator;
+        self
+    }
+
     pub fn build(self) -> Result<CreateDraftOrderRequest, String> {
         Ok(CreateDraftOrderRequest {
-            amount: self.amount.ok_or("amount is required")?,
+            amount: resolve_amount(self.totals, &self.validator, self.amount, &self.items)?,
             currency: self.currency.ok_or("currency is required")?,
             merchant_reference: self.merchant_reference,
             items: if self.items.is_empty() { None } else { Some(self.items) },
@@ -167,3 +227,40 @@ pub struct DraftOrderListResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub next_page_token: Option<String>,
 }
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ListDraftOrdersParams {
+    pub limit: Option<u32>,
+    pub page_token: Option<String>,
+}
+
+impl ListDraftOrdersParams {
+    pub fn builder() -> ListDraftOrdersParamsBuilder {
+        ListDraftOrdersParamsBuilder::default()
+    }
+}
+
+#[derive(Default)]
+pub struct ListDraftOrdersParamsBuilder {
+    limit: Option<u32>,
+    page_token: Option<String>,
+}
+
+impl ListDraftOrdersParamsBuilder {
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn page_token(mut self, token: impl Into<String>) -> Self {
+        self.page_token = Some(token.into());
+        self
+    }
+
+    pub fn build(self) -> ListDraftOrdersParams {
+        ListDraftOrdersParams {
+            limit: self.limit,
+            page_token: self.page_token,
+        }
+    }
+}
@@ -27,3 +27,8 @@ pub use sessions::*;
 
 mod client;
 pub use client::*;
+
+pub use dintero_core::{
+    ApiError, Currency, DinteroError, ErrorCode, FieldError, Money, MoneyError, RequestOptions,
+    RoundingMode,
+};
@@ -1,6 +1,8 @@
 //! Type definitions and data structures.
 
 use chrono::{DateTime, Utc};
+use dintero_core::totals::{LineItem, TotalsMode, TotalsValidator, VatRate};
+use dintero_core::{Currency, Money};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -24,7 +26,7 @@ pub struct Order {
     pub id: String,
     pub status: OrderStatus,
     pub amount: i64,
-    pub currency: String,
+    pub currency: Currency,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub merchant_reference: Option<String>,
@@ -54,6 +56,13 @@ pub struct Order {
     pub metadata: Option<serde_json::Value>,
 }
 
+impl Order {
+    /// Returns the amount together with its currency.
+    pub fn money(&self) -> Money {
+        Money::new(self.amount, self.currency)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OrderItem {
     pub id: String,
@@ -109,6 +118,32 @@ impl OrderItem {
     }
 }
 
+impl LineItem for OrderItem {
+    fn line_id(&self) -> &str {
+        &self.line_id
+    }
+
+    fn quantity(&self) -> i64 {
+        self.quantity
+    }
+
+    fn amount(&self) -> i64 {
+        self.amount
+    }
+
+    fn vat_amount(&self) -> i64 {
+        self.vat_amount
+    }
+
+    fn vat_rate(&self) -> VatRate {
+        VatRate::from_percent(self.vat)
+    }
+
+    fn discount_amount(&self) -> i64 {
+        self.discount_amount.unwrap_or(0)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Customer {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -154,7 +189,7 @@ pub struct Address {
 #[derive(Debug, Clone, Serialize)]
 pub struct CreateOrderRequest {
     pub amount: i64,
-    pub currency: String,
+    pub currency: Currency,
     pub items: Vec<OrderItem>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -180,12 +215,17 @@ impl CreateOrderRequest {
     pub fn builder() -> CreateOrderRequestBuilder {
         CreateOrderRequestBuilder::default()
     }
+
+    /// Returns the amount together with its currency.
+    pub fn money(&self) -> Money {
+        Money::new(self.amount, self.currency)
+    }
 }
 
 #[derive(Default)]
 pub struct CreateOrderRequestBuilder {
     amount: Option<i64>,
-    currency: Option<String>,
+    currency: Option<Currency>,
     items: Vec<OrderItem>,
     merchant_reference: Option<String>,
     merchant_reference_2: Option<String>,
@@ -193,6 +233,8 @@ pub struct CreateOrderRequestBuilder {
     shipping_address: Option<Address>,
     billing_address: Option<Address>,
     metadata: Option<serde_json::Value>,
+    totals: TotalsMode,
+    validator: TotalsValidator,
 }
 
 impl CreateOrderRequestBuilder {
@@ -201,11 +243,19 @@ impl CreateOrderRequestBuilder {
         self
     }
 
-    pub fn currency(mut self, currency: impl Into<String>) -> Self {
+    /// Sets the currency, e.g. `Currency::Nok` or `"NOK"`.
+    pub fn currency(mut self, currency: impl Into<Currency>) -> Self {
         self.currency = Some(currency.into());
         self
     }
 
+    /// Sets the amount and the currency.
+    pub fn money(mut self, money: Money) -> Self {
+        self.amount = Some(money.amount());
+        self.currency = Some(money.currency());
+        self
+    }
+
     pub fn add_item(mut self, item: OrderItem) -> Self {
         self.items.push(item);
         self
@@ -246,9 +296,24 @@ impl CreateOrderRequestBuilder {
         self
     }
 
+    /// Sets how the amount is checked against the items.
+    ///
+    /// With [`TotalsMode::Calculate`], the amount is the sum of the items and need not be
+    /// set. With [`TotalsMode::Strict`], it must be the sum of the items.
+    pub fn totals(mut self, mode: TotalsMode) -> Self {
+        self.totals = mode;
+        self
+    }
+
+    /// Sets the rounding and VAT tolerance the items are checked with.
+    pub fn totals_validator(mut self, validator: TotalsValidator) -> Self {
+        self.validator = validator;
+        self
+    }
+
     pub fn build(self) -> Result<CreateOrderRequest, String> {
         Ok(CreateOrderRequest {
-            amount: self.amount.ok_or("amount is required")?,
+            amount: resolve_amount(self.totals, &self.validator, self.amount, &self.items)?,
             currency: self.currency.ok_or("currency is required")?,
             items: self.items,
             merchant_reference: self.merchant_reference,
@@ -260,3 +325,28 @@ impl CreateOrderRequestBuilder {
         })
     }
 }
+
+/// Resolves the request amount according to `mode`.
+///
+/// Without items there is nothing to calculate or check, and the given amount is used.
+pub(crate) fn resolve_amount<L: LineItem>(
+    mode: TotalsMode,
+    validator: &TotalsValidator,
+    amount: Option<i64>,
+    items: &[L],
+) -> Result<i64, String> {
+    if items.is_empty() {
+        return amount.ok_or_else(|| "amount is required".to_string());
+    }
+    match mode {
+        TotalsMode::AsGiven => amount.ok_or_else(|| "amount is required".to_string()),
+        TotalsMode::Calculate => {
+            validator.check_lines(items).map(|totals| totals.amount).map_err(|e| e.to_string())
+        }
+        TotalsMode::Strict => {
+            let amount = amount.ok_or("amount is required")?;
+            validator.validate(items, amount, None).map_err(|e| e.to_string())?;
+            Ok(amount)
+        }
+    }
+}
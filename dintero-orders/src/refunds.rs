@@ -1,6 +1,7 @@
 //! Module implementation.
 
 use chrono::{DateTime, Utc};
+use dintero_core::{Currency, Money};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -8,7 +9,7 @@ pub struct Refund {
     pub id: String,
     pub order_id: String,
     pub amount: i64,
-    pub currency: String,
+    pub currency: Currency,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub created_at: Option<DateTime<Utc>>,
@@ -20,6 +21,13 @@ pub struct Refund {
     pub items: Option<Vec<RefundItem>>,
 }
 
+impl Refund {
+    /// Returns the amount together with its currency.
+    pub fn money(&self) -> Money {
+        Money::new(self.amount, self.currency)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RefundItem {
     pub line_id: String,
@@ -6,23 +6,37 @@ use crate::sellers::*;
 use crate::settlements::*;
 use crate::transactions::*;
 use async_trait::async_trait;
+use dintero_core::{paginate, Endpoint, Idempotent, Page, PageRequest, Paginator, RequestOptions};
 
 pub type Result<T> = std::result::Result<T, PaymentsError>;
 
-#[derive(Debug, thiserror::Error)]
-pub enum PaymentsError {
-    #[error("Serialization error: {0}")]
-    Serialization(String),
-
-    #[error("Client error: {0}")]
-    Client(String),
-}
-
-impl From<serde_json::Error> for PaymentsError {
-    fn from(err: serde_json::Error) -> Self {
-        PaymentsError::Serialization(err.to_string())
-    }
-}
+pub type PaymentsError = dintero_core::DinteroError;
+
+/// Endpoints called by the Payments API client, used to name the spans and metrics
+/// of its calls.
+#[rustfmt::skip]
+pub const ENDPOINTS: &[Endpoint] = &[
+    Endpoint::new("GET", "accounts/{account_id}/transactions/{transaction_id}", "payments.get_transaction"),
+    Endpoint::new("GET", "accounts/{account_id}/transactions", "payments.list_transactions"),
+    Endpoint::new("PUT", "accounts/{account_id}/transactions/{transaction_id}", "payments.update_transaction"),
+    Endpoint::new("POST", "accounts/{account_id}/transactions/{transaction_id}/capture", "payments.capture_transaction").with_idempotency_key(),
+    Endpoint::new("POST", "accounts/{account_id}/transactions/{transaction_id}/refund", "payments.refund_transaction").with_idempotency_key(),
+    Endpoint::new("POST", "accounts/{account_id}/transactions/{transaction_id}/void", "payments.void_transaction").with_idempotency_key(),
+    Endpoint::new("POST", "accounts/{account_id}/transactions/{transaction_id}/extend_authorization", "payments.extend_authorization"),
+    Endpoint::new("GET", "accounts/{account_id}/settlements", "payments.list_settlements"),
+    Endpoint::new("GET", "accounts/{account_id}/settlement_report_configs/{config_id}", "payments.get_settlement_report_config"),
+    Endpoint::new("GET", "accounts/{account_id}/settlement_report_configs", "payments.list_settlement_report_configs"),
+    Endpoint::new("POST", "accounts/{account_id}/settlement_report_configs", "payments.create_settlement_report_config"),
+    Endpoint::new("PUT", "accounts/{account_id}/settlement_report_configs/{config_id}", "payments.update_settlement_report_config"),
+    Endpoint::new("DELETE", "accounts/{account_id}/settlement_report_configs/{config_id}", "payments.delete_settlement_report_config"),
+    Endpoint::new("GET", "accounts/{account_id}/management/settings/approvals/payout_destinations", "payments.list_payout_destinations"),
+    Endpoint::new("POST", "accounts/{account_id}/management/settings/approvals/payout_destinations", "payments.create_payout_destination"),
+    // Also called by `get_seller_balance` and `list_seller_transfers`.
+    Endpoint::new("GET", "accounts/{account_id}/payout_destinations/{destination_id}/balance", "payments.get_payout_balance"),
+    Endpoint::new("GET", "accounts/{account_id}/payout_destinations/{destination_id}/transfers", "payments.list_payout_transfers"),
+    // Also called by `initiate_fund_transfer`, which names its calls itself.
+    Endpoint::new("POST", "accounts/{account_id}/payout/fund_transfers", "payments.create_payout_transfer").with_idempotency_key(),
+];
 
 #[async_trait]
 pub trait PaymentsOperations: Send + Sync {
@@ -58,7 +72,10 @@ pub trait PaymentsOperations: Send + Sync {
         request: ExtendAuthorizationRequest,
     ) -> Result<Transaction>;
 
-    async fn list_settlements(&self) -> Result<SettlementListResponse>;
+    async fn list_settlements(
+        &self,
+        params: ListSettlementsParams,
+    ) -> Result<SettlementListResponse>;
     async fn get_settlement_report_config(&self, config_id: &str)
         -> Result<SettlementReportConfig>;
     async fn list_settlement_report_configs(&self) -> Result<Vec<SettlementReportConfig>>;
@@ -82,6 +99,7 @@ pub trait PaymentsOperations: Send + Sync {
     async fn list_payout_transfers(
         &self,
         destination_id: &str,
+        params: ListPayoutTransfersParams,
     ) -> Result<PayoutTransferListResponse>;
     async fn create_payout_transfer(
         &self,
@@ -111,6 +129,78 @@ pub trait PaymentsAdapter: Send + Sync {
         body: &B,
     ) -> Result<T>;
     async fn delete(&self, path: &str) -> Result<()>;
+    /// Sends a `PATCH` request. Adapters that predate PATCH support get a default that
+    /// returns [`DinteroError::Config`](dintero_core::DinteroError::Config).
+    async fn patch_json<T: serde::de::DeserializeOwned, B: serde::Serialize + Send + Sync>(
+        &self,
+        path: &str,
+        _body: &B,
+    ) -> Result<T> {
+        Err(dintero_core::DinteroError::Config(format!(
+            "PATCH {} is not supported by this adapter",
+            path
+        )))
+    }
+    /// Sends a `GET` request with query parameters serialized from `query`, e.g. a
+    /// struct or a slice of pairs.
+    async fn get_json_with_query<
+        T: serde::de::DeserializeOwned,
+        Q: serde::Serialize + Send + Sync + ?Sized,
+    >(
+        &self,
+        path: &str,
+        query: &Q,
+    ) -> Result<T> {
+        self.get_json(&dintero_core::path::with_query(path, query)?).await
+    }
+
+    /// Sends a `POST` request with per-request options such as an idempotency key.
+    ///
+    /// The default implementation cannot send headers, so it returns
+    /// [`DinteroError::Config`](dintero_core::DinteroError::Config) unless the options
+    /// are empty.
+    async fn post_json_with_options<
+        T: serde::de::DeserializeOwned,
+        B: serde::Serialize + Send + Sync,
+    >(
+        &self,
+        path: &str,
+        body: &B,
+        options: &RequestOptions,
+    ) -> Result<T> {
+        if options.idempotency_key.is_some() || options.retry_policy.is_some() {
+            return Err(dintero_core::DinteroError::Config(format!(
+                "POST {} with request options is not supported by this adapter",
+                path
+            )));
+        }
+        self.post_json(path, body).await
+    }
+
+    /// Sends a `POST` request with the idempotency key from `options` and reports
+    /// whether the API replayed an earlier result with the same key.
+    ///
+    /// The default implementation forwards to
+    /// [`post_json_with_options`](Self::post_json_with_options) and cannot see the
+    /// response headers, so it never reports a replay.
+    async fn post_json_idempotent<
+        T: serde::de::DeserializeOwned,
+        B: serde::Serialize + Send + Sync,
+    >(
+        &self,
+        path: &str,
+        body: &B,
+        options: &RequestOptions,
+    ) -> Result<Idempotent<T>> {
+        let Some(idempotency_key) = options.idempotency_key.clone() else {
+            return Err(dintero_core::DinteroError::Config(format!(
+                "POST {} requires an idempotency key",
+                path
+            )));
+        };
+        let value = self.post_json_with_options(path, body, options).await?;
+        Ok(Idempotent { value, idempotency_key, replayed: false })
+    }
 }
 
 pub struct PaymentsClient<A: PaymentsAdapter> {
@@ -122,6 +212,234 @@ impl<A: PaymentsAdapter> PaymentsClient<A> {
     pub fn new(adapter: A, account_id: String) -> Self {
         Self { adapter, account_id }
     }
+
+    /// Streams all transactions matching `params`, fetching further pages as needed.
+    ///
+    /// `params.page_token` sets the page to start from.
+    pub fn transactions_stream(
+        &self,
+        params: ListTransactionsParams,
+    ) -> Paginator<'_, Transaction> {
+        paginate(move |page: PageRequest| {
+            let params = ListTransactionsParams {
+                limit: page.page_size.or(params.limit),
+                page_token: page.page_token().or_else(|| params.page_token.clone()),
+                ..params.clone()
+            };
+            async move {
+                let response = self.list_transactions(params).await?;
+                Ok(Page::from_token(
+                    response.transactions,
+                    response.next_page_token,
+                ))
+            }
+        })
+    }
+
+    /// Streams all settlements, fetching further pages as needed.
+    pub fn settlements_stream(&self, params: ListSettlementsParams) -> Paginator<'_, Settlement> {
+        paginate(move |page: PageRequest| {
+            let params = ListSettlementsParams {
+                limit: page.page_size.or(params.limit),
+                page_token: page.page_token().or_else(|| params.page_token.clone()),
+            };
+            async move {
+                let response = self.list_settlements(params).await?;
+                Ok(Page::from_token(
+                    response.settlements,
+                    response.next_page_token,
+                ))
+            }
+        })
+    }
+
+    /// Streams all transfers to a payout destination, fetching further pages as needed.
+    pub fn payout_transfers_stream<'a>(
+        &'a self,
+        destination_id: &'a str,
+        params: ListPayoutTransfersParams,
+    ) -> Paginator<'a, PayoutTransfer> {
+        paginate(move |page: PageRequest| {
+            let params = ListPayoutTransfersParams {
+                limit: page.page_size.or(params.limit),
+                page_token: page.page_token().or_else(|| params.page_token.clone()),
+            };
+            async move {
+                let response = self.list_payout_transfers(destination_id, params).await?;
+                Ok(Page::from_token(
+                    response.transfers,
+                    response.next_page_token,
+                ))
+            }
+        })
+    }
+
+    /// Streams all transfers to a seller matching `params`, fetching further pages as
+    /// needed.
+    pub fn seller_transfers_stream<'a>(
+        &'a self,
+        destination_id: &'a str,
+        params: ListSellerTransfersParams,
+    ) -> Paginator<'a, SellerTransfer> {
+        paginate(move |page: PageRequest| {
+            let params = ListSellerTransfersParams {
+                limit: page.page_size.or(params.limit),
+                page_token: page.page_token().or_else(|| params.page_token.clone()),
+                ..params.clone()
+            };
+            async move {
+                let response = self.list_seller_transfers(destination_id, params).await?;
+                Ok(Page::from_token(
+                    response.transfers,
+                    response.next_page_token,
+                ))
+            }
+        })
+    }
+
+    /// Like [`PaymentsOperations::capture_transaction`], but also returns the idempotency key
+    /// the request was sent with and whether the API replayed an earlier result.
+    pub async fn capture_transaction_with_meta(
+        &self,
+        transaction_id: &str,
+        request: CaptureTransactionRequest,
+    ) -> Result<Idempotent<Transaction>> {
+        self.capture_transaction_with_options(transaction_id, &request, &RequestOptions::default())
+            .await
+    }
+
+    /// Like [`capture_transaction_with_meta`](Self::capture_transaction_with_meta), but sends
+    /// the request with `options`, e.g. an idempotency key of the caller's choosing or a retry
+    /// policy.
+    ///
+    /// A key is generated if `options` has none.
+    pub async fn capture_transaction_with_options(
+        &self,
+        transaction_id: &str,
+        request: &CaptureTransactionRequest,
+        options: &RequestOptions,
+    ) -> Result<Idempotent<Transaction>> {
+        let path = format!(
+            "accounts/{}/transactions/{}/capture",
+            self.account_id, transaction_id
+        );
+        let options = options.clone().ensure_idempotency_key();
+        self.adapter.post_json_idempotent(&path, request, &options).await
+    }
+
+    /// Like [`PaymentsOperations::refund_transaction`], but also returns the idempotency key
+    /// the request was sent with and whether the API replayed an earlier result.
+    pub async fn refund_transaction_with_meta(
+        &self,
+        transaction_id: &str,
+        request: RefundTransactionRequest,
+    ) -> Result<Idempotent<Transaction>> {
+        self.refund_transaction_with_options(transaction_id, &request, &RequestOptions::default())
+            .await
+    }
+
+    /// Like [`refund_transaction_with_meta`](Self::refund_transaction_with_meta), but sends the
+    /// request with `options`, e.g. an idempotency key of the caller's choosing or a retry
+    /// policy.
+    ///
+    /// A key is generated if `options` has none.
+    pub async fn refund_transaction_with_options(
+        &self,
+        transaction_id: &str,
+        request: &RefundTransactionRequest,
+        options: &RequestOptions,
+    ) -> Result<Idempotent<Transaction>> {
+        let path = format!(
+            "accounts/{}/transactions/{}/refund",
+            self.account_id, transaction_id
+        );
+        let options = options.clone().ensure_idempotency_key();
+        self.adapter.post_json_idempotent(&path, request, &options).await
+    }
+
+    /// Like [`PaymentsOperations::void_transaction`], but also returns the idempotency key
+    /// the request was sent with and whether the API replayed an earlier result.
+    pub async fn void_transaction_with_meta(
+        &self,
+        transaction_id: &str,
+        request: VoidTransactionRequest,
+    ) -> Result<Idempotent<Transaction>> {
+        self.void_transaction_with_options(transaction_id, &request, &RequestOptions::default())
+            .await
+    }
+
+    /// Like [`void_transaction_with_meta`](Self::void_transaction_with_meta), but sends the
+    /// request with `options`, e.g. an idempotency key of the caller's choosing or a retry
+    /// policy.
+    ///
+    /// A key is generated if `options` has none.
+    pub async fn void_transaction_with_options(
+        &self,
+        transaction_id: &str,
+        request: &VoidTransactionRequest,
+        options: &RequestOptions,
+    ) -> Result<Idempotent<Transaction>> {
+        let path = format!(
+            "accounts/{}/transactions/{}/void",
+            self.account_id, transaction_id
+        );
+        let options = options.clone().ensure_idempotency_key();
+        self.adapter.post_json_idempotent(&path, request, &options).await
+    }
+
+    /// Like [`PaymentsOperations::create_payout_transfer`], but also returns the idempotency key
+    /// the request was sent with and whether the API replayed an earlier result.
+    pub async fn create_payout_transfer_with_meta(
+        &self,
+        request: CreatePayoutTransferRequest,
+    ) -> Result<Idempotent<PayoutTransfer>> {
+        self.create_payout_transfer_with_options(&request, &RequestOptions::default()).await
+    }
+
+    /// Like [`create_payout_transfer_with_meta`](Self::create_payout_transfer_with_meta), but
+    /// sends the request with `options`, e.g. an idempotency key of the caller's choosing or a
+    /// retry policy.
+    ///
+    /// A key is generated if `options` has none.
+    pub async fn create_payout_transfer_with_options(
+        &self,
+        request: &CreatePayoutTransferRequest,
+        options: &RequestOptions,
+    ) -> Result<Idempotent<PayoutTransfer>> {
+        let path = format!("accounts/{}/payout/fund_transfers", self.account_id);
+        let options = options
+            .clone()
+            .ensure_idempotency_key()
+            .with_operation("payments.create_payout_transfer");
+        self.adapter.post_json_idempotent(&path, request, &options).await
+    }
+
+    /// Like [`PaymentsOperations::initiate_fund_transfer`], but also returns the idempotency key
+    /// the request was sent with and whether the API replayed an earlier result.
+    pub async fn initiate_fund_transfer_with_meta(
+        &self,
+        request: FundTransferRequest,
+    ) -> Result<Idempotent<FundTransfer>> {
+        self.initiate_fund_transfer_with_options(&request, &RequestOptions::default()).await
+    }
+
+    /// Like [`initiate_fund_transfer_with_meta`](Self::initiate_fund_transfer_with_meta), but
+    /// sends the request with `options`, e.g. an idempotency key of the caller's choosing or a
+    /// retry policy.
+    ///
+    /// A key is generated if `options` has none.
+    pub async fn initiate_fund_transfer_with_options(
+        &self,
+        request: &FundTransferRequest,
+        options: &RequestOptions,
+    ) -> Result<Idempotent<FundTransfer>> {
+        let path = format!("accounts/{}/payout/fund_transfers", self.account_id);
+        let options = options
+            .clone()
+            .ensure_idempotency_key()
+            .with_operation("payments.initiate_fund_transfer");
+        self.adapter.post_json_idempotent(&path, request, &options).await
+    }
 }
 
 #[async_trait]
@@ -138,28 +456,8 @@ impl<A: PaymentsAdapter> PaymentsOperations for PaymentsClient<A> {
         &self,
         params: ListTransactionsParams,
     ) -> Result<TransactionListResponse> {
-        let mut path = format!("accounts/{}/transactions", self.account_id);
-
-        let mut query_params = Vec::new();
-        if let Some(limit) = params.limit {
-            query_params.push(format!("limit={}", limit));
-        }
-        if let Some(token) = params.page_token {
-            query_params.push(format!("page_token={}", token));
-        }
-        if let Some(status) = params.status {
-            query_params.push(format!("status={:?}", status));
-        }
-        if let Some(reference) = params.merchant_reference {
-            query_params.push(format!("merchant_reference={}", reference));
-        }
-
-        if !query_params.is_empty() {
-            path.push('?');
-            path.push_str(&query_params.join("&"));
-        }
-
-        self.adapter.get_json(&path).await
+        let path = format!("accounts/{}/transactions", self.account_id);
+        self.adapter.get_json_with_query(&path, &params).await
     }
 
     async fn update_transaction(
@@ -179,11 +477,9 @@ impl<A: PaymentsAdapter> PaymentsOperations for PaymentsClient<A> {
         transaction_id: &str,
         request: CaptureTransactionRequest,
     ) -> Result<Transaction> {
-        let path = format!(
-            "accounts/{}/transactions/{}/capture",
-            self.account_id, transaction_id
-        );
-        self.adapter.post_json(&path, &request).await
+        self.capture_transaction_with_meta(transaction_id, request)
+            .await
+            .map(Idempotent::into_inner)
     }
 
     async fn refund_transaction(
@@ -191,11 +487,7 @@ impl<A: PaymentsAdapter> PaymentsOperations for PaymentsClient<A> {
         transaction_id: &str,
         request: RefundTransactionRequest,
     ) -> Result<Transaction> {
-        let path = format!(
-            "accounts/{}/transactions/{}/refund",
-            self.account_id, transaction_id
-        );
-        self.adapter.post_json(&path, &request).await
+        self.refund_transaction_with_meta(transaction_id, request).await.map(Idempotent::into_inner)
     }
 
     async fn void_transaction(
@@ -203,11 +495,7 @@ impl<A: PaymentsAdapter> PaymentsOperations for PaymentsClient<A> {
         transaction_id: &str,
         request: VoidTransactionRequest,
     ) -> Result<Transaction> {
-        let path = format!(
-            "accounts/{}/transactions/{}/void",
-            self.account_id, transaction_id
-        );
-        self.adapter.post_json(&path, &request).await
+        self.void_transaction_with_meta(transaction_id, request).await.map(Idempotent::into_inner)
     }
 
     async fn extend_authorization(
@@ -222,9 +510,12 @@ impl<A: PaymentsAdapter> PaymentsOperations for PaymentsClient<A> {
         self.adapter.post_json(&path, &request).await
     }
 
-    async fn list_settlements(&self) -> Result<SettlementListResponse> {
+    async fn list_settlements(
+        &self,
+        params: ListSettlementsParams,
+    ) -> Result<SettlementListResponse> {
         let path = format!("accounts/{}/settlements", self.account_id);
-        self.adapter.get_json(&path).await
+        self.adapter.get_json_with_query(&path, &params).await
     }
 
     async fn get_settlement_report_config(
@@ -301,25 +592,24 @@ impl<A: PaymentsAdapter> PaymentsOperations for PaymentsClient<A> {
     async fn list_payout_transfers(
         &self,
         destination_id: &str,
+        params: ListPayoutTransfersParams,
     ) -> Result<PayoutTransferListResponse> {
         let path = format!(
             "accounts/{}/payout_destinations/{}/transfers",
             self.account_id, destination_id
         );
-        self.adapter.get_json(&path).await
+        self.adapter.get_json_with_query(&path, &params).await
     }
 
     async fn create_payout_transfer(
         &self,
         request: CreatePayoutTransferRequest,
     ) -> Result<PayoutTransfer> {
-        let path = format!("accounts/{}/payout/fund_transfers", self.account_id);
-        self.adapter.post_json(&path, &request).await
+        self.create_payout_transfer_with_meta(request).await.map(Idempotent::into_inner)
     }
 
     async fn initiate_fund_transfer(&self, request: FundTransferRequest) -> Result<FundTransfer> {
-        let path = format!("accounts/{}/payout/fund_transfers", self.account_id);
-        self.adapter.post_json(&path, &request).await
+        self.initiate_fund_transfer_with_meta(request).await.map(Idempotent::into_inner)
     }
 
     async fn get_seller_balance(&self, destination_id: &str) -> Result<SellerBalance> {
@@ -335,30 +625,10 @@ impl<A: PaymentsAdapter> PaymentsOperations for PaymentsClient<A> {
         destination_id: &str,
         params: ListSellerTransfersParams,
     ) -> Result<SellerTransfersResponse> {
-        let mut path = format!(
+        let path = format!(
             "accounts/{}/payout_destinations/{}/transfers",
             self.account_id, destination_id
         );
-
-        let mut query_params = Vec::new();
-        if let Some(limit) = params.limit {
-            query_params.push(format!("limit={}", limit));
-        }
-        if let Some(token) = params.page_token {
-            query_params.push(format!("page_token={}", token));
-        }
-        if let Some(from_date) = params.from_date {
-            query_params.push(format!("from_date={}", from_date));
-        }
-        if let Some(to_date) = params.to_date {
-            query_params.push(format!("to_date={}", to_date));
-        }
-
-        if !query_params.is_empty() {
-            path.push('?');
-            path.push_str(&query_params.join("&"));
-        }
-
-        self.adapter.get_json(&path).await
+        self.adapter.get_json_with_query(&path, &params).await
     }
 }
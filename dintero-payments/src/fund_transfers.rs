@@ -1,11 +1,12 @@
 //! Module implementation.
 
+use dintero_core::{Currency, Money};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize)]
 pub struct FundTransferRequest {
     pub amount: i64,
-    pub currency: String,
+    pub currency: Currency,
     pub from_payout_destination: String,
     pub to_payout_destination: String,
 
@@ -20,7 +21,7 @@ pub struct FundTransferRequest {
 pub struct FundTransfer {
     pub id: String,
     pub amount: i64,
-    pub currency: String,
+    pub currency: Currency,
     pub from_payout_destination: String,
     pub to_payout_destination: String,
     pub status: String,
@@ -33,16 +34,28 @@ pub struct FundTransfer {
     pub description: Option<String>,
 }
 
+impl FundTransfer {
+    /// Returns the amount together with its currency.
+    pub fn money(&self) -> Money {
+        Money::new(self.amount, self.currency)
+    }
+}
+
 impl FundTransferRequest {
     pub fn builder() -> FundTransferRequestBuilder {
         FundTransferRequestBuilder::default()
     }
+
+    /// Returns the amount together with its currency.
+    pub fn money(&self) -> Money {
+        Money::new(self.amount, self.currency)
+    }
 }
 
 #[derive(Debug, Default)]
 pub struct FundTransferRequestBuilder {
     amount: Option<i64>,
-    currency: Option<String>,
+    currency: Option<Currency>,
     from_payout_destination: Option<String>,
     to_payout_destination: Option<String>,
     reference: Option<String>,
@@ -55,11 +68,19 @@ impl FundTransferRequestBuilder {
         self
     }
 
-    pub fn currency(mut self, currency: impl Into<String>) -> Self {
+    /// Sets the currency, e.g. `Currency::Nok` or `"NOK"`.
+    pub fn currency(mut self, currency: impl Into<Currency>) -> Self {
         self.currency = Some(currency.into());
         self
     }
 
+    /// Sets the amount and the currency.
+    pub fn money(mut self, money: Money) -> Self {
+        self.amount = Some(money.amount());
+        self.currency = Some(money.currency());
+        self
+    }
+
     pub fn from_payout_destination(mut self, destination: impl Into<String>) -> Self {
         self.from_payout_destination = Some(destination.into());
         self
@@ -19,3 +19,8 @@ pub use transactions::*;
 
 mod client;
 pub use client::*;
+
+pub use dintero_core::{
+    ApiError, Currency, DinteroError, ErrorCode, FieldError, Money, MoneyError, RequestOptions,
+    RoundingMode,
+};
@@ -1,6 +1,7 @@
 //! Module implementation.
 
 use chrono::{DateTime, Utc};
+use dintero_core::{Currency, Money};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -52,16 +53,28 @@ impl CreatePayoutDestinationRequest {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PayoutBalance {
-    pub currency: String,
+    pub currency: Currency,
     pub available: i64,
     pub pending: i64,
 }
 
+impl PayoutBalance {
+    /// Returns the available balance together with its currency.
+    pub fn available_money(&self) -> Money {
+        Money::new(self.available, self.currency)
+    }
+
+    /// Returns the pending balance together with its currency.
+    pub fn pending_money(&self) -> Money {
+        Money::new(self.pending, self.currency)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PayoutTransfer {
     pub id: String,
     pub amount: i64,
-    pub currency: String,
+    pub currency: Currency,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub status: Option<String>,
@@ -73,6 +86,13 @@ pub struct PayoutTransfer {
     pub payout_destination_id: Option<String>,
 }
 
+impl PayoutTransfer {
+    /// Returns the amount together with its currency.
+    pub fn money(&self) -> Money {
+        Money::new(self.amount, self.currency)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PayoutTransferListResponse {
     pub transfers: Vec<PayoutTransfer>,
@@ -81,23 +101,61 @@ pub struct PayoutTransferListResponse {
     pub next_page_token: Option<String>,
 }
 
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ListPayoutTransfersParams {
+    pub limit: Option<u32>,
+    pub page_token: Option<String>,
+}
+
+impl ListPayoutTransfersParams {
+    pub fn builder() -> ListPayoutTransfersParamsBuilder {
+        ListPayoutTransfersParamsBuilder::default()
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ListPayoutTransfersParamsBuilder {
+    limit: Option<u32>,
+    page_token: Option<String>,
+}
+
+impl ListPayoutTransfersParamsBuilder {
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn page_token(mut self, token: impl Into<String>) -> Self {
+        self.page_token = Some(token.into());
+        self
+    }
+
+    pub fn build(self) -> ListPayoutTransfersParams {
+        ListPayoutTransfersParams {
+            limit: self.limit,
+            page_token: self.page_token,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct CreatePayoutTransferRequest {
     pub amount: i64,
-    pub currency: String,
+    pub currency: Currency,
     pub payout_destination_id: String,
 }
 
 impl CreatePayoutTransferRequest {
-    pub fn new(
-        amount: i64,
-        currency: impl Into<String>,
-        payout_destination_id: impl Into<String>,
-    ) -> Self {
+    pub fn new(amount: i64, currency: Currency, payout_destination_id: impl Into<String>) -> Self {
         Self {
             amount,
-            currency: currency.into(),
+            currency,
             payout_destination_id: payout_destination_id.into(),
         }
     }
+
+    /// Returns the amount together with its currency.
+    pub fn money(&self) -> Money {
+        Money::new(self.amount, self.currency)
+    }
 }
@@ -1,5 +1,6 @@
 //! Module implementation.
 
+use dintero_core::{Currency, Money};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -7,17 +8,29 @@ pub struct SellerBalance {
     pub payout_destination: String,
     pub available_balance: i64,
     pub pending_balance: i64,
-    pub currency: String,
+    pub currency: Currency,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub last_updated: Option<String>,
 }
 
+impl SellerBalance {
+    /// Returns the available balance together with its currency.
+    pub fn available_money(&self) -> Money {
+        Money::new(self.available_balance, self.currency)
+    }
+
+    /// Returns the pending balance together with its currency.
+    pub fn pending_money(&self) -> Money {
+        Money::new(self.pending_balance, self.currency)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SellerTransfer {
     pub id: String,
     pub amount: i64,
-    pub currency: String,
+    pub currency: Currency,
     pub payout_destination: String,
     pub status: String,
     pub created_at: String,
@@ -29,7 +42,14 @@ pub struct SellerTransfer {
     pub reference: Option<String>,
 }
 
-#[derive(Debug, Clone, Default)]
+impl SellerTransfer {
+    /// Returns the amount together with its currency.
+    pub fn money(&self) -> Money {
+        Money::new(self.amount, self.currency)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct ListSellerTransfersParams {
     pub limit: Option<u32>,
     pub page_token: Option<String>,
@@ -1,13 +1,14 @@
 //! Module implementation.
 
 use chrono::{DateTime, Utc};
+use dintero_core::{Currency, Money};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Settlement {
     pub id: String,
     pub account_id: String,
-    pub currency: String,
+    pub currency: Currency,
     pub amount: i64,
 
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -23,6 +24,13 @@ pub struct Settlement {
     pub status: Option<String>,
 }
 
+impl Settlement {
+    /// Returns the amount together with its currency.
+    pub fn money(&self) -> Money {
+        Money::new(self.amount, self.currency)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SettlementListResponse {
     pub settlements: Vec<Settlement>,
@@ -31,6 +39,43 @@ pub struct SettlementListResponse {
     pub next_page_token: Option<String>,
 }
 
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ListSettlementsParams {
+    pub limit: Option<u32>,
+    pub page_token: Option<String>,
+}
+
+impl ListSettlementsParams {
+    pub fn builder() -> ListSettlementsParamsBuilder {
+        ListSettlementsParamsBuilder::default()
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ListSettlementsParamsBuilder {
+    limit: Option<u32>,
+    page_token: Option<String>,
+}
+
+impl ListSettlementsParamsBuilder {
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn page_token(mut self, token: impl Into<String>) -> Self {
+        self.page_token = Some(token.into());
+        self
+    }
+
+    pub fn build(self) -> ListSettlementsParams {
+        ListSettlementsParams {
+            limit: self.limit,
+            page_token: self.page_token,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SettlementReportConfig {
     pub id: String,
@@ -1,15 +1,18 @@
 //! Module implementation.
 
 use chrono::{DateTime, Utc};
+use dintero_core::{Currency, Money};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum TransactionStatus {
     Authorized,
+    PartiallyCaptured,
     Captured,
     PartiallyRefunded,
     Refunded,
+    #[serde(alias = "AUTHORIZATION_VOIDED")]
     Voided,
     Failed,
 }
@@ -18,7 +21,7 @@ pub enum TransactionStatus {
 pub struct Transaction {
     pub id: String,
     pub amount: i64,
-    pub currency: String,
+    pub currency: Currency,
     pub status: TransactionStatus,
 
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -40,6 +43,13 @@ pub struct Transaction {
     pub metadata: Option<serde_json::Value>,
 }
 
+impl Transaction {
+    /// Returns the amount together with its currency.
+    pub fn money(&self) -> Money {
+        Money::new(self.amount, self.currency)
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct CaptureTransactionRequest {
     pub amount: i64,
@@ -181,7 +191,7 @@ pub struct TransactionListResponse {
     pub next_page_token: Option<String>,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct ListTransactionsParams {
     pub limit: Option<u32>,
     pub page_token: Option<String>,
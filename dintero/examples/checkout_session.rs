@@ -1,5 +1,5 @@
 use dintero::checkout::sessions::{CreateSessionRequest, Order, OrderItem};
-use dintero::checkout::CheckoutOperations;
+use dintero::checkout::{CheckoutOperations, Currency, Money};
 use dintero::{Config, DinteroClient, Environment, Result};
 
 #[tokio::main]
@@ -17,8 +17,7 @@ async fn main() -> Result<()> {
     let item = OrderItem::new("item-1", "line-1", "Product 1", 2, 20000, 4000, 25);
 
     let order = Order::builder()
-        .amount(20000)
-        .currency("NOK")
+        .money(Money::new(20000, Currency::Nok))
         .merchant_reference("order-123")
         .add_item(item)
         .vat_amount(4000)
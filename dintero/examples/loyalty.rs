@@ -31,7 +31,7 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
         metadata: None,
     };
 
-    match loyalty.customers().create_customer(create_customer_req).await {
+    match loyalty.create_customer(create_customer_req).await {
         Ok(customer) => {
             println!(
                 "✓ Customer created: {} ({})",
@@ -49,7 +49,7 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
                 metadata: None,
             };
 
-            match loyalty.wallets().create_virtual_card(create_card_req).await {
+            match loyalty.create_virtual_card(create_card_req).await {
                 Ok(card) => {
                     println!(
                         "✓ Virtual card created: {} (Balance: {} {})",
@@ -63,7 +63,7 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
                         active: Some(true),
                     };
 
-                    match loyalty.products().create_product_catalog(catalog_req).await {
+                    match loyalty.create_product_catalog(catalog_req).await {
                         Ok(catalog) => {
                             println!("✓ Product catalog created: {}", catalog.name);
 
@@ -81,7 +81,7 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
                                 metadata: None,
                             };
 
-                            match loyalty.products().create_product_item(product_req).await {
+                            match loyalty.create_product_item(product_req).await {
                                 Ok(product) => {
                                     println!(
                                         "✓ Product added: {} (SKU: {})",
@@ -106,7 +106,7 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
                         active: Some(true),
                     };
 
-                    match loyalty.discounts().create_discount_campaign(campaign_req).await {
+                    match loyalty.create_discount_campaign(campaign_req).await {
                         Ok(campaign) => {
                             println!(
                                 "✓ Discount campaign created: {} (Code: {})",
@@ -137,7 +137,7 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
                         metadata: None,
                     };
 
-                    match loyalty.receipts().create_receipt(receipt_req).await {
+                    match loyalty.create_receipt(receipt_req).await {
                         Ok(receipt) => {
                             println!(
                                 "✓ Receipt created: {} (Amount: {} {})",
@@ -169,7 +169,7 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
                         metadata: None,
                     };
 
-                    match loyalty.locations().create_location(location_req).await {
+                    match loyalty.create_location(location_req).await {
                         Ok(location) => {
                             println!("✓ Location created: {}", location.name);
                         }
@@ -187,7 +187,7 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
                         description: Some("Main webhook endpoint".to_string()),
                     };
 
-                    match loyalty.webhooks().create_webhook_subscription(webhook_req).await {
+                    match loyalty.create_webhook_subscription(webhook_req).await {
                         Ok(subscription) => {
                             println!(
                                 "✓ Webhook subscription created: {} events: {}",
@@ -215,7 +215,7 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
                         }],
                     };
 
-                    match loyalty.automations().create_automation_rule(automation_req).await {
+                    match loyalty.create_automation_rule(automation_req).await {
                         Ok(rule) => {
                             println!("✓ Automation rule created: {}", rule.name);
                         }
@@ -30,8 +30,7 @@ async fn main() -> StdResult<()> {
     };
 
     let draft_order = CreateDraftOrderRequest::builder()
-        .amount(99900)
-        .currency("NOK")
+        .money(Money::new(99900, Currency::Nok))
         .merchant_reference("DRAFT-2024-001")
         .add_item(draft_item)
         .build()?;
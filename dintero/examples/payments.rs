@@ -97,7 +97,7 @@ async fn main() -> StdResult<()> {
 
     // ===== SETTLEMENTS =====
     println!("\n💼 8. Listing Settlements...");
-    let settlements = payments_client.list_settlements().await?;
+    let settlements = payments_client.list_settlements(ListSettlementsParams::default()).await?;
     println!(
         "   ✅ Found {} settlement(s)",
         settlements.settlements.len()
@@ -169,11 +169,14 @@ async fn main() -> StdResult<()> {
         println!("   ✅ Pending: {}", balance.pending);
 
         println!("\n📊 16. Listing Payout Transfers...");
-        let transfers = payments_client.list_payout_transfers(&dest.id).await?;
+        let transfers = payments_client
+            .list_payout_transfers(&dest.id, ListPayoutTransfersParams::default())
+            .await?;
         println!("   ✅ Found {} transfer(s)", transfers.transfers.len());
 
         println!("\n💸 17. Creating Payout Transfer...");
-        let transfer_request = CreatePayoutTransferRequest::new(100000, "NOK", dest.id.clone());
+        let transfer_request =
+            CreatePayoutTransferRequest::new(100000, Currency::Nok, dest.id.clone());
 
         let created_transfer = payments_client.create_payout_transfer(transfer_request).await?;
         println!("   ✅ Transfer created: {}", created_transfer.id);
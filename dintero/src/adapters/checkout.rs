@@ -2,6 +2,7 @@
 
 use crate::client::HttpClient as DinteroHttpClient;
 use async_trait::async_trait;
+use dintero_core::{Idempotent, RequestOptions};
 use serde::{de::DeserializeOwned, Serialize};
 use std::sync::Arc;
 
@@ -22,29 +23,13 @@ impl dintero_checkout::HttpClient for CheckoutHttpAdapter {
         path: &str,
         body: &B,
     ) -> dintero_checkout::Result<T> {
-        let builder = self
-            .http
-            .post(path)
-            .await
-            .map_err(|e| dintero_checkout::CheckoutError::Client(e.to_string()))?;
-
-        self.http
-            .send_json(builder, body)
-            .await
-            .map_err(|e| dintero_checkout::CheckoutError::Client(e.to_string()))
+        self.http.post_json(path, body).await
     }
 
     async fn get_json<T: DeserializeOwned>(&self, path: &str) -> dintero_checkout::Result<T> {
-        let builder = self
-            .http
-            .get(path)
-            .await
-            .map_err(|e| dintero_checkout::CheckoutError::Client(e.to_string()))?;
+        let builder = self.http.get(path).await?;
 
-        self.http
-            .send(builder)
-            .await
-            .map_err(|e| dintero_checkout::CheckoutError::Client(e.to_string()))
+        self.http.send(builder).await
     }
 
     async fn put_json<B: Serialize + Send + Sync, T: DeserializeOwned>(
@@ -52,28 +37,40 @@ impl dintero_checkout::HttpClient for CheckoutHttpAdapter {
         path: &str,
         body: &B,
     ) -> dintero_checkout::Result<T> {
-        let builder = self
-            .http
-            .put(path)
-            .await
-            .map_err(|e| dintero_checkout::CheckoutError::Client(e.to_string()))?;
+        let builder = self.http.put(path).await?;
 
-        self.http
-            .send_json(builder, body)
-            .await
-            .map_err(|e| dintero_checkout::CheckoutError::Client(e.to_string()))
+        self.http.send_json(builder, body).await
+    }
+
+    async fn patch_json<B: Serialize + Send + Sync, T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> dintero_checkout::Result<T> {
+        self.http.patch_json(path, body).await
     }
 
     async fn delete(&self, path: &str) -> dintero_checkout::Result<()> {
-        let builder = self
-            .http
-            .delete(path)
-            .await
-            .map_err(|e| dintero_checkout::CheckoutError::Client(e.to_string()))?;
+        let builder = self.http.delete(path).await?;
+
+        self.http.send_empty(builder).await
+    }
 
-        self.http
-            .send_empty(builder)
-            .await
-            .map_err(|e| dintero_checkout::CheckoutError::Client(e.to_string()))
+    async fn post_json_with_options<B: Serialize + Send + Sync, T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &B,
+        options: &RequestOptions,
+    ) -> dintero_checkout::Result<T> {
+        self.http.post_json_with_options(path, body, options).await
+    }
+
+    async fn post_json_idempotent<B: Serialize + Send + Sync, T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &B,
+        options: &RequestOptions,
+    ) -> dintero_checkout::Result<Idempotent<T>> {
+        self.http.post_json_idempotent(path, body, options).await
     }
 }
@@ -1,14 +1,44 @@
 //! Module implementation.
 
-use crate::DinteroClient;
+use crate::client::HttpClient;
 
 #[cfg(feature = "insights")]
-impl DinteroClient {
-    pub fn insights(&self) -> dintero_insights::InsightsClient {
-        dintero_insights::InsightsClient::new(
-            std::sync::Arc::new(self.http.clone_inner()),
-            self.http.base_url().to_string(),
-            self.http.account_id().to_string(),
-        )
+use async_trait::async_trait;
+#[cfg(feature = "insights")]
+use dintero_insights::{InsightsAdapter, Result as InsightsResult};
+
+#[cfg(feature = "insights")]
+#[async_trait]
+impl InsightsAdapter for HttpClient {
+    async fn get_json<T: serde::de::DeserializeOwned>(&self, path: &str) -> InsightsResult<T> {
+        self.get_json(path).await
+    }
+
+    async fn post_json<T: serde::de::DeserializeOwned, B: serde::Serialize + Send + Sync>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> InsightsResult<T> {
+        self.post_json(path, body).await
+    }
+
+    async fn put_json<T: serde::de::DeserializeOwned, B: serde::Serialize + Send + Sync>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> InsightsResult<T> {
+        self.put_json(path, body).await
+    }
+
+    async fn patch_json<T: serde::de::DeserializeOwned, B: serde::Serialize + Send + Sync>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> InsightsResult<T> {
+        self.patch_json(path, body).await
+    }
+
+    async fn delete(&self, path: &str) -> InsightsResult<()> {
+        self.delete_request(path).await
     }
 }
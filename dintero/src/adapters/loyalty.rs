@@ -1,54 +1,44 @@
 //! Loyalty API adapter.
 
-use crate::DinteroClient;
+use crate::client::HttpClient;
 
 #[cfg(feature = "loyalty")]
-pub struct LoyaltyAdapter {
-    client: dintero_loyalty::LoyaltyClient,
-}
-
+use async_trait::async_trait;
 #[cfg(feature = "loyalty")]
-impl LoyaltyAdapter {
-    pub(crate) fn new(dintero_client: &DinteroClient) -> Self {
-        let http_client = dintero_client.http_client();
-        let loyalty_client = dintero_loyalty::LoyaltyClient::new(
-            http_client.clone_inner(),
-            http_client.base_url().to_string(),
-            http_client.account_id().to_string(),
-        );
-
-        Self { client: loyalty_client }
-    }
-
-    pub fn customers(&self) -> &dintero_loyalty::LoyaltyClient {
-        &self.client
-    }
+use dintero_loyalty::{LoyaltyAdapter, Result as LoyaltyResult};
 
-    pub fn discounts(&self) -> &dintero_loyalty::LoyaltyClient {
-        &self.client
-    }
-
-    pub fn products(&self) -> &dintero_loyalty::LoyaltyClient {
-        &self.client
-    }
-
-    pub fn receipts(&self) -> &dintero_loyalty::LoyaltyClient {
-        &self.client
+#[cfg(feature = "loyalty")]
+#[async_trait]
+impl LoyaltyAdapter for HttpClient {
+    async fn get_json<T: serde::de::DeserializeOwned>(&self, path: &str) -> LoyaltyResult<T> {
+        self.get_json(path).await
     }
 
-    pub fn wallets(&self) -> &dintero_loyalty::LoyaltyClient {
-        &self.client
+    async fn post_json<T: serde::de::DeserializeOwned, B: serde::Serialize + Send + Sync>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> LoyaltyResult<T> {
+        self.post_json(path, body).await
     }
 
-    pub fn webhooks(&self) -> &dintero_loyalty::LoyaltyClient {
-        &self.client
+    async fn put_json<T: serde::de::DeserializeOwned, B: serde::Serialize + Send + Sync>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> LoyaltyResult<T> {
+        self.put_json(path, body).await
     }
 
-    pub fn locations(&self) -> &dintero_loyalty::LoyaltyClient {
-        &self.client
+    async fn patch_json<T: serde::de::DeserializeOwned, B: serde::Serialize + Send + Sync>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> LoyaltyResult<T> {
+        self.patch_json(path, body).await
     }
 
-    pub fn automations(&self) -> &dintero_loyalty::LoyaltyClient {
-        &self.client
+    async fn delete(&self, path: &str) -> LoyaltyResult<()> {
+        self.delete_request(path).await
     }
 }
@@ -18,8 +18,5 @@ pub mod accounts;
 #[cfg(feature = "loyalty")]
 pub mod loyalty;
 
-#[cfg(feature = "loyalty")]
-pub use loyalty::LoyaltyAdapter;
-
 #[cfg(feature = "insights")]
 pub mod insights;
@@ -5,13 +5,15 @@ use crate::client::HttpClient;
 #[cfg(feature = "orders")]
 use async_trait::async_trait;
 #[cfg(feature = "orders")]
+use dintero_core::{Idempotent, RequestOptions};
+#[cfg(feature = "orders")]
 use dintero_orders::{OrdersAdapter, Result as OrdersResult};
 
 #[cfg(feature = "orders")]
 #[async_trait]
 impl OrdersAdapter for HttpClient {
     async fn get_json<T: serde::de::DeserializeOwned>(&self, path: &str) -> OrdersResult<T> {
-        self.get_json(path).await.map_err(|e| dintero_orders::OrdersError::Client(e.to_string()))
+        self.get_json(path).await
     }
 
     async fn post_json<T: serde::de::DeserializeOwned, B: serde::Serialize + Send + Sync>(
@@ -19,9 +21,7 @@ impl OrdersAdapter for HttpClient {
         path: &str,
         body: &B,
     ) -> OrdersResult<T> {
-        self.post_json(path, body)
-            .await
-            .map_err(|e| dintero_orders::OrdersError::Client(e.to_string()))
+        self.post_json(path, body).await
     }
 
     async fn put_json<T: serde::de::DeserializeOwned, B: serde::Serialize + Send + Sync>(
@@ -29,14 +29,42 @@ impl OrdersAdapter for HttpClient {
         path: &str,
         body: &B,
     ) -> OrdersResult<T> {
-        self.put_json(path, body)
-            .await
-            .map_err(|e| dintero_orders::OrdersError::Client(e.to_string()))
+        self.put_json(path, body).await
+    }
+
+    async fn patch_json<T: serde::de::DeserializeOwned, B: serde::Serialize + Send + Sync>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> OrdersResult<T> {
+        self.patch_json(path, body).await
     }
 
     async fn delete(&self, path: &str) -> OrdersResult<()> {
-        self.delete_request(path)
-            .await
-            .map_err(|e| dintero_orders::OrdersError::Client(e.to_string()))
+        self.delete_request(path).await
+    }
+
+    async fn post_json_with_options<
+        T: serde::de::DeserializeOwned,
+        B: serde::Serialize + Send + Sync,
+    >(
+        &self,
+        path: &str,
+        body: &B,
+        options: &RequestOptions,
+    ) -> OrdersResult<T> {
+        self.post_json_with_options(path, body, options).await
+    }
+
+    async fn post_json_idempotent<
+        T: serde::de::DeserializeOwned,
+        B: serde::Serialize + Send + Sync,
+    >(
+        &self,
+        path: &str,
+        body: &B,
+        options: &RequestOptions,
+    ) -> OrdersResult<Idempotent<T>> {
+        self.post_json_idempotent(path, body, options).await
     }
 }
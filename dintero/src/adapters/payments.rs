@@ -5,15 +5,15 @@ use crate::client::HttpClient;
 #[cfg(feature = "payments")]
 use async_trait::async_trait;
 #[cfg(feature = "payments")]
+use dintero_core::{Idempotent, RequestOptions};
+#[cfg(feature = "payments")]
 use dintero_payments::{PaymentsAdapter, Result as PaymentsResult};
 
 #[cfg(feature = "payments")]
 #[async_trait]
 impl PaymentsAdapter for HttpClient {
     async fn get_json<T: serde::de::DeserializeOwned>(&self, path: &str) -> PaymentsResult<T> {
-        self.get_json(path)
-            .await
-            .map_err(|e| dintero_payments::PaymentsError::Client(e.to_string()))
+        self.get_json(path).await
     }
 
     async fn post_json<T: serde::de::DeserializeOwned, B: serde::Serialize + Send + Sync>(
@@ -21,9 +21,7 @@ impl PaymentsAdapter for HttpClient {
         path: &str,
         body: &B,
     ) -> PaymentsResult<T> {
-        self.post_json(path, body)
-            .await
-            .map_err(|e| dintero_payments::PaymentsError::Client(e.to_string()))
+        self.post_json(path, body).await
     }
 
     async fn put_json<T: serde::de::DeserializeOwned, B: serde::Serialize + Send + Sync>(
@@ -31,14 +29,42 @@ impl PaymentsAdapter for HttpClient {
         path: &str,
         body: &B,
     ) -> PaymentsResult<T> {
-        self.put_json(path, body)
-            .await
-            .map_err(|e| dintero_payments::PaymentsError::Client(e.to_string()))
+        self.put_json(path, body).await
+    }
+
+    async fn patch_json<T: serde::de::DeserializeOwned, B: serde::Serialize + Send + Sync>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> PaymentsResult<T> {
+        self.patch_json(path, body).await
     }
 
     async fn delete(&self, path: &str) -> PaymentsResult<()> {
-        self.delete_request(path)
-            .await
-            .map_err(|e| dintero_payments::PaymentsError::Client(e.to_string()))
+        self.delete_request(path).await
+    }
+
+    async fn post_json_with_options<
+        T: serde::de::DeserializeOwned,
+        B: serde::Serialize + Send + Sync,
+    >(
+        &self,
+        path: &str,
+        body: &B,
+        options: &RequestOptions,
+    ) -> PaymentsResult<T> {
+        self.post_json_with_options(path, body, options).await
+    }
+
+    async fn post_json_idempotent<
+        T: serde::de::DeserializeOwned,
+        B: serde::Serialize + Send + Sync,
+    >(
+        &self,
+        path: &str,
+        body: &B,
+        options: &RequestOptions,
+    ) -> PaymentsResult<Idempotent<T>> {
+        self.post_json_idempotent(path, body, options).await
     }
 }
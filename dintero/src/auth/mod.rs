@@ -0,0 +1,466 @@
+//! Authentication providers for the Dintero API.
+//!
+//! This module provides different authentication mechanisms including API keys,
+//! OAuth2, and JWT tokens.
+
+pub mod store;
+
+pub use store::{FileTokenStore, InMemoryTokenStore, StoredToken, TokenStore};
+
+use crate::client::HttpClient;
+use crate::config::{AuthConfig, Config, Environment};
+use crate::error::{Error, Result};
+use async_trait::async_trait;
+use dintero_core::ApiPath;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tracing::{debug, warn};
+
+/// Tokens are refreshed this long before they expire.
+const TOKEN_REFRESH_MARGIN: Duration = Duration::from_secs(60);
+
+/// Trait for authentication providers.
+#[async_trait]
+pub trait AuthProvider: Send + Sync {
+    /// Returns the authentication header value for HTTP requests.
+    async fn get_auth_header(&self) -> Result<String>;
+
+    /// Discards the credentials a request was rejected with and obtains new ones.
+    ///
+    /// `rejected` is the header value the request was sent with. If the provider has
+    /// already replaced those credentials, e.g. because a concurrent request was rejected
+    /// first, the new ones are kept instead of being fetched again.
+    ///
+    /// Returns `true` if the provider has fresh credentials, in which case a request
+    /// rejected with `401 Unauthorized` is worth retrying once.
+    async fn force_refresh(&self, rejected: &str) -> Result<bool> {
+        let _ = rejected;
+        Ok(false)
+    }
+}
+
+/// API key authentication provider.
+pub struct ApiKeyAuth {
+    api_key: String,
+}
+
+impl ApiKeyAuth {
+    /// Creates a new API key authentication provider.
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self { api_key: api_key.into() }
+    }
+}
+
+#[async_trait]
+impl AuthProvider for ApiKeyAuth {
+    async fn get_auth_header(&self) -> Result<String> {
+        Ok(format!("Token {}", self.api_key))
+    }
+}
+
+/// OAuth2 authentication provider.
+///
+/// Exchanges the client credentials for an access token at the account's
+/// `accounts/{aid}/auth/token` endpoint, caches the token and refreshes it shortly
+/// before it expires. Tokens are shared through a [`TokenStore`], so clients using the
+/// same credentials and store reuse a still-valid token instead of requesting a new one.
+pub struct OAuthAuth {
+    client_id: String,
+    client_secret: String,
+    token_url: String,
+    audience: String,
+    http: reqwest::Client,
+    store: Arc<dyn TokenStore>,
+    token: Arc<RwLock<Option<OAuthToken>>>,
+}
+
+#[derive(Clone)]
+struct OAuthToken {
+    access_token: String,
+    refresh_at: Instant,
+}
+
+impl OAuthToken {
+    fn new(access_token: String, expires_in: Duration) -> Self {
+        let margin = TOKEN_REFRESH_MARGIN.min(expires_in / 2);
+        Self {
+            access_token,
+            refresh_at: Instant::now() + expires_in - margin,
+        }
+    }
+
+    fn is_fresh(&self) -> bool {
+        Instant::now() < self.refresh_at
+    }
+}
+
+#[derive(Serialize)]
+struct TokenRequest<'a> {
+    grant_type: &'a str,
+    audience: &'a str,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: u64,
+}
+
+impl OAuthAuth {
+    /// Creates a new OAuth2 authentication provider for the given account.
+    ///
+    /// # Arguments
+    ///
+    /// * `client_id` - The OAuth client ID
+    /// * `client_secret` - The OAuth client secret
+    /// * `base_url` - The API base URL, e.g. `https://api.dintero.com`
+    /// * `account_id` - The Dintero account ID the token is issued for
+    pub fn new(
+        client_id: impl Into<String>,
+        client_secret: impl Into<String>,
+        base_url: &str,
+        account_id: &str,
+    ) -> Self {
+        let base_url = base_url.trim_end_matches('/');
+        let account = ApiPath::new().segment("accounts").segment(account_id);
+        let token_path = account.clone().segment("auth").segment("token");
+
+        Self {
+            client_id: client_id.into(),
+            client_secret: client_secret.into(),
+            token_url: format!("{}/v1/{}", base_url, token_path),
+            audience: format!("{}/v1/{}", base_url, account),
+            http: reqwest::Client::new(),
+            store: InMemoryTokenStore::shared(),
+            token: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// Sets the token store used to share tokens between clients.
+    pub fn with_token_store(mut self, store: Arc<dyn TokenStore>) -> Self {
+        self.store = store;
+        self
+    }
+
+    /// Sets the HTTP client used for the token exchange.
+    pub fn with_http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = http;
+        self
+    }
+
+    async fn refresh_token_if_needed(&self) -> Result<()> {
+        let token = self.token.read().await;
+
+        if token.as_ref().is_some_and(OAuthToken::is_fresh) {
+            return Ok(());
+        }
+
+        drop(token);
+
+        let mut token = self.token.write().await;
+
+        if token.as_ref().is_some_and(OAuthToken::is_fresh) {
+            return Ok(());
+        }
+
+        if let Some(stored) = self.load_stored_token().await {
+            *token = Some(stored);
+            return Ok(());
+        }
+
+        let new_token = self.fetch_token().await?;
+        *token = Some(new_token);
+        Ok(())
+    }
+
+    fn store_key(&self) -> String {
+        format!("{}#{}", self.token_url, self.client_id)
+    }
+
+    async fn load_stored_token(&self) -> Option<OAuthToken> {
+        match self.store.load(&self.store_key()).await {
+            // A token about to expire is not worth adopting, as it would be refreshed
+            // again right away.
+            Ok(Some(stored)) if stored.is_valid_for(TOKEN_REFRESH_MARGIN) => {
+                let remaining = stored.remaining();
+                Some(OAuthToken::new(stored.access_token, remaining))
+            }
+            Ok(_) => None,
+            Err(e) => {
+                warn!("Failed to load token from store: {}", e);
+                None
+            }
+        }
+    }
+
+    async fn fetch_token(&self) -> Result<OAuthToken> {
+        debug!("Requesting OAuth access token from {}", self.token_url);
+
+        let request = TokenRequest {
+            grant_type: "client_credentials",
+            audience: &self.audience,
+        };
+
+        let response = self
+            .http
+            .post(&self.token_url)
+            .basic_auth(&self.client_id, Some(&self.client_secret))
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| Error::Auth(format!("Token request failed: {}", e)))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(Error::Auth(format!(
+                "Token request rejected ({}): {}",
+                status, body
+            )));
+        }
+
+        let token: TokenResponse = response
+            .json()
+            .await
+            .map_err(|e| Error::Auth(format!("Invalid token response: {}", e)))?;
+
+        let expires_in = Duration::from_secs(token.expires_in);
+        let stored = StoredToken::new(token.access_token.clone(), expires_in);
+        if let Err(e) = self.store.save(&self.store_key(), &stored).await {
+            warn!("Failed to save token to store: {}", e);
+        }
+
+        Ok(OAuthToken::new(token.access_token, expires_in))
+    }
+}
+
+#[async_trait]
+impl AuthProvider for OAuthAuth {
+    async fn get_auth_header(&self) -> Result<String> {
+        self.refresh_token_if_needed().await?;
+
+        let token = self.token.read().await;
+        let access_token = token
+            .as_ref()
+            .ok_or_else(|| Error::Auth("No access token available".to_string()))?
+            .access_token
+            .clone();
+
+        Ok(format!("Bearer {}", access_token))
+    }
+
+    async fn force_refresh(&self, rejected: &str) -> Result<bool> {
+        let mut token = self.token.write().await;
+        if let Some(current) = token.as_ref() {
+            if current.is_fresh() && format!("Bearer {}", current.access_token) != rejected {
+                debug!("Access token already refreshed since the request was sent");
+                return Ok(true);
+            }
+        }
+        if let Err(e) = self.store.remove(&self.store_key()).await {
+            warn!("Failed to remove token from store: {}", e);
+        }
+        *token = Some(self.fetch_token().await?);
+        Ok(true)
+    }
+}
+
+/// JWT token authentication provider.
+pub struct JwtAuth {
+    token: String,
+}
+
+impl JwtAuth {
+    /// Creates a new JWT authentication provider.
+    pub fn new(token: impl Into<String>) -> Self {
+        Self { token: token.into() }
+    }
+}
+
+#[async_trait]
+impl AuthProvider for JwtAuth {
+    async fn get_auth_header(&self) -> Result<String> {
+        Ok(format!("Bearer {}", self.token))
+    }
+}
+
+/// Creates an authentication provider from authentication settings.
+///
+/// OAuth tokens are issued per account, so the environment and account ID locate the
+/// token endpoint. OAuth tokens are kept in the process-wide [`InMemoryTokenStore`]; use
+/// [`create_auth_provider_from_config`] to apply the configured token store and transport
+/// settings.
+pub fn create_auth_provider(
+    config: &AuthConfig,
+    environment: &Environment,
+    account_id: &str,
+) -> Arc<dyn AuthProvider> {
+    match config {
+        AuthConfig::ApiKey(key) => Arc::new(ApiKeyAuth::new(key.clone())),
+        AuthConfig::OAuth { client_id, client_secret } => Arc::new(OAuthAuth::new(
+            client_id.clone(),
+            client_secret.clone(),
+            environment.base_url(),
+            account_id,
+        )),
+        AuthConfig::Jwt(token) => Arc::new(JwtAuth::new(token.clone())),
+    }
+}
+
+/// Creates an authentication provider from configuration.
+///
+/// The OAuth token exchange uses an HTTP client built from the configuration's
+/// transport settings.
+///
+/// # Errors
+///
+/// Returns an error if the transport settings are invalid, e.g. an unparsable proxy URL.
+pub fn create_auth_provider_from_config(config: &Config) -> Result<Arc<dyn AuthProvider>> {
+    let http = HttpClient::build_transport(config)?;
+    Ok(create_auth_provider_with_client(config, http))
+}
+
+/// Creates an authentication provider that exchanges OAuth tokens through the given
+/// HTTP client.
+pub fn create_auth_provider_with_client(
+    config: &Config,
+    http: reqwest::Client,
+) -> Arc<dyn AuthProvider> {
+    match &config.auth {
+        AuthConfig::ApiKey(key) => Arc::new(ApiKeyAuth::new(key.clone())),
+        AuthConfig::OAuth { client_id, client_secret } => {
+            let mut auth = OAuthAuth::new(
+                client_id.clone(),
+                client_secret.clone(),
+                config.environment.base_url(),
+                &config.account_id,
+            )
+            .with_http_client(http);
+
+            if let Some(store) = &config.token_store {
+                auth = auth.with_token_store(Arc::clone(store));
+            }
+
+            Arc::new(auth)
+        }
+        AuthConfig::Jwt(token) => Arc::new(JwtAuth::new(token.clone())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    async fn token_server(expires_in: u64) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&hits);
+
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let n = counter.fetch_add(1, Ordering::SeqCst);
+                let mut buf = [0u8; 4096];
+                let _ = socket.read(&mut buf).await;
+                let body = format!(
+                    r#"{{"access_token":"token-{}","token_type":"Bearer","expires_in":{}}}"#,
+                    n, expires_in
+                );
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+
+        (format!("http://{}", addr), hits)
+    }
+
+    #[test]
+    fn test_oauth_urls_encode_the_account_id() {
+        let auth = OAuthAuth::new("client", "secret", "https://api.dintero.com/", "T1/../x?y");
+        assert_eq!(
+            auth.token_url,
+            "https://api.dintero.com/v1/accounts/T1%2F..%2Fx%3Fy/auth/token"
+        );
+        assert_eq!(
+            auth.audience,
+            "https://api.dintero.com/v1/accounts/T1%2F..%2Fx%3Fy"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_oauth_token_is_cached_until_refresh() {
+        let (base_url, hits) = token_server(3600).await;
+        let auth = OAuthAuth::new("client", "secret", &base_url, "T12345678")
+            .with_token_store(Arc::new(InMemoryTokenStore::new()));
+
+        assert_eq!(auth.get_auth_header().await.unwrap(), "Bearer token-0");
+        assert_eq!(auth.get_auth_header().await.unwrap(), "Bearer token-0");
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+        assert!(auth.force_refresh("Bearer token-0").await.unwrap());
+        assert_eq!(auth.get_auth_header().await.unwrap(), "Bearer token-1");
+
+        // A request rejected with the replaced token reuses the refreshed one.
+        assert!(auth.force_refresh("Bearer token-0").await.unwrap());
+        assert_eq!(auth.get_auth_header().await.unwrap(), "Bearer token-1");
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_oauth_token_refreshed_before_expiry() {
+        let (base_url, hits) = token_server(0).await;
+        let auth = OAuthAuth::new("client", "secret", &base_url, "T12345678")
+            .with_token_store(Arc::new(InMemoryTokenStore::new()));
+
+        auth.get_auth_header().await.unwrap();
+        auth.get_auth_header().await.unwrap();
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_oauth_token_shared_through_store() {
+        let (base_url, hits) = token_server(3600).await;
+        let store: Arc<dyn TokenStore> = Arc::new(InMemoryTokenStore::new());
+
+        let first = OAuthAuth::new("client", "secret", &base_url, "T12345678")
+            .with_token_store(Arc::clone(&store));
+        let second = OAuthAuth::new("client", "secret", &base_url, "T12345678")
+            .with_token_store(Arc::clone(&store));
+
+        assert_eq!(first.get_auth_header().await.unwrap(), "Bearer token-0");
+        assert_eq!(second.get_auth_header().await.unwrap(), "Bearer token-0");
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_oauth_ignores_stored_token_about_to_expire() {
+        let (base_url, hits) = token_server(3600).await;
+        let store: Arc<dyn TokenStore> = Arc::new(InMemoryTokenStore::new());
+        let config = AuthConfig::OAuth {
+            client_id: "client".to_string(),
+            client_secret: "secret".to_string(),
+        };
+        let auth =
+            create_auth_provider(&config, &Environment::Custom(base_url.clone()), "T12345678");
+
+        assert_eq!(auth.get_auth_header().await.unwrap(), "Bearer token-0");
+
+        let auth = OAuthAuth::new("client", "secret", &base_url, "T12345678")
+            .with_token_store(Arc::clone(&store));
+        let expiring = StoredToken::new("expiring", Duration::from_secs(30));
+        store.save(&auth.store_key(), &expiring).await.unwrap();
+
+        assert_eq!(auth.get_auth_header().await.unwrap(), "Bearer token-1");
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+    }
+}
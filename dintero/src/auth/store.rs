@@ -0,0 +1,278 @@
+//! Token stores for sharing access tokens between clients and processes.
+//!
+//! Refreshable authentication providers such as [`OAuthAuth`](super::OAuthAuth) keep the
+//! access token in a [`TokenStore`]. By default tokens are kept in a process-wide
+//! [`InMemoryTokenStore`], so every client for the same credentials reuses one token.
+//! A [`FileTokenStore`] lets separate processes share a still-valid token.
+
+use crate::error::{Error, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use fs2::FileExt;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::{Mutex, RwLock};
+use uuid::Uuid;
+
+/// An access token together with its absolute expiry time.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoredToken {
+    /// The bearer access token.
+    pub access_token: String,
+    /// When the token expires.
+    pub expires_at: DateTime<Utc>,
+}
+
+impl StoredToken {
+    /// Creates a token that expires `expires_in` from now.
+    pub fn new(access_token: impl Into<String>, expires_in: Duration) -> Self {
+        let expires_in = chrono::Duration::from_std(expires_in).unwrap_or(chrono::Duration::MAX);
+        Self {
+            access_token: access_token.into(),
+            expires_at: Utc::now() + expires_in,
+        }
+    }
+
+    /// Returns the time left until the token expires.
+    pub fn remaining(&self) -> Duration {
+        (self.expires_at - Utc::now()).to_std().unwrap_or(Duration::ZERO)
+    }
+
+    /// Returns `true` if the token is still valid for at least `margin`.
+    pub fn is_valid_for(&self, margin: Duration) -> bool {
+        self.remaining() > margin
+    }
+}
+
+/// Storage for access tokens issued to refreshable authentication providers.
+///
+/// Keys identify the credentials a token was issued for (token endpoint and client ID).
+#[async_trait]
+pub trait TokenStore: Send + Sync + std::fmt::Debug {
+    /// Loads the token stored under `key`, if any.
+    async fn load(&self, key: &str) -> Result<Option<StoredToken>>;
+
+    /// Stores `token` under `key`, replacing any previous token.
+    async fn save(&self, key: &str, token: &StoredToken) -> Result<()>;
+
+    /// Removes the token stored under `key`.
+    async fn remove(&self, key: &str) -> Result<()>;
+}
+
+/// In-memory token store.
+#[derive(Debug, Default)]
+pub struct InMemoryTokenStore {
+    tokens: RwLock<HashMap<String, StoredToken>>,
+}
+
+impl InMemoryTokenStore {
+    /// Creates a new, empty in-memory token store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the process-wide store used when no store is configured.
+    pub fn shared() -> Arc<InMemoryTokenStore> {
+        static SHARED: OnceLock<Arc<InMemoryTokenStore>> = OnceLock::new();
+        Arc::clone(SHARED.get_or_init(|| Arc::new(InMemoryTokenStore::new())))
+    }
+}
+
+#[async_trait]
+impl TokenStore for InMemoryTokenStore {
+    async fn load(&self, key: &str) -> Result<Option<StoredToken>> {
+        Ok(self.tokens.read().await.get(key).cloned())
+    }
+
+    async fn save(&self, key: &str, token: &StoredToken) -> Result<()> {
+        self.tokens.write().await.insert(key.to_string(), token.clone());
+        Ok(())
+    }
+
+    async fn remove(&self, key: &str) -> Result<()> {
+        self.tokens.write().await.remove(key);
+        Ok(())
+    }
+}
+
+/// File-backed token store.
+///
+/// Tokens are kept as JSON in a single file, which is replaced atomically on every write
+/// and readable only by the current user on Unix systems. Expired tokens are pruned
+/// whenever the file is written.
+///
+/// Writers take an advisory lock on a `.lock` file next to the token file, so processes
+/// sharing the file do not lose each other's tokens.
+#[derive(Debug)]
+pub struct FileTokenStore {
+    path: PathBuf,
+    write_lock: Mutex<()>,
+}
+
+impl FileTokenStore {
+    /// Creates a token store backed by the file at `path`.
+    ///
+    /// The file and its parent directory are created on first write.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    /// Returns the path of the backing file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    async fn read_all(&self) -> Result<HashMap<String, StoredToken>> {
+        match tokio::fs::read(&self.path).await {
+            Ok(bytes) if bytes.is_empty() => Ok(HashMap::new()),
+            Ok(bytes) => Ok(serde_json::from_slice(&bytes)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(HashMap::new()),
+            Err(e) => Err(store_error("read", &self.path, e)),
+        }
+    }
+
+    /// Returns the path of a file next to the token file, named after it with `suffix`.
+    fn sidecar(&self, suffix: &str) -> PathBuf {
+        let mut name = self.path.file_name().unwrap_or_default().to_os_string();
+        name.push(suffix);
+        self.path.with_file_name(name)
+    }
+
+    async fn create_parent(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| store_error("create directory for", &self.path, e))?;
+        }
+        Ok(())
+    }
+
+    /// Takes the lock that serializes writers across processes. It is released when the
+    /// returned file is dropped.
+    async fn lock(&self) -> Result<std::fs::File> {
+        self.create_parent().await?;
+        let lock_path = self.sidecar(".lock");
+
+        tokio::task::spawn_blocking(move || {
+            let file = std::fs::OpenOptions::new()
+                .write(true)
+                .create(true)
+                .truncate(false)
+                .open(&lock_path)
+                .map_err(|e| store_error("open lock of", &lock_path, e))?;
+            file.lock_exclusive().map_err(|e| store_error("lock", &lock_path, e))?;
+            Ok(file)
+        })
+        .await
+        .map_err(|e| Error::Auth(format!("Failed to lock token store: {}", e)))?
+    }
+
+    /// Replaces the token file. Callers hold the lock.
+    async fn write_all(&self, tokens: &HashMap<String, StoredToken>) -> Result<()> {
+        let tmp_path = self.sidecar(&format!(".{}.tmp", Uuid::new_v4()));
+        let contents = serde_json::to_vec(tokens)?;
+
+        // The temp file is created anew, readable only by the current user from the start.
+        let mut options = tokio::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        options.mode(0o600);
+        let mut file =
+            options.open(&tmp_path).await.map_err(|e| store_error("create", &tmp_path, e))?;
+        file.write_all(&contents).await.map_err(|e| store_error("write", &tmp_path, e))?;
+        file.sync_all().await.map_err(|e| store_error("write", &tmp_path, e))?;
+        drop(file);
+
+        if let Err(e) = tokio::fs::rename(&tmp_path, &self.path).await {
+            let _ = tokio::fs::remove_file(&tmp_path).await;
+            return Err(store_error("replace", &self.path, e));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl TokenStore for FileTokenStore {
+    async fn load(&self, key: &str) -> Result<Option<StoredToken>> {
+        Ok(self.read_all().await?.remove(key))
+    }
+
+    async fn save(&self, key: &str, token: &StoredToken) -> Result<()> {
+        let _guard = self.write_lock.lock().await;
+        let _lock = self.lock().await?;
+        let mut tokens = self.read_all().await?;
+        tokens.retain(|_, t| t.is_valid_for(Duration::ZERO));
+        tokens.insert(key.to_string(), token.clone());
+        self.write_all(&tokens).await
+    }
+
+    async fn remove(&self, key: &str) -> Result<()> {
+        let _guard = self.write_lock.lock().await;
+        let _lock = self.lock().await?;
+        let mut tokens = self.read_all().await?;
+        if tokens.remove(key).is_some() {
+            self.write_all(&tokens).await?;
+        }
+        Ok(())
+    }
+}
+
+fn store_error(action: &str, path: &Path, err: std::io::Error) -> Error {
+    Error::Auth(format!(
+        "Failed to {} token store {}: {}",
+        action,
+        path.display(),
+        err
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_file_token_store_round_trip() {
+        let path = std::env::temp_dir()
+            .join(format!("dintero-token-store-{}", uuid::Uuid::new_v4()))
+            .join("tokens.json");
+        let store = FileTokenStore::new(&path);
+        let token = StoredToken::new("abc", Duration::from_secs(600));
+
+        assert_eq!(store.load("key").await.unwrap(), None);
+
+        store.save("key", &token).await.unwrap();
+        let other = FileTokenStore::new(&path);
+        assert_eq!(other.load("key").await.unwrap(), Some(token.clone()));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        other.remove("key").await.unwrap();
+        assert_eq!(store.load("key").await.unwrap(), None);
+
+        // Writers through separate stores keep each other's tokens.
+        let (first, second) = tokio::join!(store.save("a", &token), other.save("b", &token));
+        first.unwrap();
+        second.unwrap();
+        assert!(store.load("a").await.unwrap().is_some());
+        assert!(store.load("b").await.unwrap().is_some());
+        let mut files: Vec<_> = std::fs::read_dir(path.parent().unwrap())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        files.sort();
+        assert_eq!(files, ["tokens.json", "tokens.json.lock"]);
+
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+}
@@ -0,0 +1,175 @@
+//! Blocking Accounts API operations.
+
+use super::Blocking;
+use crate::client::HttpClient;
+use crate::error::Result;
+use dintero_accounts::account::{AccountDetails, Asset, CreateAssetRequest, PricePackage};
+use dintero_accounts::auth::{
+    GenerateRecoveryCodesResponse, MfaChallengeRequest, MfaSetupRequest, MfaSetupResponse,
+    MfaVerifyRequest, OidcConfiguration, PasswordlessAuthRequest, PasswordlessVerifyRequest,
+    RecoveryCode, RevokeTokenRequest, TokenRequest, TokenResponse,
+};
+use dintero_accounts::clients::{
+    ApiCredential, ClientGrant, CreateApiCredentialRequest, CreateClientGrantRequest,
+    CreateOAuthClientRequest, OAuthClient, RotateClientSecretResponse, UpdateOAuthClientRequest,
+};
+use dintero_accounts::gateways::{
+    ApplePayConfiguration, BamboraConfiguration, CreateGatewayRequest, GatewayConnection,
+    KlarnaConfiguration, SwishConfiguration, UpdateGatewayRequest, VippsConfiguration,
+};
+use dintero_accounts::locations::{
+    CreateLocationRequest, CreateTerminalRequest, Location, Terminal, UpdateLocationRequest,
+    UpdateTerminalRequest,
+};
+use dintero_accounts::partners::{
+    AccountSignup, CreateAccountSignupRequest, CreateOnboardingLinkRequest, MerchantMatch,
+    OnboardingLink, SearchMerchantsRequest, TerminateMerchantRequest, VerifySignupRequest,
+};
+use dintero_accounts::types::{
+    Account, AccountList, Profile, ProfileList, Session, UpdateProfileRequest,
+};
+use dintero_accounts::users::{
+    CreateUserRequest, Permission, ResetPasswordRequest, Role, UpdatePasswordRequest,
+    UpdateUserRequest, User, UserActivity,
+};
+use dintero_accounts::AccountsClient;
+use dintero_accounts::{account, types};
+
+blocking_operations! {
+    AccountsClient<HttpClient>, AccountsClient;
+
+    fn get_account_details(&self) -> Result<AccountDetails>;
+    fn update_account_details(
+        &self,
+        request: &account::UpdateAccountRequest,
+    ) -> Result<AccountDetails>;
+    fn get_price_packages(&self) -> Result<Vec<PricePackage>>;
+    fn list_assets(&self) -> Result<Vec<Asset>>;
+    fn upload_asset(&self, request: &CreateAssetRequest) -> Result<Asset>;
+    fn delete_asset(&self, asset_id: &str) -> Result<()>;
+    fn create_token(&self, request: &TokenRequest) -> Result<TokenResponse>;
+    fn revoke_token(&self, request: &RevokeTokenRequest) -> Result<()>;
+    fn initiate_passwordless_auth(&self, request: &PasswordlessAuthRequest) -> Result<()>;
+    fn verify_passwordless_auth(
+        &self,
+        request: &PasswordlessVerifyRequest,
+    ) -> Result<TokenResponse>;
+    fn setup_mfa(&self, request: &MfaSetupRequest) -> Result<MfaSetupResponse>;
+    fn verify_mfa(&self, request: &MfaVerifyRequest) -> Result<()>;
+    fn disable_mfa(&self) -> Result<()>;
+    fn create_mfa_challenge(&self, request: &MfaChallengeRequest) -> Result<()>;
+    fn get_oidc_configuration(&self) -> Result<OidcConfiguration>;
+    fn generate_recovery_codes(&self) -> Result<GenerateRecoveryCodesResponse>;
+    fn list_recovery_codes(&self) -> Result<Vec<RecoveryCode>>;
+    fn get_account(&self, account_id: &str) -> Result<Account>;
+    fn list_accounts(&self, page_token: Option<&str>) -> Result<AccountList>;
+    fn update_account(
+        &self,
+        account_id: &str,
+        request: types::UpdateAccountRequest,
+    ) -> Result<Account>;
+    fn get_profile(&self, account_id: &str, profile_id: &str) -> Result<Profile>;
+    fn list_profiles(&self, account_id: &str, page_token: Option<&str>) -> Result<ProfileList>;
+    fn update_profile(
+        &self,
+        account_id: &str,
+        profile_id: &str,
+        request: UpdateProfileRequest,
+    ) -> Result<Profile>;
+    fn get_session(&self) -> Result<Session>;
+    fn list_oauth_clients(&self) -> Result<Vec<OAuthClient>>;
+    fn get_oauth_client(&self, client_id: &str) -> Result<OAuthClient>;
+    fn create_oauth_client(&self, request: &CreateOAuthClientRequest) -> Result<OAuthClient>;
+    fn update_oauth_client(
+        &self,
+        client_id: &str,
+        request: &UpdateOAuthClientRequest,
+    ) -> Result<OAuthClient>;
+    fn delete_oauth_client(&self, client_id: &str) -> Result<()>;
+    fn rotate_client_secret(&self, client_id: &str) -> Result<RotateClientSecretResponse>;
+    fn list_client_grants(&self) -> Result<Vec<ClientGrant>>;
+    fn create_client_grant(&self, request: &CreateClientGrantRequest) -> Result<ClientGrant>;
+    fn delete_client_grant(&self, grant_id: &str) -> Result<()>;
+    fn list_api_credentials(&self) -> Result<Vec<ApiCredential>>;
+    fn create_api_credential(&self, request: &CreateApiCredentialRequest) -> Result<ApiCredential>;
+    fn delete_api_credential(&self, credential_id: &str) -> Result<()>;
+    fn list_gateway_connections(&self) -> Result<Vec<GatewayConnection>>;
+    fn get_gateway_connection(&self, gateway_id: &str) -> Result<GatewayConnection>;
+    fn create_gateway_connection(
+        &self,
+        request: &CreateGatewayRequest,
+    ) -> Result<GatewayConnection>;
+    fn update_gateway_connection(
+        &self,
+        gateway_id: &str,
+        request: &UpdateGatewayRequest,
+    ) -> Result<GatewayConnection>;
+    fn delete_gateway_connection(&self, gateway_id: &str) -> Result<()>;
+    fn create_bambora_connection(
+        &self,
+        name: String,
+        config: BamboraConfiguration,
+    ) -> Result<GatewayConnection>;
+    fn create_vipps_connection(
+        &self,
+        name: String,
+        config: VippsConfiguration,
+    ) -> Result<GatewayConnection>;
+    fn create_klarna_connection(
+        &self,
+        name: String,
+        config: KlarnaConfiguration,
+    ) -> Result<GatewayConnection>;
+    fn create_swish_connection(
+        &self,
+        name: String,
+        config: SwishConfiguration,
+    ) -> Result<GatewayConnection>;
+    fn create_apple_pay_connection(
+        &self,
+        name: String,
+        config: ApplePayConfiguration,
+    ) -> Result<GatewayConnection>;
+    fn list_locations(&self) -> Result<Vec<Location>>;
+    fn get_location(&self, location_id: &str) -> Result<Location>;
+    fn create_location(&self, request: &CreateLocationRequest) -> Result<Location>;
+    fn update_location(
+        &self,
+        location_id: &str,
+        request: &UpdateLocationRequest,
+    ) -> Result<Location>;
+    fn delete_location(&self, location_id: &str) -> Result<()>;
+    fn list_terminals(&self, location_id: &str) -> Result<Vec<Terminal>>;
+    fn get_terminal(&self, location_id: &str, terminal_id: &str) -> Result<Terminal>;
+    fn create_terminal(
+        &self,
+        location_id: &str,
+        request: &CreateTerminalRequest,
+    ) -> Result<Terminal>;
+    fn update_terminal(
+        &self,
+        location_id: &str,
+        terminal_id: &str,
+        request: &UpdateTerminalRequest,
+    ) -> Result<Terminal>;
+    fn delete_terminal(&self, location_id: &str, terminal_id: &str) -> Result<()>;
+    fn create_onboarding_link(
+        &self,
+        request: &CreateOnboardingLinkRequest,
+    ) -> Result<OnboardingLink>;
+    fn get_onboarding_link(&self, link_id: &str) -> Result<OnboardingLink>;
+    fn create_account_signup(&self, request: &CreateAccountSignupRequest) -> Result<AccountSignup>;
+    fn verify_signup(&self, request: &VerifySignupRequest) -> Result<AccountSignup>;
+    fn search_merchants(&self, request: &SearchMerchantsRequest) -> Result<Vec<MerchantMatch>>;
+    fn terminate_merchant(&self, request: &TerminateMerchantRequest) -> Result<()>;
+    fn list_users(&self) -> Result<Vec<User>>;
+    fn get_user(&self, user_id: &str) -> Result<User>;
+    fn create_user(&self, request: &CreateUserRequest) -> Result<User>;
+    fn update_user(&self, user_id: &str, request: &UpdateUserRequest) -> Result<User>;
+    fn delete_user(&self, user_id: &str) -> Result<()>;
+    fn list_user_activities(&self, user_id: &str) -> Result<Vec<UserActivity>>;
+    fn list_permissions(&self) -> Result<Vec<Permission>>;
+    fn list_roles(&self) -> Result<Vec<Role>>;
+    fn update_password(&self, request: &UpdatePasswordRequest) -> Result<()>;
+    fn reset_password(&self, request: &ResetPasswordRequest) -> Result<()>;
+}
@@ -0,0 +1,77 @@
+//! Blocking Checkout API operations.
+
+use super::Blocking;
+use crate::adapters::CheckoutHttpAdapter;
+use crate::error::Result;
+use dintero_checkout::api_keys::{
+    ApiKey, CreateApiKeyRequest, CreateApiKeyResponse, RotateApiKeyResponse,
+};
+use dintero_checkout::card_tokens::{CardToken, CardTokenListResponse, ListCardTokensParams};
+use dintero_checkout::credit_checks::{CreditCheckRequest, CreditCheckResponse};
+use dintero_checkout::qr_codes::{QrCodeRequest, QrCodeResponse};
+use dintero_checkout::secrets::{CreateSignatureSecretRequest, SignatureSecret};
+use dintero_checkout::sessions::{
+    CheckoutSession, CreateProfileRequest, CreateSessionRequest, ListSessionsParams,
+    SessionListResponse, SessionProfile,
+};
+use dintero_checkout::transactions::{
+    CaptureRequest, ListTransactionsParams, RefundRequest, Transaction, TransactionListResponse,
+    UpdateTransactionRequest, VoidRequest,
+};
+use dintero_checkout::{CheckoutClient, CheckoutOperations};
+
+blocking_operations! {
+    CheckoutClient<CheckoutHttpAdapter>, CheckoutOperations;
+
+    fn create_session(&self, request: CreateSessionRequest) -> Result<CheckoutSession>;
+    fn get_session(&self, session_id: &str) -> Result<CheckoutSession>;
+    fn update_session(
+        &self,
+        session_id: &str,
+        request: CreateSessionRequest,
+    ) -> Result<CheckoutSession>;
+    fn list_sessions(&self, params: ListSessionsParams) -> Result<SessionListResponse>;
+    fn cancel_session(&self, session_id: &str) -> Result<CheckoutSession>;
+    fn create_profile(&self, request: CreateProfileRequest) -> Result<SessionProfile>;
+    fn get_profile(&self, profile_id: &str) -> Result<SessionProfile>;
+    fn update_profile(
+        &self,
+        profile_id: &str,
+        request: CreateProfileRequest,
+    ) -> Result<SessionProfile>;
+    fn delete_profile(&self, profile_id: &str) -> Result<()>;
+    fn list_profiles(&self) -> Result<Vec<SessionProfile>>;
+    fn get_transaction(&self, transaction_id: &str) -> Result<Transaction>;
+    fn list_transactions(&self, params: ListTransactionsParams) -> Result<TransactionListResponse>;
+    fn update_transaction(
+        &self,
+        transaction_id: &str,
+        request: UpdateTransactionRequest,
+    ) -> Result<Transaction>;
+    fn extend_authorization(&self, transaction_id: &str, days: u32) -> Result<Transaction>;
+    fn capture_transaction(
+        &self,
+        transaction_id: &str,
+        request: CaptureRequest,
+    ) -> Result<Transaction>;
+    fn refund_transaction(
+        &self,
+        transaction_id: &str,
+        request: RefundRequest,
+    ) -> Result<Transaction>;
+    fn void_transaction(&self, transaction_id: &str, request: VoidRequest) -> Result<Transaction>;
+    fn get_card_token(&self, token_id: &str) -> Result<CardToken>;
+    fn list_card_tokens(&self, params: ListCardTokensParams) -> Result<CardTokenListResponse>;
+    fn delete_card_token(&self, token_id: &str) -> Result<()>;
+    fn create_api_key(&self, request: CreateApiKeyRequest) -> Result<CreateApiKeyResponse>;
+    fn list_api_keys(&self) -> Result<Vec<ApiKey>>;
+    fn delete_api_key(&self, api_key_id: &str) -> Result<()>;
+    fn rotate_api_key(&self, api_key_id: &str) -> Result<RotateApiKeyResponse>;
+    fn create_signature_secret(
+        &self,
+        request: CreateSignatureSecretRequest,
+    ) -> Result<SignatureSecret>;
+    fn get_signature_secret(&self) -> Result<SignatureSecret>;
+    fn generate_qr_code(&self, request: QrCodeRequest) -> Result<QrCodeResponse>;
+    fn perform_credit_check(&self, request: CreditCheckRequest) -> Result<CreditCheckResponse>;
+}
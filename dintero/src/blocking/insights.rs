@@ -0,0 +1,74 @@
+//! Blocking Insights API operations.
+
+use super::Blocking;
+use crate::client::HttpClient;
+use crate::error::Result;
+use dintero_insights::kpis::KpisClient;
+use dintero_insights::report_configs::ReportConfigsClient;
+use dintero_insights::reports::ReportsClient;
+use dintero_insights::types::{
+    CheckoutTransactionStatusKpiResponse, CreateReportConfigurationRequest, KpiQueryParams,
+    PaymentMethodKpiResponse, ReportConfiguration, RevenueKpiResponse, TransactionKpiResponse,
+    UpdateReportConfigurationRequest,
+};
+use dintero_insights::InsightsClient;
+
+impl Blocking<InsightsClient<HttpClient>> {
+    /// Returns the KPI operations.
+    pub fn kpis(&self) -> Blocking<KpisClient<'_, HttpClient>> {
+        self.with(self.client.kpis())
+    }
+
+    /// Returns the report operations.
+    pub fn reports(&self) -> Blocking<ReportsClient<'_, HttpClient>> {
+        self.with(self.client.reports())
+    }
+
+    /// Returns the report configuration operations.
+    pub fn report_configs(&self) -> Blocking<ReportConfigsClient<'_, HttpClient>> {
+        self.with(self.client.report_configs())
+    }
+}
+
+blocking_operations! {
+    KpisClient<'_, HttpClient>, KpisClient;
+
+    fn get_checkout_transaction_status(
+        &self,
+        params: KpiQueryParams,
+    ) -> Result<CheckoutTransactionStatusKpiResponse>;
+    fn get_transactions(&self, params: KpiQueryParams) -> Result<TransactionKpiResponse>;
+    fn get_payment_methods(&self, params: KpiQueryParams) -> Result<PaymentMethodKpiResponse>;
+    fn get_revenue(&self, params: KpiQueryParams) -> Result<RevenueKpiResponse>;
+}
+
+blocking_operations! {
+    ReportConfigsClient<'_, HttpClient>, ReportConfigsClient;
+
+    fn create(&self, request: CreateReportConfigurationRequest) -> Result<ReportConfiguration>;
+    fn get(&self, config_id: &str) -> Result<ReportConfiguration>;
+    fn list(&self) -> Result<Vec<ReportConfiguration>>;
+    fn update(
+        &self,
+        config_id: &str,
+        request: UpdateReportConfigurationRequest,
+    ) -> Result<ReportConfiguration>;
+    fn delete(&self, config_id: &str) -> Result<()>;
+}
+
+blocking_operations! {
+    ReportsClient<'_, HttpClient>, ReportsClient;
+
+    fn list_configurations(&self) -> Result<Vec<ReportConfiguration>>;
+    fn get_configuration(&self, configuration_id: &str) -> Result<ReportConfiguration>;
+    fn create_configuration(
+        &self,
+        request: CreateReportConfigurationRequest,
+    ) -> Result<ReportConfiguration>;
+    fn update_configuration(
+        &self,
+        configuration_id: &str,
+        request: UpdateReportConfigurationRequest,
+    ) -> Result<ReportConfiguration>;
+    fn delete_configuration(&self, configuration_id: &str) -> Result<()>;
+}
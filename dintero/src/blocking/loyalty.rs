@@ -0,0 +1,173 @@
+//! Blocking Loyalty API operations.
+
+use super::Blocking;
+use crate::client::HttpClient;
+use crate::error::Result;
+use dintero_loyalty::automations::{
+    AutomationRule, CreateAutomationRuleRequest, ListAutomationRulesRequest,
+    UpdateAutomationRuleRequest,
+};
+use dintero_loyalty::customers::{
+    AcceptTermsRequest, CreateCustomerRequest, CreateCustomerTokenRequest, Customer,
+    CustomerSettings, CustomerTerms, CustomerToken, ListCustomersRequest, UpdateCustomerRequest,
+    UpdateCustomerSettingsRequest,
+};
+use dintero_loyalty::discounts::{
+    AssignDiscountRequest, CreateDiscountCampaignRequest, CreateDiscountRuleRequest,
+    CustomerDiscount, DiscountCampaign, DiscountRule, ListDiscountsRequest,
+    UpdateDiscountCampaignRequest, UpdateDiscountRuleRequest,
+};
+use dintero_loyalty::locations::{
+    CreateLocationRequest, ListLocationsRequest, Location, UpdateLocationRequest,
+};
+use dintero_loyalty::products::{
+    CreateProductCatalogRequest, CreateProductItemRequest, ListProductsRequest, ProductCatalog,
+    ProductItem, UpdateProductCatalogRequest, UpdateProductItemRequest,
+};
+use dintero_loyalty::receipts::{CreateReceiptRequest, ListReceiptsRequest, Receipt};
+use dintero_loyalty::types::PaginatedResponse;
+use dintero_loyalty::wallets::{
+    CardTransaction, CreateCardTransactionRequest, CreateVirtualCardRequest, ListCardsRequest,
+    ListTransactionsRequest, UpdateVirtualCardRequest, VirtualCard,
+};
+use dintero_loyalty::webhooks::{
+    CreateWebhookSubscriptionRequest, ListDeliveriesRequest, ListWebhooksRequest,
+    UpdateWebhookSubscriptionRequest, WebhookDelivery, WebhookSubscription,
+};
+use dintero_loyalty::LoyaltyClient;
+use uuid::Uuid;
+
+blocking_operations! {
+    LoyaltyClient<HttpClient>, LoyaltyClient;
+
+    fn create_automation_rule(&self, req: CreateAutomationRuleRequest) -> Result<AutomationRule>;
+    fn get_automation_rule(&self, rule_id: &Uuid) -> Result<AutomationRule>;
+    fn update_automation_rule(
+        &self,
+        rule_id: &Uuid,
+        req: UpdateAutomationRuleRequest,
+    ) -> Result<AutomationRule>;
+    fn delete_automation_rule(&self, rule_id: &Uuid) -> Result<()>;
+    fn list_automation_rules(
+        &self,
+        req: ListAutomationRulesRequest,
+    ) -> Result<PaginatedResponse<AutomationRule>>;
+    fn create_customer(&self, req: CreateCustomerRequest) -> Result<Customer>;
+    fn get_customer(&self, customer_id: &Uuid) -> Result<Customer>;
+    fn update_customer(&self, customer_id: &Uuid, req: UpdateCustomerRequest) -> Result<Customer>;
+    fn delete_customer(&self, customer_id: &Uuid) -> Result<()>;
+    fn list_customers(&self, req: ListCustomersRequest) -> Result<PaginatedResponse<Customer>>;
+    fn create_customer_token(
+        &self,
+        customer_id: &Uuid,
+        req: CreateCustomerTokenRequest,
+    ) -> Result<CustomerToken>;
+    fn accept_terms(&self, customer_id: &Uuid, req: AcceptTermsRequest) -> Result<CustomerTerms>;
+    fn get_customer_settings(&self, customer_id: &Uuid) -> Result<CustomerSettings>;
+    fn update_customer_settings(
+        &self,
+        customer_id: &Uuid,
+        req: UpdateCustomerSettingsRequest,
+    ) -> Result<CustomerSettings>;
+    fn create_discount_rule(&self, req: CreateDiscountRuleRequest) -> Result<DiscountRule>;
+    fn get_discount_rule(&self, rule_id: &Uuid) -> Result<DiscountRule>;
+    fn update_discount_rule(
+        &self,
+        rule_id: &Uuid,
+        req: UpdateDiscountRuleRequest,
+    ) -> Result<DiscountRule>;
+    fn delete_discount_rule(&self, rule_id: &Uuid) -> Result<()>;
+    fn list_discount_rules(
+        &self,
+        req: ListDiscountsRequest,
+    ) -> Result<PaginatedResponse<DiscountRule>>;
+    fn create_discount_campaign(
+        &self,
+        req: CreateDiscountCampaignRequest,
+    ) -> Result<DiscountCampaign>;
+    fn get_discount_campaign(&self, campaign_id: &Uuid) -> Result<DiscountCampaign>;
+    fn update_discount_campaign(
+        &self,
+        campaign_id: &Uuid,
+        req: UpdateDiscountCampaignRequest,
+    ) -> Result<DiscountCampaign>;
+    fn delete_discount_campaign(&self, campaign_id: &Uuid) -> Result<()>;
+    fn list_discount_campaigns(
+        &self,
+        req: ListDiscountsRequest,
+    ) -> Result<PaginatedResponse<DiscountCampaign>>;
+    fn assign_discount_to_customer(
+        &self,
+        customer_id: &Uuid,
+        req: AssignDiscountRequest,
+    ) -> Result<CustomerDiscount>;
+    fn list_customer_discounts(&self, customer_id: &Uuid) -> Result<Vec<CustomerDiscount>>;
+    fn create_location(&self, req: CreateLocationRequest) -> Result<Location>;
+    fn get_location(&self, location_id: &Uuid) -> Result<Location>;
+    fn update_location(&self, location_id: &Uuid, req: UpdateLocationRequest) -> Result<Location>;
+    fn delete_location(&self, location_id: &Uuid) -> Result<()>;
+    fn list_locations(&self, req: ListLocationsRequest) -> Result<PaginatedResponse<Location>>;
+    fn create_product_catalog(&self, req: CreateProductCatalogRequest) -> Result<ProductCatalog>;
+    fn get_product_catalog(&self, catalog_id: &Uuid) -> Result<ProductCatalog>;
+    fn update_product_catalog(
+        &self,
+        catalog_id: &Uuid,
+        req: UpdateProductCatalogRequest,
+    ) -> Result<ProductCatalog>;
+    fn delete_product_catalog(&self, catalog_id: &Uuid) -> Result<()>;
+    fn list_product_catalogs(
+        &self,
+        req: ListProductsRequest,
+    ) -> Result<PaginatedResponse<ProductCatalog>>;
+    fn create_product_item(&self, req: CreateProductItemRequest) -> Result<ProductItem>;
+    fn get_product_item(&self, item_id: &Uuid) -> Result<ProductItem>;
+    fn update_product_item(
+        &self,
+        item_id: &Uuid,
+        req: UpdateProductItemRequest,
+    ) -> Result<ProductItem>;
+    fn delete_product_item(&self, item_id: &Uuid) -> Result<()>;
+    fn list_product_items(
+        &self,
+        req: ListProductsRequest,
+    ) -> Result<PaginatedResponse<ProductItem>>;
+    fn create_receipt(&self, req: CreateReceiptRequest) -> Result<Receipt>;
+    fn get_receipt(&self, receipt_id: &Uuid) -> Result<Receipt>;
+    fn list_receipts(&self, req: ListReceiptsRequest) -> Result<Vec<Receipt>>;
+    fn create_virtual_card(&self, req: CreateVirtualCardRequest) -> Result<VirtualCard>;
+    fn get_virtual_card(&self, card_id: &Uuid) -> Result<VirtualCard>;
+    fn update_virtual_card(
+        &self,
+        card_id: &Uuid,
+        req: UpdateVirtualCardRequest,
+    ) -> Result<VirtualCard>;
+    fn delete_virtual_card(&self, card_id: &Uuid) -> Result<()>;
+    fn list_virtual_cards(&self, req: ListCardsRequest) -> Result<PaginatedResponse<VirtualCard>>;
+    fn create_card_transaction(&self, req: CreateCardTransactionRequest) -> Result<CardTransaction>;
+    fn get_card_transaction(&self, transaction_id: &Uuid) -> Result<CardTransaction>;
+    fn list_card_transactions(
+        &self,
+        req: ListTransactionsRequest,
+    ) -> Result<PaginatedResponse<CardTransaction>>;
+    fn create_webhook_subscription(
+        &self,
+        req: CreateWebhookSubscriptionRequest,
+    ) -> Result<WebhookSubscription>;
+    fn get_webhook_subscription(&self, subscription_id: &Uuid) -> Result<WebhookSubscription>;
+    fn update_webhook_subscription(
+        &self,
+        subscription_id: &Uuid,
+        req: UpdateWebhookSubscriptionRequest,
+    ) -> Result<WebhookSubscription>;
+    fn delete_webhook_subscription(&self, subscription_id: &Uuid) -> Result<()>;
+    fn list_webhook_subscriptions(
+        &self,
+        req: ListWebhooksRequest,
+    ) -> Result<PaginatedResponse<WebhookSubscription>>;
+    fn get_webhook_delivery(&self, delivery_id: &Uuid) -> Result<WebhookDelivery>;
+    fn list_webhook_deliveries(
+        &self,
+        req: ListDeliveriesRequest,
+    ) -> Result<PaginatedResponse<WebhookDelivery>>;
+    fn retry_webhook_delivery(&self, delivery_id: &Uuid) -> Result<WebhookDelivery>;
+}
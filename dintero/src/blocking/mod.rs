@@ -0,0 +1,355 @@
+//! Blocking client for code that does not run in an async runtime.
+//!
+//! [`DinteroClient`] mirrors [`crate::DinteroClient`] and owns a small Tokio runtime
+//! that drives its requests. Each sub-client is wrapped in [`Blocking`], which has a
+//! blocking method for every operation of the async sub-client, and whose
+//! [`iter`](Blocking::iter) turns a paginated stream into an iterator. The request,
+//! response and error types are those of the async API:
+//!
+//! ```no_run
+//! use dintero::blocking::DinteroClient;
+//! use dintero::checkout::transactions::CaptureRequest;
+//! use dintero::orders::ListOrdersParams;
+//!
+//! # fn example() -> dintero::Result<()> {
+//! let client = DinteroClient::from_env()?;
+//!
+//! let transaction =
+//!     client.checkout().capture_transaction("T12345678.abc", CaptureRequest::new(10000))?;
+//!
+//! let orders = client.orders();
+//! for order in orders.iter(|orders| orders.orders_stream(ListOrdersParams::default())) {
+//!     println!("{}", order?.id);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The blocking client must not be used, or dropped, from within an async runtime;
+//! async code should use [`crate::DinteroClient`] instead.
+//!
+//! Available when the `blocking` feature is enabled.
+
+use crate::client::HttpClient;
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::request::{ApiRequest, ApiResponse};
+use dintero_core::Paginator;
+use futures_core::Stream;
+use serde::de::DeserializeOwned;
+use std::fmt;
+use std::future::{poll_fn, Future};
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+
+/// Implements a blocking method on `Blocking<$client>` for each listed async operation of
+/// the sub-client, documented as the blocking version of `$docs::$name`.
+#[cfg(any(
+    feature = "checkout",
+    feature = "orders",
+    feature = "payments",
+    feature = "accounts",
+    feature = "loyalty",
+    feature = "insights"
+))]
+macro_rules! blocking_operations {
+    (
+        $client:ty, $docs:ident;
+        $(fn $name:ident(&self $(, $arg:ident: $ty:ty)* $(,)?) -> $ret:ty;)*
+    ) => {
+        impl Blocking<$client> {
+            $(
+                #[doc = concat!(
+                    "Blocking version of [`", stringify!($docs), "::", stringify!($name), "`]."
+                )]
+                pub fn $name(&self $(, $arg: $ty)*) -> $ret {
+                    self.runtime.block_on(self.client.$name($($arg),*))
+                }
+            )*
+        }
+    };
+}
+
+#[cfg(feature = "accounts")]
+mod accounts;
+#[cfg(feature = "checkout")]
+mod checkout;
+#[cfg(feature = "insights")]
+mod insights;
+#[cfg(feature = "loyalty")]
+mod loyalty;
+#[cfg(feature = "orders")]
+mod orders;
+#[cfg(feature = "payments")]
+mod payments;
+
+/// Blocking client for interacting with the Dintero API.
+///
+/// Clones share the same connection pool and runtime.
+#[derive(Clone)]
+pub struct DinteroClient {
+    inner: crate::DinteroClient,
+    runtime: Arc<Runtime>,
+}
+
+impl DinteroClient {
+    /// Creates a new blocking client with the given configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client or the runtime cannot be created.
+    pub fn new(config: Config) -> Result<Self> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("dintero-blocking")
+            .enable_all()
+            .build()
+            .map_err(|e| Error::Config(format!("Failed to create runtime: {}", e)))?;
+
+        let inner = {
+            let _guard = runtime.enter();
+            crate::DinteroClient::new(config)?
+        };
+
+        Ok(Self { inner, runtime: Arc::new(runtime) })
+    }
+
+    /// Creates a new blocking client from environment variables.
+    ///
+    /// See [`crate::DinteroClient::from_env`] for the supported settings.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if required settings are missing or invalid.
+    pub fn from_env() -> Result<Self> {
+        Self::new(Config::from_env()?)
+    }
+
+    /// Returns a reference to the underlying HTTP client.
+    pub fn http(&self) -> &Arc<HttpClient> {
+        self.inner.http()
+    }
+
+    /// Returns the async client sharing this client's connection pool.
+    pub fn async_client(&self) -> &crate::DinteroClient {
+        &self.inner
+    }
+
+    /// Sends a request to any endpoint, including ones without a typed client.
+    ///
+    /// See [`HttpClient::call`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request is invalid or fails, or the body does not
+    /// deserialize into `T`.
+    pub fn call<T: DeserializeOwned>(&self, request: ApiRequest) -> Result<ApiResponse<T>> {
+        self.runtime.block_on(self.inner.call(request))
+    }
+
+    /// Runs a future to completion on the client's runtime.
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+
+    #[cfg(any(
+        feature = "checkout",
+        feature = "orders",
+        feature = "payments",
+        feature = "accounts",
+        feature = "loyalty",
+        feature = "insights"
+    ))]
+    fn wrap<C>(&self, client: C) -> Blocking<C> {
+        Blocking {
+            client,
+            runtime: Arc::clone(&self.runtime),
+        }
+    }
+
+    /// Returns a checkout client for managing payment sessions.
+    ///
+    /// Available when the `checkout` feature is enabled.
+    #[cfg(feature = "checkout")]
+    pub fn checkout(
+        &self,
+    ) -> Blocking<crate::checkout::CheckoutClient<crate::adapters::CheckoutHttpAdapter>> {
+        self.wrap(self.inner.checkout())
+    }
+
+    /// Returns an orders client for managing orders.
+    ///
+    /// Available when the `orders` feature is enabled.
+    #[cfg(feature = "orders")]
+    pub fn orders(&self) -> Blocking<crate::orders::OrdersClient<HttpClient>> {
+        self.wrap(self.inner.orders())
+    }
+
+    /// Returns a payments client for managing payment operations.
+    ///
+    /// Available when the `payments` feature is enabled.
+    #[cfg(feature = "payments")]
+    pub fn payments(&self) -> Blocking<crate::payments::PaymentsClient<HttpClient>> {
+        self.wrap(self.inner.payments())
+    }
+
+    /// Returns an accounts client for account management.
+    ///
+    /// Available when the `accounts` feature is enabled.
+    #[cfg(feature = "accounts")]
+    pub fn accounts(&self) -> Blocking<crate::accounts::AccountsClient<HttpClient>> {
+        self.wrap(self.inner.accounts())
+    }
+
+    /// Returns a loyalty client for managing loyalty programs.
+    ///
+    /// Available when the `loyalty` feature is enabled.
+    #[cfg(feature = "loyalty")]
+    pub fn loyalty(&self) -> Blocking<crate::loyalty::LoyaltyClient<HttpClient>> {
+        self.wrap(self.inner.loyalty())
+    }
+
+    /// Returns an insights client for analytics and reporting.
+    ///
+    /// Available when the `insights` feature is enabled.
+    #[cfg(feature = "insights")]
+    pub fn insights(&self) -> Blocking<crate::insights::InsightsClient<HttpClient>> {
+        self.wrap(self.inner.insights())
+    }
+}
+
+impl fmt::Debug for DinteroClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DinteroClient")
+            .field("account_id", &self.inner.http().account_id())
+            .finish_non_exhaustive()
+    }
+}
+
+/// An async sub-client whose operations run to completion on the blocking client's
+/// runtime.
+pub struct Blocking<C> {
+    client: C,
+    runtime: Arc<Runtime>,
+}
+
+impl<C> Blocking<C> {
+    /// Runs an operation of the sub-client and waits for its result.
+    ///
+    /// Every operation has a blocking method of its own; this runs anything else the
+    /// async sub-client offers, e.g. an operation of an adapter-specific extension.
+    ///
+    /// ```no_run
+    /// # use dintero::checkout::CheckoutOperations;
+    /// # fn example(client: dintero::blocking::DinteroClient) -> dintero::Result<()> {
+    /// let session = client.checkout().call(|checkout| checkout.get_session("T12345678.abc"))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn call<'a, F, Fut>(&'a self, operation: F) -> Fut::Output
+    where
+        F: FnOnce(&'a C) -> Fut,
+        Fut: Future,
+    {
+        self.runtime.block_on(operation(&self.client))
+    }
+
+    /// Iterates over the items of a paginated stream of the sub-client, fetching
+    /// further pages as needed.
+    pub fn iter<'a, T, F>(&'a self, stream: F) -> Iter<'a, T>
+    where
+        F: FnOnce(&'a C) -> Paginator<'a, T>,
+    {
+        Iter {
+            paginator: stream(&self.client),
+            runtime: &self.runtime,
+        }
+    }
+
+    /// Returns the async sub-client.
+    pub fn get_ref(&self) -> &C {
+        &self.client
+    }
+
+    /// Wraps another sub-client running on the same runtime.
+    #[cfg(feature = "insights")]
+    fn with<D>(&self, client: D) -> Blocking<D> {
+        Blocking {
+            client,
+            runtime: Arc::clone(&self.runtime),
+        }
+    }
+}
+
+impl<C> fmt::Debug for Blocking<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Blocking").finish_non_exhaustive()
+    }
+}
+
+/// Blocking iterator over the items of a [`Paginator`].
+///
+/// Like the stream, the iterator yields an error after the items already received and
+/// then ends.
+pub struct Iter<'a, T> {
+    paginator: Paginator<'a, T>,
+    runtime: &'a Runtime,
+}
+
+impl<T> Iterator for Iter<'_, T> {
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let paginator = &mut self.paginator;
+        self.runtime.block_on(poll_fn(|cx| Pin::new(&mut *paginator).poll_next(cx)))
+    }
+}
+
+impl<T> fmt::Debug for Iter<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Iter").field("paginator", &self.paginator).finish_non_exhaustive()
+    }
+}
+
+#[cfg(all(test, feature = "checkout"))]
+mod tests {
+    use super::*;
+    use crate::checkout::sessions::{CreateSessionRequest, ListSessionsParams, Order};
+    use crate::checkout::transactions::{CaptureRequest, TransactionStatus};
+    use crate::checkout::{CheckoutOperations, Currency};
+    use dintero_mock::MockServer;
+
+    #[test]
+    fn test_blocking_checkout_flow() {
+        let server_runtime = Runtime::new().unwrap();
+        let server = server_runtime.block_on(MockServer::start());
+
+        let config =
+            Config::builder("T12345678").api_key("test").base_url(server.url()).build().unwrap();
+        let client = DinteroClient::new(config).unwrap();
+        let checkout = client.checkout();
+
+        for amount in [10000, 20000] {
+            let request = CreateSessionRequest::builder()
+                .return_url("https://example.com/return")
+                .order(Order::builder().amount(amount).currency(Currency::Nok).build())
+                .build()
+                .unwrap();
+            checkout.create_session(request).unwrap();
+        }
+
+        let sessions: Vec<_> = checkout
+            .iter(|checkout| checkout.sessions_stream(ListSessionsParams::default()).page_size(1))
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(sessions.len(), 2);
+
+        let transaction_id = server.authorize_session(&sessions[0].id).unwrap();
+        let transaction =
+            checkout.capture_transaction(&transaction_id, CaptureRequest::new(10000)).unwrap();
+        assert_eq!(transaction.status, TransactionStatus::Captured);
+        let session = checkout.call(|checkout| checkout.get_session(&sessions[0].id)).unwrap();
+        assert_eq!(session.id, sessions[0].id);
+    }
+}
@@ -0,0 +1,81 @@
+//! Blocking Orders API operations.
+
+use super::Blocking;
+use crate::client::HttpClient;
+use crate::error::Result;
+use dintero_orders::{
+    AddDraftOrderItemRequest, Authorization, AuthorizationListResponse, Cancellation,
+    CancellationListResponse, Capture, CaptureListResponse, Comment, CreateAuthorizationRequest,
+    CreateCancellationRequest, CreateCaptureRequest, CreateCommentRequest, CreateDraftOrderRequest,
+    CreateEventRequest, CreateOrderRequest, CreateOrderSessionRequest, CreateRefundRequest,
+    DraftOrder, DraftOrderItem, DraftOrderListResponse, EventListResponse, ListDraftOrdersParams,
+    ListOrdersParams, Order, OrderEvent, OrderListResponse, OrderSession, OrderSessionListResponse,
+    OrdersClient, OrdersOperations, Refund, RefundListResponse, UpdateDraftOrderItemRequest,
+};
+
+blocking_operations! {
+    OrdersClient<HttpClient>, OrdersOperations;
+
+    fn create_order(&self, request: CreateOrderRequest) -> Result<Order>;
+    fn get_order(&self, order_id: &str) -> Result<Order>;
+    fn update_order(&self, order_id: &str, request: CreateOrderRequest) -> Result<Order>;
+    fn list_orders(&self, params: ListOrdersParams) -> Result<OrderListResponse>;
+    fn close_order(&self, order_id: &str) -> Result<Order>;
+    fn open_order(&self, order_id: &str) -> Result<Order>;
+    fn create_draft_order(&self, request: CreateDraftOrderRequest) -> Result<DraftOrder>;
+    fn get_draft_order(&self, draft_id: &str) -> Result<DraftOrder>;
+    fn update_draft_order(
+        &self,
+        draft_id: &str,
+        request: CreateDraftOrderRequest,
+    ) -> Result<DraftOrder>;
+    fn list_draft_orders(&self, params: ListDraftOrdersParams) -> Result<DraftOrderListResponse>;
+    fn complete_draft_order(&self, draft_id: &str) -> Result<Order>;
+    fn add_draft_order_item(
+        &self,
+        draft_id: &str,
+        request: AddDraftOrderItemRequest,
+    ) -> Result<DraftOrderItem>;
+    fn update_draft_order_item(
+        &self,
+        draft_id: &str,
+        line_id: &str,
+        request: UpdateDraftOrderItemRequest,
+    ) -> Result<DraftOrderItem>;
+    fn delete_draft_order_item(&self, draft_id: &str, line_id: &str) -> Result<()>;
+    fn create_authorization(
+        &self,
+        order_id: &str,
+        request: CreateAuthorizationRequest,
+    ) -> Result<Authorization>;
+    fn get_authorization(&self, order_id: &str, auth_id: &str) -> Result<Authorization>;
+    fn list_authorizations(&self, order_id: &str) -> Result<AuthorizationListResponse>;
+    fn create_capture(&self, order_id: &str, request: CreateCaptureRequest) -> Result<Capture>;
+    fn get_capture(&self, order_id: &str, capture_id: &str) -> Result<Capture>;
+    fn list_captures(&self, order_id: &str) -> Result<CaptureListResponse>;
+    fn create_refund(&self, order_id: &str, request: CreateRefundRequest) -> Result<Refund>;
+    fn get_refund(&self, order_id: &str, refund_id: &str) -> Result<Refund>;
+    fn list_refunds(&self, order_id: &str) -> Result<RefundListResponse>;
+    fn create_cancellation(
+        &self,
+        order_id: &str,
+        request: CreateCancellationRequest,
+    ) -> Result<Cancellation>;
+    fn get_cancellation(&self, order_id: &str, cancellation_id: &str) -> Result<Cancellation>;
+    fn list_cancellations(&self, order_id: &str) -> Result<CancellationListResponse>;
+    fn create_comment(&self, order_id: &str, request: CreateCommentRequest) -> Result<Comment>;
+    fn delete_comment(&self, order_id: &str, comment_id: &str) -> Result<()>;
+    fn get_events(&self, order_id: &str) -> Result<EventListResponse>;
+    fn create_event(&self, order_id: &str, request: CreateEventRequest) -> Result<OrderEvent>;
+    fn create_order_session(
+        &self,
+        order_id: &str,
+        request: CreateOrderSessionRequest,
+    ) -> Result<OrderSession>;
+    fn get_order_session(&self, order_id: &str, session_id: &str) -> Result<OrderSession>;
+    fn list_order_sessions(&self, order_id: &str) -> Result<OrderSessionListResponse>;
+    fn list_customer_orders(&self, customer_id: &str) -> Result<OrderListResponse>;
+    fn get_customer_order(&self, customer_id: &str, order_id: &str) -> Result<Order>;
+    fn list_store_orders(&self, store_id: &str) -> Result<OrderListResponse>;
+    fn get_store_order(&self, store_id: &str, order_id: &str) -> Result<Order>;
+}
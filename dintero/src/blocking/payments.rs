@@ -0,0 +1,82 @@
+//! Blocking Payments API operations.
+
+use super::Blocking;
+use crate::client::HttpClient;
+use crate::error::Result;
+use dintero_payments::{
+    CaptureTransactionRequest, CreatePayoutDestinationRequest, CreatePayoutTransferRequest,
+    CreateSettlementReportConfigRequest, ExtendAuthorizationRequest, FundTransfer,
+    FundTransferRequest, ListPayoutTransfersParams, ListSellerTransfersParams,
+    ListSettlementsParams, ListTransactionsParams, PaymentsClient, PaymentsOperations,
+    PayoutBalance, PayoutDestination, PayoutDestinationListResponse, PayoutTransfer,
+    PayoutTransferListResponse, RefundTransactionRequest, SellerBalance, SellerTransfersResponse,
+    SettlementListResponse, SettlementReportConfig, Transaction, TransactionListResponse,
+    UpdateSettlementReportConfigRequest, UpdateTransactionRequest, VoidTransactionRequest,
+};
+
+blocking_operations! {
+    PaymentsClient<HttpClient>, PaymentsOperations;
+
+    fn get_transaction(&self, transaction_id: &str) -> Result<Transaction>;
+    fn list_transactions(&self, params: ListTransactionsParams) -> Result<TransactionListResponse>;
+    fn update_transaction(
+        &self,
+        transaction_id: &str,
+        request: UpdateTransactionRequest,
+    ) -> Result<Transaction>;
+    fn capture_transaction(
+        &self,
+        transaction_id: &str,
+        request: CaptureTransactionRequest,
+    ) -> Result<Transaction>;
+    fn refund_transaction(
+        &self,
+        transaction_id: &str,
+        request: RefundTransactionRequest,
+    ) -> Result<Transaction>;
+    fn void_transaction(
+        &self,
+        transaction_id: &str,
+        request: VoidTransactionRequest,
+    ) -> Result<Transaction>;
+    fn extend_authorization(
+        &self,
+        transaction_id: &str,
+        request: ExtendAuthorizationRequest,
+    ) -> Result<Transaction>;
+    fn list_settlements(&self, params: ListSettlementsParams) -> Result<SettlementListResponse>;
+    fn get_settlement_report_config(&self, config_id: &str) -> Result<SettlementReportConfig>;
+    fn list_settlement_report_configs(&self) -> Result<Vec<SettlementReportConfig>>;
+    fn create_settlement_report_config(
+        &self,
+        request: CreateSettlementReportConfigRequest,
+    ) -> Result<SettlementReportConfig>;
+    fn update_settlement_report_config(
+        &self,
+        config_id: &str,
+        request: UpdateSettlementReportConfigRequest,
+    ) -> Result<SettlementReportConfig>;
+    fn delete_settlement_report_config(&self, config_id: &str) -> Result<()>;
+    fn list_payout_destinations(&self) -> Result<PayoutDestinationListResponse>;
+    fn create_payout_destination(
+        &self,
+        request: CreatePayoutDestinationRequest,
+    ) -> Result<PayoutDestination>;
+    fn get_payout_balance(&self, destination_id: &str) -> Result<PayoutBalance>;
+    fn list_payout_transfers(
+        &self,
+        destination_id: &str,
+        params: ListPayoutTransfersParams,
+    ) -> Result<PayoutTransferListResponse>;
+    fn create_payout_transfer(
+        &self,
+        request: CreatePayoutTransferRequest,
+    ) -> Result<PayoutTransfer>;
+    fn initiate_fund_transfer(&self, request: FundTransferRequest) -> Result<FundTransfer>;
+    fn get_seller_balance(&self, destination_id: &str) -> Result<SellerBalance>;
+    fn list_seller_transfers(
+        &self,
+        destination_id: &str,
+        params: ListSellerTransfersParams,
+    ) -> Result<SellerTransfersResponse>;
+}
@@ -1,64 +1,147 @@
 //! HTTP client for making authenticated requests to the Dintero API.
 //!
-//! This module provides the core HTTP client that handles authentication,
-//! retries, and request/response processing.
+//! This module provides the core HTTP client. Authentication, retries and logging are
+//! handled by the [`middleware`](crate::middleware) chain each request passes through,
+//! and each call is traced as described in [`telemetry`](crate::telemetry).
 
 use crate::auth::AuthProvider;
 use crate::config::Config;
-use crate::error::{Error, Result};
-use reqwest::{header, Client, Method, RequestBuilder, Response, StatusCode};
+use crate::error::{ApiError, Error, Result};
+use crate::middleware::{
+    AuthMiddleware, Layer, LoggingMiddleware, Middleware, Next, RateLimitMiddleware,
+    RequestContext, RetryMiddleware,
+};
+use crate::rate_limit::RateLimiter;
+use crate::request::{ApiRequest, ApiResponse};
+use crate::telemetry::ApiCall;
+use dintero_core::path::with_query;
+use dintero_core::request::{IDEMPOTENCY_KEY_HEADER, IDEMPOTENT_REPLAYED_HEADER};
+use dintero_core::{Endpoint, Idempotent, PathTemplate, RequestOptions};
+use reqwest::{header, Client, Method, RequestBuilder, Response};
 use serde::{de::DeserializeOwned, Serialize};
 use std::sync::Arc;
 use std::time::Duration;
-use tracing::{debug, warn};
+use tracing::{info, Instrument};
+use uuid::Uuid;
 
 /// HTTP client for making authenticated requests to the Dintero API.
+///
+/// Requests pass through the middleware chain configured in [`Config::layers`] before
+/// they are sent.
+#[derive(Clone)]
 pub struct HttpClient {
     client: Client,
-    auth: Arc<dyn AuthProvider>,
-    pub(crate) base_url: String,
-    pub(crate) api_token: String,
+    base_url: String,
     account_id: String,
-    max_retries: u32,
-    initial_backoff_ms: u64,
-    max_backoff_ms: u64,
-    backoff_multiplier: f64,
+    middleware: Arc<[Arc<dyn Middleware>]>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    endpoints: Option<&'static [Endpoint]>,
 }
 
 impl HttpClient {
     pub fn new(config: &Config, auth: Arc<dyn AuthProvider>) -> Result<Self> {
-        let timeout = Duration::from_secs(config.timeout_secs);
+        let client = Self::build_transport(config)?;
+        Self::with_client(config, auth, client)
+    }
 
-        let client = Client::builder()
-            .timeout(timeout)
-            .connection_verbose(true)
-            .pool_max_idle_per_host(10)
-            .build()
-            .map_err(|e| Error::Config(format!("Failed to create HTTP client: {}", e)))?;
+    /// Builds the `reqwest::Client` described by the configuration's transport settings,
+    /// or returns the pre-built client if one is configured.
+    pub fn build_transport(config: &Config) -> Result<Client> {
+        let transport = &config.transport;
+
+        if let Some(client) = &transport.http_client {
+            return Ok(client.clone());
+        }
 
-        let api_token = match &config.auth {
-            crate::config::AuthConfig::ApiKey(key) => key.clone(),
-            crate::config::AuthConfig::Jwt(token) => token.clone(),
-            crate::config::AuthConfig::OAuth { client_id, .. } => client_id.clone(),
-        };
+        let mut builder = Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .connection_verbose(transport.connection_verbose)
+            .pool_max_idle_per_host(transport.pool_max_idle_per_host)
+            .pool_idle_timeout(transport.pool_idle_timeout);
+
+        if let Some(timeout) = transport.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(timeout) = transport.read_timeout {
+            builder = builder.read_timeout(timeout);
+        }
+        for proxy in &transport.proxies {
+            builder = builder.proxy(proxy.clone());
+        }
+        for certificate in &transport.root_certificates {
+            builder = builder.add_root_certificate(certificate.clone());
+        }
+        if let Some(identity) = &transport.identity {
+            builder = builder.identity(identity.clone());
+        }
+
+        builder.build().map_err(|e| Error::Config(format!("Failed to create HTTP client: {}", e)))
+    }
+
+    /// Creates a client sending requests through the given `reqwest::Client`.
+    pub fn with_client(
+        config: &Config,
+        auth: Arc<dyn AuthProvider>,
+        client: Client,
+    ) -> Result<Self> {
+        let middleware = config
+            .layers
+            .iter()
+            .filter_map(|layer| -> Option<Arc<dyn Middleware>> {
+                match layer {
+                    Layer::Logging => Some(Arc::new(LoggingMiddleware::new())),
+                    Layer::Retry => Some(Arc::new(RetryMiddleware::new(
+                        Arc::clone(&config.retry_policy),
+                        config.retry_budget.clone(),
+                    ))),
+                    Layer::RateLimit => config.rate_limiter.as_ref().map(|limiter| {
+                        Arc::new(RateLimitMiddleware::new(
+                            Arc::clone(limiter),
+                            config.account_id.clone(),
+                        )) as Arc<dyn Middleware>
+                    }),
+                    Layer::Auth => Some(Arc::new(AuthMiddleware::new(Arc::clone(&auth)))),
+                    Layer::Custom(middleware) => Some(Arc::clone(middleware)),
+                }
+            })
+            .collect();
 
         Ok(Self {
             client,
-            auth,
             base_url: config.environment.base_url().to_string(),
-            api_token,
             account_id: config.account_id.clone(),
-            max_retries: config.retry_config.max_retries,
-            initial_backoff_ms: config.retry_config.initial_backoff_ms,
-            max_backoff_ms: config.retry_config.max_backoff_ms,
-            backoff_multiplier: config.retry_config.backoff_multiplier,
+            middleware,
+            rate_limiter: config.rate_limiter.clone(),
+            endpoints: None,
         })
     }
 
+    /// Returns a client whose calls are named after `endpoints`, the endpoint table of
+    /// the API crate using it.
+    #[cfg(any(
+        feature = "checkout",
+        feature = "orders",
+        feature = "payments",
+        feature = "accounts",
+        feature = "loyalty",
+        feature = "insights"
+    ))]
+    pub(crate) fn for_api(&self, endpoints: &'static [Endpoint]) -> Self {
+        Self {
+            endpoints: Some(endpoints),
+            ..self.clone()
+        }
+    }
+
     pub fn account_id(&self) -> &str {
         &self.account_id
     }
 
+    /// Returns the client-side rate limiter, if one is configured.
+    pub fn rate_limiter(&self) -> Option<&Arc<RateLimiter>> {
+        self.rate_limiter.as_ref()
+    }
+
     pub fn base_url(&self) -> &str {
         &self.base_url
     }
@@ -72,16 +155,10 @@ impl HttpClient {
         format!("{}/v1/{}", self.base_url, path)
     }
 
-    async fn add_auth_header(&self, builder: RequestBuilder) -> Result<RequestBuilder> {
-        let auth_header = self.auth.get_auth_header().await?;
-        Ok(builder.header(header::AUTHORIZATION, auth_header))
-    }
-
     pub async fn request(&self, method: Method, path: &str) -> Result<RequestBuilder> {
         let url = self.build_url(path);
         let builder = self.client.request(method, url);
-        let builder = builder.header(header::CONTENT_TYPE, "application/json");
-        self.add_auth_header(builder).await
+        Ok(builder.header(header::CONTENT_TYPE, "application/json"))
     }
 
     pub async fn get(&self, path: &str) -> Result<RequestBuilder> {
@@ -104,97 +181,82 @@ impl HttpClient {
         self.request(Method::PATCH, path).await
     }
 
-    async fn execute_with_retry(&self, builder: RequestBuilder) -> Result<Response> {
-        let mut attempts = 0;
-        let mut backoff = self.initial_backoff_ms;
-
-        loop {
-            let request = builder.try_clone().ok_or_else(|| {
-                Error::Validation("Request could not be cloned for retry".to_string())
-            })?;
-
-            match request.send().await {
-                Ok(response) => {
-                    let status = response.status();
-
-                    if status.is_success() {
-                        debug!("Request succeeded with status: {}", status);
-                        return Ok(response);
-                    }
-
-                    if status == StatusCode::TOO_MANY_REQUESTS {
-                        let retry_after = response
-                            .headers()
-                            .get(header::RETRY_AFTER)
-                            .and_then(|v| v.to_str().ok())
-                            .and_then(|v| v.parse::<u64>().ok())
-                            .map(Duration::from_secs);
-
-                        if attempts >= self.max_retries {
-                            return Err(Error::RateLimited { retry_after });
-                        }
-
-                        let wait_time = retry_after.unwrap_or(Duration::from_millis(backoff));
-                        warn!("Rate limited, retrying after {:?}", wait_time);
-                        tokio::time::sleep(wait_time).await;
-                        attempts += 1;
-                        backoff = (backoff as f64 * self.backoff_multiplier)
-                            .min(self.max_backoff_ms as f64)
-                            as u64;
-                        continue;
-                    }
-
-                    if status.is_server_error() && attempts < self.max_retries {
-                        warn!("Server error ({}), retrying in {}ms", status, backoff);
-                        tokio::time::sleep(Duration::from_millis(backoff)).await;
-                        attempts += 1;
-                        backoff = (backoff as f64 * self.backoff_multiplier)
-                            .min(self.max_backoff_ms as f64)
-                            as u64;
-                        continue;
-                    }
-
-                    let error_body = response.text().await.unwrap_or_default();
-
-                    if let Ok(api_error) = serde_json::from_str::<ApiError>(&error_body) {
-                        return Err(Error::Api {
-                            code: api_error.error.code,
-                            message: api_error.error.message,
-                        });
-                    }
-
-                    return Err(Error::Api {
-                        code: status.as_str().to_string(),
-                        message: error_body,
-                    });
-                }
-                Err(e) if e.is_timeout() && attempts < self.max_retries => {
-                    warn!("Request timeout, retrying in {}ms", backoff);
-                    tokio::time::sleep(Duration::from_millis(backoff)).await;
-                    attempts += 1;
-                    backoff = (backoff as f64 * self.backoff_multiplier)
-                        .min(self.max_backoff_ms as f64) as u64;
-                    continue;
-                }
-                Err(e) => return Err(Error::Http(e)),
-            }
+    async fn execute(
+        &self,
+        builder: RequestBuilder,
+        options: &RequestOptions,
+        template: Option<&PathTemplate>,
+    ) -> Result<Response> {
+        let mut request = builder.build()?;
+        let mut ctx = RequestContext::new(options.clone());
+
+        let call = ApiCall::new(&request, self.endpoints, template, options.operation);
+        ctx.idempotent_endpoint = call.idempotent();
+        let span = call.span(&request, &self.account_id);
+        call.propagate(&span, request.headers_mut());
+
+        let result = Next::new(&self.client, &self.middleware)
+            .run(request, &mut ctx)
+            .instrument(span.clone())
+            .await;
+        call.finish(&span, &result, &ctx);
+        let response = result?;
+
+        if response.status().is_success() {
+            Ok(response)
+        } else {
+            Err(ApiError::from_response(response).await.into())
         }
     }
 
     pub async fn send<T: DeserializeOwned>(&self, builder: RequestBuilder) -> Result<T> {
-        let response = self.execute_with_retry(builder).await?;
+        let response = self.execute(builder, &RequestOptions::default(), None).await?;
+        Self::parse_body(response).await
+    }
+
+    async fn parse_body<T: DeserializeOwned>(response: Response) -> Result<T> {
         let body = response.bytes().await?;
 
         if body.is_empty() {
-            return Err(Error::Api {
-                code: "EMPTY_RESPONSE".to_string(),
-                message: "Expected JSON response but got empty body".to_string(),
-            });
+            return serde_json::from_slice(b"null").map_err(Error::from);
         }
 
         serde_json::from_slice(&body).map_err(Error::from)
     }
 
+    /// Sends a `POST` request with an idempotency key and reports whether the API
+    /// replayed an earlier result.
+    ///
+    /// Uses the key from `options`, or generates one. The key is sent in the
+    /// `Idempotency-Key` header and reused for every retry of the request. Only use this
+    /// for endpoints that deduplicate requests by their key.
+    pub async fn post_json_idempotent<T: DeserializeOwned, B: Serialize>(
+        &self,
+        path: &str,
+        body: &B,
+        options: &RequestOptions,
+    ) -> Result<Idempotent<T>> {
+        let idempotency_key =
+            options.idempotency_key.clone().unwrap_or_else(|| Uuid::new_v4().to_string());
+
+        let builder =
+            self.post(path).await?.header(IDEMPOTENCY_KEY_HEADER, &idempotency_key).json(body);
+
+        let response = self.execute(builder, options, None).await?;
+        let replayed = response
+            .headers()
+            .get(IDEMPOTENT_REPLAYED_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.eq_ignore_ascii_case("true"));
+
+        if replayed {
+            info!("Idempotent replay of request with key {}", idempotency_key);
+        }
+
+        let value = Self::parse_body(response).await?;
+        Ok(Idempotent { value, idempotency_key, replayed })
+    }
+
     pub async fn send_json<B: Serialize, T: DeserializeOwned>(
         &self,
         builder: RequestBuilder,
@@ -205,7 +267,7 @@ impl HttpClient {
     }
 
     pub async fn send_empty(&self, builder: RequestBuilder) -> Result<()> {
-        self.execute_with_retry(builder).await?;
+        self.execute(builder, &RequestOptions::default(), None).await?;
         Ok(())
     }
 
@@ -223,6 +285,20 @@ impl HttpClient {
         self.send_json(builder, body).await
     }
 
+    pub async fn post_json_with_options<T: DeserializeOwned, B: Serialize>(
+        &self,
+        path: &str,
+        body: &B,
+        options: &RequestOptions,
+    ) -> Result<T> {
+        let mut builder = self.post(path).await?;
+        if let Some(idempotency_key) = &options.idempotency_key {
+            builder = builder.header(IDEMPOTENCY_KEY_HEADER, idempotency_key);
+        }
+        let response = self.execute(builder.json(body), options, None).await?;
+        Self::parse_body(response).await
+    }
+
     pub async fn put_json<T: DeserializeOwned, B: Serialize>(
         &self,
         path: &str,
@@ -232,35 +308,194 @@ impl HttpClient {
         self.send_json(builder, body).await
     }
 
+    pub async fn patch_json<T: DeserializeOwned, B: Serialize>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<T> {
+        let builder = self.patch(path).await?;
+        self.send_json(builder, body).await
+    }
+
     pub async fn delete_request(&self, path: &str) -> Result<()> {
         let builder = self.delete(path).await?;
         self.send_empty(builder).await
     }
-}
 
-#[derive(serde::Deserialize)]
-struct ApiError {
-    error: ApiErrorDetail,
-}
+    /// Sends a `GET` request with query parameters serialized from `query`.
+    pub async fn get_json_with_query<T: DeserializeOwned, Q: Serialize + ?Sized>(
+        &self,
+        path: &str,
+        query: &Q,
+    ) -> Result<T> {
+        self.get_json(&with_query(path, query)?).await
+    }
+
+    /// Sends a request to any endpoint and returns the body with the response metadata.
+    ///
+    /// The request goes through the same authentication, retries and middleware as the
+    /// typed clients. An idempotency key set in [`ApiRequest::options`] is sent in the
+    /// `Idempotency-Key` header.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Validation`] if the path, query parameters, body or headers are
+    /// invalid, and the usual errors if the request fails or the body does not
+    /// deserialize into `T`.
+    pub async fn call<T: DeserializeOwned>(
+        &self,
+        mut request: ApiRequest,
+    ) -> Result<ApiResponse<T>> {
+        let target = request.target(&self.account_id)?;
+        let mut builder = self.request(request.method.clone(), &target).await?;
+
+        if let Some(idempotency_key) = &request.options.idempotency_key {
+            if !request.headers.contains_key(IDEMPOTENCY_KEY_HEADER) {
+                builder = builder.header(IDEMPOTENCY_KEY_HEADER, idempotency_key);
+            }
+        }
+        builder = builder.headers(request.headers);
+        if let Some(body) = &request.body {
+            builder = builder.json(body);
+        }
 
-#[derive(serde::Deserialize)]
-struct ApiErrorDetail {
-    code: String,
-    message: String,
+        let response = self.execute(builder, &request.options, Some(&request.path)).await?;
+        let status = response.status();
+        let headers = response.headers().clone();
+        let data = Self::parse_body(response).await?;
+        Ok(ApiResponse::new(data, status, headers))
+    }
 }
 
-impl Clone for HttpClient {
-    fn clone(&self) -> Self {
-        Self {
-            client: self.client.clone(),
-            auth: Arc::clone(&self.auth),
-            base_url: self.base_url.clone(),
-            api_token: self.api_token.clone(),
-            account_id: self.account_id.clone(),
-            max_retries: self.max_retries,
-            initial_backoff_ms: self.initial_backoff_ms,
-            max_backoff_ms: self.max_backoff_ms,
-            backoff_multiplier: self.backoff_multiplier,
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::ApiKeyAuth;
+    use std::sync::Mutex;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Serves the given responses in order and records the lowercased head of each request.
+    async fn scripted_server(responses: Vec<&'static str>) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&requests);
+
+        tokio::spawn(async move {
+            for response in responses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buf = [0u8; 8192];
+                let n = socket.read(&mut buf).await.unwrap();
+                seen.lock().unwrap().push(String::from_utf8_lossy(&buf[..n]).to_lowercase());
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+
+        (format!("http://{}", addr), requests)
+    }
+
+    fn header_values(requests: &Mutex<Vec<String>>, name: &str) -> Vec<String> {
+        let prefix = format!("{}: ", name);
+        requests
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|request| {
+                request.lines().find_map(|line| line.strip_prefix(&prefix)).unwrap_or_default()
+            })
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_retried_post_reuses_idempotency_key() {
+        let (base_url, requests) = scripted_server(vec![
+            "HTTP/1.1 503 Service Unavailable\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+            "HTTP/1.1 200 OK\r\nidempotent-replayed: true\r\ncontent-length: 11\r\nconnection: close\r\n\r\n{\"id\":\"t1\"}",
+        ])
+        .await;
+
+        let config =
+            Config::builder("T12345678").api_key("key").base_url(base_url).build().unwrap();
+        let client = HttpClient::new(&config, Arc::new(ApiKeyAuth::new("key"))).unwrap();
+
+        let options = RequestOptions::new().with_idempotency_key("capture-1");
+        let response: Idempotent<serde_json::Value> = client
+            .post_json_idempotent("accounts/T12345678/transactions/t1/capture", &(), &options)
+            .await
+            .unwrap();
+
+        assert!(response.replayed);
+        assert_eq!(response.value["id"], "t1");
+        assert_eq!(
+            header_values(&requests, "idempotency-key"),
+            vec!["capture-1", "capture-1"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_plain_post_is_sent_once_without_idempotency_key() {
+        let (base_url, requests) = scripted_server(vec![
+            "HTTP/1.1 503 Service Unavailable\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+            "HTTP/1.1 200 OK\r\ncontent-length: 2\r\nconnection: close\r\n\r\n{}",
+        ])
+        .await;
+
+        let config =
+            Config::builder("T12345678").api_key("key").base_url(base_url).build().unwrap();
+        let client = HttpClient::new(&config, Arc::new(ApiKeyAuth::new("key"))).unwrap();
+
+        let result: Result<serde_json::Value> =
+            client.post_json("accounts/T12345678/sessions", &()).await;
+
+        assert!(result.is_err());
+        assert_eq!(header_values(&requests, "idempotency-key"), vec![""]);
+    }
+
+    #[derive(Debug)]
+    struct AttemptHeader;
+
+    #[async_trait::async_trait]
+    impl Middleware for AttemptHeader {
+        async fn handle(
+            &self,
+            mut request: reqwest::Request,
+            ctx: &mut RequestContext,
+            next: Next<'_>,
+        ) -> Result<Response> {
+            let value = header::HeaderValue::from(ctx.attempt);
+            request.headers_mut().insert("x-attempt", value);
+            next.run(request, ctx).await
         }
     }
+
+    #[tokio::test]
+    async fn test_custom_middleware_runs_for_each_attempt() {
+        let (base_url, requests) = scripted_server(vec![
+            "HTTP/1.1 500 Internal Server Error\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+            "HTTP/1.1 200 OK\r\ncontent-length: 2\r\nconnection: close\r\n\r\n{}",
+        ])
+        .await;
+
+        let config = Config::builder("T12345678")
+            .api_key("key")
+            .base_url(base_url)
+            .retry_config(crate::config::RetryConfig {
+                initial_backoff_ms: 1,
+                ..Default::default()
+            })
+            .middleware(AttemptHeader)
+            .build()
+            .unwrap();
+        let client = HttpClient::new(&config, Arc::new(ApiKeyAuth::new("key"))).unwrap();
+
+        let _: serde_json::Value = client.get_json("accounts/T12345678/sessions").await.unwrap();
+
+        assert_eq!(header_values(&requests, "x-attempt"), vec!["0", "1"]);
+        assert_eq!(
+            header_values(&requests, "authorization"),
+            vec!["token key", "token key"]
+        );
+    }
 }
@@ -0,0 +1,533 @@
+//! Layered configuration loading.
+//!
+//! [`ConfigLoader`] builds a [`Config`] from, in increasing order of precedence:
+//!
+//! 1. the built-in defaults,
+//! 2. the top-level settings of an optional TOML or JSON config file,
+//! 3. the selected profile of that file, e.g. `[profiles.staging]`,
+//! 4. `DINTERO_*` environment variables.
+//!
+//! `environment` and `base_url` both select the API endpoint and are resolved together:
+//! a layer setting either one replaces both from the layers below. A single layer
+//! setting both is rejected.
+//!
+//! ```toml
+//! account_id = "T12345678"
+//! environment = "test"
+//! timeout_secs = 30
+//!
+//! [auth]
+//! type = "oauth"
+//! client_id = "client-id"
+//! client_secret = "client-secret"
+//!
+//! [retry]
+//! max_retries = 5
+//! jitter = "decorrelated"
+//!
+//! [profiles.production]
+//! account_id = "P12345678"
+//! environment = "production"
+//! ```
+//!
+//! The following environment variables are read:
+//!
+//! | Variable | Setting |
+//! |----------|---------|
+//! | `DINTERO_CONFIG_FILE` | Path of the config file |
+//! | `DINTERO_PROFILE` | Profile to select from the config file |
+//! | `DINTERO_ACCOUNT_ID` | Account ID |
+//! | `DINTERO_ENVIRONMENT` | `production`, `test` or a base URL |
+//! | `DINTERO_BASE_URL` | Custom base URL |
+//! | `DINTERO_TIMEOUT_SECS` | Request timeout in seconds |
+//! | `DINTERO_MAX_RETRIES` | Maximum number of retries |
+//! | `DINTERO_API_KEY` | API key authentication |
+//! | `DINTERO_CLIENT_ID`, `DINTERO_CLIENT_SECRET` | OAuth2 client credentials |
+//! | `DINTERO_JWT` | JWT token authentication |
+
+use super::{AuthConfig, Config, ConfigBuilder, Environment, Jitter, RetryConfig};
+use crate::error::{Error, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Loads a [`Config`] from a config file and environment variables.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigLoader {
+    file: Option<PathBuf>,
+    profile: Option<String>,
+    skip_env: bool,
+}
+
+/// Settings read from a config file or one of its profiles.
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FileSettings {
+    account_id: Option<String>,
+    environment: Option<String>,
+    base_url: Option<String>,
+    timeout_secs: Option<u64>,
+    auth: Option<FileAuth>,
+    retry: Option<FileRetry>,
+    #[serde(default)]
+    profiles: HashMap<String, FileSettings>,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum FileAuth {
+    ApiKey {
+        api_key: String,
+    },
+    #[serde(rename = "oauth")]
+    OAuth {
+        client_id: String,
+        client_secret: String,
+    },
+    Jwt {
+        token: String,
+    },
+}
+
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FileRetry {
+    max_retries: Option<u32>,
+    initial_backoff_ms: Option<u64>,
+    max_backoff_ms: Option<u64>,
+    backoff_multiplier: Option<f64>,
+    jitter: Option<FileJitter>,
+    max_elapsed_ms: Option<u64>,
+    retryable_statuses: Option<Vec<u16>>,
+}
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum FileJitter {
+    None,
+    Full,
+    Decorrelated,
+}
+
+impl From<FileJitter> for Jitter {
+    fn from(jitter: FileJitter) -> Self {
+        match jitter {
+            FileJitter::None => Jitter::None,
+            FileJitter::Full => Jitter::Full,
+            FileJitter::Decorrelated => Jitter::Decorrelated,
+        }
+    }
+}
+
+impl From<FileAuth> for AuthConfig {
+    fn from(auth: FileAuth) -> Self {
+        match auth {
+            FileAuth::ApiKey { api_key } => AuthConfig::ApiKey(api_key),
+            FileAuth::OAuth { client_id, client_secret } => {
+                AuthConfig::OAuth { client_id, client_secret }
+            }
+            FileAuth::Jwt { token } => AuthConfig::Jwt(token),
+        }
+    }
+}
+
+impl FileSettings {
+    /// Rejects a layer that sets both `environment` and `base_url`.
+    fn check_endpoint(&self, layer: &str) -> Result<()> {
+        if self.environment.is_some() && self.base_url.is_some() {
+            return Err(Error::Config(format!(
+                "{} sets both environment and base_url; set only one",
+                layer
+            )));
+        }
+        Ok(())
+    }
+
+    /// Overlays `other` on top of these settings.
+    fn merge(&mut self, other: FileSettings) {
+        if other.account_id.is_some() {
+            self.account_id = other.account_id;
+        }
+        // The environment and the base URL both pick the API endpoint, so a layer
+        // setting either replaces both.
+        if other.environment.is_some() || other.base_url.is_some() {
+            self.environment = other.environment;
+            self.base_url = other.base_url;
+        }
+        if other.timeout_secs.is_some() {
+            self.timeout_secs = other.timeout_secs;
+        }
+        if other.auth.is_some() {
+            self.auth = other.auth;
+        }
+        if let Some(retry) = other.retry {
+            let current = self.retry.get_or_insert_with(FileRetry::default);
+            current.max_retries = retry.max_retries.or(current.max_retries);
+            current.initial_backoff_ms = retry.initial_backoff_ms.or(current.initial_backoff_ms);
+            current.max_backoff_ms = retry.max_backoff_ms.or(current.max_backoff_ms);
+            current.backoff_multiplier = retry.backoff_multiplier.or(current.backoff_multiplier);
+            current.jitter = retry.jitter.or(current.jitter);
+            current.max_elapsed_ms = retry.max_elapsed_ms.or(current.max_elapsed_ms);
+            current.retryable_statuses =
+                retry.retryable_statuses.or(current.retryable_statuses.take());
+        }
+    }
+}
+
+impl FileRetry {
+    fn into_retry_config(self) -> Result<RetryConfig> {
+        let defaults = RetryConfig::default();
+        let config = RetryConfig {
+            max_retries: self.max_retries.unwrap_or(defaults.max_retries),
+            initial_backoff_ms: self.initial_backoff_ms.unwrap_or(defaults.initial_backoff_ms),
+            max_backoff_ms: self.max_backoff_ms.unwrap_or(defaults.max_backoff_ms),
+            backoff_multiplier: self.backoff_multiplier.unwrap_or(defaults.backoff_multiplier),
+            jitter: self.jitter.map_or(defaults.jitter, Jitter::from),
+            max_elapsed_ms: self.max_elapsed_ms.or(defaults.max_elapsed_ms),
+            retryable_statuses: self.retryable_statuses.unwrap_or(defaults.retryable_statuses),
+            ..defaults
+        };
+
+        if config.backoff_multiplier < 1.0 {
+            return Err(Error::Config(
+                "retry.backoff_multiplier must be at least 1.0".to_string(),
+            ));
+        }
+        if config.initial_backoff_ms > config.max_backoff_ms {
+            return Err(Error::Config(
+                "retry.initial_backoff_ms must not exceed retry.max_backoff_ms".to_string(),
+            ));
+        }
+
+        Ok(config)
+    }
+}
+
+fn parse_environment(value: &str) -> Result<Environment> {
+    match value.to_lowercase().as_str() {
+        "production" | "prod" => Ok(Environment::Production),
+        "test" | "sandbox" => Ok(Environment::Test),
+        url if url.starts_with("http://") || url.starts_with("https://") => {
+            Ok(Environment::Custom(value.to_string()))
+        }
+        _ => Err(Error::Config(format!(
+            "environment must be 'production', 'test' or a base URL, got '{}'",
+            value
+        ))),
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(name: &str, value: &str) -> Result<T> {
+    value.trim().parse().map_err(|_| {
+        Error::Config(format!(
+            "{} must be a non-negative integer, got '{}'",
+            name, value
+        ))
+    })
+}
+
+impl ConfigLoader {
+    /// Creates a loader reading only environment variables, unless `DINTERO_CONFIG_FILE`
+    /// points to a config file.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads settings from the given TOML or JSON file.
+    ///
+    /// The format is chosen by the file extension. The file must exist.
+    pub fn file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.file = Some(path.into());
+        self
+    }
+
+    /// Selects a profile from the config file.
+    pub fn profile(mut self, profile: impl Into<String>) -> Self {
+        self.profile = Some(profile.into());
+        self
+    }
+
+    /// Ignores `DINTERO_*` environment variables.
+    pub fn skip_env(mut self) -> Self {
+        self.skip_env = true;
+        self
+    }
+
+    /// Loads and validates the configuration.
+    pub fn load(&self) -> Result<Config> {
+        self.builder()?.build()
+    }
+
+    /// Loads the settings into a [`ConfigBuilder`] for further customization.
+    pub fn builder(&self) -> Result<ConfigBuilder> {
+        if self.skip_env {
+            self.builder_with_env(|_| None)
+        } else {
+            self.builder_with_env(|name| std::env::var(name).ok())
+        }
+    }
+
+    fn builder_with_env(&self, env: impl Fn(&str) -> Option<String>) -> Result<ConfigBuilder> {
+        let file = self.file.clone().or_else(|| env("DINTERO_CONFIG_FILE").map(PathBuf::from));
+        let profile = self.profile.clone().or_else(|| env("DINTERO_PROFILE"));
+
+        let mut settings = match &file {
+            Some(path) => read_file(path)?,
+            None => FileSettings::default(),
+        };
+        settings.check_endpoint("config file")?;
+
+        if let Some(name) = &profile {
+            let selected = settings.profiles.remove(name).ok_or_else(|| {
+                Error::Config(format!("profile '{}' not found in config file", name))
+            })?;
+            if !selected.profiles.is_empty() {
+                return Err(Error::Config(format!(
+                    "profile '{}' cannot contain profiles",
+                    name
+                )));
+            }
+            selected.check_endpoint(&format!("profile '{}'", name))?;
+            settings.merge(selected);
+        }
+
+        settings.merge(env_settings(&env)?);
+
+        let account_id = settings.account_id.ok_or_else(|| {
+            Error::Config("account_id is required (set DINTERO_ACCOUNT_ID)".to_string())
+        })?;
+        let auth = settings.auth.ok_or_else(|| {
+            Error::Config(
+                "auth is required (set DINTERO_API_KEY, DINTERO_CLIENT_ID and \
+                 DINTERO_CLIENT_SECRET, or DINTERO_JWT)"
+                    .to_string(),
+            )
+        })?;
+
+        let mut builder = Config::builder(account_id).auth(auth.into());
+
+        if let Some(environment) = &settings.environment {
+            builder = builder.environment(parse_environment(environment)?);
+        }
+        if let Some(base_url) = settings.base_url {
+            builder = builder.base_url(base_url);
+        }
+        if let Some(timeout_secs) = settings.timeout_secs {
+            builder = builder.timeout_secs(timeout_secs);
+        }
+        if let Some(retry) = settings.retry {
+            builder = builder.retry_config(retry.into_retry_config()?);
+        }
+
+        Ok(builder)
+    }
+}
+
+fn read_file(path: &Path) -> Result<FileSettings> {
+    let contents = std::fs::read_to_string(path).map_err(|e| {
+        Error::Config(format!(
+            "failed to read config file {}: {}",
+            path.display(),
+            e
+        ))
+    })?;
+    let invalid = |e: &dyn std::fmt::Display| {
+        Error::Config(format!("invalid config file {}: {}", path.display(), e))
+    };
+
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("toml") => toml::from_str(&contents).map_err(|e| invalid(&e)),
+        Some("json") => serde_json::from_str(&contents).map_err(|e| invalid(&e)),
+        _ => Err(Error::Config(format!(
+            "unsupported config file format {} (expected .toml or .json)",
+            path.display()
+        ))),
+    }
+}
+
+fn env_settings(env: &impl Fn(&str) -> Option<String>) -> Result<FileSettings> {
+    let var = |name: &str| env(name).filter(|value| !value.is_empty());
+
+    let auth = match (
+        var("DINTERO_API_KEY"),
+        var("DINTERO_CLIENT_ID"),
+        var("DINTERO_CLIENT_SECRET"),
+        var("DINTERO_JWT"),
+    ) {
+        (None, None, None, None) => None,
+        (Some(api_key), None, None, None) => Some(FileAuth::ApiKey { api_key }),
+        (None, Some(client_id), Some(client_secret), None) => {
+            Some(FileAuth::OAuth { client_id, client_secret })
+        }
+        (None, None, None, Some(token)) => Some(FileAuth::Jwt { token }),
+        (None, Some(_), None, None) | (None, None, Some(_), None) => {
+            return Err(Error::Config(
+                "DINTERO_CLIENT_ID and DINTERO_CLIENT_SECRET must be set together".to_string(),
+            ))
+        }
+        _ => {
+            return Err(Error::Config(
+                "only one of DINTERO_API_KEY, DINTERO_CLIENT_ID/DINTERO_CLIENT_SECRET and \
+                 DINTERO_JWT may be set"
+                    .to_string(),
+            ))
+        }
+    };
+
+    let environment = var("DINTERO_ENVIRONMENT");
+    let base_url = var("DINTERO_BASE_URL");
+    if environment.is_some() && base_url.is_some() {
+        return Err(Error::Config(
+            "only one of DINTERO_ENVIRONMENT and DINTERO_BASE_URL may be set".to_string(),
+        ));
+    }
+
+    let retry = match var("DINTERO_MAX_RETRIES") {
+        Some(value) => Some(FileRetry {
+            max_retries: Some(parse_number("DINTERO_MAX_RETRIES", &value)?),
+            ..FileRetry::default()
+        }),
+        None => None,
+    };
+
+    Ok(FileSettings {
+        account_id: var("DINTERO_ACCOUNT_ID"),
+        environment,
+        base_url,
+        timeout_secs: var("DINTERO_TIMEOUT_SECS")
+            .map(|value| parse_number("DINTERO_TIMEOUT_SECS", &value))
+            .transpose()?,
+        auth,
+        retry,
+        profiles: HashMap::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+account_id = "T12345678"
+timeout_secs = 10
+
+[auth]
+type = "api_key"
+api_key = "test-key"
+
+[retry]
+max_retries = 5
+
+[profiles.production]
+account_id = "P12345678"
+environment = "production"
+
+[profiles.production.auth]
+type = "oauth"
+client_id = "client"
+client_secret = "secret"
+"#;
+
+    fn write_config(name: &str, contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("dintero-{}-{}", std::process::id(), name));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_profile_and_env_layering() {
+        let path = write_config("layering.toml", CONFIG);
+        let env: HashMap<&str, &str> =
+            [("DINTERO_PROFILE", "production"), ("DINTERO_TIMEOUT_SECS", "45")].into();
+
+        let config = ConfigLoader::new()
+            .file(&path)
+            .builder_with_env(|name| env.get(name).map(|v| v.to_string()))
+            .unwrap()
+            .build()
+            .unwrap();
+
+        assert_eq!(config.account_id, "P12345678");
+        assert_eq!(config.environment, Environment::Production);
+        assert_eq!(config.timeout_secs, 45);
+        assert!(matches!(config.auth, AuthConfig::OAuth { .. }));
+        assert!(!format!("{:?}", config).contains("\"secret\""));
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_env_environment_overrides_file_base_url() {
+        let path = write_config(
+            "endpoint.toml",
+            "account_id = \"T12345678\"\nbase_url = \"https://proxy.example.com\"\n\n\
+             [auth]\ntype = \"api_key\"\napi_key = \"key\"\n",
+        );
+        let load = |env: HashMap<&str, &str>| {
+            ConfigLoader::new()
+                .file(&path)
+                .builder_with_env(|name| env.get(name).map(|v| v.to_string()))
+                .unwrap()
+                .build()
+                .unwrap()
+        };
+
+        let config = load(HashMap::new());
+        assert_eq!(
+            config.environment,
+            Environment::Custom("https://proxy.example.com".to_string())
+        );
+
+        let config = load([("DINTERO_ENVIRONMENT", "production")].into());
+        assert_eq!(config.environment, Environment::Production);
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_invalid_settings_are_reported() {
+        let path = write_config("invalid.json", r#"{"account_id": "T1", "timout_secs": 5}"#);
+        let err = ConfigLoader::new().file(&path).skip_env().load().unwrap_err();
+        assert!(err.to_string().contains("timout_secs"));
+        std::fs::remove_file(path).unwrap();
+
+        let env: HashMap<&str, &str> = [
+            ("DINTERO_ACCOUNT_ID", "T12345678"),
+            ("DINTERO_API_KEY", "key"),
+            ("DINTERO_JWT", "token"),
+        ]
+        .into();
+        let err = ConfigLoader::new()
+            .builder_with_env(|name| env.get(name).map(|v| v.to_string()))
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("only one of"));
+    }
+
+    #[test]
+    fn test_layer_setting_environment_and_base_url_is_rejected() {
+        let path = write_config(
+            "both.toml",
+            "account_id = \"T12345678\"\n\n\
+             [auth]\ntype = \"api_key\"\napi_key = \"key\"\n\n\
+             [profiles.proxy]\nenvironment = \"test\"\nbase_url = \"https://proxy.example.com\"\n",
+        );
+        let err = ConfigLoader::new().file(&path).profile("proxy").skip_env().load().unwrap_err();
+        assert!(matches!(err, Error::Config(_)));
+        assert!(err.to_string().contains("profile 'proxy' sets both"));
+        std::fs::remove_file(path).unwrap();
+
+        let env: HashMap<&str, &str> = [
+            ("DINTERO_ACCOUNT_ID", "T12345678"),
+            ("DINTERO_API_KEY", "key"),
+            ("DINTERO_ENVIRONMENT", "test"),
+            ("DINTERO_BASE_URL", "https://proxy.example.com"),
+        ]
+        .into();
+        let err = ConfigLoader::new()
+            .builder_with_env(|name| env.get(name).map(|v| v.to_string()))
+            .err()
+            .unwrap();
+        assert!(matches!(err, Error::Config(_)));
+    }
+}
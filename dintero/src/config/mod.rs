@@ -0,0 +1,445 @@
+//! Configuration types for the Dintero SDK.
+
+mod loader;
+
+pub use loader::ConfigLoader;
+
+use crate::auth::TokenStore;
+use crate::error::{Error, Result};
+use crate::middleware::{Layer, Middleware};
+use crate::rate_limit::RateLimiter;
+use dintero_core::{RetryBudget, RetryPolicy};
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+pub use dintero_core::{Jitter, RetryConfig};
+pub use reqwest::{Certificate, Identity, Proxy};
+
+/// Dintero API environment.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Environment {
+    /// Production environment.
+    Production,
+    /// Test/Sandbox environment.
+    #[default]
+    Test,
+    /// A custom base URL, e.g. a regional endpoint or a local stand-in server.
+    Custom(String),
+}
+
+impl Environment {
+    /// Returns the base URL for this environment.
+    pub fn base_url(&self) -> &str {
+        match self {
+            Environment::Production => "https://api.dintero.com",
+            Environment::Test => "https://api.test.dintero.com",
+            Environment::Custom(url) => url.trim_end_matches('/'),
+        }
+    }
+}
+
+/// Authentication configuration.
+///
+/// Secrets are redacted in the `Debug` output.
+#[derive(Clone)]
+pub enum AuthConfig {
+    /// API key authentication.
+    ApiKey(String),
+    /// OAuth2 client credentials.
+    OAuth {
+        /// OAuth client ID.
+        client_id: String,
+        /// OAuth client secret.
+        client_secret: String,
+    },
+    /// JWT token authentication.
+    Jwt(String),
+}
+
+const REDACTED: &str = "<redacted>";
+
+impl fmt::Debug for AuthConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuthConfig::ApiKey(_) => f.debug_tuple("ApiKey").field(&REDACTED).finish(),
+            AuthConfig::OAuth { client_id, .. } => f
+                .debug_struct("OAuth")
+                .field("client_id", client_id)
+                .field("client_secret", &REDACTED)
+                .finish(),
+            AuthConfig::Jwt(_) => f.debug_tuple("Jwt").field(&REDACTED).finish(),
+        }
+    }
+}
+
+impl AuthConfig {
+    fn validate(&self) -> Result<()> {
+        let empty = match self {
+            AuthConfig::ApiKey(key) => key.is_empty().then_some("api key"),
+            AuthConfig::OAuth { client_id, client_secret } => {
+                if client_id.is_empty() {
+                    Some("OAuth client_id")
+                } else if client_secret.is_empty() {
+                    Some("OAuth client_secret")
+                } else {
+                    None
+                }
+            }
+            AuthConfig::Jwt(token) => token.is_empty().then_some("JWT token"),
+        };
+
+        match empty {
+            Some(name) => Err(Error::Config(format!("{} cannot be empty", name))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// HTTP transport settings.
+///
+/// These settings are used to build the `reqwest::Client` shared by API requests and
+/// the OAuth token exchange. When `http_client` is set, that client is used as is and
+/// all other transport settings are ignored.
+///
+/// The `Debug` output shows the number of proxies and certificates, not their contents,
+/// so proxy credentials are never printed.
+#[derive(Clone)]
+pub struct TransportConfig {
+    /// Proxies requests are routed through.
+    pub proxies: Vec<Proxy>,
+    /// Additional trusted root certificates.
+    pub root_certificates: Vec<Certificate>,
+    /// Client certificate for mutual TLS.
+    pub identity: Option<Identity>,
+    /// Timeout for establishing a connection.
+    pub connect_timeout: Option<Duration>,
+    /// Timeout for each read from the connection.
+    pub read_timeout: Option<Duration>,
+    /// Maximum number of idle connections kept per host.
+    pub pool_max_idle_per_host: usize,
+    /// How long idle connections are kept in the pool.
+    pub pool_idle_timeout: Option<Duration>,
+    /// Whether to log connection reads and writes at `trace` level.
+    pub connection_verbose: bool,
+    /// Pre-built HTTP client used instead of building one from these settings.
+    pub http_client: Option<reqwest::Client>,
+}
+
+impl fmt::Debug for TransportConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // reqwest types print proxy URLs with their credentials, so only their number or
+        // presence is shown.
+        f.debug_struct("TransportConfig")
+            .field("proxies", &self.proxies.len())
+            .field("root_certificates", &self.root_certificates.len())
+            .field("identity", &self.identity.as_ref().map(|_| Opaque))
+            .field("connect_timeout", &self.connect_timeout)
+            .field("read_timeout", &self.read_timeout)
+            .field("pool_max_idle_per_host", &self.pool_max_idle_per_host)
+            .field("pool_idle_timeout", &self.pool_idle_timeout)
+            .field("connection_verbose", &self.connection_verbose)
+            .field("http_client", &self.http_client.as_ref().map(|_| Opaque))
+            .finish()
+    }
+}
+
+/// Stands in for a value that is not printed, shown as `..`.
+struct Opaque;
+
+impl fmt::Debug for Opaque {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("..")
+    }
+}
+
+impl Default for TransportConfig {
+    fn default() -> Self {
+        Self {
+            proxies: Vec::new(),
+            root_certificates: Vec::new(),
+            identity: None,
+            connect_timeout: Some(Duration::from_secs(10)),
+            read_timeout: None,
+            pool_max_idle_per_host: 10,
+            pool_idle_timeout: Some(Duration::from_secs(90)),
+            connection_verbose: false,
+            http_client: None,
+        }
+    }
+}
+
+/// Main configuration for the Dintero SDK.
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// Dintero account ID (e.g., "T12345678").
+    pub account_id: String,
+    /// API environment to use.
+    pub environment: Environment,
+    /// Authentication configuration.
+    pub auth: AuthConfig,
+    /// Request timeout in seconds.
+    pub timeout_secs: u64,
+    /// HTTP transport settings.
+    pub transport: TransportConfig,
+    /// Retry policy for failed requests.
+    pub retry_policy: Arc<dyn RetryPolicy>,
+    /// Budget limiting retries across all requests of the client.
+    ///
+    /// Clients created from clones of this configuration share the budget.
+    pub retry_budget: Option<Arc<RetryBudget>>,
+    /// Token store for OAuth access tokens.
+    ///
+    /// When unset, tokens are shared through a process-wide in-memory store.
+    pub token_store: Option<Arc<dyn TokenStore>>,
+    /// Client-side rate limiter applied by the [`Layer::RateLimit`] layer.
+    pub rate_limiter: Option<Arc<RateLimiter>>,
+    /// Middleware chain every request passes through, outermost layer first.
+    pub layers: Vec<Layer>,
+}
+
+impl Config {
+    /// Creates a new configuration builder.
+    pub fn builder(account_id: impl Into<String>) -> ConfigBuilder {
+        ConfigBuilder::new(account_id)
+    }
+
+    /// Loads the configuration from `DINTERO_*` environment variables and the config
+    /// file named by `DINTERO_CONFIG_FILE`, if any.
+    ///
+    /// See [`ConfigLoader`] for the supported settings.
+    pub fn from_env() -> Result<Config> {
+        ConfigLoader::new().load()
+    }
+
+    /// Validates the configuration.
+    pub fn validate(&self) -> Result<()> {
+        if self.account_id.is_empty() {
+            return Err(Error::Config("account_id cannot be empty".to_string()));
+        }
+        self.auth.validate()?;
+        if self.timeout_secs == 0 {
+            return Err(Error::Config(
+                "timeout_secs must be greater than 0".to_string(),
+            ));
+        }
+        if let Environment::Custom(base_url) = &self.environment {
+            let url = url::Url::parse(base_url)?;
+            if !matches!(url.scheme(), "http" | "https") {
+                return Err(Error::Config(format!(
+                    "base URL must use http or https: {}",
+                    base_url
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Builder for creating a Dintero SDK configuration.
+pub struct ConfigBuilder {
+    account_id: String,
+    environment: Environment,
+    auth: Option<AuthConfig>,
+    timeout_secs: u64,
+    transport: TransportConfig,
+    retry_policy: Arc<dyn RetryPolicy>,
+    retry_budget: Option<Arc<RetryBudget>>,
+    token_store: Option<Arc<dyn TokenStore>>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    layers: Vec<Layer>,
+}
+
+impl ConfigBuilder {
+    /// Creates a new configuration builder.
+    pub fn new(account_id: impl Into<String>) -> Self {
+        Self {
+            account_id: account_id.into(),
+            environment: Environment::default(),
+            auth: None,
+            timeout_secs: 30,
+            transport: TransportConfig::default(),
+            retry_policy: Arc::new(RetryConfig::default()),
+            retry_budget: Some(Arc::new(RetryBudget::default())),
+            token_store: None,
+            rate_limiter: None,
+            layers: Layer::defaults(),
+        }
+    }
+
+    /// Sets the API environment.
+    pub fn environment(mut self, environment: Environment) -> Self {
+        self.environment = environment;
+        self
+    }
+
+    /// Sets the authentication configuration.
+    pub fn auth(mut self, auth: AuthConfig) -> Self {
+        self.auth = Some(auth);
+        self
+    }
+
+    /// Sets API key authentication.
+    pub fn api_key(self, api_key: impl Into<String>) -> Self {
+        self.auth(AuthConfig::ApiKey(api_key.into()))
+    }
+
+    /// Sets OAuth2 authentication.
+    pub fn oauth(self, client_id: impl Into<String>, client_secret: impl Into<String>) -> Self {
+        self.auth(AuthConfig::OAuth {
+            client_id: client_id.into(),
+            client_secret: client_secret.into(),
+        })
+    }
+
+    /// Sets JWT token authentication.
+    pub fn jwt(self, token: impl Into<String>) -> Self {
+        self.auth(AuthConfig::Jwt(token.into()))
+    }
+
+    /// Sets the request timeout in seconds.
+    pub fn timeout_secs(mut self, timeout_secs: u64) -> Self {
+        self.timeout_secs = timeout_secs;
+        self
+    }
+
+    /// Sets a custom base URL, e.g. for a local stand-in server.
+    pub fn base_url(self, base_url: impl Into<String>) -> Self {
+        self.environment(Environment::Custom(base_url.into()))
+    }
+
+    /// Sets the HTTP transport settings.
+    pub fn transport(mut self, transport: TransportConfig) -> Self {
+        self.transport = transport;
+        self
+    }
+
+    /// Routes requests through the given proxy.
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.transport.proxies.push(proxy);
+        self
+    }
+
+    /// Adds a trusted root certificate.
+    pub fn root_certificate(mut self, certificate: Certificate) -> Self {
+        self.transport.root_certificates.push(certificate);
+        self
+    }
+
+    /// Sets the client certificate used for mutual TLS.
+    pub fn identity(mut self, identity: Identity) -> Self {
+        self.transport.identity = Some(identity);
+        self
+    }
+
+    /// Sets the timeout for establishing a connection.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.transport.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the timeout for each read from the connection.
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.transport.read_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the maximum number of idle connections kept per host.
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.transport.pool_max_idle_per_host = max;
+        self
+    }
+
+    /// Sets how long idle connections are kept in the pool.
+    pub fn pool_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.transport.pool_idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Uses a pre-built HTTP client instead of building one.
+    ///
+    /// The client's own timeout, proxy and TLS settings apply; the other transport
+    /// settings and `timeout_secs` are ignored.
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.transport.http_client = Some(client);
+        self
+    }
+
+    /// Sets the retry configuration.
+    pub fn retry_config(self, retry_config: RetryConfig) -> Self {
+        self.retry_policy(retry_config)
+    }
+
+    /// Sets a custom retry policy.
+    pub fn retry_policy(mut self, retry_policy: impl RetryPolicy + 'static) -> Self {
+        self.retry_policy = Arc::new(retry_policy);
+        self
+    }
+
+    /// Sets the budget limiting retries across all requests of the client.
+    ///
+    /// Pass `None` to allow every retry permitted by the retry policy.
+    pub fn retry_budget(mut self, retry_budget: Option<RetryBudget>) -> Self {
+        self.retry_budget = retry_budget.map(Arc::new);
+        self
+    }
+
+    /// Sets the token store used to cache and share OAuth access tokens.
+    pub fn token_store(mut self, store: Arc<dyn TokenStore>) -> Self {
+        self.token_store = Some(store);
+        self
+    }
+
+    /// Sets a client-side rate limiter.
+    ///
+    /// Keep a clone of the `Arc` to read the limiter's state for metrics.
+    pub fn rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Appends a middleware to the end of the chain.
+    ///
+    /// With the default chain the middleware runs after authentication, once for every
+    /// attempt, and sees the request exactly as it is sent.
+    pub fn middleware(mut self, middleware: impl Middleware + 'static) -> Self {
+        self.layers.push(Layer::custom(middleware));
+        self
+    }
+
+    /// Replaces the middleware chain.
+    ///
+    /// Use this to reorder the built-in layers or to replace them with custom ones. Layers
+    /// run in the given order; omitting [`Layer::Auth`] sends requests without
+    /// credentials.
+    pub fn layers(mut self, layers: Vec<Layer>) -> Self {
+        self.layers = layers;
+        self
+    }
+
+    /// Builds the configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if required fields are missing or validation fails.
+    pub fn build(self) -> Result<Config> {
+        let auth =
+            self.auth.ok_or_else(|| Error::Config("auth configuration required".to_string()))?;
+
+        let config = Config {
+            account_id: self.account_id,
+            environment: self.environment,
+            auth,
+            timeout_secs: self.timeout_secs,
+            transport: self.transport,
+            retry_policy: self.retry_policy,
+            retry_budget: self.retry_budget,
+            token_store: self.token_store,
+            rate_limiter: self.rate_limiter,
+            layers: self.layers,
+        };
+
+        config.validate()?;
+        Ok(config)
+    }
+}
@@ -0,0 +1,67 @@
+//! Authentication layer.
+
+use super::{Middleware, Next, RequestContext};
+use crate::auth::AuthProvider;
+use crate::error::{Error, Result};
+use async_trait::async_trait;
+use reqwest::header::{HeaderValue, AUTHORIZATION};
+use reqwest::{Request, Response, StatusCode};
+use std::fmt;
+use std::sync::Arc;
+use tracing::warn;
+
+/// Adds the `Authorization` header from an [`AuthProvider`].
+///
+/// If the API rejects the request with `401 Unauthorized` and the provider is able to
+/// refresh its credentials, the request is sent once more with the new credentials.
+pub struct AuthMiddleware {
+    auth: Arc<dyn AuthProvider>,
+}
+
+impl AuthMiddleware {
+    /// Creates an authentication layer for the given provider.
+    pub fn new(auth: Arc<dyn AuthProvider>) -> Self {
+        Self { auth }
+    }
+}
+
+impl fmt::Debug for AuthMiddleware {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AuthMiddleware").finish_non_exhaustive()
+    }
+}
+
+fn set_auth_header(request: &mut Request, auth_header: &str) -> Result<()> {
+    let value = HeaderValue::from_str(auth_header)
+        .map_err(|e| Error::Auth(format!("Invalid auth header: {}", e)))?;
+    request.headers_mut().insert(AUTHORIZATION, value);
+    Ok(())
+}
+
+#[async_trait]
+impl Middleware for AuthMiddleware {
+    async fn handle(
+        &self,
+        mut request: Request,
+        ctx: &mut RequestContext,
+        next: Next<'_>,
+    ) -> Result<Response> {
+        let auth_header = self.auth.get_auth_header().await?;
+        set_auth_header(&mut request, &auth_header)?;
+        let retry = request.try_clone();
+
+        let response = next.run(request, ctx).await?;
+
+        match retry {
+            Some(mut retry)
+                if response.status() == StatusCode::UNAUTHORIZED
+                    && self.auth.force_refresh(&auth_header).await? =>
+            {
+                warn!("Request unauthorized, retrying with refreshed credentials");
+                set_auth_header(&mut retry, &self.auth.get_auth_header().await?)?;
+                next.run(retry, ctx).await
+            }
+            _ => Ok(response),
+        }
+    }
+}
@@ -0,0 +1,679 @@
+//! Record/replay layer.
+
+use super::{Middleware, Next, RequestContext};
+use crate::error::{Error, Result};
+use async_trait::async_trait;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_LENGTH, CONTENT_TYPE};
+use reqwest::{Request, Response};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+use tracing::error;
+
+/// Replacement for redacted header and field values.
+pub const REDACTED: &str = "[REDACTED]";
+
+const DEFAULT_REDACTED_HEADERS: &[&str] =
+    &["authorization", "proxy-authorization", "cookie", "set-cookie", "x-api-key"];
+
+const DEFAULT_REDACTED_FIELDS: &[&str] = &[
+    // Credentials
+    "access_token",
+    "refresh_token",
+    "id_token",
+    "client_secret",
+    "api_key",
+    "secret",
+    "password",
+    // Personal data
+    "email",
+    "phone_number",
+    "first_name",
+    "last_name",
+    "date_of_birth",
+    "address_line",
+    "address_line_2",
+    "postal_code",
+    "postal_place",
+    "social_security_number",
+    "customer_ip",
+    "user_agent",
+];
+
+/// Recorded request/response pairs.
+///
+/// Cassettes are stored as JSON or YAML, chosen by the file extension (`.json`,
+/// `.yaml` or `.yml`).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Cassette {
+    /// Interactions in the order they were recorded.
+    pub interactions: Vec<Interaction>,
+}
+
+impl Cassette {
+    /// Reads a cassette file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path).map_err(|e| {
+            Error::Config(format!("failed to read cassette {}: {}", path.display(), e))
+        })?;
+        let invalid = |e: &dyn fmt::Display| {
+            Error::Config(format!("invalid cassette {}: {}", path.display(), e))
+        };
+
+        match Format::of(path)? {
+            Format::Json => serde_json::from_str(&contents).map_err(|e| invalid(&e)),
+            Format::Yaml => serde_yaml::from_str(&contents).map_err(|e| invalid(&e)),
+        }
+    }
+
+    /// Writes the cassette to a file, creating its parent directories.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let contents = match Format::of(path)? {
+            Format::Json => serde_json::to_string_pretty(self)?,
+            Format::Yaml => serde_yaml::to_string(self)
+                .map_err(|e| Error::Config(format!("failed to encode cassette: {}", e)))?,
+        };
+
+        let write = || -> std::io::Result<()> {
+            if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(path, contents)
+        };
+        write().map_err(|e| {
+            Error::Config(format!(
+                "failed to write cassette {}: {}",
+                path.display(),
+                e
+            ))
+        })
+    }
+}
+
+enum Format {
+    Json,
+    Yaml,
+}
+
+impl Format {
+    fn of(path: &Path) -> Result<Self> {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Ok(Format::Json),
+            Some("yaml" | "yml") => Ok(Format::Yaml),
+            _ => Err(Error::Config(format!(
+                "unsupported cassette format {} (expected .json, .yaml or .yml)",
+                path.display()
+            ))),
+        }
+    }
+}
+
+/// A recorded request and the response it received.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+/// A request as stored in a cassette, after redaction.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub method: String,
+    /// URL path without the host, e.g. `/v1/accounts/T12345678/sessions`.
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    /// JSON body, or the body as a string if it is not JSON.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+}
+
+impl RecordedRequest {
+    fn query_pairs(&self) -> Vec<(String, String)> {
+        let query = self.query.as_deref().unwrap_or_default();
+        let mut pairs: Vec<_> = url::form_urlencoded::parse(query.as_bytes())
+            .map(|(name, value)| (name.into_owned(), value.into_owned()))
+            .collect();
+        pairs.sort();
+        pairs
+    }
+}
+
+impl fmt::Display for RecordedRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.method, self.path)?;
+        if let Some(query) = &self.query {
+            write!(f, "?{}", query)?;
+        }
+        Ok(())
+    }
+}
+
+/// A response as stored in a cassette, after redaction.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub status: u16,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    /// JSON body, or the body as a string if it is not JSON.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+}
+
+/// Headers and body fields whose values are replaced with [`REDACTED`] before an
+/// interaction is stored.
+///
+/// By default credentials (the `Authorization` header, tokens and secrets) and
+/// personal data (names, email addresses, phone numbers and addresses) are redacted.
+/// Field names match JSON object keys at any depth and query parameters; all names
+/// are compared case-insensitively.
+#[derive(Debug, Clone)]
+pub struct Redaction {
+    headers: BTreeSet<String>,
+    fields: BTreeSet<String>,
+}
+
+impl Default for Redaction {
+    fn default() -> Self {
+        Self {
+            headers: DEFAULT_REDACTED_HEADERS.iter().map(|name| name.to_string()).collect(),
+            fields: DEFAULT_REDACTED_FIELDS.iter().map(|name| name.to_string()).collect(),
+        }
+    }
+}
+
+impl Redaction {
+    /// Creates the default redaction rules.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates rules that redact nothing.
+    pub fn none() -> Self {
+        Self {
+            headers: BTreeSet::new(),
+            fields: BTreeSet::new(),
+        }
+    }
+
+    /// Also redacts a header.
+    pub fn header(mut self, name: impl AsRef<str>) -> Self {
+        self.headers.insert(name.as_ref().to_ascii_lowercase());
+        self
+    }
+
+    /// Also redacts a body field or query parameter.
+    pub fn field(mut self, name: impl AsRef<str>) -> Self {
+        self.fields.insert(name.as_ref().to_ascii_lowercase());
+        self
+    }
+
+    /// Stops redacting a header or field that is redacted by default.
+    pub fn keep(mut self, name: impl AsRef<str>) -> Self {
+        let name = name.as_ref().to_ascii_lowercase();
+        self.headers.remove(&name);
+        self.fields.remove(&name);
+        self
+    }
+
+    fn headers(&self, headers: &HeaderMap) -> BTreeMap<String, String> {
+        headers
+            .iter()
+            .filter(|(name, _)| *name != CONTENT_LENGTH)
+            .map(|(name, value)| {
+                let value = if self.headers.contains(name.as_str()) {
+                    REDACTED.to_string()
+                } else {
+                    String::from_utf8_lossy(value.as_bytes()).into_owned()
+                };
+                (name.as_str().to_string(), value)
+            })
+            .collect()
+    }
+
+    fn query(&self, query: Option<&str>) -> Option<String> {
+        let query = query.filter(|query| !query.is_empty())?;
+        let pairs = url::form_urlencoded::parse(query.as_bytes()).map(|(name, value)| {
+            let redact = self.fields.contains(&name.to_ascii_lowercase());
+            (name, if redact { REDACTED.into() } else { value })
+        });
+        Some(url::form_urlencoded::Serializer::new(String::new()).extend_pairs(pairs).finish())
+    }
+
+    fn body(&self, body: &[u8]) -> Option<Value> {
+        if body.is_empty() {
+            return None;
+        }
+        match serde_json::from_slice(body) {
+            Ok(mut value) => {
+                self.value(&mut value);
+                Some(value)
+            }
+            Err(_) => Some(Value::String(String::from_utf8_lossy(body).into_owned())),
+        }
+    }
+
+    fn value(&self, value: &mut Value) {
+        match value {
+            Value::Object(fields) => {
+                for (name, value) in fields {
+                    if value.is_null() {
+                        continue;
+                    }
+                    if self.fields.contains(&name.to_ascii_lowercase()) {
+                        *value = Value::String(REDACTED.to_string());
+                    } else {
+                        self.value(value);
+                    }
+                }
+            }
+            Value::Array(values) => values.iter_mut().for_each(|value| self.value(value)),
+            _ => {}
+        }
+    }
+
+    fn request(&self, request: &Request) -> RecordedRequest {
+        let body = request.body().and_then(|body| body.as_bytes()).unwrap_or_default();
+        RecordedRequest {
+            method: request.method().to_string(),
+            path: request.url().path().to_string(),
+            query: self.query(request.url().query()),
+            headers: self.headers(request.headers()),
+            body: self.body(body),
+        }
+    }
+}
+
+/// Which parts of a request must equal a recorded request for its response to be
+/// replayed.
+///
+/// All parts are compared by default. Query parameters are compared regardless of
+/// their order, and bodies are compared as JSON after redaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MatchRules {
+    pub method: bool,
+    pub path: bool,
+    pub query: bool,
+    pub body: bool,
+}
+
+impl Default for MatchRules {
+    fn default() -> Self {
+        Self {
+            method: true,
+            path: true,
+            query: true,
+            body: true,
+        }
+    }
+}
+
+impl MatchRules {
+    fn matches(&self, recorded: &RecordedRequest, request: &RecordedRequest) -> bool {
+        (!self.method || recorded.method.eq_ignore_ascii_case(&request.method))
+            && (!self.path || recorded.path == request.path)
+            && (!self.query || recorded.query_pairs() == request.query_pairs())
+            && (!self.body || recorded.body == request.body)
+    }
+}
+
+/// Whether a [`CassetteMiddleware`] records or replays interactions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CassetteMode {
+    /// Sends requests to the API and stores each interaction in the cassette.
+    Record,
+    /// Answers requests from the cassette without touching the network.
+    Replay,
+}
+
+/// Returned, wrapped in [`DinteroError::Http`](crate::error::DinteroError::Http), when
+/// no unused interaction in the cassette matches a replayed request.
+#[derive(Debug, Clone)]
+pub struct UnmatchedRequest {
+    /// The cassette file.
+    pub cassette: PathBuf,
+    /// The request, after redaction.
+    pub request: RecordedRequest,
+}
+
+impl fmt::Display for UnmatchedRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "no unused interaction in cassette {} matches {}",
+            self.cassette.display(),
+            self.request
+        )
+    }
+}
+
+impl std::error::Error for UnmatchedRequest {}
+
+#[derive(Debug)]
+struct Tape {
+    cassette: Cassette,
+    used: Vec<bool>,
+}
+
+/// Records API interactions to a cassette file, or replays them from one.
+///
+/// In [`Record`](CassetteMode::Record) mode requests are sent as usual and every
+/// request/response pair is redacted and appended to the cassette, which is written
+/// after each interaction. In [`Replay`](CassetteMode::Replay) mode each request is
+/// answered with the first unused recorded interaction that matches it under the
+/// [`MatchRules`]; a request without a match fails with [`UnmatchedRequest`] instead
+/// of reaching the network.
+///
+/// Add the layer last, so it sits below authentication and retries and sees each
+/// attempt exactly as it would be sent:
+///
+/// ```no_run
+/// use dintero::middleware::{CassetteMiddleware, CassetteMode};
+/// use dintero::Config;
+///
+/// # fn example() -> dintero::Result<()> {
+/// let mode = match std::env::var("DINTERO_RECORD") {
+///     Ok(_) => CassetteMode::Record,
+///     Err(_) => CassetteMode::Replay,
+/// };
+/// let cassette = CassetteMiddleware::new("tests/cassettes/capture.yaml", mode)?;
+///
+/// let config = Config::builder("T12345678")
+///     .api_key("your-api-key")
+///     .middleware(cassette.clone())
+///     .build()?;
+///
+/// // ... run the test, then check that every recorded request was made.
+/// assert!(cassette.unused().is_empty());
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Clones share the same cassette. The OAuth token exchange does not pass through the
+/// middleware chain, so replay tests should authenticate with an API key.
+#[derive(Clone)]
+pub struct CassetteMiddleware {
+    path: PathBuf,
+    mode: CassetteMode,
+    rules: MatchRules,
+    redaction: Redaction,
+    tape: Arc<Mutex<Tape>>,
+}
+
+impl CassetteMiddleware {
+    /// Creates a layer in the given mode. Replay mode loads the cassette immediately;
+    /// record mode starts an empty cassette that replaces the file.
+    pub fn new(path: impl Into<PathBuf>, mode: CassetteMode) -> Result<Self> {
+        let path = path.into();
+        let cassette = match mode {
+            CassetteMode::Record => {
+                Format::of(&path)?;
+                Cassette::default()
+            }
+            CassetteMode::Replay => Cassette::load(&path)?,
+        };
+        let used = vec![false; cassette.interactions.len()];
+
+        Ok(Self {
+            path,
+            mode,
+            rules: MatchRules::default(),
+            redaction: Redaction::default(),
+            tape: Arc::new(Mutex::new(Tape { cassette, used })),
+        })
+    }
+
+    /// Creates a layer recording to `path`.
+    pub fn record(path: impl Into<PathBuf>) -> Result<Self> {
+        Self::new(path, CassetteMode::Record)
+    }
+
+    /// Creates a layer replaying the cassette at `path`.
+    pub fn replay(path: impl Into<PathBuf>) -> Result<Self> {
+        Self::new(path, CassetteMode::Replay)
+    }
+
+    /// Sets the rules for matching requests to recorded interactions.
+    pub fn match_rules(mut self, rules: MatchRules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// Sets the headers and fields redacted before interactions are stored.
+    ///
+    /// Replayed requests are redacted the same way before they are matched, so the
+    /// rules must be the same when recording and replaying.
+    pub fn redaction(mut self, redaction: Redaction) -> Self {
+        self.redaction = redaction;
+        self
+    }
+
+    /// Returns the mode of the layer.
+    pub fn mode(&self) -> CassetteMode {
+        self.mode
+    }
+
+    /// Returns the interactions recorded or loaded so far.
+    pub fn cassette(&self) -> Cassette {
+        self.tape().cassette.clone()
+    }
+
+    /// Returns the recorded interactions that have not been replayed.
+    pub fn unused(&self) -> Vec<Interaction> {
+        let tape = self.tape();
+        tape.cassette
+            .interactions
+            .iter()
+            .zip(&tape.used)
+            .filter(|(_, used)| !**used)
+            .map(|(interaction, _)| interaction.clone())
+            .collect()
+    }
+
+    fn tape(&self) -> MutexGuard<'_, Tape> {
+        self.tape.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn replay_response(&self, request: RecordedRequest) -> Result<Response> {
+        let recorded = {
+            let mut tape = self.tape();
+            let Tape { cassette, used } = &mut *tape;
+            let found =
+                cassette.interactions.iter().zip(used.iter_mut()).find(|(interaction, used)| {
+                    !**used && self.rules.matches(&interaction.request, &request)
+                });
+            found.map(|(interaction, used)| {
+                *used = true;
+                interaction.response.clone()
+            })
+        };
+
+        let Some(recorded) = recorded else {
+            let unmatched = UnmatchedRequest { cassette: self.path.clone(), request };
+            error!("{}", unmatched);
+            return Err(Error::Http(Box::new(unmatched)));
+        };
+
+        let is_json =
+            recorded.headers.get(CONTENT_TYPE.as_str()).is_none_or(|v| v.contains("json"));
+        let body = match recorded.body {
+            None => Vec::new(),
+            Some(Value::String(text)) if !is_json => text.into_bytes(),
+            Some(value) => serde_json::to_vec(&value)?,
+        };
+
+        let mut response = http::Response::new(body);
+        *response.status_mut() = http::StatusCode::from_u16(recorded.status)
+            .map_err(|e| Error::Config(format!("invalid status in cassette: {}", e)))?;
+        for (name, value) in &recorded.headers {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::try_from(name.as_str()),
+                HeaderValue::try_from(value.as_str()),
+            ) {
+                response.headers_mut().append(name, value);
+            }
+        }
+        Ok(Response::from(response))
+    }
+}
+
+impl fmt::Debug for CassetteMiddleware {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CassetteMiddleware")
+            .field("path", &self.path)
+            .field("mode", &self.mode)
+            .field("rules", &self.rules)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl Middleware for CassetteMiddleware {
+    async fn handle(
+        &self,
+        request: Request,
+        ctx: &mut RequestContext,
+        next: Next<'_>,
+    ) -> Result<Response> {
+        let recorded_request = self.redaction.request(&request);
+
+        if self.mode == CassetteMode::Replay {
+            return self.replay_response(recorded_request);
+        }
+
+        let response = next.run(request, ctx).await?;
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response.bytes().await?;
+
+        let interaction = Interaction {
+            request: recorded_request,
+            response: RecordedResponse {
+                status: status.as_u16(),
+                headers: self.redaction.headers(&headers),
+                body: self.redaction.body(&body),
+            },
+        };
+        let cassette = {
+            let mut tape = self.tape();
+            tape.cassette.interactions.push(interaction);
+            tape.used.push(true);
+            tape.cassette.clone()
+        };
+        cassette.save(&self.path)?;
+
+        let mut response = http::Response::new(body);
+        *response.status_mut() = status;
+        *response.headers_mut() = headers;
+        Ok(Response::from(response))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_redaction_and_matching() {
+        let redaction = Redaction::new().field("merchant_reference");
+        let mut body = json!({
+            "customer": { "email": "kari@example.com", "phone_number": null },
+            "items": [{ "merchant_reference": "ref-1", "amount": 100 }],
+        });
+        redaction.value(&mut body);
+        assert_eq!(
+            body,
+            json!({
+                "customer": { "email": REDACTED, "phone_number": null },
+                "items": [{ "merchant_reference": REDACTED, "amount": 100 }],
+            })
+        );
+        assert_eq!(
+            redaction.query(Some("limit=10&email=kari%40example.com")).as_deref(),
+            Some("limit=10&email=%5BREDACTED%5D")
+        );
+
+        let recorded = RecordedRequest {
+            method: "GET".to_string(),
+            path: "/v1/accounts/T12345678/transactions".to_string(),
+            query: Some("limit=10&status=CAPTURED".to_string()),
+            headers: BTreeMap::new(),
+            body: None,
+        };
+        let reordered = RecordedRequest {
+            query: Some("status=CAPTURED&limit=10".to_string()),
+            ..recorded.clone()
+        };
+        let other_page = RecordedRequest {
+            query: Some("limit=20".to_string()),
+            ..recorded.clone()
+        };
+
+        assert!(MatchRules::default().matches(&recorded, &reordered));
+        assert!(!MatchRules::default().matches(&recorded, &other_page));
+        let ignore_query = MatchRules { query: false, ..Default::default() };
+        assert!(ignore_query.matches(&recorded, &other_page));
+    }
+
+    #[tokio::test]
+    async fn test_replays_recorded_interactions_offline() {
+        use crate::client::HttpClient;
+        use crate::config::Config;
+        use dintero_mock::MockServer;
+
+        let path = std::env::temp_dir().join(format!("cassette-{}.yaml", uuid::Uuid::new_v4()));
+        let client = |base_url: &str, cassette: &CassetteMiddleware| {
+            let config = Config::builder("T12345678")
+                .api_key("secret-key")
+                .base_url(base_url)
+                .middleware(cassette.clone())
+                .build()
+                .unwrap();
+            let auth = crate::auth::create_auth_provider_from_config(&config).unwrap();
+            HttpClient::new(&config, auth).unwrap()
+        };
+        let session = json!({
+            "order": { "amount": 10000, "currency": "NOK" },
+            "customer": { "email": "kari@example.com" },
+        });
+
+        let server = MockServer::start().await;
+        let recorder = CassetteMiddleware::record(&path).unwrap();
+        let recording = client(&server.url(), &recorder);
+        let created: Value =
+            recording.post_json("accounts/T12345678/sessions", &session).await.unwrap();
+        let path_of_session = format!(
+            "accounts/T12345678/sessions/{}",
+            created["id"].as_str().unwrap()
+        );
+        let fetched: Value = recording.get_json(&path_of_session).await.unwrap();
+        drop(server);
+
+        let stored = std::fs::read_to_string(&path).unwrap();
+        assert!(!stored.contains("secret-key"));
+        assert!(!stored.contains("kari@example.com"));
+
+        let player = CassetteMiddleware::replay(&path).unwrap();
+        let replaying = client("http://127.0.0.1:9", &player);
+        let replayed: Value =
+            replaying.post_json("accounts/T12345678/sessions", &session).await.unwrap();
+        assert_eq!(replayed, created);
+        assert_eq!(
+            replaying.get_json::<Value>(&path_of_session).await.unwrap(),
+            fetched
+        );
+        assert!(player.unused().is_empty());
+
+        let err = replaying.get_json::<Value>(&path_of_session).await.unwrap_err();
+        assert!(err.to_string().contains("no unused interaction"));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
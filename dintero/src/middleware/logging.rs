@@ -0,0 +1,63 @@
+//! Logging layer.
+
+use super::{Middleware, Next, RequestContext};
+use crate::error::Result;
+use async_trait::async_trait;
+use reqwest::{Request, Response};
+use std::time::Instant;
+use tracing::{debug, warn};
+
+/// Logs the method, URL, status and duration of each request.
+///
+/// Successful requests are logged at `debug` level, failed ones at `warn` level. Request
+/// and response bodies are never logged.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LoggingMiddleware;
+
+impl LoggingMiddleware {
+    /// Creates a logging layer.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl Middleware for LoggingMiddleware {
+    async fn handle(
+        &self,
+        request: Request,
+        ctx: &mut RequestContext,
+        next: Next<'_>,
+    ) -> Result<Response> {
+        let method = request.method().clone();
+        let path = request.url().path().to_string();
+        let started = Instant::now();
+
+        let result = next.run(request, ctx).await;
+        let elapsed = started.elapsed();
+
+        match &result {
+            Ok(response) if response.status().is_success() => {
+                debug!(
+                    "{} {} -> {} in {:?}",
+                    method,
+                    path,
+                    response.status(),
+                    elapsed
+                );
+            }
+            Ok(response) => {
+                warn!(
+                    "{} {} -> {} in {:?}",
+                    method,
+                    path,
+                    response.status(),
+                    elapsed
+                );
+            }
+            Err(e) => warn!("{} {} failed after {:?}: {}", method, path, elapsed, e),
+        }
+
+        result
+    }
+}
@@ -0,0 +1,167 @@
+//! Request/response middleware for the HTTP client.
+//!
+//! Every request sent by [`HttpClient`](crate::client::HttpClient) passes through an
+//! ordered chain of [`Middleware`] layers before it reaches the network. Each layer can
+//! inspect or modify the request, decide whether and how often to call the rest of the
+//! chain, and inspect or replace the response.
+//!
+//! Authentication, retries and logging are built-in layers, and [`CassetteMiddleware`]
+//! records and replays interactions for tests. The chain is configured with
+//! [`ConfigBuilder::middleware`](crate::config::ConfigBuilder::middleware) and
+//! [`ConfigBuilder::layers`](crate::config::ConfigBuilder::layers):
+//!
+//! ```no_run
+//! use async_trait::async_trait;
+//! use dintero::middleware::{Layer, Middleware, Next, RequestContext};
+//! use dintero::Config;
+//! use reqwest::{Request, Response};
+//!
+//! #[derive(Debug)]
+//! struct TenantHeader;
+//!
+//! #[async_trait]
+//! impl Middleware for TenantHeader {
+//!     async fn handle(
+//!         &self,
+//!         mut request: Request,
+//!         ctx: &mut RequestContext,
+//!         next: Next<'_>,
+//!     ) -> dintero::Result<Response> {
+//!         request.headers_mut().insert("x-tenant", "acme".parse().unwrap());
+//!         next.run(request, ctx).await
+//!     }
+//! }
+//!
+//! # fn example() -> dintero::Result<()> {
+//! let config = Config::builder("T12345678")
+//!     .api_key("your-api-key")
+//!     .layers(vec![Layer::Logging, Layer::custom(TenantHeader), Layer::Retry, Layer::Auth])
+//!     .build()?;
+//! # Ok(())
+//! # }
+//! ```
+
+mod auth;
+mod cassette;
+mod logging;
+mod rate_limit;
+mod retry;
+
+pub use auth::AuthMiddleware;
+pub use cassette::{
+    Cassette, CassetteMiddleware, CassetteMode, Interaction, MatchRules, RecordedRequest,
+    RecordedResponse, Redaction, UnmatchedRequest, REDACTED,
+};
+pub use logging::LoggingMiddleware;
+pub use rate_limit::RateLimitMiddleware;
+pub use retry::RetryMiddleware;
+
+use crate::error::Result;
+use async_trait::async_trait;
+use dintero_core::RequestOptions;
+use reqwest::{Client, Request, Response};
+use std::sync::Arc;
+
+/// A layer in the request pipeline.
+///
+/// Implementations receive the request together with the remaining chain. Calling
+/// [`Next::run`] passes the request on; a layer may call it several times (e.g. to
+/// retry) as long as it clones the request first, or not at all (e.g. to short-circuit
+/// with an error).
+///
+/// The response returned by the chain may have any status. Non-success responses are
+/// converted to [`DinteroError`](crate::error::DinteroError) after the whole chain has run.
+#[async_trait]
+pub trait Middleware: Send + Sync + std::fmt::Debug {
+    /// Handles a request and returns its response.
+    async fn handle(
+        &self,
+        request: Request,
+        ctx: &mut RequestContext,
+        next: Next<'_>,
+    ) -> Result<Response>;
+}
+
+/// State shared by the layers handling a single API call.
+#[derive(Debug, Clone, Default)]
+pub struct RequestContext {
+    /// Options passed by the caller for this request.
+    pub options: RequestOptions,
+    /// Zero-based attempt number, updated by the retry layer.
+    pub attempt: u32,
+    /// Number of attempts answered with `429 Too Many Requests`, updated by the retry
+    /// layer.
+    pub rate_limited: u32,
+    /// Whether the endpoint deduplicates requests by their `Idempotency-Key` header, so
+    /// a request carrying one may be retried.
+    pub idempotent_endpoint: bool,
+}
+
+impl RequestContext {
+    /// Creates a context for a request sent with the given options.
+    pub fn new(options: RequestOptions) -> Self {
+        Self {
+            options,
+            attempt: 0,
+            rate_limited: 0,
+            idempotent_endpoint: false,
+        }
+    }
+}
+
+/// The remaining layers of the pipeline.
+#[derive(Debug, Clone, Copy)]
+pub struct Next<'a> {
+    client: &'a Client,
+    middleware: &'a [Arc<dyn Middleware>],
+}
+
+impl<'a> Next<'a> {
+    pub(crate) fn new(client: &'a Client, middleware: &'a [Arc<dyn Middleware>]) -> Self {
+        Self { client, middleware }
+    }
+
+    /// Passes the request to the next layer, or sends it if no layers remain.
+    pub async fn run(self, request: Request, ctx: &mut RequestContext) -> Result<Response> {
+        match self.middleware.split_first() {
+            Some((layer, rest)) => layer.handle(request, ctx, Next::new(self.client, rest)).await,
+            None => Ok(self.client.execute(request).await?),
+        }
+    }
+}
+
+/// An entry in the configured middleware chain.
+///
+/// Layers run in the order they are listed: the first layer sees the request first and
+/// the response last.
+#[derive(Debug, Clone)]
+pub enum Layer {
+    /// Logs each request with its status and duration. See [`LoggingMiddleware`].
+    Logging,
+    /// Retries failed requests using the configured retry policy and budget. See
+    /// [`RetryMiddleware`].
+    Retry,
+    /// Waits for the configured [`RateLimiter`](crate::rate_limit::RateLimiter), if any.
+    /// See [`RateLimitMiddleware`].
+    RateLimit,
+    /// Adds the `Authorization` header and refreshes credentials once on
+    /// `401 Unauthorized`. See [`AuthMiddleware`].
+    Auth,
+    /// A user-provided layer.
+    Custom(Arc<dyn Middleware>),
+}
+
+impl Layer {
+    /// Wraps a user-provided middleware.
+    pub fn custom(middleware: impl Middleware + 'static) -> Self {
+        Layer::Custom(Arc::new(middleware))
+    }
+
+    /// Returns the default chain: logging, retries, rate limiting, then authentication.
+    ///
+    /// Rate limiting and authentication run inside the retry layer so every attempt
+    /// waits for the limiter and carries a current access token.
+    pub fn defaults() -> Vec<Layer> {
+        vec![Layer::Logging, Layer::Retry, Layer::RateLimit, Layer::Auth]
+    }
+}
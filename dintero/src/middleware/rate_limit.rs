@@ -0,0 +1,89 @@
+//! Rate limiting layer.
+
+use super::{Middleware, Next, RequestContext};
+use crate::error::Result;
+use crate::rate_limit::{classify_path, RateLimiter};
+use async_trait::async_trait;
+use dintero_core::error::parse_retry_after;
+use reqwest::{header, Request, Response, StatusCode};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::debug;
+
+const RATE_LIMIT_REMAINING_HEADER: &str = "x-ratelimit-remaining";
+const RATE_LIMIT_RESET_HEADER: &str = "x-ratelimit-reset";
+
+/// Waits for a [`RateLimiter`] token before each request and feeds the API's rate limit
+/// headers back into the limiter.
+#[derive(Debug, Clone)]
+pub struct RateLimitMiddleware {
+    limiter: Arc<RateLimiter>,
+    account_id: String,
+}
+
+impl RateLimitMiddleware {
+    /// Creates a rate limiting layer. Requests to endpoints outside an account are
+    /// attributed to `account_id`.
+    pub fn new(limiter: Arc<RateLimiter>, account_id: impl Into<String>) -> Self {
+        Self { limiter, account_id: account_id.into() }
+    }
+}
+
+fn header_value(response: &Response, name: impl header::AsHeaderName) -> Option<&str> {
+    response.headers().get(name).and_then(|v| v.to_str().ok())
+}
+
+/// Parses `X-RateLimit-Reset`, given either as seconds until the reset or as a Unix
+/// timestamp.
+fn parse_reset(value: &str) -> Option<Duration> {
+    let seconds: u64 = value.trim().parse().ok()?;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+
+    if seconds > now / 2 {
+        Some(Duration::from_secs(seconds.saturating_sub(now)))
+    } else {
+        Some(Duration::from_secs(seconds))
+    }
+}
+
+#[async_trait]
+impl Middleware for RateLimitMiddleware {
+    async fn handle(
+        &self,
+        request: Request,
+        ctx: &mut RequestContext,
+        next: Next<'_>,
+    ) -> Result<Response> {
+        let path = request.url().path().to_string();
+        let (account_id, group) = classify_path(&path, &self.account_id);
+
+        self.limiter.acquire(account_id, group).await;
+        let response = next.run(request, ctx).await?;
+
+        let remaining = header_value(&response, RATE_LIMIT_REMAINING_HEADER)
+            .and_then(|v| v.trim().parse::<u64>().ok());
+        let reset = header_value(&response, RATE_LIMIT_RESET_HEADER).and_then(parse_reset);
+        let retry_after = header_value(&response, header::RETRY_AFTER).and_then(parse_retry_after);
+
+        if let Some(remaining) = remaining {
+            self.limiter.set_remaining(account_id, group, remaining);
+        }
+
+        let pause = match (response.status(), retry_after, remaining, reset) {
+            (StatusCode::TOO_MANY_REQUESTS, Some(retry_after), _, _) => Some(retry_after),
+            (_, _, Some(0), Some(reset)) => Some(reset),
+            (StatusCode::TOO_MANY_REQUESTS, None, _, reset) => reset,
+            _ => None,
+        };
+
+        if let Some(pause) = pause {
+            debug!(
+                "Rate limit reached for {}/{}, pausing for {:?}",
+                account_id, group, pause
+            );
+            self.limiter.pause(account_id, group, pause);
+        }
+
+        Ok(response)
+    }
+}
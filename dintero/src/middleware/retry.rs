@@ -0,0 +1,117 @@
+//! Retry layer.
+
+use super::{Middleware, Next, RequestContext};
+use crate::error::{Error, Result};
+use async_trait::async_trait;
+use dintero_core::error::parse_retry_after;
+use dintero_core::request::IDEMPOTENCY_KEY_HEADER;
+use dintero_core::{RetryAttempt, RetryBudget, RetryFailure, RetryPolicy};
+use reqwest::{header, Request, Response, StatusCode};
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::warn;
+
+/// Retries failed requests as decided by a [`RetryPolicy`].
+///
+/// The policy set in the request's [`RequestOptions`](dintero_core::RequestOptions)
+/// takes precedence over the client's policy. Every retry withdraws from the client's
+/// [`RetryBudget`], if one is configured; once the budget is exhausted failures are
+/// returned without retrying. Requests whose body cannot be cloned are sent once.
+#[derive(Debug, Clone)]
+pub struct RetryMiddleware {
+    policy: Arc<dyn RetryPolicy>,
+    budget: Option<Arc<RetryBudget>>,
+}
+
+impl RetryMiddleware {
+    /// Creates a retry layer with the given policy and budget.
+    pub fn new(policy: Arc<dyn RetryPolicy>, budget: Option<Arc<RetryBudget>>) -> Self {
+        Self { policy, budget }
+    }
+}
+
+fn is_connect_error(error: &Error) -> bool {
+    match error {
+        Error::Http(source) => {
+            source.downcast_ref::<reqwest::Error>().is_some_and(reqwest::Error::is_connect)
+        }
+        _ => false,
+    }
+}
+
+#[async_trait]
+impl Middleware for RetryMiddleware {
+    async fn handle(
+        &self,
+        request: Request,
+        ctx: &mut RequestContext,
+        next: Next<'_>,
+    ) -> Result<Response> {
+        let policy = ctx.options.retry_policy.clone().unwrap_or_else(|| Arc::clone(&self.policy));
+        let method = request.method().as_str().to_string();
+        let has_idempotency_key =
+            ctx.idempotent_endpoint && request.headers().contains_key(IDEMPOTENCY_KEY_HEADER);
+        let started = Instant::now();
+        let mut previous_delay = None;
+        let mut request = request;
+
+        loop {
+            let Some(retry) = request.try_clone() else {
+                return next.run(request, ctx).await;
+            };
+
+            let result = next.run(request, ctx).await;
+
+            let (failure, retry_after) = match &result {
+                Ok(response) if response.status().is_success() => {
+                    if let Some(budget) = &self.budget {
+                        budget.deposit();
+                    }
+                    return result;
+                }
+                Ok(response) => {
+                    if response.status() == StatusCode::TOO_MANY_REQUESTS {
+                        ctx.rate_limited += 1;
+                    }
+                    let retry_after = response
+                        .headers()
+                        .get(header::RETRY_AFTER)
+                        .and_then(|v| v.to_str().ok())
+                        .and_then(parse_retry_after);
+                    (
+                        RetryFailure::Status(response.status().as_u16()),
+                        retry_after,
+                    )
+                }
+                Err(Error::Timeout(_)) => (RetryFailure::Timeout, None),
+                Err(e) if is_connect_error(e) => (RetryFailure::Connect, None),
+                Err(_) => return result,
+            };
+
+            let attempt = RetryAttempt {
+                method: &method,
+                has_idempotency_key,
+                failure,
+                retry_after,
+                retries: ctx.attempt,
+                elapsed: started.elapsed(),
+                previous_delay,
+            };
+
+            let Some(delay) = policy.retry_delay(&attempt) else {
+                return result;
+            };
+
+            if self.budget.as_ref().is_some_and(|budget| !budget.try_withdraw()) {
+                warn!("Retry budget exhausted, not retrying {:?}", failure);
+                return result;
+            }
+
+            warn!("Request failed ({:?}), retrying in {:?}", failure, delay);
+            tokio::time::sleep(delay).await;
+            ctx.attempt += 1;
+            previous_delay = Some(delay);
+            request = retry;
+        }
+    }
+}
@@ -0,0 +1,364 @@
+//! Client-side rate limiting.
+//!
+//! A [`RateLimiter`] keeps a token bucket per account and endpoint group, so bulk jobs
+//! slow down before the API starts rejecting them with `429 Too Many Requests`. When the
+//! API asks the client to back off, through `Retry-After` or `X-RateLimit-*` headers,
+//! the affected bucket is paused until the given time.
+//!
+//! Requests waiting for the same bucket are served in the order they arrived.
+//!
+//! ```no_run
+//! use dintero::rate_limit::{BucketLimit, RateLimitConfig, RateLimiter};
+//! use dintero::Config;
+//! use std::sync::Arc;
+//!
+//! # fn example() -> dintero::Result<()> {
+//! let limiter = Arc::new(RateLimiter::new(
+//!     RateLimitConfig::new(BucketLimit::new(20.0, 40)).group("settlements", BucketLimit::new(2.0, 5)),
+//! ));
+//!
+//! let config = Config::builder("T12345678")
+//!     .api_key("your-api-key")
+//!     .rate_limiter(Arc::clone(&limiter))
+//!     .build()?;
+//!
+//! for state in limiter.snapshot() {
+//!     println!("{}/{}: {:.1} tokens", state.account_id, state.group, state.available);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Lowest sustained rate of a bucket. Lower, zero, negative or NaN rates are raised to
+/// it, so a bucket always refills.
+pub const MIN_REQUESTS_PER_SECOND: f64 = 0.001;
+
+/// Sustained rate and burst size of a token bucket.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BucketLimit {
+    /// Tokens added per second, at least [`MIN_REQUESTS_PER_SECOND`].
+    pub requests_per_second: f64,
+    /// Maximum number of tokens, i.e. the largest burst sent without waiting.
+    pub burst: u32,
+}
+
+impl BucketLimit {
+    /// Creates a limit of `requests_per_second` with bursts of up to `burst` requests.
+    ///
+    /// The rate is raised to [`MIN_REQUESTS_PER_SECOND`] if it is lower or NaN.
+    pub fn new(requests_per_second: f64, burst: u32) -> Self {
+        let limit = Self { requests_per_second, burst: burst.max(1) };
+        Self {
+            requests_per_second: limit.rate(),
+            ..limit
+        }
+    }
+
+    /// Returns the rate, clamped to a positive, finite value. The fields are public, so
+    /// the limit may not have been made with [`new`](Self::new).
+    fn rate(&self) -> f64 {
+        if self.requests_per_second.is_nan() {
+            return MIN_REQUESTS_PER_SECOND;
+        }
+        self.requests_per_second.clamp(MIN_REQUESTS_PER_SECOND, f64::MAX)
+    }
+}
+
+/// Limits applied by a [`RateLimiter`].
+#[derive(Debug, Clone)]
+pub struct RateLimitConfig {
+    /// Limit for endpoint groups without a specific limit.
+    pub default_limit: BucketLimit,
+    /// Limits for specific endpoint groups.
+    pub group_limits: HashMap<String, BucketLimit>,
+}
+
+impl RateLimitConfig {
+    /// Creates a configuration applying `default_limit` to every endpoint group.
+    pub fn new(default_limit: BucketLimit) -> Self {
+        Self {
+            default_limit,
+            group_limits: HashMap::new(),
+        }
+    }
+
+    /// Sets the limit for an endpoint group.
+    ///
+    /// The group of a request is the first path segment after the account, e.g.
+    /// `transactions` for `/v1/accounts/{aid}/transactions/{id}/capture`, or the first
+    /// path segment for endpoints outside an account, e.g. `sessions`.
+    pub fn group(mut self, group: impl Into<String>, limit: BucketLimit) -> Self {
+        self.group_limits.insert(group.into(), limit);
+        self
+    }
+
+    fn limit_for(&self, group: &str) -> BucketLimit {
+        self.group_limits.get(group).copied().unwrap_or(self.default_limit)
+    }
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self::new(BucketLimit::new(10.0, 20))
+    }
+}
+
+/// State of a single bucket, for metrics.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimitState {
+    /// Account the bucket applies to.
+    pub account_id: String,
+    /// Endpoint group the bucket applies to.
+    pub group: String,
+    /// Tokens currently available.
+    pub available: f64,
+    /// Limit of the bucket.
+    pub limit: BucketLimit,
+    /// Remaining time the bucket is paused at the API's request.
+    pub paused_for: Option<Duration>,
+    /// Number of requests waiting for a token.
+    pub queued: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct BucketKey {
+    account_id: String,
+    group: String,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    limit: BucketLimit,
+    turn: tokio::sync::Mutex<()>,
+    queued: AtomicUsize,
+    state: Mutex<BucketState>,
+}
+
+#[derive(Debug)]
+struct BucketState {
+    tokens: f64,
+    refilled_at: Instant,
+    paused_until: Option<Instant>,
+}
+
+impl Bucket {
+    fn new(limit: BucketLimit) -> Self {
+        Self {
+            limit,
+            turn: tokio::sync::Mutex::new(()),
+            queued: AtomicUsize::new(0),
+            state: Mutex::new(BucketState {
+                tokens: limit.burst as f64,
+                refilled_at: Instant::now(),
+                paused_until: None,
+            }),
+        }
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, BucketState> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        let refill = now.duration_since(state.refilled_at).as_secs_f64() * self.limit.rate();
+        state.tokens = (state.tokens + refill).min(self.limit.burst as f64);
+        state.refilled_at = now;
+        if state.paused_until.is_some_and(|until| until <= now) {
+            state.paused_until = None;
+        }
+        state
+    }
+
+    /// Takes a token, or returns how long to wait before trying again.
+    fn try_take(&self) -> Option<Duration> {
+        let mut state = self.state();
+
+        if let Some(until) = state.paused_until {
+            return Some(until - Instant::now());
+        }
+
+        if state.tokens >= 1.0 {
+            state.tokens -= 1.0;
+            None
+        } else {
+            let missing = 1.0 - state.tokens;
+            Some(Duration::from_secs_f64(missing / self.limit.rate()))
+        }
+    }
+
+    async fn acquire(&self) {
+        let _queued = Queued::enter(&self.queued);
+        let _turn = self.turn.lock().await;
+
+        while let Some(wait) = self.try_take() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// Counts a waiting request, also when the waiting future is dropped.
+struct Queued<'a>(&'a AtomicUsize);
+
+impl<'a> Queued<'a> {
+    fn enter(counter: &'a AtomicUsize) -> Self {
+        counter.fetch_add(1, Ordering::SeqCst);
+        Self(counter)
+    }
+}
+
+impl Drop for Queued<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Token-bucket rate limiter keyed by account and endpoint group.
+///
+/// Install it with [`ConfigBuilder::rate_limiter`](crate::config::ConfigBuilder::rate_limiter).
+/// A limiter can be shared by several clients, which then share its buckets.
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    buckets: Mutex<HashMap<BucketKey, Arc<Bucket>>>,
+}
+
+impl RateLimiter {
+    /// Creates a rate limiter with the given limits.
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn bucket(&self, account_id: &str, group: &str) -> Arc<Bucket> {
+        let key = BucketKey {
+            account_id: account_id.to_string(),
+            group: group.to_string(),
+        };
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        let limit = self.config.limit_for(group);
+        Arc::clone(buckets.entry(key).or_insert_with(|| Arc::new(Bucket::new(limit))))
+    }
+
+    /// Waits until a request to the given account and endpoint group may be sent.
+    pub async fn acquire(&self, account_id: &str, group: &str) {
+        self.bucket(account_id, group).acquire().await;
+    }
+
+    /// Pauses the bucket for the given account and endpoint group.
+    ///
+    /// Called when the API reports that the limit was reached. Requests queued for the
+    /// bucket wait until the pause ends.
+    pub fn pause(&self, account_id: &str, group: &str, duration: Duration) {
+        let bucket = self.bucket(account_id, group);
+        let mut state = bucket.state();
+        let until = Instant::now() + duration;
+        state.paused_until = Some(state.paused_until.map_or(until, |current| current.max(until)));
+        state.tokens = 0.0;
+    }
+
+    /// Caps the available tokens at the number of requests the API reports as remaining.
+    pub fn set_remaining(&self, account_id: &str, group: &str, remaining: u64) {
+        let bucket = self.bucket(account_id, group);
+        let mut state = bucket.state();
+        state.tokens = state.tokens.min(remaining as f64);
+    }
+
+    /// Returns the state of every bucket used so far.
+    pub fn snapshot(&self) -> Vec<RateLimitState> {
+        let buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+
+        buckets
+            .iter()
+            .map(|(key, bucket)| {
+                let state = bucket.state();
+                RateLimitState {
+                    account_id: key.account_id.clone(),
+                    group: key.group.clone(),
+                    available: state.tokens,
+                    limit: bucket.limit,
+                    paused_for: state.paused_until.map(|until| until - now),
+                    queued: bucket.queued.load(Ordering::SeqCst),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Returns the account and endpoint group of an API path such as
+/// `/v1/accounts/T12345678/transactions/{id}`.
+///
+/// Paths outside an account are attributed to `default_account`.
+pub(crate) fn classify_path<'a>(path: &'a str, default_account: &'a str) -> (&'a str, &'a str) {
+    let mut segments = path.split('/').filter(|s| !s.is_empty()).skip_while(|s| *s == "v1");
+
+    match segments.next() {
+        Some("accounts") => {
+            let account = segments.next().unwrap_or(default_account);
+            (account, segments.next().unwrap_or("accounts"))
+        }
+        Some(group) => (default_account, group),
+        None => (default_account, ""),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_path() {
+        assert_eq!(
+            classify_path("/v1/accounts/T1/transactions/t1/capture", "T0"),
+            ("T1", "transactions")
+        );
+        assert_eq!(classify_path("/v1/accounts/T1", "T0"), ("T1", "accounts"));
+        assert_eq!(classify_path("/v1/sessions/s1", "T0"), ("T0", "sessions"));
+    }
+
+    #[tokio::test]
+    async fn test_buckets_limit_bursts_and_pause() {
+        let limiter = RateLimiter::new(
+            RateLimitConfig::new(BucketLimit::new(1000.0, 10))
+                .group("transactions", BucketLimit::new(1.0, 2)),
+        );
+
+        limiter.acquire("T1", "transactions").await;
+        limiter.acquire("T1", "transactions").await;
+        limiter.acquire("T1", "sessions").await;
+
+        let state = |group: &str| {
+            limiter.snapshot().into_iter().find(|state| state.group == group).unwrap()
+        };
+        assert!(state("transactions").available < 1.0);
+        assert!(state("sessions").available >= 9.0);
+
+        limiter.pause("T1", "sessions", Duration::from_secs(5));
+        assert!(state("sessions").paused_for.is_some());
+        assert!(limiter.bucket("T1", "sessions").try_take().is_some());
+    }
+
+    #[test]
+    fn test_invalid_rates_are_clamped() {
+        for rate in [0.0, -5.0, f64::NAN] {
+            let limit = BucketLimit::new(rate, 1);
+            assert_eq!(limit.requests_per_second, MIN_REQUESTS_PER_SECOND);
+
+            let bucket = Bucket::new(BucketLimit { requests_per_second: rate, burst: 1 });
+            assert!(bucket.try_take().is_none());
+            let wait = bucket.try_take().unwrap();
+            assert!(wait <= Duration::from_secs_f64(1.0 / MIN_REQUESTS_PER_SECOND));
+        }
+        let bucket = Bucket::new(BucketLimit {
+            requests_per_second: f64::INFINITY,
+            burst: 1,
+        });
+        assert!(bucket.try_take().is_none());
+        assert!(bucket.try_take().is_none_or(|wait| wait.is_zero()));
+    }
+}
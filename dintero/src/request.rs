@@ -0,0 +1,231 @@
+//! Low-level requests to any API endpoint.
+//!
+//! [`ApiRequest`] reaches endpoints that have no typed client yet while keeping the
+//! client's authentication, retries, rate limiting and middleware. Path parameters are
+//! percent-encoded and query parameters are serialized from any [`Serialize`] type:
+//!
+//! ```no_run
+//! use dintero::{ApiRequest, DinteroClient};
+//! use reqwest::Method;
+//! use serde_json::{json, Value};
+//!
+//! # async fn example(client: DinteroClient) -> dintero::Result<()> {
+//! let request = ApiRequest::new(Method::PATCH, "accounts/{account_id}/orders/{id}")
+//!     .param("id", "T12345678.abc")
+//!     .query(&[("dry_run", "true")])
+//!     .json(&json!({ "merchant_reference": "order-42" }))
+//!     .header("Dintero-Feature", "beta");
+//!
+//! let response = client.call::<Value>(request).await?;
+//! println!("{} {:?}: {}", response.status, response.request_id, response.data);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{Error, Result};
+use dintero_core::error::REQUEST_ID_HEADERS;
+use dintero_core::path::encode_query;
+use dintero_core::{PathTemplate, RequestOptions};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Method, StatusCode};
+use serde::Serialize;
+use serde_json::Value;
+
+/// A request to an API endpoint, sent with [`HttpClient::call`](crate::HttpClient::call).
+///
+/// An `{account_id}` placeholder left unset is filled with the client's account id.
+/// Invalid query parameters, bodies or headers are reported when the request is sent.
+#[derive(Debug)]
+pub struct ApiRequest {
+    pub(crate) method: Method,
+    pub(crate) path: PathTemplate,
+    pub(crate) query: Vec<String>,
+    pub(crate) body: Option<Value>,
+    pub(crate) headers: HeaderMap,
+    pub(crate) options: RequestOptions,
+    error: Option<Error>,
+}
+
+impl ApiRequest {
+    /// Creates a request for a path below the API version, e.g.
+    /// `accounts/{account_id}/transactions/{id}`.
+    pub fn new(method: Method, path: impl Into<PathTemplate>) -> Self {
+        Self {
+            method,
+            path: path.into(),
+            query: Vec::new(),
+            body: None,
+            headers: HeaderMap::new(),
+            options: RequestOptions::default(),
+            error: None,
+        }
+    }
+
+    /// Creates a `GET` request.
+    pub fn get(path: impl Into<PathTemplate>) -> Self {
+        Self::new(Method::GET, path)
+    }
+
+    /// Creates a `POST` request.
+    pub fn post(path: impl Into<PathTemplate>) -> Self {
+        Self::new(Method::POST, path)
+    }
+
+    /// Creates a `PUT` request.
+    pub fn put(path: impl Into<PathTemplate>) -> Self {
+        Self::new(Method::PUT, path)
+    }
+
+    /// Creates a `PATCH` request.
+    pub fn patch(path: impl Into<PathTemplate>) -> Self {
+        Self::new(Method::PATCH, path)
+    }
+
+    /// Creates a `DELETE` request.
+    pub fn delete(path: impl Into<PathTemplate>) -> Self {
+        Self::new(Method::DELETE, path)
+    }
+
+    /// Sets the value of a path placeholder. The value is percent-encoded.
+    pub fn param(mut self, name: impl Into<String>, value: impl std::fmt::Display) -> Self {
+        self.path = self.path.param(name, value);
+        self
+    }
+
+    /// Adds query parameters, e.g. a struct or a slice of pairs. `None` fields are left
+    /// out.
+    pub fn query<Q: Serialize + ?Sized>(mut self, query: &Q) -> Self {
+        match encode_query(query) {
+            Ok(query) if query.is_empty() => {}
+            Ok(query) => self.query.push(query),
+            Err(e) => self.fail(e),
+        }
+        self
+    }
+
+    /// Sets a JSON body.
+    pub fn json<B: Serialize + ?Sized>(mut self, body: &B) -> Self {
+        match serde_json::to_value(body) {
+            Ok(body) => self.body = Some(body),
+            Err(e) => self.fail(e.into()),
+        }
+        self
+    }
+
+    /// Adds a header.
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        match (HeaderName::try_from(name), HeaderValue::try_from(value)) {
+            (Ok(name), Ok(value)) => {
+                self.headers.append(name, value);
+            }
+            _ => self.fail(Error::Validation(format!("invalid header {:?}", name))),
+        }
+        self
+    }
+
+    /// Sets per-request options.
+    ///
+    /// An idempotency key from the options is sent in the `Idempotency-Key` header and
+    /// reused for every retry. The key makes a `POST` retryable only if the endpoint
+    /// deduplicates requests by it.
+    pub fn options(mut self, options: RequestOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    fn fail(&mut self, error: Error) {
+        self.error.get_or_insert(error);
+    }
+
+    /// Returns the path and query string, filling `{account_id}` if it is unset.
+    pub(crate) fn target(&mut self, account_id: &str) -> Result<String> {
+        if let Some(error) = self.error.take() {
+            return Err(error);
+        }
+        let mut path = self.path.clone();
+        if path.has_placeholder("account_id") && !path.has_param("account_id") {
+            path = path.param("account_id", account_id);
+        }
+
+        let path = path.build()?;
+        if self.query.is_empty() {
+            Ok(path.to_string())
+        } else {
+            Ok(format!("{}?{}", path, self.query.join("&")))
+        }
+    }
+}
+
+/// A deserialized response body with the response metadata.
+#[derive(Debug, Clone)]
+pub struct ApiResponse<T> {
+    /// The response body.
+    pub data: T,
+    /// The HTTP status.
+    pub status: StatusCode,
+    /// The response headers.
+    pub headers: HeaderMap,
+    /// The id Dintero assigned to the request, if the response carries one.
+    pub request_id: Option<String>,
+}
+
+impl<T> ApiResponse<T> {
+    pub(crate) fn new(data: T, status: StatusCode, headers: HeaderMap) -> Self {
+        let request_id = REQUEST_ID_HEADERS
+            .iter()
+            .find_map(|name| headers.get(*name))
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+
+        Self { data, status, headers, request_id }
+    }
+
+    /// Returns the response body.
+    pub fn into_inner(self) -> T {
+        self.data
+    }
+}
+
+#[cfg(all(test, feature = "checkout"))]
+mod tests {
+    use super::*;
+    use crate::{Config, DinteroClient};
+    use dintero_mock::MockServer;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_call_fills_path_and_returns_metadata() {
+        let server = MockServer::start().await;
+        let config =
+            Config::builder("T12345678").api_key("test").base_url(server.url()).build().unwrap();
+        let client = DinteroClient::new(config).unwrap();
+
+        let request = ApiRequest::post("accounts/{account_id}/sessions").json(&json!({
+            "url": { "return_url": "https://example.com/return" },
+            "order": { "amount": 10000, "currency": "NOK" },
+        }));
+        let created = client.call::<Value>(request).await.unwrap();
+        assert!(created.status.is_success());
+        let id = created.data["id"].as_str().unwrap().to_string();
+
+        let request = ApiRequest::get("accounts/{account_id}/sessions/{id}")
+            .param("id", &id)
+            .query(&[("expand", "order items")]);
+        let fetched = client.call::<Value>(request).await.unwrap();
+        assert_eq!(fetched.status, StatusCode::OK);
+        assert_eq!(fetched.data["order"]["amount"], 10000);
+
+        let recorded = server.requests();
+        assert!(recorded[0].idempotency_key.is_none());
+        assert_eq!(
+            recorded[1].path,
+            format!("/v1/accounts/T12345678/sessions/{}?expand=order+items", id)
+        );
+
+        let missing = ApiRequest::get("accounts/{account_id}/sessions/{id}");
+        assert!(matches!(
+            client.call::<Value>(missing).await,
+            Err(Error::Validation(_))
+        ));
+    }
+}
@@ -0,0 +1,524 @@
+//! Tracing and metrics for API calls.
+//!
+//! Every call made by [`HttpClient`](crate::client::HttpClient) runs inside a
+//! `dintero.request` span at `INFO` level. The span follows the OpenTelemetry semantic
+//! conventions for HTTP clients, so with `tracing-opentelemetry` installed it is
+//! exported as a client span named after the operation:
+//!
+//! | Field                       | Example                                         |
+//! |-----------------------------|-------------------------------------------------|
+//! | `otel.name`                 | `checkout.capture_transaction`                  |
+//! | `dintero.operation`         | `checkout.capture_transaction`                  |
+//! | `dintero.account_id`        | `T12345678`                                     |
+//! | `http.request.method`       | `POST`                                          |
+//! | `http.route`                | `/v1/accounts/{account_id}/transactions/{transaction_id}/capture` |
+//! | `server.address`            | `checkout.dintero.com`                          |
+//! | `http.response.status_code` | `200`                                           |
+//! | `dintero.retry_count`       | `1`                                             |
+//! | `error.type`                | `503`, or `timeout` if no response arrived      |
+//!
+//! The operation name and route come from the `ENDPOINTS` table of the API crate whose
+//! client made the call, e.g. `dintero_checkout::ENDPOINTS`. A call made with
+//! [`HttpClient::call`](crate::client::HttpClient::call) uses the path template of its
+//! [`ApiRequest`](crate::ApiRequest) as the route. Calls to endpoints missing from the
+//! tables are named `dintero.request`, and every segment of their route but the first
+//! is replaced by `{id}`, so IDs never end up in span names or metric attributes.
+//! Operations sharing an endpoint with another operation name their calls through
+//! [`RequestOptions::operation`](dintero_core::RequestOptions::operation), which also
+//! names raw calls.
+//!
+//! With the `opentelemetry` feature, calls also record metrics through the global
+//! `opentelemetry` meter provider, and carry the W3C `traceparent` and `tracestate`
+//! headers of their span so the API call joins the caller's trace:
+//!
+//! | Instrument                          | Kind      | Description                       |
+//! |-------------------------------------|-----------|-----------------------------------|
+//! | `dintero.client.request.duration`   | histogram | Duration of API calls in seconds, including retries |
+//! | `dintero.client.retries`            | counter   | Retried attempts                  |
+//! | `dintero.client.rate_limited`       | counter   | Responses with `429 Too Many Requests` |
+//! | `dintero.client.errors`             | counter   | Failed API calls                  |
+//!
+//! All instruments carry the `dintero.operation`, `http.request.method` and
+//! `http.route` attributes, and the status or error type of the call. The instruments
+//! are created on the first API call, so install the meter provider before that.
+
+use crate::error::{Error, Result};
+use crate::middleware::RequestContext;
+use dintero_core::{Endpoint, PathTemplate};
+use reqwest::header::HeaderMap;
+use reqwest::{Request, Response};
+#[cfg(feature = "opentelemetry")]
+use std::time::Instant;
+use tracing::field::Empty;
+use tracing::Span;
+
+/// Endpoints of the enabled API crates, searched for requests made without a client's
+/// endpoint table, e.g. through [`HttpClient::call`](crate::client::HttpClient::call).
+const ENDPOINTS: &[&[Endpoint]] = &[
+    #[cfg(feature = "checkout")]
+    dintero_checkout::ENDPOINTS,
+    #[cfg(feature = "orders")]
+    dintero_orders::ENDPOINTS,
+    #[cfg(feature = "payments")]
+    dintero_payments::ENDPOINTS,
+    #[cfg(feature = "accounts")]
+    dintero_accounts::ENDPOINTS,
+    #[cfg(feature = "loyalty")]
+    dintero_loyalty::ENDPOINTS,
+    #[cfg(feature = "insights")]
+    dintero_insights::ENDPOINTS,
+];
+
+/// Operation name of calls to endpoints missing from the endpoint tables.
+const UNKNOWN_OPERATION: &str = "dintero.request";
+
+/// Describes an API call for its span and metrics.
+pub(crate) struct ApiCall {
+    operation: String,
+    route: String,
+    method: String,
+    idempotent: bool,
+    #[cfg(feature = "opentelemetry")]
+    started: Instant,
+}
+
+impl ApiCall {
+    /// Describes a request made by a client calling `endpoints`, or by a raw call to
+    /// `template`. Without either, the tables of all enabled APIs are searched.
+    ///
+    /// An `operation` named by the caller takes precedence over the tables.
+    pub(crate) fn new(
+        request: &Request,
+        endpoints: Option<&[Endpoint]>,
+        template: Option<&PathTemplate>,
+        operation: Option<&str>,
+    ) -> Self {
+        let method = request.method().as_str().to_string();
+        let path = api_path(request.url().path());
+        let template = template.filter(|template| template.has_placeholders());
+        let target = template.map_or(path, PathTemplate::as_str);
+
+        let endpoint = match endpoints {
+            Some(endpoints) => Endpoint::find(endpoints, &method, target),
+            None => Endpoint::find(ENDPOINTS.iter().copied().flatten(), &method, target),
+        };
+        let (route, listed) = match (endpoint, template) {
+            (Some(endpoint), _) => (endpoint.route.to_string(), endpoint.operation),
+            (None, Some(template)) => (template.as_str().to_string(), UNKNOWN_OPERATION),
+            (None, None) => (anonymous_route(path), UNKNOWN_OPERATION),
+        };
+
+        Self {
+            operation: operation.unwrap_or(listed).to_string(),
+            route: format!("/v1/{}", route.trim_start_matches('/')),
+            method,
+            idempotent: endpoint.is_some_and(|endpoint| endpoint.idempotency_key),
+            #[cfg(feature = "opentelemetry")]
+            started: Instant::now(),
+        }
+    }
+
+    /// Returns `true` if the endpoint deduplicates requests by their `Idempotency-Key`
+    /// header.
+    pub(crate) fn idempotent(&self) -> bool {
+        self.idempotent
+    }
+
+    /// Creates the span of the call.
+    pub(crate) fn span(&self, request: &Request, account_id: &str) -> Span {
+        tracing::info_span!(
+            "dintero.request",
+            otel.name = %self.operation,
+            otel.kind = "client",
+            otel.status_code = Empty,
+            dintero.operation = %self.operation,
+            dintero.account_id = %account_id,
+            http.request.method = %self.method,
+            http.route = %self.route,
+            server.address = request.url().host_str().unwrap_or_default(),
+            http.response.status_code = Empty,
+            dintero.retry_count = Empty,
+            error.type = Empty,
+        )
+    }
+
+    /// Adds the trace context of `span` to the request headers.
+    #[allow(unused_variables)]
+    pub(crate) fn propagate(&self, span: &Span, headers: &mut HeaderMap) {
+        #[cfg(feature = "opentelemetry")]
+        otel::inject_trace_context(span, headers);
+    }
+
+    /// Records the outcome of the call on its span and in the metrics.
+    pub(crate) fn finish(&self, span: &Span, result: &Result<Response>, ctx: &RequestContext) {
+        let status = result.as_ref().ok().map(Response::status);
+        let error_type = match (status, result) {
+            (Some(status), _) if status.is_success() => None,
+            (Some(status), _) => Some(status.as_u16().to_string()),
+            (None, Err(Error::Timeout(_))) => Some("timeout".to_string()),
+            (None, _) => Some("transport".to_string()),
+        };
+
+        if let Some(status) = status {
+            span.record("http.response.status_code", status.as_u16());
+        }
+        span.record("dintero.retry_count", ctx.attempt);
+        if let Some(error_type) = &error_type {
+            span.record("error.type", error_type.as_str());
+            span.record("otel.status_code", "ERROR");
+        }
+
+        #[cfg(feature = "opentelemetry")]
+        otel::record(self, status, error_type.as_deref(), ctx);
+    }
+}
+
+/// Returns the path of a request below the API version.
+fn api_path(path: &str) -> &str {
+    match path.find("/v1/") {
+        Some(index) => &path[index + "/v1/".len()..],
+        None => path.trim_start_matches('/'),
+    }
+}
+
+/// Returns a route for a path missing from the endpoint tables, with every segment
+/// but the first replaced by `{id}`, e.g. `accounts/{id}/customers/{id}`.
+///
+/// The first segment names a part of the API, such as `accounts` or `oauth`, and never
+/// holds an ID, so merchant-chosen IDs stay out of the route and the number of routes
+/// stays bounded.
+fn anonymous_route(path: &str) -> String {
+    let mut segments = path.split('/').filter(|segment| !segment.is_empty());
+    let first = segments.next().unwrap_or_default();
+    std::iter::once(first).chain(segments.map(|_| "{id}")).collect::<Vec<_>>().join("/")
+}
+
+#[cfg(feature = "opentelemetry")]
+mod otel {
+    use super::ApiCall;
+    use crate::middleware::RequestContext;
+    use opentelemetry::metrics::{Counter, Histogram};
+    use opentelemetry::trace::TraceContextExt;
+    use opentelemetry::{global, KeyValue};
+    use reqwest::header::{HeaderMap, HeaderValue};
+    use reqwest::StatusCode;
+    use std::sync::OnceLock;
+    use tracing::Span;
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+    struct Instruments {
+        duration: Histogram<f64>,
+        retries: Counter<u64>,
+        rate_limited: Counter<u64>,
+        errors: Counter<u64>,
+    }
+
+    fn instruments() -> &'static Instruments {
+        static INSTRUMENTS: OnceLock<Instruments> = OnceLock::new();
+        INSTRUMENTS.get_or_init(|| {
+            let meter = global::meter("dintero");
+            Instruments {
+                duration: meter
+                    .f64_histogram("dintero.client.request.duration")
+                    .with_unit("s")
+                    .with_description("Duration of Dintero API calls, including retries")
+                    .build(),
+                retries: meter
+                    .u64_counter("dintero.client.retries")
+                    .with_description("Retried attempts of Dintero API calls")
+                    .build(),
+                rate_limited: meter
+                    .u64_counter("dintero.client.rate_limited")
+                    .with_description("Dintero API responses with 429 Too Many Requests")
+                    .build(),
+                errors: meter
+                    .u64_counter("dintero.client.errors")
+                    .with_description("Failed Dintero API calls")
+                    .build(),
+            }
+        })
+    }
+
+    pub(super) fn record(
+        call: &ApiCall,
+        status: Option<StatusCode>,
+        error_type: Option<&str>,
+        ctx: &RequestContext,
+    ) {
+        let instruments = instruments();
+        // Every 429 seen by the retry layer is counted there; without it only the final
+        // response is known.
+        let rate_limited =
+            ctx.rate_limited.max(u32::from(status == Some(StatusCode::TOO_MANY_REQUESTS)));
+        let mut attributes = vec![
+            KeyValue::new("dintero.operation", call.operation.clone()),
+            KeyValue::new("http.request.method", call.method.clone()),
+            KeyValue::new("http.route", call.route.clone()),
+        ];
+        if let Some(status) = status {
+            attributes.push(KeyValue::new(
+                "http.response.status_code",
+                i64::from(status.as_u16()),
+            ));
+        }
+        if let Some(error_type) = error_type {
+            attributes.push(KeyValue::new("error.type", error_type.to_string()));
+        }
+
+        instruments.duration.record(call.started.elapsed().as_secs_f64(), &attributes);
+        if ctx.attempt > 0 {
+            instruments.retries.add(u64::from(ctx.attempt), &attributes);
+        }
+        if rate_limited > 0 {
+            instruments.rate_limited.add(u64::from(rate_limited), &attributes);
+        }
+        if error_type.is_some() {
+            instruments.errors.add(1, &attributes);
+        }
+    }
+
+    /// Adds the W3C `traceparent` and `tracestate` headers of the span's OpenTelemetry
+    /// context. Does nothing unless the span is exported by `tracing-opentelemetry`.
+    pub(super) fn inject_trace_context(span: &Span, headers: &mut HeaderMap) {
+        let context = span.context();
+        let otel_span = context.span();
+        let span_context = otel_span.span_context();
+        if !span_context.is_valid() {
+            return;
+        }
+
+        let traceparent = format!(
+            "00-{}-{}-{:02x}",
+            span_context.trace_id(),
+            span_context.span_id(),
+            span_context.trace_flags().to_u8()
+        );
+        if let Ok(value) = HeaderValue::from_str(&traceparent) {
+            headers.insert("traceparent", value);
+        }
+
+        let tracestate = span_context.trace_state().header();
+        if let Ok(value) = HeaderValue::from_str(&tracestate) {
+            if !tracestate.is_empty() {
+                headers.insert("tracestate", value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn describe(
+        method: &str,
+        path: &str,
+        endpoints: Option<&[Endpoint]>,
+        template: Option<&str>,
+    ) -> (String, String) {
+        let request = reqwest::Client::new()
+            .request(
+                method.parse().unwrap(),
+                format!("https://api.dintero.com/v1/{}", path),
+            )
+            .build()
+            .unwrap();
+        let template = template.map(PathTemplate::new);
+        let call = ApiCall::new(&request, endpoints, template.as_ref(), None);
+        (call.operation, call.route)
+    }
+
+    #[cfg(all(
+        feature = "checkout",
+        feature = "payments",
+        feature = "accounts",
+        feature = "loyalty"
+    ))]
+    #[test]
+    fn test_routes_and_operation_names_come_from_endpoint_tables() {
+        assert_eq!(
+            describe(
+                "POST",
+                "accounts/T12345678/transactions/T12345678.4Xd2/capture",
+                Some(dintero_payments::ENDPOINTS),
+                None,
+            ),
+            (
+                "payments.capture_transaction".to_string(),
+                "/v1/accounts/{account_id}/transactions/{transaction_id}/capture".to_string()
+            )
+        );
+        assert_eq!(
+            describe(
+                "GET",
+                "accounts/T12345678/customers/john-doe",
+                Some(dintero_loyalty::ENDPOINTS),
+                None
+            ),
+            (
+                "loyalty.get_customer".to_string(),
+                "/v1/accounts/{account_id}/customers/{customer_id}".to_string()
+            )
+        );
+        assert_eq!(
+            describe("GET", "accounts/current/users/u1", None, None).0,
+            "accounts.get_user"
+        );
+        assert_eq!(
+            describe(
+                "POST",
+                "accounts/T12345678/sessions/abc/cancel",
+                None,
+                Some("accounts/{account_id}/sessions/{id}/cancel")
+            )
+            .0,
+            "checkout.cancel_session"
+        );
+    }
+
+    /// Records the operation name of each call instead of sending it.
+    #[cfg(feature = "payments")]
+    #[derive(Debug, Default)]
+    struct RecordOperations(std::sync::Mutex<Vec<String>>);
+
+    #[cfg(feature = "payments")]
+    #[async_trait::async_trait]
+    impl crate::middleware::Middleware for RecordOperations {
+        async fn handle(
+            &self,
+            request: Request,
+            ctx: &mut RequestContext,
+            _next: crate::middleware::Next<'_>,
+        ) -> Result<Response> {
+            let endpoints = Some(dintero_payments::ENDPOINTS);
+            let call = ApiCall::new(&request, endpoints, None, ctx.options.operation);
+            self.0.lock().unwrap().push(call.operation);
+            Ok(http::Response::new("{}").into())
+        }
+    }
+
+    #[cfg(feature = "payments")]
+    #[tokio::test]
+    async fn test_payments_operations_are_named_after_their_method() {
+        use dintero_payments::*;
+
+        let recorder = std::sync::Arc::new(RecordOperations::default());
+        let config = crate::Config::builder("T12345678")
+            .api_key("key")
+            .layers(vec![crate::middleware::Layer::Custom(recorder.clone())])
+            .build()
+            .unwrap();
+        let payments = crate::DinteroClient::new(config).unwrap().payments();
+        let transfer = FundTransferRequest {
+            amount: 100,
+            currency: Currency::Nok,
+            from_payout_destination: "a".to_string(),
+            to_payout_destination: "b".to_string(),
+            reference: None,
+            description: None,
+        };
+
+        // Only the calls matter; `{}` does not deserialize into most responses.
+        let _ = payments.get_transaction("t1").await;
+        let _ = payments.list_transactions(ListTransactionsParams::default()).await;
+        let _ = payments.update_transaction("t1", UpdateTransactionRequest::new()).await;
+        let _ = payments.capture_transaction("t1", CaptureTransactionRequest::new(1)).await;
+        let _ = payments.refund_transaction("t1", RefundTransactionRequest::new(1)).await;
+        let _ = payments.void_transaction("t1", VoidTransactionRequest::new()).await;
+        let _ = payments.extend_authorization("t1", ExtendAuthorizationRequest::new(7)).await;
+        let _ = payments.list_settlements(Default::default()).await;
+        let _ = payments.get_settlement_report_config("c1").await;
+        let _ = payments.list_settlement_report_configs().await;
+        let create_config = CreateSettlementReportConfigRequest::new();
+        let _ = payments.create_settlement_report_config(create_config).await;
+        let update_config = UpdateSettlementReportConfigRequest::new();
+        let _ = payments.update_settlement_report_config("c1", update_config).await;
+        let _ = payments.delete_settlement_report_config("c1").await;
+        let _ = payments.list_payout_destinations().await;
+        let destination = CreatePayoutDestinationRequest::new("Shop", "12345678903");
+        let _ = payments.create_payout_destination(destination).await;
+        let _ = payments.get_payout_balance("d1").await;
+        let _ = payments.list_payout_transfers("d1", Default::default()).await;
+        let payout = CreatePayoutTransferRequest::new(100, Currency::Nok, "d1");
+        let _ = payments.create_payout_transfer(payout).await;
+        let _ = payments.initiate_fund_transfer(transfer).await;
+        // The seller endpoints are the payout destination endpoints.
+        let _ = payments.get_seller_balance("d1").await;
+        let _ = payments.list_seller_transfers("d1", Default::default()).await;
+
+        assert_eq!(
+            *recorder.0.lock().unwrap(),
+            [
+                "get_transaction",
+                "list_transactions",
+                "update_transaction",
+                "capture_transaction",
+                "refund_transaction",
+                "void_transaction",
+                "extend_authorization",
+                "list_settlements",
+                "get_settlement_report_config",
+                "list_settlement_report_configs",
+                "create_settlement_report_config",
+                "update_settlement_report_config",
+                "delete_settlement_report_config",
+                "list_payout_destinations",
+                "create_payout_destination",
+                "get_payout_balance",
+                "list_payout_transfers",
+                "create_payout_transfer",
+                "initiate_fund_transfer",
+                "get_payout_balance",
+                "list_payout_transfers",
+            ]
+            .map(|name| format!("payments.{}", name))
+        );
+    }
+
+    #[test]
+    fn test_unknown_endpoints_keep_ids_out_of_routes() {
+        assert_eq!(
+            describe("GET", "accounts/T12345678/widgets/john-doe", None, None),
+            (
+                "dintero.request".to_string(),
+                "/v1/accounts/{id}/{id}/{id}".to_string()
+            )
+        );
+        assert_eq!(
+            describe(
+                "GET",
+                "accounts/T12345678/widgets/john-doe",
+                None,
+                Some("accounts/{account_id}/widgets/{id}")
+            )
+            .1,
+            "/v1/accounts/{account_id}/widgets/{id}"
+        );
+    }
+
+    #[cfg(feature = "opentelemetry")]
+    #[test]
+    fn test_injects_w3c_trace_context() {
+        use opentelemetry::trace::TracerProvider as _;
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let provider = opentelemetry_sdk::trace::SdkTracerProvider::builder().build();
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let request = reqwest::Client::new()
+            .post("https://checkout.dintero.com/v1/accounts/T12345678/sessions")
+            .build()
+            .unwrap();
+        let call = ApiCall::new(&request, None, None, None);
+        let span = call.span(&request, "T12345678");
+        let mut headers = HeaderMap::new();
+        call.propagate(&span, &mut headers);
+
+        let traceparent = headers["traceparent"].to_str().unwrap();
+        let parts: Vec<&str> = traceparent.split('-').collect();
+        assert_eq!(parts.len(), 4);
+        assert_eq!((parts[0], parts[1].len(), parts[2].len()), ("00", 32, 16));
+    }
+}
@@ -4,8 +4,12 @@ pub mod address;
 pub mod metadata;
 pub mod money;
 pub mod pagination;
+pub mod totals;
 
 pub use address::{Address, AddressBuilder};
 pub use metadata::{Metadata, MetadataMap};
-pub use money::{Currency, Money};
+pub use money::{Currency, Money, MoneyError, RoundingMode};
 pub use pagination::{Pagination, PaginationParams, PaginationParamsBuilder};
+pub use totals::{
+    LineItem, Totals, TotalsError, TotalsErrors, TotalsMode, TotalsValidator, VatRate,
+};
@@ -1,5 +1,6 @@
 //! Module implementation.
 
+use dintero_core::{Page, PageRequest};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -27,6 +28,13 @@ impl<T> Pagination<T> {
     pub fn len(&self) -> usize {
         self.data.len()
     }
+
+    /// Converts the response into a [`Page`], using `starting_after` as the cursor of the
+    /// next page unless `has_more` is `false`.
+    pub fn into_page(self) -> Page<T> {
+        let next = self.starting_after.filter(|_| self.has_more != Some(false));
+        Page::from_token(self.data, next)
+    }
 }
 
 impl<T> Default for Pagination<T> {
@@ -49,6 +57,15 @@ impl PaginationParams {
     pub fn builder() -> PaginationParamsBuilder {
         PaginationParamsBuilder::default()
     }
+
+    /// Returns the parameters for a page requested by a
+    /// [`Paginator`](dintero_core::Paginator).
+    pub fn for_page(&self, page: &PageRequest) -> Self {
+        Self {
+            limit: page.page_size.or(self.limit),
+            starting_after: page.page_token().or_else(|| self.starting_after.clone()),
+        }
+    }
 }
 
 impl Default for PaginationParams {
@@ -0,0 +1,8 @@
+//! Order line totals and VAT.
+//!
+//! The types live in `dintero_core` so the API crates can share them, and are
+//! re-exported here.
+
+pub use dintero_core::totals::{
+    LineItem, Totals, TotalsError, TotalsErrors, TotalsMode, TotalsValidator, VatRate,
+};
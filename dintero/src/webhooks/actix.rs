@@ -0,0 +1,123 @@
+//! actix-web integration.
+//!
+//! [`VerifiedEvent`] extracts a verified callback using the [`WebhookVerifier`]
+//! registered as app data, either directly or wrapped in [`web::Data`]. Rejections and
+//! [`DispatchError`]s implement [`ResponseError`], so handlers can return them directly:
+//!
+//! ```no_run
+//! use actix_web::{web, App, HttpResponse};
+//! use dintero::webhooks::{DispatchError, EventDispatcher, VerifiedEvent, WebhookVerifier};
+//!
+//! async fn callback(
+//!     dispatcher: web::Data<EventDispatcher>,
+//!     VerifiedEvent(event): VerifiedEvent,
+//! ) -> Result<HttpResponse, DispatchError> {
+//!     dispatcher.dispatch(event).await?;
+//!     Ok(HttpResponse::Ok().finish())
+//! }
+//!
+//! # fn app(dispatcher: web::Data<EventDispatcher>) {
+//! App::new()
+//!     .app_data(web::Data::new(WebhookVerifier::new("secret")))
+//!     .app_data(dispatcher)
+//!     .route("/dintero/callback", web::post().to(callback));
+//! # }
+//! ```
+
+use super::dispatcher::DispatchError;
+use super::event::Event;
+use super::extract::{VerifiedEvent, WebhookRejection};
+use super::signature::{WebhookError, WebhookVerifier, SIGNATURE_HEADER, TIMESTAMP_HEADER};
+use ::actix_web::dev::Payload;
+use ::actix_web::http::StatusCode;
+use ::actix_web::web::{self, Bytes};
+use ::actix_web::{FromRequest, HttpRequest, ResponseError};
+use std::future::Future;
+use std::pin::Pin;
+
+impl FromRequest for VerifiedEvent {
+    type Error = WebhookRejection;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let verifier = req
+            .app_data::<web::Data<WebhookVerifier>>()
+            .map(|data| data.get_ref().clone())
+            .or_else(|| req.app_data::<WebhookVerifier>().cloned());
+        let headers = header(req, SIGNATURE_HEADER).and_then(|signature| {
+            Ok((
+                signature.to_string(),
+                header(req, TIMESTAMP_HEADER)?.to_string(),
+            ))
+        });
+        let body = Bytes::from_request(req, payload);
+
+        Box::pin(async move {
+            let verifier = verifier.ok_or(WebhookRejection::MissingVerifier)?;
+            let body = body.await.map_err(|err| WebhookRejection::Body {
+                status: err.as_response_error().status_code().as_u16(),
+                message: err.to_string(),
+            })?;
+
+            let (signature, timestamp) = headers?;
+            verifier.verify_parts(&signature, &timestamp, &body)?;
+            Ok(VerifiedEvent(Event::from_slice(&body)?))
+        })
+    }
+}
+
+fn header<'a>(req: &'a HttpRequest, name: &'static str) -> Result<&'a str, WebhookError> {
+    req.headers()
+        .get(name)
+        .ok_or(WebhookError::MissingHeader(name))?
+        .to_str()
+        .map_err(|_| WebhookError::MalformedHeader(name))
+}
+
+fn status(code: u16) -> StatusCode {
+    StatusCode::from_u16(code).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+impl ResponseError for WebhookRejection {
+    fn status_code(&self) -> StatusCode {
+        status(WebhookRejection::status_code(self))
+    }
+}
+
+impl ResponseError for DispatchError {
+    fn status_code(&self) -> StatusCode {
+        status(DispatchError::status_code(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::webhooks::sign;
+    use ::actix_web::test::TestRequest;
+
+    #[::actix_web::test]
+    async fn test_extracts_verified_events_and_rejects_missing_headers() {
+        let body = r#"{"id":"evt_1","event":"ORDER_CREATED","data":{"order_id":"o1"}}"#;
+        let timestamp = chrono::Utc::now().timestamp();
+
+        let (req, mut payload) = TestRequest::post()
+            .app_data(web::Data::new(WebhookVerifier::new("secret")))
+            .insert_header((SIGNATURE_HEADER, sign("secret", timestamp, body.as_bytes())))
+            .insert_header((TIMESTAMP_HEADER, timestamp.to_string()))
+            .set_payload(body)
+            .to_http_parts();
+        let VerifiedEvent(event) = VerifiedEvent::from_request(&req, &mut payload).await.unwrap();
+        assert_eq!(event.id, "evt_1");
+
+        let (req, mut payload) = TestRequest::post()
+            .app_data(WebhookVerifier::new("secret"))
+            .set_payload(body)
+            .to_http_parts();
+        let rejection = VerifiedEvent::from_request(&req, &mut payload).await.unwrap_err();
+        assert_eq!(
+            ResponseError::status_code(&rejection),
+            StatusCode::BAD_REQUEST
+        );
+    }
+}
@@ -0,0 +1,109 @@
+//! axum integration.
+//!
+//! [`VerifiedEvent`] extracts a verified callback in any handler whose state provides a
+//! [`WebhookVerifier`] through [`FromRef`]. Rejections and [`DispatchError`]s turn into
+//! responses with the matching status, so handlers can return them directly:
+//!
+//! ```no_run
+//! use axum::{extract::State, http::StatusCode, routing::post, Router};
+//! use dintero::webhooks::{DispatchError, EventDispatcher, VerifiedEvent, WebhookVerifier};
+//! use std::sync::Arc;
+//!
+//! #[derive(Clone)]
+//! struct AppState {
+//!     verifier: WebhookVerifier,
+//!     dispatcher: Arc<EventDispatcher>,
+//! }
+//!
+//! impl axum::extract::FromRef<AppState> for WebhookVerifier {
+//!     fn from_ref(state: &AppState) -> Self {
+//!         state.verifier.clone()
+//!     }
+//! }
+//!
+//! async fn callback(
+//!     State(state): State<AppState>,
+//!     VerifiedEvent(event): VerifiedEvent,
+//! ) -> Result<StatusCode, DispatchError> {
+//!     state.dispatcher.dispatch(event).await?;
+//!     Ok(StatusCode::OK)
+//! }
+//!
+//! # fn app(state: AppState) -> Router {
+//! Router::new().route("/dintero/callback", post(callback)).with_state(state)
+//! # }
+//! ```
+
+use super::dispatcher::DispatchError;
+use super::event::Event;
+use super::extract::{VerifiedEvent, WebhookRejection};
+use super::signature::WebhookVerifier;
+use ::axum::body::Bytes;
+use ::axum::extract::{FromRef, FromRequest, Request};
+use ::axum::http::StatusCode;
+use ::axum::response::{IntoResponse, Response};
+
+impl<S> FromRequest<S> for VerifiedEvent
+where
+    S: Send + Sync,
+    WebhookVerifier: FromRef<S>,
+{
+    type Rejection = WebhookRejection;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let verifier = WebhookVerifier::from_ref(state);
+        let headers = req.headers().clone();
+        let body =
+            Bytes::from_request(req, state).await.map_err(|rejection| WebhookRejection::Body {
+                status: rejection.status().as_u16(),
+                message: rejection.body_text(),
+            })?;
+
+        verifier.verify(&headers, &body)?;
+        Ok(VerifiedEvent(Event::from_slice(&body)?))
+    }
+}
+
+fn respond(status: u16, message: String) -> Response {
+    let status = StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    (status, message).into_response()
+}
+
+impl IntoResponse for WebhookRejection {
+    fn into_response(self) -> Response {
+        respond(self.status_code(), self.to_string())
+    }
+}
+
+impl IntoResponse for DispatchError {
+    fn into_response(self) -> Response {
+        respond(self.status_code(), self.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::webhooks::{sign, SIGNATURE_HEADER, TIMESTAMP_HEADER};
+
+    #[tokio::test]
+    async fn test_extracts_verified_events_and_rejects_bad_signatures() {
+        let verifier = WebhookVerifier::new("secret");
+        let body = r#"{"id":"evt_1","event":"SESSION_COMPLETED","data":{"session_id":"s1"}}"#;
+        let timestamp = chrono::Utc::now().timestamp();
+        let request = |secret: &str| {
+            Request::builder()
+                .header(SIGNATURE_HEADER, sign(secret, timestamp, body.as_bytes()))
+                .header(TIMESTAMP_HEADER, timestamp.to_string())
+                .body(::axum::body::Body::from(body))
+                .unwrap()
+        };
+
+        let VerifiedEvent(event) =
+            VerifiedEvent::from_request(request("secret"), &verifier).await.unwrap();
+        assert_eq!(event.id, "evt_1");
+
+        let rejection = VerifiedEvent::from_request(request("other"), &verifier).await.unwrap_err();
+        assert_eq!(rejection.into_response().status(), StatusCode::UNAUTHORIZED);
+    }
+}
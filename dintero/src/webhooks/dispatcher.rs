@@ -0,0 +1,320 @@
+//! Routing of verified callbacks to handlers.
+
+use super::event::{Event, EventType};
+use super::signature::{WebhookError, WebhookVerifier};
+use async_trait::async_trait;
+use reqwest::header::HeaderMap;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Error returned by an [`EventHandler`].
+pub type HandlerError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Handles events routed to it by an [`EventDispatcher`].
+///
+/// Implemented for async closures taking an [`Event`]:
+///
+/// ```
+/// use dintero::webhooks::{Event, EventDispatcher, EventType, HandlerError};
+///
+/// let dispatcher = EventDispatcher::new().on(EventType::TransactionCaptured, |event: Event| async move {
+///     println!("captured: {}", event.id);
+///     Ok::<_, HandlerError>(())
+/// });
+/// ```
+#[async_trait]
+pub trait EventHandler: Send + Sync {
+    /// Handles `event`. Returning an error makes the dispatch fail, so the callback can
+    /// be answered with an error status and is redelivered by Dintero.
+    async fn handle(&self, event: Event) -> Result<(), HandlerError>;
+}
+
+#[async_trait]
+impl<F, Fut> EventHandler for F
+where
+    F: Fn(Event) -> Fut + Send + Sync,
+    Fut: Future<Output = Result<(), HandlerError>> + Send,
+{
+    async fn handle(&self, event: Event) -> Result<(), HandlerError> {
+        self(event).await
+    }
+}
+
+/// Storage of the IDs of events already handled, used to skip redeliveries.
+#[async_trait]
+pub trait DedupStore: Send + Sync + fmt::Debug {
+    /// Marks `event_id` as handled. Returns `false` if it already was.
+    async fn insert(&self, event_id: &str) -> bool;
+
+    /// Unmarks `event_id`, so a redelivery is handled again.
+    async fn remove(&self, event_id: &str);
+}
+
+/// In-memory [`DedupStore`] remembering the most recent event IDs.
+#[derive(Debug)]
+pub struct InMemoryDedupStore {
+    capacity: usize,
+    seen: Mutex<(HashSet<String>, VecDeque<String>)>,
+}
+
+impl InMemoryDedupStore {
+    /// Default number of event IDs remembered.
+    pub const DEFAULT_CAPACITY: usize = 10_000;
+
+    /// Creates a store remembering the last `capacity` event IDs.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            seen: Mutex::new((HashSet::new(), VecDeque::new())),
+        }
+    }
+}
+
+impl Default for InMemoryDedupStore {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CAPACITY)
+    }
+}
+
+#[async_trait]
+impl DedupStore for InMemoryDedupStore {
+    async fn insert(&self, event_id: &str) -> bool {
+        let mut guard = self.seen.lock().await;
+        let (ids, order) = &mut *guard;
+        if !ids.insert(event_id.to_string()) {
+            return false;
+        }
+        order.push_back(event_id.to_string());
+        while order.len() > self.capacity {
+            if let Some(oldest) = order.pop_front() {
+                ids.remove(&oldest);
+            }
+        }
+        true
+    }
+
+    async fn remove(&self, event_id: &str) {
+        let mut guard = self.seen.lock().await;
+        let (ids, order) = &mut *guard;
+        if ids.remove(event_id) {
+            order.retain(|id| id != event_id);
+        }
+    }
+}
+
+/// Outcome of a successful dispatch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dispatch {
+    /// The event was passed to this many handlers.
+    Handled(usize),
+    /// The event was handled before and was skipped.
+    Duplicate,
+    /// No handler is registered for the event type.
+    Unhandled,
+}
+
+/// Reasons a dispatch fails.
+#[derive(Debug, thiserror::Error)]
+pub enum DispatchError {
+    /// The callback failed signature verification.
+    #[error("callback verification failed: {0}")]
+    Verification(#[from] WebhookError),
+
+    /// The callback body is not a valid event.
+    #[error("invalid callback body: {0}")]
+    Parse(#[from] serde_json::Error),
+
+    /// A handler failed.
+    #[error("handler for {event_type} failed: {source}")]
+    Handler {
+        /// Type of the event being handled.
+        event_type: EventType,
+        /// Error returned by the handler.
+        source: HandlerError,
+    },
+}
+
+impl DispatchError {
+    /// Returns the HTTP status to answer the callback with. Handler failures map to 500,
+    /// so Dintero redelivers the callback.
+    pub fn status_code(&self) -> u16 {
+        match self {
+            DispatchError::Verification(err) => err.status_code(),
+            DispatchError::Parse(_) => 400,
+            DispatchError::Handler { .. } => 500,
+        }
+    }
+}
+
+/// Routes events to the handlers registered for their type.
+///
+/// Handlers registered for an event type run in registration order. Events without a
+/// handler for their type go to the [`on_any`](Self::on_any) handlers. With
+/// [`deduplicate`](Self::deduplicate), events whose ID was already handled are skipped;
+/// if a handler fails the ID is released again, so the redelivery is processed.
+///
+/// ```no_run
+/// use dintero::webhooks::{Event, EventDispatcher, EventType, HandlerError, WebhookVerifier};
+/// use dintero::webhooks::InMemoryDedupStore;
+/// use std::sync::Arc;
+///
+/// # async fn run(headers: reqwest::header::HeaderMap, body: Vec<u8>) -> Result<(), Box<dyn std::error::Error>> {
+/// let verifier = WebhookVerifier::new("secret");
+/// let dispatcher = EventDispatcher::new()
+///     .on(EventType::TransactionAuthorized, |event: Event| async move {
+///         println!("authorized: {}", event.id);
+///         Ok::<_, HandlerError>(())
+///     })
+///     .deduplicate(Arc::new(InMemoryDedupStore::default()));
+///
+/// dispatcher.dispatch_request(&verifier, &headers, &body).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Default)]
+pub struct EventDispatcher {
+    handlers: HashMap<EventType, Vec<Arc<dyn EventHandler>>>,
+    fallback: Vec<Arc<dyn EventHandler>>,
+    dedup: Option<Arc<dyn DedupStore>>,
+}
+
+impl EventDispatcher {
+    /// Creates a dispatcher without handlers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `handler` for events of `event_type`.
+    pub fn on(mut self, event_type: EventType, handler: impl EventHandler + 'static) -> Self {
+        self.handlers.entry(event_type).or_default().push(Arc::new(handler));
+        self
+    }
+
+    /// Registers `handler` for events without a handler for their type, including
+    /// unknown events.
+    pub fn on_any(mut self, handler: impl EventHandler + 'static) -> Self {
+        self.fallback.push(Arc::new(handler));
+        self
+    }
+
+    /// Skips events whose ID is already recorded in `store`.
+    pub fn deduplicate(mut self, store: Arc<dyn DedupStore>) -> Self {
+        self.dedup = Some(store);
+        self
+    }
+
+    /// Verifies a callback, parses its body and dispatches the event.
+    pub async fn dispatch_request(
+        &self,
+        verifier: &WebhookVerifier,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) -> Result<Dispatch, DispatchError> {
+        verifier.verify(headers, body)?;
+        self.dispatch(Event::from_slice(body)?).await
+    }
+
+    /// Dispatches an already verified event.
+    pub async fn dispatch(&self, event: Event) -> Result<Dispatch, DispatchError> {
+        let event_type = event.event_type();
+        let handlers = match self.handlers.get(&event_type) {
+            Some(handlers) if !handlers.is_empty() => handlers,
+            _ if !self.fallback.is_empty() => &self.fallback,
+            _ => return Ok(Dispatch::Unhandled),
+        };
+
+        if let Some(dedup) = &self.dedup {
+            if !dedup.insert(&event.id).await {
+                return Ok(Dispatch::Duplicate);
+            }
+        }
+
+        for handler in handlers {
+            if let Err(source) = handler.handle(event.clone()).await {
+                if let Some(dedup) = &self.dedup {
+                    dedup.remove(&event.id).await;
+                }
+                return Err(DispatchError::Handler { event_type, source });
+            }
+        }
+
+        Ok(Dispatch::Handled(handlers.len()))
+    }
+}
+
+impl fmt::Debug for EventDispatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventDispatcher")
+            .field("event_types", &self.handlers.keys().collect::<Vec<_>>())
+            .field("fallback", &self.fallback.len())
+            .field("dedup", &self.dedup)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn event(id: &str, event_type: &str) -> Event {
+        let body = format!(r#"{{"id":"{id}","event":"{event_type}","data":{{"order_id":"o1"}}}}"#);
+        Event::from_slice(body.as_bytes()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_routes_by_type_and_skips_duplicates() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let failures = Arc::new(AtomicUsize::new(1));
+
+        let dispatcher = EventDispatcher::new()
+            .on(EventType::OrderCreated, move |_event: Event| {
+                let counter = Arc::clone(&counter);
+                async move {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    Ok::<_, HandlerError>(())
+                }
+            })
+            .on(EventType::OrderClosed, move |_event: Event| {
+                let failures = Arc::clone(&failures);
+                async move {
+                    if failures.fetch_sub(1, Ordering::SeqCst) > 0 {
+                        return Err::<(), HandlerError>("unavailable".into());
+                    }
+                    Ok(())
+                }
+            })
+            .deduplicate(Arc::new(InMemoryDedupStore::new(10)));
+
+        let created = event("evt_1", "ORDER_CREATED");
+        assert_eq!(
+            dispatcher.dispatch(created.clone()).await.unwrap(),
+            Dispatch::Handled(1)
+        );
+        assert_eq!(
+            dispatcher.dispatch(created).await.unwrap(),
+            Dispatch::Duplicate
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        assert_eq!(
+            dispatcher.dispatch(event("evt_2", "ORDER_UPDATED")).await.unwrap(),
+            Dispatch::Unhandled
+        );
+
+        // A failed event is handled again when redelivered.
+        let closed = event("evt_3", "ORDER_CLOSED");
+        assert!(matches!(
+            dispatcher.dispatch(closed.clone()).await,
+            Err(DispatchError::Handler { event_type: EventType::OrderClosed, .. })
+        ));
+        assert_eq!(
+            dispatcher.dispatch(closed).await.unwrap(),
+            Dispatch::Handled(1)
+        );
+    }
+}
@@ -0,0 +1,349 @@
+//! Typed callback events.
+
+use chrono::{DateTime, Utc};
+use dintero_core::{Currency, Money};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
+use std::fmt;
+
+/// A callback sent by Dintero.
+///
+/// Callbacks are JSON objects with the event `id`, the `event` type and the event `data`:
+///
+/// ```json
+/// {
+///   "id": "evt_7f0b3c",
+///   "event": "TRANSACTION_CAPTURED",
+///   "created_at": "2024-05-01T12:00:00Z",
+///   "account_id": "T12345678",
+///   "data": { "transaction_id": "T12345678.4XZz3", "amount": 10000, "currency": "NOK" }
+/// }
+/// ```
+///
+/// Events of types this SDK does not know yet are kept as [`EventPayload::Unknown`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    /// Unique ID of the event. Redeliveries of an event have the same ID.
+    pub id: String,
+    /// When the event occurred.
+    pub created_at: Option<DateTime<Utc>>,
+    /// Account the event belongs to.
+    pub account_id: Option<String>,
+    /// Type and data of the event.
+    pub payload: EventPayload,
+}
+
+impl Event {
+    /// Parses a callback body.
+    pub fn from_slice(body: &[u8]) -> serde_json::Result<Self> {
+        serde_json::from_slice(body)
+    }
+
+    /// Returns the type of the event.
+    pub fn event_type(&self) -> EventType {
+        self.payload.event_type()
+    }
+}
+
+/// Data of checkout transaction events.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TransactionEventData {
+    pub transaction_id: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merchant_reference: Option<String>,
+
+    /// Amount the event applies to, e.g. the captured amount, in minor units.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amount: Option<i64>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub currency: Option<Currency>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payment_product: Option<String>,
+
+    /// Fields not covered above.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl TransactionEventData {
+    /// Returns the amount together with its currency, if both are present.
+    pub fn money(&self) -> Option<Money> {
+        Some(Money::new(self.amount?, self.currency?))
+    }
+}
+
+/// Data of checkout session events.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionEventData {
+    pub session_id: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction_id: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merchant_reference: Option<String>,
+
+    /// Fields not covered above.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Data of order events.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderEventData {
+    pub order_id: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merchant_reference: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+
+    /// Amount the event applies to, e.g. the refunded amount, in minor units.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amount: Option<i64>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub currency: Option<Currency>,
+
+    /// Fields not covered above.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl OrderEventData {
+    /// Returns the amount together with its currency, if both are present.
+    pub fn money(&self) -> Option<Money> {
+        Some(Money::new(self.amount?, self.currency?))
+    }
+}
+
+/// Data of loyalty events. The resource the event is about, e.g. a customer or a
+/// receipt, is kept as JSON.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoyaltyEventData {
+    /// ID of the resource the event is about.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    /// Fields of the resource.
+    #[serde(flatten)]
+    pub resource: Map<String, Value>,
+}
+
+impl LoyaltyEventData {
+    /// Deserializes the resource, e.g. into a `dintero::loyalty::Customer`.
+    pub fn resource<T: serde::de::DeserializeOwned>(&self) -> serde_json::Result<T> {
+        let mut resource = self.resource.clone();
+        if let Some(id) = &self.id {
+            resource.insert("id".to_string(), Value::String(id.clone()));
+        }
+        serde_json::from_value(Value::Object(resource))
+    }
+}
+
+macro_rules! events {
+    ($($(#[$doc:meta])* $variant:ident($data:ty) = $name:literal,)*) => {
+        /// Type of an [`Event`], used to route events to handlers.
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        #[non_exhaustive]
+        pub enum EventType {
+            $($(#[$doc])* $variant,)*
+            /// An event type this SDK does not know.
+            Unknown(String),
+        }
+
+        impl EventType {
+            /// Returns the name of the event type as sent by Dintero.
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(EventType::$variant => $name,)*
+                    EventType::Unknown(name) => name,
+                }
+            }
+
+            /// Returns the event type with the given name.
+            pub fn from_name(name: &str) -> Self {
+                match name {
+                    $($name => EventType::$variant,)*
+                    other => EventType::Unknown(other.to_string()),
+                }
+            }
+        }
+
+        /// Type and data of an [`Event`].
+        #[derive(Debug, Clone, PartialEq)]
+        #[non_exhaustive]
+        pub enum EventPayload {
+            $($(#[$doc])* $variant($data),)*
+            /// An event type this SDK does not know, with its raw data.
+            Unknown {
+                /// Name of the event type.
+                event_type: String,
+                /// Data of the event.
+                data: Value,
+            },
+        }
+
+        impl EventPayload {
+            /// Returns the type of the event.
+            pub fn event_type(&self) -> EventType {
+                match self {
+                    $(EventPayload::$variant(_) => EventType::$variant,)*
+                    EventPayload::Unknown { event_type, .. } => {
+                        EventType::Unknown(event_type.clone())
+                    }
+                }
+            }
+
+            fn from_parts(name: &str, data: Value) -> serde_json::Result<Self> {
+                Ok(match name {
+                    $($name => EventPayload::$variant(serde_json::from_value(data)?),)*
+                    other => EventPayload::Unknown { event_type: other.to_string(), data },
+                })
+            }
+
+            fn data(&self) -> serde_json::Result<Value> {
+                match self {
+                    $(EventPayload::$variant(data) => serde_json::to_value(data),)*
+                    EventPayload::Unknown { data, .. } => Ok(data.clone()),
+                }
+            }
+        }
+    };
+}
+
+events! {
+    /// A payment was authorized.
+    TransactionAuthorized(TransactionEventData) = "TRANSACTION_AUTHORIZED",
+    /// An authorized payment was captured, fully or partially.
+    TransactionCaptured(TransactionEventData) = "TRANSACTION_CAPTURED",
+    /// A captured payment was refunded, fully or partially.
+    TransactionRefunded(TransactionEventData) = "TRANSACTION_REFUNDED",
+    /// An authorization was voided.
+    TransactionVoided(TransactionEventData) = "TRANSACTION_VOIDED",
+    /// A payment failed.
+    TransactionFailed(TransactionEventData) = "TRANSACTION_FAILED",
+    /// A checkout session was created.
+    SessionCreated(SessionEventData) = "SESSION_CREATED",
+    /// A checkout session was completed by the customer.
+    SessionCompleted(SessionEventData) = "SESSION_COMPLETED",
+    /// A checkout session was cancelled.
+    SessionCancelled(SessionEventData) = "SESSION_CANCELLED",
+    /// A checkout session expired before it was completed.
+    SessionExpired(SessionEventData) = "SESSION_EXPIRED",
+    /// An order was created.
+    OrderCreated(OrderEventData) = "ORDER_CREATED",
+    /// An order was updated.
+    OrderUpdated(OrderEventData) = "ORDER_UPDATED",
+    /// An order was captured, fully or partially.
+    OrderCaptured(OrderEventData) = "ORDER_CAPTURED",
+    /// An order was refunded, fully or partially.
+    OrderRefunded(OrderEventData) = "ORDER_REFUNDED",
+    /// An order was cancelled.
+    OrderCancelled(OrderEventData) = "ORDER_CANCELLED",
+    /// An order was closed.
+    OrderClosed(OrderEventData) = "ORDER_CLOSED",
+    /// A loyalty customer was created.
+    CustomerCreated(LoyaltyEventData) = "customer_created",
+    /// A loyalty customer was updated.
+    CustomerUpdated(LoyaltyEventData) = "customer_updated",
+    /// A loyalty customer was deleted.
+    CustomerDeleted(LoyaltyEventData) = "customer_deleted",
+    /// A receipt was created.
+    ReceiptCreated(LoyaltyEventData) = "receipt_created",
+    /// A loyalty card transaction was created.
+    LoyaltyTransactionCreated(LoyaltyEventData) = "transaction_created",
+    /// A loyalty card was created.
+    CardCreated(LoyaltyEventData) = "card_created",
+    /// A loyalty card was updated.
+    CardUpdated(LoyaltyEventData) = "card_updated",
+    /// A discount was assigned to a customer.
+    DiscountAssigned(LoyaltyEventData) = "discount_assigned",
+    /// A discount campaign was created.
+    CampaignCreated(LoyaltyEventData) = "campaign_created",
+}
+
+impl fmt::Display for EventType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Wire format of an [`Event`].
+#[derive(Serialize, Deserialize)]
+struct RawEvent {
+    id: String,
+    #[serde(alias = "event_type")]
+    event: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    created_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    account_id: Option<String>,
+    #[serde(default, alias = "payload")]
+    data: Value,
+}
+
+impl<'de> Deserialize<'de> for Event {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = RawEvent::deserialize(deserializer)?;
+        let payload =
+            EventPayload::from_parts(&raw.event, raw.data).map_err(serde::de::Error::custom)?;
+        Ok(Event {
+            id: raw.id,
+            created_at: raw.created_at,
+            account_id: raw.account_id,
+            payload,
+        })
+    }
+}
+
+impl Serialize for Event {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        RawEvent {
+            id: self.id.clone(),
+            event: self.event_type().as_str().to_string(),
+            created_at: self.created_at,
+            account_id: self.account_id.clone(),
+            data: self.payload.data().map_err(serde::ser::Error::custom)?,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_known_and_unknown_events() {
+        let body = br#"{
+            "id": "evt_1",
+            "event": "TRANSACTION_CAPTURED",
+            "account_id": "T12345678",
+            "data": {"transaction_id": "T1.abc", "amount": 10000, "currency": "NOK", "psp": "x"}
+        }"#;
+        let event = Event::from_slice(body).unwrap();
+        let EventPayload::TransactionCaptured(data) = &event.payload else {
+            panic!("unexpected payload: {:?}", event.payload);
+        };
+        assert_eq!(data.money(), Some(Money::new(10000, Currency::Nok)));
+        assert_eq!(data.extra["psp"], "x");
+
+        let round_trip = Event::from_slice(&serde_json::to_vec(&event).unwrap()).unwrap();
+        assert_eq!(round_trip, event);
+
+        let unknown =
+            Event::from_slice(br#"{"id": "evt_2", "event": "PAYOUT_SENT", "data": {}}"#).unwrap();
+        assert_eq!(
+            unknown.event_type(),
+            EventType::Unknown("PAYOUT_SENT".to_string())
+        );
+    }
+}
@@ -0,0 +1,59 @@
+//! Types shared by the web framework integrations.
+
+use super::event::Event;
+use super::signature::WebhookError;
+
+/// A callback whose signature was verified and whose body parsed as an [`Event`].
+///
+/// Used as an extractor in axum (feature `axum`) and actix-web (feature `actix-web`)
+/// handlers. The extractor reads the raw body, verifies it with the [`WebhookVerifier`]
+/// configured on the application and rejects the request with a [`WebhookRejection`]
+/// if either step fails.
+///
+/// [`WebhookVerifier`]: super::WebhookVerifier
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerifiedEvent(pub Event);
+
+impl VerifiedEvent {
+    /// Returns the verified event.
+    pub fn into_inner(self) -> Event {
+        self.0
+    }
+}
+
+/// Reasons a callback is rejected by the [`VerifiedEvent`] extractor.
+#[derive(Debug, thiserror::Error)]
+pub enum WebhookRejection {
+    /// The request body could not be read.
+    #[error("failed to read callback body: {message}")]
+    Body {
+        /// HTTP status reported by the framework, e.g. 413 for a body that is too large.
+        status: u16,
+        /// Description of the failure.
+        message: String,
+    },
+
+    /// No `WebhookVerifier` is configured on the application.
+    #[error("no webhook verifier configured")]
+    MissingVerifier,
+
+    /// The callback failed signature verification.
+    #[error("callback verification failed: {0}")]
+    Verification(#[from] WebhookError),
+
+    /// The verified body is not a valid event.
+    #[error("invalid callback body: {0}")]
+    Parse(#[from] serde_json::Error),
+}
+
+impl WebhookRejection {
+    /// Returns the HTTP status the request is rejected with.
+    pub fn status_code(&self) -> u16 {
+        match self {
+            WebhookRejection::Body { status, .. } => *status,
+            WebhookRejection::MissingVerifier => 500,
+            WebhookRejection::Verification(err) => err.status_code(),
+            WebhookRejection::Parse(_) => 400,
+        }
+    }
+}
@@ -0,0 +1,49 @@
+//! Handling of callbacks and webhooks sent by Dintero.
+//!
+//! Dintero signs the callbacks it sends with a secret created through
+//! `CheckoutOperations::create_signature_secret`, or the `secret` of a loyalty webhook
+//! subscription. Verify every callback with a [`WebhookVerifier`] before trusting its
+//! body:
+//!
+//! ```no_run
+//! use dintero::webhooks::{WebhookError, WebhookVerifier};
+//! use reqwest::header::HeaderMap;
+//!
+//! fn handle_callback(headers: &HeaderMap, body: &[u8]) -> Result<(), WebhookError> {
+//!     let verifier = WebhookVerifier::new(std::env::var("DINTERO_SIGNATURE_SECRET").unwrap());
+//!     verifier.verify(headers, body)?;
+//!     // The body was sent by Dintero and can be processed.
+//!     Ok(())
+//! }
+//! ```
+//!
+//! Verified bodies parse into typed [`Event`]s, which an [`EventDispatcher`] routes to
+//! async handlers by [`EventType`], optionally skipping redeliveries of events already
+//! handled.
+//!
+//! With the `axum` or `actix-web` feature, the [`VerifiedEvent`] extractor does the
+//! verification and parsing in a handler, and rejects invalid callbacks with the
+//! matching HTTP status.
+
+#[cfg(feature = "actix-web")]
+pub mod actix;
+#[cfg(feature = "axum")]
+pub mod axum;
+mod dispatcher;
+mod event;
+mod extract;
+mod signature;
+
+pub use dispatcher::{
+    DedupStore, Dispatch, DispatchError, EventDispatcher, EventHandler, HandlerError,
+    InMemoryDedupStore,
+};
+pub use event::{
+    Event, EventPayload, EventType, LoyaltyEventData, OrderEventData, SessionEventData,
+    TransactionEventData,
+};
+
+pub use extract::{VerifiedEvent, WebhookRejection};
+pub use signature::{
+    sign, WebhookError, WebhookVerifier, DEFAULT_TOLERANCE, SIGNATURE_HEADER, TIMESTAMP_HEADER,
+};
@@ -0,0 +1,296 @@
+//! Signature verification of incoming callbacks.
+
+use hmac::{Hmac, Mac};
+use reqwest::header::HeaderMap;
+use sha2::Sha256;
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Header carrying the signature of a callback.
+pub const SIGNATURE_HEADER: &str = "dintero-signature";
+
+/// Header carrying the Unix timestamp, in seconds, at which the callback was signed.
+pub const TIMESTAMP_HEADER: &str = "dintero-signature-timestamp";
+
+/// Version prefix of the signatures in [`SIGNATURE_HEADER`].
+const SIGNATURE_SCHEME: &str = "v1";
+
+/// Default maximum age of a callback.
+pub const DEFAULT_TOLERANCE: Duration = Duration::from_secs(300);
+
+/// Reasons a callback fails verification.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum WebhookError {
+    /// A signature header is missing.
+    #[error("missing header: {0}")]
+    MissingHeader(&'static str),
+
+    /// A signature header is not in the expected format.
+    #[error("malformed header: {0}")]
+    MalformedHeader(&'static str),
+
+    /// The callback was signed too long ago, or in the future.
+    #[error("timestamp {timestamp} is outside the tolerance of {}s", tolerance.as_secs())]
+    TimestampOutOfTolerance {
+        /// Timestamp of the callback.
+        timestamp: i64,
+        /// Configured tolerance.
+        tolerance: Duration,
+    },
+
+    /// No signature matches any of the configured secrets.
+    #[error("signature does not match")]
+    SignatureMismatch,
+
+    /// The verifier has no secrets to check against.
+    #[error("no signature secrets configured")]
+    NoSecrets,
+}
+
+impl WebhookError {
+    /// Returns the HTTP status to answer a rejected callback with: 400 for malformed
+    /// requests, 401 for signatures that do not verify and 500 for a misconfigured
+    /// verifier.
+    pub fn status_code(&self) -> u16 {
+        match self {
+            WebhookError::MissingHeader(_) | WebhookError::MalformedHeader(_) => 400,
+            WebhookError::TimestampOutOfTolerance { .. } | WebhookError::SignatureMismatch => 401,
+            WebhookError::NoSecrets => 500,
+        }
+    }
+}
+
+/// Verifies the signature headers of callbacks sent by Dintero.
+///
+/// Callbacks carry the Unix timestamp at which they were signed in
+/// [`TIMESTAMP_HEADER`], and one or more HMAC-SHA256 signatures of
+/// `"{timestamp}.{body}"` in [`SIGNATURE_HEADER`], as `v1=<hex>` entries separated by
+/// commas. A callback is accepted if any signature matches any configured secret, so
+/// the old and new secret can both be configured while a secret is rotated.
+///
+/// ```
+/// use dintero::webhooks::WebhookVerifier;
+/// use std::time::Duration;
+///
+/// let verifier = WebhookVerifier::new("new-secret")
+///     .with_secret("old-secret")
+///     .tolerance(Duration::from_secs(120));
+///
+/// # let body = br#"{"id":"T12345678.abc"}"#;
+/// # let timestamp = std::time::SystemTime::now()
+/// #     .duration_since(std::time::UNIX_EPOCH).unwrap().as_secs() as i64;
+/// # let signature = dintero::webhooks::sign("old-secret", timestamp, body);
+/// verifier.verify_parts(&signature, &timestamp.to_string(), body)?;
+/// # Ok::<(), dintero::webhooks::WebhookError>(())
+/// ```
+#[derive(Clone)]
+pub struct WebhookVerifier {
+    secrets: Vec<Vec<u8>>,
+    tolerance: Duration,
+}
+
+impl WebhookVerifier {
+    /// Creates a verifier accepting callbacks signed with `secret`.
+    pub fn new(secret: impl AsRef<[u8]>) -> Self {
+        Self::from_secrets([secret])
+    }
+
+    /// Creates a verifier accepting callbacks signed with any of `secrets`.
+    pub fn from_secrets<I>(secrets: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<[u8]>,
+    {
+        Self {
+            secrets: secrets.into_iter().map(|secret| secret.as_ref().to_vec()).collect(),
+            tolerance: DEFAULT_TOLERANCE,
+        }
+    }
+
+    /// Creates a verifier accepting callbacks signed with any of `secrets`, as returned by
+    /// `CheckoutOperations::get_signature_secret`.
+    #[cfg(feature = "checkout")]
+    pub fn from_signature_secrets<'a, I>(secrets: I) -> Self
+    where
+        I: IntoIterator<Item = &'a dintero_checkout::secrets::SignatureSecret>,
+    {
+        Self::from_secrets(secrets.into_iter().map(|secret| secret.secret.as_bytes()))
+    }
+
+    /// Also accepts callbacks signed with `secret`, e.g. the previous secret during
+    /// rotation.
+    pub fn with_secret(mut self, secret: impl AsRef<[u8]>) -> Self {
+        self.secrets.push(secret.as_ref().to_vec());
+        self
+    }
+
+    /// Sets how far the callback timestamp may be from the current time. Defaults to
+    /// five minutes.
+    pub fn tolerance(mut self, tolerance: Duration) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Verifies a callback from its headers and raw body.
+    pub fn verify(&self, headers: &HeaderMap, body: &[u8]) -> Result<(), WebhookError> {
+        let header = |name: &'static str| {
+            headers
+                .get(name)
+                .ok_or(WebhookError::MissingHeader(name))?
+                .to_str()
+                .map_err(|_| WebhookError::MalformedHeader(name))
+        };
+        self.verify_parts(header(SIGNATURE_HEADER)?, header(TIMESTAMP_HEADER)?, body)
+    }
+
+    /// Verifies a callback from the values of its signature headers and raw body.
+    pub fn verify_parts(
+        &self,
+        signature: &str,
+        timestamp: &str,
+        body: &[u8],
+    ) -> Result<(), WebhookError> {
+        self.verify_at(signature, timestamp, body, SystemTime::now())
+    }
+
+    fn verify_at(
+        &self,
+        signature: &str,
+        timestamp: &str,
+        body: &[u8],
+        now: SystemTime,
+    ) -> Result<(), WebhookError> {
+        if self.secrets.is_empty() {
+            return Err(WebhookError::NoSecrets);
+        }
+
+        let timestamp: i64 = timestamp
+            .trim()
+            .parse()
+            .map_err(|_| WebhookError::MalformedHeader(TIMESTAMP_HEADER))?;
+        let now = now.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs() as i64);
+        if now.abs_diff(timestamp) > self.tolerance.as_secs() {
+            return Err(WebhookError::TimestampOutOfTolerance {
+                timestamp,
+                tolerance: self.tolerance,
+            });
+        }
+
+        let signatures = parse_signatures(signature)?;
+        let matches = self.secrets.iter().any(|secret| {
+            signatures.iter().any(|candidate| {
+                // `verify_slice` compares in constant time.
+                mac(secret, timestamp, body).verify_slice(candidate).is_ok()
+            })
+        });
+
+        if matches {
+            Ok(())
+        } else {
+            Err(WebhookError::SignatureMismatch)
+        }
+    }
+}
+
+impl fmt::Debug for WebhookVerifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebhookVerifier")
+            .field(
+                "secrets",
+                &format_args!("[{} redacted]", self.secrets.len()),
+            )
+            .field("tolerance", &self.tolerance)
+            .finish()
+    }
+}
+
+fn mac(secret: &[u8], timestamp: i64, body: &[u8]) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    mac
+}
+
+/// Parses the `v1=<hex>` entries of a signature header. Entries of other schemes are
+/// ignored, so new schemes can be introduced without breaking older verifiers.
+fn parse_signatures(header: &str) -> Result<Vec<Vec<u8>>, WebhookError> {
+    let signatures: Vec<Vec<u8>> = header
+        .split(',')
+        .filter_map(|entry| {
+            let (scheme, value) = entry.trim().split_once('=')?;
+            if scheme != SIGNATURE_SCHEME {
+                return None;
+            }
+            hex::decode(value.trim()).ok()
+        })
+        .collect();
+
+    if signatures.is_empty() {
+        Err(WebhookError::MalformedHeader(SIGNATURE_HEADER))
+    } else {
+        Ok(signatures)
+    }
+}
+
+/// Computes the [`SIGNATURE_HEADER`] value Dintero sends for a callback, e.g. to test
+/// callback handlers.
+pub fn sign(secret: impl AsRef<[u8]>, timestamp: i64, body: &[u8]) -> String {
+    let signature = mac(secret.as_ref(), timestamp, body).finalize().into_bytes();
+    format!("{}={}", SIGNATURE_SCHEME, hex::encode(signature))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BODY: &[u8] = br#"{"id":"T12345678.abc","event":"TRANSACTION_AUTHORIZED"}"#;
+
+    fn at(timestamp: i64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(timestamp as u64)
+    }
+
+    #[test]
+    fn test_verifies_any_configured_secret_and_signature() {
+        let verifier = WebhookVerifier::new("new").with_secret("old");
+        let signature = format!(
+            "v0=ignored, {}, {}",
+            sign("unknown", 1000, BODY),
+            sign("old", 1000, BODY)
+        );
+
+        assert_eq!(
+            verifier.verify_at(&signature, "1000", BODY, at(1100)),
+            Ok(())
+        );
+        assert_eq!(
+            verifier.verify_at(&sign("new", 1000, BODY), "1000", b"{}", at(1000)),
+            Err(WebhookError::SignatureMismatch)
+        );
+        assert_eq!(
+            verifier.verify_at("v0=abc", "1000", BODY, at(1000)),
+            Err(WebhookError::MalformedHeader(SIGNATURE_HEADER))
+        );
+    }
+
+    #[test]
+    fn test_rejects_timestamps_outside_tolerance() {
+        let verifier = WebhookVerifier::new("secret").tolerance(Duration::from_secs(60));
+        let signature = sign("secret", 1000, BODY);
+
+        assert!(matches!(
+            verifier.verify_at(&signature, "1000", BODY, at(1061)),
+            Err(WebhookError::TimestampOutOfTolerance { timestamp: 1000, .. })
+        ));
+        assert!(verifier.verify_at(&signature, "1000", BODY, at(940)).is_ok());
+
+        let mut headers = HeaderMap::new();
+        headers.insert(SIGNATURE_HEADER, signature.parse().unwrap());
+        assert_eq!(
+            verifier.verify(&headers, BODY),
+            Err(WebhookError::MissingHeader(TIMESTAMP_HEADER))
+        );
+    }
+}